@@ -16,7 +16,8 @@ use std::sync::{Arc, Mutex};
 #[cfg(feature = "remote")]
 use std::time::Duration;
 use tdoc::formatter::{Formatter, FormattingStyle};
-use tdoc::{ftml, gemini, html, markdown, pager, Document};
+use tdoc::links::LinkBase;
+use tdoc::{format, ftml, gemini, html, json, markdown, pager, Document, Format, Paragraph};
 use url::Url;
 
 /// How often `--watch` polls the input file for modifications.
@@ -29,9 +30,9 @@ const WATCH_POLL_INTERVAL: std::time::Duration = std::time::Duration::from_milli
     about = "View and export FTML, HTML, Markdown, and Gemini documents"
 )]
 struct Cli {
-    /// Input file or URL (omit to read from stdin)
+    /// Input files or URLs, rendered one after another (omit to read from stdin)
     #[arg(value_name = "INPUT")]
-    input: Option<String>,
+    inputs: Vec<String>,
 
     /// Disable ANSI escape sequences in terminal output
     #[arg(long = "no-ansi")]
@@ -48,14 +49,40 @@ struct Cli {
     /// Watch the input file and refresh the view (or regenerate --output) on every change
     #[arg(short = 'w', long = "watch")]
     watch: bool,
+
+    /// Wrap output to COLS columns instead of the detected terminal width. Applies to
+    /// --no-ansi (or redirected) output and to plain-text --output files; the
+    /// interactive pager always follows the terminal size
+    #[arg(long = "width", value_name = "COLS", value_parser = clap::value_parser!(u16).range(1..))]
+    width: Option<u16>,
+
+    /// Print an outline of the document's headings instead of its content
+    #[arg(long = "toc", conflicts_with = "watch")]
+    toc: bool,
+
+    /// Color scheme for ANSI terminal output
+    #[arg(long = "theme", value_enum, default_value = "mono")]
+    theme: Theme,
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
-enum InputFormat {
-    Ftml,
-    Html,
-    Markdown,
-    Gemini,
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum Theme {
+    /// Colored headings, code and links for dark backgrounds
+    Dark,
+    /// Colored headings, code and links for light backgrounds
+    Light,
+    /// Bold, italic and other emphasis only, without colors
+    Mono,
+}
+
+impl Theme {
+    fn style(self) -> FormattingStyle {
+        match self {
+            Theme::Dark => FormattingStyle::ansi_dark(),
+            Theme::Light => FormattingStyle::ansi_light(),
+            Theme::Mono => FormattingStyle::ansi(),
+        }
+    }
 }
 
 #[derive(Copy, Clone, ValueEnum)]
@@ -64,15 +91,17 @@ enum InputFormatArg {
     Html,
     Markdown,
     Gemini,
+    Json,
 }
 
-impl From<InputFormatArg> for InputFormat {
+impl From<InputFormatArg> for Format {
     fn from(value: InputFormatArg) -> Self {
         match value {
-            InputFormatArg::Ftml => InputFormat::Ftml,
-            InputFormatArg::Html => InputFormat::Html,
-            InputFormatArg::Markdown => InputFormat::Markdown,
-            InputFormatArg::Gemini => InputFormat::Gemini,
+            InputFormatArg::Ftml => Format::Ftml,
+            InputFormatArg::Html => Format::Html,
+            InputFormatArg::Markdown => Format::Markdown,
+            InputFormatArg::Gemini => Format::Gemini,
+            InputFormatArg::Json => Format::Json,
         }
     }
 }
@@ -86,7 +115,7 @@ enum ContentOrigin {
 }
 
 struct InputSource {
-    format: InputFormat,
+    format: Format,
     reader: Box<dyn Read>,
     display_name: String,
     origin: ContentOrigin,
@@ -98,6 +127,7 @@ enum OutputFormat {
     Markdown,
     Html,
     Gemini,
+    Json,
 }
 
 fn main() {
@@ -109,17 +139,18 @@ fn main() {
 
 fn run() -> Result<(), String> {
     let cli = Cli::parse();
-    let input_override = cli.input_format.map(InputFormat::from);
-    let input_source = create_reader(cli.input.as_deref(), input_override)?;
-    let InputSource {
-        format,
-        reader,
-        display_name,
-        origin,
-    } = input_source;
-    let document = parse_document(format, reader, &display_name)?;
+    let input_override = cli.input_format.map(Format::from);
+    let (document, origin) = load_inputs(&cli.inputs, input_override)?;
+    let width = cli.width.map(usize::from);
+
+    if cli.toc {
+        return write_toc(&document, cli.output.as_deref());
+    }
 
     if cli.watch {
+        if cli.inputs.len() > 1 {
+            return Err("--watch only supports a single input file".to_string());
+        }
         let watch_path = match &origin {
             ContentOrigin::File(path) => path.clone(),
             _ => return Err("--watch is only supported for file inputs".to_string()),
@@ -127,25 +158,101 @@ fn run() -> Result<(), String> {
 
         if let Some(output_path) = cli.output {
             return watch_to_file(
-                cli.input.as_deref(),
+                cli.inputs.first().map(String::as_str),
                 &watch_path,
                 &output_path,
                 input_override,
+                width,
             );
         }
 
-        return watch_in_terminal(document, cli.no_ansi, origin, input_override);
+        return watch_in_terminal(
+            document,
+            cli.no_ansi,
+            width,
+            cli.theme,
+            origin,
+            input_override,
+        );
     }
 
     if let Some(output_path) = cli.output {
-        write_output(&document, &output_path)?;
+        write_output(&document, &output_path, width)?;
     } else {
-        view_document(document, cli.no_ansi, origin, input_override)?;
+        view_document(
+            document,
+            cli.no_ansi,
+            width,
+            cli.theme,
+            origin,
+            input_override,
+        )?;
     }
 
     Ok(())
 }
 
+/// Parses every input and concatenates them, separated by horizontal rules.
+/// Without inputs, stdin is read. Relative links resolve against the first
+/// input, whose origin is returned.
+fn load_inputs(
+    inputs: &[String],
+    input_override: Option<Format>,
+) -> Result<(Document, ContentOrigin), String> {
+    if inputs.is_empty() {
+        return load_input(None, input_override);
+    }
+
+    let (mut combined, origin) = load_input(Some(&inputs[0]), input_override)?;
+    for input in &inputs[1..] {
+        let (document, _) = load_input(Some(input), input_override)?;
+        combined.add_paragraph(Paragraph::HorizontalRule);
+        combined.append(document);
+    }
+    Ok((combined, origin))
+}
+
+fn load_input(
+    input: Option<&str>,
+    input_override: Option<Format>,
+) -> Result<(Document, ContentOrigin), String> {
+    let InputSource {
+        format,
+        reader,
+        display_name,
+        origin,
+    } = create_reader(input, input_override)?;
+    let document = parse_document(format, reader, &display_name)?;
+    Ok((document, origin))
+}
+
+/// Writes the table of contents to `output_path`, or to stdout without one.
+/// Files also list each heading's anchor slug.
+fn write_toc(document: &Document, output_path: Option<&Path>) -> Result<(), String> {
+    match output_path {
+        Some(path) => std::fs::write(path, format_toc(document, true))
+            .map_err(|err| format!("Unable to write {}: {err}", path.display())),
+        None => io::stdout()
+            .write_all(format_toc(document, false).as_bytes())
+            .map_err(|err| format!("Unable to write table of contents: {err}")),
+    }
+}
+
+/// Renders one line per heading, indented by two spaces per level below the
+/// first.
+fn format_toc(document: &Document, with_slugs: bool) -> String {
+    let mut output = String::new();
+    for entry in document.table_of_contents() {
+        let indent = "  ".repeat(usize::from(entry.level.saturating_sub(1)));
+        if with_slugs {
+            output.push_str(&format!("{indent}{} (#{})\n", entry.title, entry.slug));
+        } else {
+            output.push_str(&format!("{indent}{}\n", entry.title));
+        }
+    }
+    output
+}
+
 /// Most recent modification time of `path`, or `None` if it can't be read
 /// (e.g. the file is momentarily absent while an editor saves it).
 fn file_mtime(path: &Path) -> Option<std::time::SystemTime> {
@@ -158,15 +265,9 @@ fn file_mtime(path: &Path) -> Option<std::time::SystemTime> {
 /// as the initial load so format overrides and extension sniffing still apply.
 fn reload_document(
     input: Option<&str>,
-    input_override: Option<InputFormat>,
+    input_override: Option<Format>,
 ) -> Result<Document, String> {
-    let InputSource {
-        format,
-        reader,
-        display_name,
-        ..
-    } = create_reader(input, input_override)?;
-    parse_document(format, reader, &display_name)
+    load_input(input, input_override).map(|(document, _)| document)
 }
 
 /// Regenerate `output_path` from `watch_path` whenever the input changes,
@@ -175,7 +276,8 @@ fn watch_to_file(
     input: Option<&str>,
     watch_path: &Path,
     output_path: &Path,
-    input_override: Option<InputFormat>,
+    input_override: Option<Format>,
+    width: Option<usize>,
 ) -> Result<(), String> {
     eprintln!(
         "Watching {} -> {} (press Ctrl-C to stop)",
@@ -189,7 +291,7 @@ fn watch_to_file(
         if mtime.is_some() && mtime != last_mtime {
             last_mtime = mtime;
             match reload_document(input, input_override)
-                .and_then(|document| write_output(&document, output_path))
+                .and_then(|document| write_output(&document, output_path, width))
             {
                 Ok(()) => eprintln!("Regenerated {}", output_path.display()),
                 Err(message) => eprintln!("{message}"),
@@ -204,8 +306,10 @@ fn watch_to_file(
 fn watch_in_terminal(
     document: Document,
     no_ansi: bool,
+    width: Option<usize>,
+    theme: Theme,
     origin: ContentOrigin,
-    input_override: Option<InputFormat>,
+    input_override: Option<Format>,
 ) -> Result<(), String> {
     let stdout_is_tty = atty::is(atty::Stream::Stdout);
     let use_ansi = !no_ansi && stdout_is_tty;
@@ -213,16 +317,16 @@ fn watch_in_terminal(
     // Watching only makes sense in the interactive pager; fall back to a plain
     // one-shot render when output isn't an ANSI terminal.
     if !use_ansi {
-        return view_document(document, no_ansi, origin, input_override);
+        return view_document(document, no_ansi, width, theme, origin, input_override);
     }
 
+    let initial =
+        render_document_for_terminal(&document, theme, matches!(origin, ContentOrigin::Url(_)))?;
     let shared_state = Arc::new(Mutex::new(LinkEnvironment {
-        document: document.clone(),
+        document,
         origin: origin.clone(),
     }));
 
-    let initial = render_document_for_terminal(&document, matches!(origin, ContentOrigin::Url(_)))?;
-
     let regen_state = shared_state.clone();
     let regenerator = move |new_width: u16, _new_height: u16| -> Result<String, String> {
         let guard = regen_state
@@ -231,6 +335,7 @@ fn watch_in_terminal(
         render_document_for_width(
             &guard.document,
             new_width as usize,
+            theme,
             matches!(guard.origin, ContentOrigin::Url(_)),
         )
     };
@@ -275,7 +380,7 @@ fn watch_in_terminal(
         let path_str = path.to_str()?;
         match reload_document(Some(path_str), input_override) {
             Ok(reloaded) => {
-                let rendered = render_document_for_width(&reloaded, width as usize, false);
+                let rendered = render_document_for_width(&reloaded, width as usize, theme, false);
                 if let Ok(mut guard) = watch_state.lock() {
                     guard.document = reloaded;
                 }
@@ -287,7 +392,7 @@ fn watch_in_terminal(
 
     let link_policy = build_link_policy(&origin);
     let link_callback: Option<Arc<dyn pager::LinkCallback>> = Some(Arc::new(
-        LinkCallbackState::new(shared_state.clone(), input_override),
+        LinkCallbackState::new(shared_state.clone(), input_override, theme),
     ));
 
     let options = pager::PagerOptions {
@@ -308,21 +413,10 @@ fn watch_in_terminal(
 
 fn create_reader(
     argument: Option<&str>,
-    override_format: Option<InputFormat>,
+    override_format: Option<Format>,
 ) -> Result<InputSource, String> {
     match argument {
-        None => Ok(InputSource {
-            format: override_format.unwrap_or(InputFormat::Ftml),
-            reader: Box::new(io::stdin()),
-            display_name: "stdin".to_string(),
-            origin: ContentOrigin::Stdin,
-        }),
-        Some("-") => Ok(InputSource {
-            format: override_format.unwrap_or(InputFormat::Ftml),
-            reader: Box::new(io::stdin()),
-            display_name: "stdin".to_string(),
-            origin: ContentOrigin::Stdin,
-        }),
+        None | Some("-") => stdin_source(override_format),
         Some(value) => {
             if let Ok(url) = Url::parse(value) {
                 #[cfg(feature = "remote")]
@@ -350,8 +444,8 @@ fn create_reader(
                             .extension()
                             .and_then(|ext| ext.to_str());
                         let format = override_format
-                            .or_else(|| detect_input_format(extension))
-                            .unwrap_or(InputFormat::Html);
+                            .or_else(|| extension.and_then(Format::from_extension))
+                            .unwrap_or(Format::Html);
                         return Ok(InputSource {
                             format,
                             reader: Box::new(response),
@@ -382,7 +476,7 @@ fn create_reader(
                         }
 
                         // Gemini responses default to text/gemini (gemtext)
-                        let format = override_format.unwrap_or(InputFormat::Gemini);
+                        let format = override_format.unwrap_or(Format::Gemini);
 
                         return Ok(InputSource {
                             format,
@@ -406,8 +500,8 @@ fn create_reader(
                 .map_err(|err| format!("Unable to open {value} for reading: {err}"))?;
             let extension = path.extension().and_then(|ext| ext.to_str());
             let format = override_format
-                .or_else(|| detect_input_format(extension))
-                .unwrap_or(InputFormat::Ftml);
+                .or_else(|| extension.and_then(Format::from_extension))
+                .unwrap_or(Format::Ftml);
 
             let origin = ContentOrigin::File(path.to_path_buf());
 
@@ -421,39 +515,58 @@ fn create_reader(
     }
 }
 
-fn detect_input_format(extension: Option<&str>) -> Option<InputFormat> {
-    let ext = extension?.to_ascii_lowercase();
-    match ext.as_str() {
-        "ftml" => Some(InputFormat::Ftml),
-        "html" | "htm" => Some(InputFormat::Html),
-        "md" | "markdown" => Some(InputFormat::Markdown),
-        "gmi" | "gemini" => Some(InputFormat::Gemini),
-        _ => None,
-    }
+/// Reads from stdin. Without an explicit format, the format is guessed from
+/// the first chunk with [`format::detect`].
+fn stdin_source(override_format: Option<Format>) -> Result<InputSource, String> {
+    let (format, reader): (Format, Box<dyn Read>) = match override_format {
+        Some(format) => (format, Box::new(io::stdin())),
+        None => {
+            let (format, reader) = format::detect(io::stdin())
+                .map_err(|err| format!("Unable to read stdin: {err}"))?;
+            (format, Box::new(reader))
+        }
+    };
+    Ok(InputSource {
+        format,
+        reader,
+        display_name: "stdin".to_string(),
+        origin: ContentOrigin::Stdin,
+    })
 }
 
 fn parse_document(
-    format: InputFormat,
+    format: Format,
     reader: Box<dyn Read>,
     display_name: &str,
 ) -> Result<Document, String> {
     match format {
-        InputFormat::Ftml => ftml::parse(reader)
-            .map_err(|err| format!("Unable to parse {display_name} as FTML: {err}")),
-        InputFormat::Html => html::parse(reader)
+        Format::Ftml => ftml::parse(reader).map_err(|err| match err.position() {
+            Some(position) => format!(
+                "{display_name}:{}:{}: {}",
+                position.line,
+                position.column,
+                err.inner()
+            ),
+            None => format!("Unable to parse {display_name} as FTML: {err}"),
+        }),
+        Format::Html => html::parse(reader)
             .map_err(|err| format!("Unable to parse {display_name} as HTML: {err}")),
-        InputFormat::Markdown => markdown::parse(reader)
+        Format::Markdown => markdown::parse(reader)
             .map_err(|err| format!("Unable to parse {display_name} as Markdown: {err}")),
-        InputFormat::Gemini => gemini::parse(reader)
+        Format::Gemini => gemini::parse(reader)
             .map_err(|err| format!("Unable to parse {display_name} as Gemini: {err}")),
+        Format::Json => json::parse(reader)
+            .map_err(|err| format!("Unable to parse {display_name} as JSON: {err}")),
     }
 }
 
 fn view_document(
     document: Document,
     no_ansi: bool,
+    width: Option<usize>,
+    theme: Theme,
     origin: ContentOrigin,
-    input_override: Option<InputFormat>,
+    input_override: Option<Format>,
 ) -> Result<(), String> {
     let stdout_is_tty = atty::is(atty::Stream::Stdout);
     let use_ansi = !no_ansi && stdout_is_tty;
@@ -461,11 +574,14 @@ fn view_document(
 
     if !use_pager {
         let mut formatter = if use_ansi {
-            let mut style = FormattingStyle::ansi();
-            configure_style_for_terminal(&mut style);
+            let mut style = theme.style();
+            match width {
+                Some(width) => configure_style_for_width(&mut style, width),
+                None => configure_style_for_terminal(&mut style),
+            }
             Formatter::new(io::stdout(), style)
         } else {
-            Formatter::new_ascii(io::stdout())
+            Formatter::new(io::stdout(), ascii_style(width))
         };
 
         return formatter
@@ -473,12 +589,12 @@ fn view_document(
             .map_err(|err| format!("Unable to write document: {err}"));
     }
 
+    let initial =
+        render_document_for_terminal(&document, theme, matches!(origin, ContentOrigin::Url(_)))?;
     let shared_state = Arc::new(Mutex::new(LinkEnvironment {
-        document: document.clone(),
+        document,
         origin: origin.clone(),
     }));
-
-    let initial = render_document_for_terminal(&document, matches!(origin, ContentOrigin::Url(_)))?;
     let regen_state = shared_state.clone();
     let regenerator = move |new_width: u16, _new_height: u16| -> Result<String, String> {
         let guard = regen_state
@@ -487,6 +603,7 @@ fn view_document(
         render_document_for_width(
             &guard.document,
             new_width as usize,
+            theme,
             matches!(guard.origin, ContentOrigin::Url(_)),
         )
     };
@@ -497,6 +614,7 @@ fn view_document(
         _ => Some(Arc::new(LinkCallbackState::new(
             shared_state.clone(),
             input_override,
+            theme,
         ))),
     };
 
@@ -512,6 +630,15 @@ fn view_document(
     pager::page_output_with_options_and_regenerator(&initial, Some(regenerator), options)
 }
 
+/// Plain-text style, wrapped to `width` when one was requested.
+fn ascii_style(width: Option<usize>) -> FormattingStyle {
+    let mut style = FormattingStyle::ascii();
+    if let Some(width) = width {
+        configure_style_for_width(&mut style, width);
+    }
+    style
+}
+
 fn configure_style_for_terminal(style: &mut FormattingStyle) {
     if let Ok((width, _height)) = terminal::size() {
         configure_style_for_width(style, width as usize);
@@ -534,10 +661,11 @@ fn configure_style_for_width(style: &mut FormattingStyle, width: usize) {
 
 fn render_document_for_terminal(
     document: &Document,
+    theme: Theme,
     disable_link_footnotes: bool,
 ) -> Result<String, String> {
     let mut buf = Vec::new();
-    let mut style = FormattingStyle::ansi();
+    let mut style = theme.style();
     configure_style_for_terminal(&mut style);
     if disable_link_footnotes {
         style.link_footnotes = false;
@@ -554,10 +682,11 @@ fn render_document_for_terminal(
 fn render_document_for_width(
     document: &Document,
     width: usize,
+    theme: Theme,
     disable_link_footnotes: bool,
 ) -> Result<String, String> {
     let mut buf = Vec::new();
-    let mut style = FormattingStyle::ansi();
+    let mut style = theme.style();
     configure_style_for_width(&mut style, width);
     if disable_link_footnotes {
         style.link_footnotes = false;
@@ -578,14 +707,20 @@ struct LinkEnvironment {
 
 struct LinkCallbackState {
     shared: Arc<Mutex<LinkEnvironment>>,
-    input_override: Option<InputFormat>,
+    input_override: Option<Format>,
+    theme: Theme,
 }
 
 impl LinkCallbackState {
-    fn new(shared: Arc<Mutex<LinkEnvironment>>, input_override: Option<InputFormat>) -> Self {
+    fn new(
+        shared: Arc<Mutex<LinkEnvironment>>,
+        input_override: Option<Format>,
+        theme: Theme,
+    ) -> Self {
         Self {
             shared,
             input_override,
+            theme,
         }
     }
 }
@@ -601,6 +736,24 @@ impl pager::LinkCallback for LinkCallbackState {
             return Ok(());
         }
 
+        if let Some(fragment) = trimmed.strip_prefix('#') {
+            let heading = {
+                let guard = self
+                    .shared
+                    .lock()
+                    .map_err(|_| "Unable to read current document state".to_string())?;
+                find_heading(&guard.document, fragment)
+            };
+            let found = heading
+                .is_some_and(|(title, occurrence)| context.scroll_to_text(&title, occurrence));
+            if found {
+                context.clear_status()?;
+            } else {
+                context.set_status("Unable to open link".to_string())?;
+            }
+            return Ok(());
+        }
+
         let origin = {
             let guard = self
                 .shared
@@ -617,6 +770,7 @@ impl pager::LinkCallback for LinkCallbackState {
                 let rendered = render_document_for_width(
                     &document,
                     render_width,
+                    self.theme,
                     matches!(new_origin, ContentOrigin::Url(_)),
                 )?;
                 context.replace_content(&rendered)?;
@@ -643,131 +797,71 @@ impl pager::LinkCallback for LinkCallbackState {
     }
 }
 
+/// Finds the heading whose anchor is `fragment`, returning its title and how
+/// many earlier headings share that title, so the pager can tell them apart.
+fn find_heading(document: &Document, fragment: &str) -> Option<(String, usize)> {
+    let toc = document.table_of_contents();
+    let index = toc.iter().position(|entry| entry.slug == fragment)?;
+    let title = toc[index].title.clone();
+    let occurrence = toc[..index]
+        .iter()
+        .filter(|entry| entry.title == title)
+        .count();
+    Some((title, occurrence))
+}
+
+/// Same-document `#fragment` links are always activatable; they scroll to the
+/// matching heading instead of loading anything.
 fn build_link_policy(origin: &ContentOrigin) -> pager::LinkPolicy {
     match origin {
-        ContentOrigin::Url(base_url) => {
-            let base = base_url.clone();
-            pager::LinkPolicy::new(
-                false,
-                Arc::new(move |target: &str| {
-                    let trimmed = target.trim();
-                    if trimmed.is_empty() || trimmed.starts_with('#') {
-                        return false;
-                    }
-                    match Url::options().base_url(Some(&base)).parse(trimmed) {
-                        Ok(resolved) => matches!(resolved.scheme(), "http" | "https" | "gemini"),
-                        Err(_) => false,
-                    }
-                }),
-            )
-        }
-        ContentOrigin::File(path) => {
-            let base_dir = path
-                .parent()
-                .map(PathBuf::from)
-                .unwrap_or_else(|| PathBuf::from("."));
-            pager::LinkPolicy::new(
-                true,
-                Arc::new(move |target: &str| {
-                    let trimmed = target.trim();
-                    if trimmed.is_empty() || is_absolute_url(trimmed) {
-                        return false;
-                    }
-                    let candidate = if Path::new(trimmed).is_absolute() {
-                        PathBuf::from(trimmed)
-                    } else {
-                        base_dir.join(trimmed)
-                    };
-                    match std::fs::canonicalize(&candidate) {
-                        Ok(resolved) => resolved.is_file(),
-                        Err(_) => false,
-                    }
-                }),
-            )
-        }
-        ContentOrigin::Stdin => pager::LinkPolicy::new(true, Arc::new(|_| false)),
+        ContentOrigin::Url(base_url) => pager::LinkPolicy::for_url_origin(base_url.clone()),
+        ContentOrigin::File(path) => pager::LinkPolicy::for_file_origin(path.clone()),
+        ContentOrigin::Stdin => pager::LinkPolicy::for_unknown_origin(),
+    }
+}
+
+fn link_base(origin: &ContentOrigin) -> Option<LinkBase> {
+    match origin {
+        ContentOrigin::Url(url) => Some(LinkBase::Url(url.clone())),
+        ContentOrigin::File(path) => Some(LinkBase::File(path.clone())),
+        ContentOrigin::Stdin => None,
     }
 }
 
 fn navigate_to_target(
     origin: &ContentOrigin,
     target: &str,
-    input_override: Option<InputFormat>,
+    input_override: Option<Format>,
 ) -> Result<Option<(Document, ContentOrigin)>, String> {
-    let trimmed = target.trim();
-    if trimmed.is_empty() {
-        return Ok(None);
-    }
-
-    match origin {
-        ContentOrigin::Url(current_url) => {
-            if trimmed.starts_with('#') {
+    let location = match pager::resolve_link(link_base(origin).as_ref(), target) {
+        Some(pager::LinkDestination::Url(resolved)) => {
+            if matches!(origin, ContentOrigin::Url(current_url) if *current_url == resolved) {
                 return Ok(None);
             }
-            let resolved = match Url::options().base_url(Some(current_url)).parse(trimmed) {
-                Ok(url) => url,
-                Err(_) => return Ok(None),
-            };
-            if !matches!(resolved.scheme(), "http" | "https" | "gemini") {
-                return Ok(None);
-            }
-            if &resolved == current_url {
-                return Ok(None);
-            }
-
-            let input_source = create_reader(Some(resolved.as_str()), input_override)?;
-            let InputSource {
-                format,
-                reader,
-                display_name,
-                origin,
-            } = input_source;
-            let document = parse_document(format, reader, &display_name)?;
-            Ok(Some((document, origin)))
-        }
-        ContentOrigin::File(current_path) => {
-            if is_absolute_url(trimmed) {
-                return Ok(None);
-            }
-            let base_dir = current_path
-                .parent()
-                .map(PathBuf::from)
-                .unwrap_or_else(|| PathBuf::from("."));
-            let candidate = if Path::new(trimmed).is_absolute() {
-                PathBuf::from(trimmed)
-            } else {
-                base_dir.join(trimmed)
-            };
-            let resolved = match std::fs::canonicalize(&candidate) {
-                Ok(path) => path,
-                Err(_) => return Ok(None),
-            };
-            if !resolved.is_file() {
-                return Ok(None);
-            }
-            let path_string = match resolved.to_str() {
-                Some(value) => value.to_owned(),
-                None => return Ok(None),
-            };
-            let input_source = create_reader(Some(path_string.as_str()), input_override)?;
-            let InputSource {
-                format,
-                reader,
-                display_name,
-                origin,
-            } = input_source;
-            let document = parse_document(format, reader, &display_name)?;
-            Ok(Some((document, origin)))
+            resolved.to_string()
         }
-        ContentOrigin::Stdin => Ok(None),
-    }
-}
+        Some(pager::LinkDestination::File(resolved)) => match resolved.to_str() {
+            Some(value) => value.to_owned(),
+            None => return Ok(None),
+        },
+        Some(pager::LinkDestination::Fragment(_)) | None => return Ok(None),
+    };
 
-fn is_absolute_url(value: &str) -> bool {
-    Url::parse(value).is_ok()
+    let InputSource {
+        format,
+        reader,
+        display_name,
+        origin,
+    } = create_reader(Some(location.as_str()), input_override)?;
+    let document = parse_document(format, reader, &display_name)?;
+    Ok(Some((document, origin)))
 }
 
-fn write_output(document: &Document, output_path: &Path) -> Result<(), String> {
+fn write_output(
+    document: &Document,
+    output_path: &Path,
+    width: Option<usize>,
+) -> Result<(), String> {
     if output_path == Path::new("-") {
         return Err(
             "Use stdout by omitting --output; it already writes to stdout by default.".to_string(),
@@ -794,7 +888,7 @@ fn write_output(document: &Document, output_path: &Path) -> Result<(), String> {
                     output_path.display()
                 )
             })?;
-            let mut formatter = Formatter::new_ascii(file);
+            let mut formatter = Formatter::new(file, ascii_style(width));
             formatter.write_document(document).map_err(|err| {
                 format!(
                     "Unable to write document to {}: {err}",
@@ -858,6 +952,19 @@ fn write_output(document: &Document, output_path: &Path) -> Result<(), String> {
             file.flush()
                 .map_err(|err| format!("Unable to flush {}: {err}", output_path.display()))
         }
+        OutputFormat::Json => {
+            let mut file = File::create(output_path).map_err(|err| {
+                format!(
+                    "Unable to open {} for writing: {err}",
+                    output_path.display()
+                )
+            })?;
+            json::write(&mut file, document).map_err(|err| {
+                format!("Unable to write JSON to {}: {err}", output_path.display())
+            })?;
+            file.flush()
+                .map_err(|err| format!("Unable to flush {}: {err}", output_path.display()))
+        }
     }
 }
 
@@ -869,6 +976,7 @@ fn determine_output_format(extension: Option<&str>) -> Option<OutputFormat> {
         "md" | "markdown" => Some(OutputFormat::Markdown),
         "html" | "htm" => Some(OutputFormat::Html),
         "gmi" | "gemini" => Some(OutputFormat::Gemini),
+        "json" => Some(OutputFormat::Json),
         _ => None,
     }
 }
@@ -876,3 +984,123 @@ fn determine_output_format(extension: Option<&str>) -> Option<OutputFormat> {
 fn write_html_document<W: Write>(mut writer: W, document: &Document) -> io::Result<()> {
     html::write_document(&mut writer, document)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tdoc::{ParagraphType, Span};
+
+    #[test]
+    fn test_theme_flag_parses() {
+        let cli = Cli::try_parse_from(["tdoc", "--theme", "dark", "notes.md"]).unwrap();
+        assert_eq!(cli.theme, Theme::Dark);
+        let cli = Cli::try_parse_from(["tdoc", "notes.md"]).unwrap();
+        assert_eq!(cli.theme, Theme::Mono);
+        assert!(Cli::try_parse_from(["tdoc", "--theme", "sepia"]).is_err());
+    }
+
+    #[test]
+    fn test_mono_theme_matches_plain_ansi_output() {
+        let document = Document::new().with_paragraphs(vec![
+            Paragraph::new_header1().with_content(vec![Span::new_text("Title")]),
+            Paragraph::new_text().with_content(vec![
+                Span::new_text("Run "),
+                Span::new_styled(tdoc::InlineStyle::Code)
+                    .with_children(vec![Span::new_text("make")]),
+                Span::new_text(" or see "),
+                Span::new_styled(tdoc::InlineStyle::Link)
+                    .with_link_target("https://example.com")
+                    .with_children(vec![Span::new_text("docs")]),
+            ]),
+        ]);
+
+        let render = |style: FormattingStyle| {
+            let mut output = Vec::new();
+            Formatter::new(&mut output, style)
+                .write_document(&document)
+                .unwrap();
+            output
+        };
+        assert_eq!(render(Theme::Mono.style()), render(FormattingStyle::ansi()));
+        assert_ne!(render(Theme::Dark.style()), render(FormattingStyle::ansi()));
+    }
+
+    #[test]
+    fn test_multiple_inputs_are_concatenated() {
+        let dir = std::env::temp_dir().join(format!("tdoc-inputs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let first = dir.join("a.md");
+        let second = dir.join("b.gmi");
+        std::fs::write(&first, "# First\n\nOne.\n").unwrap();
+        std::fs::write(&second, "## Second\n").unwrap();
+
+        let inputs = [
+            first.to_string_lossy().into_owned(),
+            second.to_string_lossy().into_owned(),
+        ];
+        let (document, origin) = load_inputs(&inputs, None).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let types: Vec<_> = document
+            .paragraphs
+            .iter()
+            .map(|paragraph| paragraph.paragraph_type())
+            .collect();
+        assert_eq!(
+            types,
+            [
+                ParagraphType::Header1,
+                ParagraphType::Text,
+                ParagraphType::HorizontalRule,
+                ParagraphType::Header2,
+            ]
+        );
+        assert!(matches!(origin, ContentOrigin::File(path) if path == first));
+    }
+
+    #[test]
+    fn test_toc_lists_headings_indented_by_level() {
+        let heading =
+            |paragraph: Paragraph, text: &str| paragraph.with_content(vec![Span::new_text(text)]);
+        let document = Document::new().with_paragraphs(vec![
+            heading(Paragraph::new_header1(), "Guide"),
+            heading(Paragraph::new_text(), "Intro text."),
+            heading(Paragraph::new_header2(), "Setup"),
+            heading(Paragraph::new_header3(), "On Linux"),
+        ]);
+
+        assert_eq!(
+            format_toc(&document, false),
+            "Guide\n  Setup\n    On Linux\n"
+        );
+        assert_eq!(
+            format_toc(&document, true),
+            "Guide (#guide)\n  Setup (#setup)\n    On Linux (#on-linux)\n"
+        );
+        assert!(
+            Cli::try_parse_from(["tdoc", "--toc", "notes.md"])
+                .unwrap()
+                .toc
+        );
+    }
+
+    #[test]
+    fn test_find_heading_counts_repeated_titles() {
+        let document = Document::new().with_paragraphs(vec![
+            Paragraph::new_header2().with_content(vec![Span::new_text("Notes")]),
+            Paragraph::new_header2()
+                .with_content(vec![Span::new_text("Notes")])
+                .with_anchor("later"),
+        ]);
+
+        assert_eq!(
+            find_heading(&document, "notes"),
+            Some(("Notes".to_string(), 0))
+        );
+        assert_eq!(
+            find_heading(&document, "later"),
+            Some(("Notes".to_string(), 1))
+        );
+        assert_eq!(find_heading(&document, "missing"), None);
+    }
+}
@@ -1,7 +1,12 @@
 //! Defines the [`Document`] root node of the document tree.
 
+use crate::formatter::Formatter;
+use crate::inline::trim_trailing_inline_whitespace;
 use crate::metadata::Metadata;
-use crate::Paragraph;
+use crate::{ChecklistItem, InlineStyle, ListDelimiter, Paragraph, ParagraphType, Span};
+use std::collections::HashSet;
+use std::fmt;
+use std::io::Cursor;
 
 #[derive(Debug, Clone, PartialEq)]
 /// A collection of top-level [`Paragraph`] nodes that make up a document.
@@ -39,6 +44,36 @@ impl Document {
         }
     }
 
+    /// Parses a Markdown string, like [`markdown::parse`](crate::markdown::parse).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, ParagraphType};
+    ///
+    /// let doc = Document::from_markdown("# Hi").unwrap();
+    /// assert_eq!(doc.paragraphs[0].paragraph_type(), ParagraphType::Header1);
+    /// assert_eq!(doc.paragraphs[0].content()[0].text, "Hi");
+    /// ```
+    pub fn from_markdown(input: &str) -> crate::Result<Self> {
+        crate::markdown::parse(Cursor::new(input))
+    }
+
+    /// Parses an HTML string, like [`html::parse`](crate::html::parse).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, ParagraphType};
+    ///
+    /// let doc = Document::from_html("<h1>Hi</h1><p>there</p>").unwrap();
+    /// assert_eq!(doc.paragraphs[0].paragraph_type(), ParagraphType::Header1);
+    /// assert_eq!(doc.paragraphs.len(), 2);
+    /// ```
+    pub fn from_html(input: &str) -> crate::Result<Self> {
+        crate::html::parse(Cursor::new(input))
+    }
+
     /// Replaces the document's paragraphs, returning the updated document.
     pub fn with_paragraphs(mut self, paragraphs: Vec<Paragraph>) -> Self {
         self.paragraphs = paragraphs;
@@ -56,10 +91,456 @@ impl Document {
         self.paragraphs.push(paragraph);
     }
 
+    /// Moves all paragraphs of `other` to the end of this document.
+    ///
+    /// The metadata of `self` is kept; `other`'s metadata is only adopted
+    /// when this document has none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, Paragraph};
+    ///
+    /// let mut doc = Document::new().with_paragraphs(vec![Paragraph::new_text()]);
+    /// doc.append(Document::new().with_paragraphs(vec![Paragraph::new_quote()]));
+    /// assert_eq!(doc.paragraphs.len(), 2);
+    /// ```
+    pub fn append(&mut self, other: Document) {
+        if self.metadata.is_none() {
+            self.metadata = other.metadata;
+        }
+        self.paragraphs.extend(other.paragraphs);
+    }
+
     /// Returns `true` when the document contains no paragraphs.
     pub fn is_empty(&self) -> bool {
         self.paragraphs.is_empty()
     }
+
+    /// Keeps only the top-level paragraphs for which `predicate` returns
+    /// `true`, preserving their order. Nested paragraphs are kept or dropped
+    /// along with their parent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, ParagraphType};
+    ///
+    /// let mut doc = Document::from_markdown("# Title\n\nIntro\n\n## Usage").unwrap();
+    /// doc.retain(|paragraph| paragraph.paragraph_type() != ParagraphType::Text);
+    /// assert_eq!(doc.paragraphs.len(), 2);
+    /// ```
+    pub fn retain<F>(&mut self, predicate: F)
+    where
+        F: FnMut(&Paragraph) -> bool,
+    {
+        self.paragraphs.retain(predicate);
+    }
+
+    /// Returns the document cut down to its first `n` top-level paragraphs.
+    /// The metadata is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::Document;
+    ///
+    /// let doc = Document::from_markdown("One\n\nTwo\n\nThree").unwrap().take(2);
+    /// assert_eq!(doc.paragraphs.len(), 2);
+    /// ```
+    pub fn take(mut self, n: usize) -> Self {
+        self.paragraphs.truncate(n);
+        self
+    }
+
+    /// Returns a copy of the document with insignificant whitespace and span
+    /// boundaries removed, for comparing documents by content.
+    ///
+    /// Empty spans are dropped, adjacent plain-text spans are merged and
+    /// trailing whitespace at the end of each paragraph's inline content is
+    /// trimmed. Code blocks and raw paragraphs are left untouched.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, InlineStyle, Paragraph, Span};
+    ///
+    /// let split = Document::new().with_paragraphs(vec![Paragraph::new_text().with_content(vec![
+    ///     Span::new_text("Hello, "),
+    ///     Span::new_text("world "),
+    ///     Span::new_styled(InlineStyle::Bold),
+    /// ])]);
+    /// let whole = Document::new().with_paragraphs(vec![
+    ///     Paragraph::new_text().with_content(vec![Span::new_text("Hello, world")]),
+    /// ]);
+    ///
+    /// assert_ne!(split, whole);
+    /// assert_eq!(split.normalized(), whole.normalized());
+    /// ```
+    pub fn normalized(&self) -> Document {
+        let mut document = self.clone();
+        for_each_inline_content(&mut document.paragraphs, &mut normalize_content);
+        document
+    }
+
+    /// Replaces every [`InlineStyle::Link`] span with its description, or
+    /// with the target URL for links without one, leaving plain text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, InlineStyle};
+    ///
+    /// let mut doc = Document::from_markdown("See [the docs](https://example.com).").unwrap();
+    /// doc.strip_links();
+    ///
+    /// let content = doc.paragraphs[0].content();
+    /// assert!(content.iter().all(|span| span.style == InlineStyle::None));
+    /// assert_eq!(content[1].text, "the docs");
+    /// ```
+    pub fn strip_links(&mut self) {
+        for_each_inline_content(&mut self.paragraphs, &mut strip_span_links);
+    }
+
+    /// Lists the document's top-level headings in order.
+    ///
+    /// A heading's slug is its [`anchor`](Paragraph::anchor) when it has one,
+    /// so `#anchor` links resolve to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{Document, Paragraph, Span};
+    ///
+    /// let doc = Document::new().with_paragraphs(vec![
+    ///     Paragraph::new_header1().with_content(vec![Span::new_text("Getting Started")]),
+    ///     Paragraph::new_header2().with_content(vec![Span::new_text("Install")]),
+    /// ]);
+    ///
+    /// let toc = doc.table_of_contents();
+    /// assert_eq!(toc[0].level, 1);
+    /// assert_eq!(toc[0].slug, "getting-started");
+    /// assert_eq!(toc[1].title, "Install");
+    /// ```
+    pub fn table_of_contents(&self) -> Vec<TocEntry> {
+        // Generated slugs must not shadow an explicit anchor.
+        let mut used_slugs: HashSet<String> = self
+            .paragraphs
+            .iter()
+            .filter_map(|paragraph| paragraph.anchor().map(str::to_string))
+            .collect();
+        self.paragraphs
+            .iter()
+            .filter_map(|paragraph| {
+                let level = match paragraph.paragraph_type() {
+                    ParagraphType::Header1 => 1,
+                    ParagraphType::Header2 => 2,
+                    ParagraphType::Header3 => 3,
+                    _ => return None,
+                };
+
+                let mut text = String::new();
+                for span in paragraph.content() {
+                    collect_heading_text(span, &mut text);
+                }
+                let title = text.split_whitespace().collect::<Vec<_>>().join(" ");
+
+                if let Some(anchor) = paragraph.anchor() {
+                    let slug = anchor.to_string();
+                    return Some(TocEntry { level, title, slug });
+                }
+
+                let base = slugify(&title);
+                let mut slug = base.clone();
+                let mut suffix = 1;
+                while !used_slugs.insert(slug.clone()) {
+                    slug = format!("{base}-{suffix}");
+                    suffix += 1;
+                }
+
+                Some(TocEntry { level, title, slug })
+            })
+            .collect()
+    }
+
+    /// Returns an indented outline of the document tree for debugging.
+    ///
+    /// Every paragraph and span gets a line of its own, indented by two spaces
+    /// per level: paragraphs show their [`ParagraphType`], spans their
+    /// [`InlineStyle`] (`Text` for unstyled spans), quoted text and link
+    /// target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::Document;
+    ///
+    /// let doc = Document::from_markdown("# Intro\n\nSee [docs](https://example.com).").unwrap();
+    /// assert_eq!(
+    ///     doc.tree_string(),
+    ///     concat!(
+    ///         "Header1\n",
+    ///         "  Text \"Intro\"\n",
+    ///         "Text\n",
+    ///         "  Text \"See \"\n",
+    ///         "  Link -> \"https://example.com\"\n",
+    ///         "    Text \"docs\"\n",
+    ///         "  Text \".\"\n",
+    ///     )
+    /// );
+    /// ```
+    pub fn tree_string(&self) -> String {
+        let mut out = String::new();
+        write_tree_paragraphs(&mut out, &self.paragraphs, 0);
+        out
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A heading listed by [`Document::table_of_contents`].
+pub struct TocEntry {
+    /// Heading level, from 1 for [`ParagraphType::Header1`] to 3.
+    pub level: u8,
+    /// The heading's text without any inline styling.
+    pub title: String,
+    /// The heading's anchor, or a slug derived from the title that is unique
+    /// within the document.
+    pub slug: String,
+}
+
+/// Calls `f` with every run of inline content in `paragraphs`: paragraph
+/// and heading text, checklist items, table captions and cells, definition terms and
+/// details summaries. Code blocks are not inline content and are skipped.
+fn for_each_inline_content(paragraphs: &mut [Paragraph], f: &mut impl FnMut(&mut Vec<Span>)) {
+    for paragraph in paragraphs {
+        match paragraph {
+            Paragraph::Text { content, .. }
+            | Paragraph::Header1 { content, .. }
+            | Paragraph::Header2 { content, .. }
+            | Paragraph::Header3 { content, .. } => f(content),
+            Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
+                for entry in entries {
+                    for_each_inline_content(entry, f);
+                }
+            }
+            Paragraph::Checklist { items } => for_each_checklist_content(items, f),
+            Paragraph::Quote { children } | Paragraph::Admonition { children, .. } => {
+                for_each_inline_content(children, f)
+            }
+            Paragraph::Table { rows, caption, .. } => {
+                f(caption);
+                for cell in rows.iter_mut().flat_map(|row| &mut row.cells) {
+                    f(&mut cell.content);
+                }
+            }
+            Paragraph::DefinitionList { items } => {
+                for item in items {
+                    f(&mut item.term);
+                    for_each_inline_content(&mut item.definition, f);
+                }
+            }
+            Paragraph::Details { summary, children } => {
+                f(summary);
+                for_each_inline_content(children, f);
+            }
+            Paragraph::CodeBlock { .. } | Paragraph::HorizontalRule | Paragraph::Raw { .. } => {}
+        }
+    }
+}
+
+fn for_each_checklist_content(items: &mut [ChecklistItem], f: &mut impl FnMut(&mut Vec<Span>)) {
+    for item in items {
+        f(&mut item.content);
+        for_each_checklist_content(&mut item.children, f);
+    }
+}
+
+/// Normalizes the inline content of a single paragraph (or cell, term, …).
+fn normalize_content(spans: &mut Vec<Span>) {
+    normalize_spans(spans);
+    trim_trailing_inline_whitespace(spans);
+}
+
+fn normalize_spans(spans: &mut Vec<Span>) {
+    let mut result: Vec<Span> = Vec::with_capacity(spans.len());
+    for mut span in spans.drain(..) {
+        if span.style != InlineStyle::Raw {
+            normalize_spans(&mut span.children);
+        }
+        // Links without content still show their target.
+        if span.is_content_empty() && span.style != InlineStyle::Link {
+            continue;
+        }
+        if let Some(prev) = result.last_mut() {
+            if is_plain_text(prev) && is_plain_text(&span) {
+                prev.text.push_str(&span.text);
+                continue;
+            }
+        }
+        result.push(span);
+    }
+    *spans = result;
+}
+
+fn strip_span_links(spans: &mut Vec<Span>) {
+    let mut result = Vec::with_capacity(spans.len());
+    for mut span in spans.drain(..) {
+        if span.style != InlineStyle::Raw {
+            strip_span_links(&mut span.children);
+        }
+        if span.style != InlineStyle::Link {
+            result.push(span);
+        } else if span.is_content_empty() {
+            result.push(Span::new_text(span.link_target.unwrap_or_default()));
+        } else {
+            if !span.text.is_empty() {
+                result.push(Span::new_text(span.text));
+            }
+            result.append(&mut span.children);
+        }
+    }
+    *spans = result;
+}
+
+fn is_plain_text(span: &Span) -> bool {
+    span.style == InlineStyle::None && span.children.is_empty() && span.link_target.is_none()
+}
+
+fn collect_heading_text(span: &Span, text: &mut String) {
+    if span.style == InlineStyle::Raw {
+        return;
+    }
+    text.push_str(&span.text);
+    for child in &span.children {
+        collect_heading_text(child, text);
+    }
+}
+
+/// Lowercases `title`, turns spaces and hyphens into single hyphens and drops
+/// all other punctuation, similar to how GitHub derives heading anchors.
+fn slugify(title: &str) -> String {
+    let mut slug = String::new();
+    for ch in title.chars() {
+        if ch.is_alphanumeric() || ch == '_' {
+            slug.extend(ch.to_lowercase());
+        } else if (ch.is_whitespace() || ch == '-') && !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+fn write_tree_line(out: &mut String, depth: usize, line: &str) {
+    for _ in 0..depth {
+        out.push_str("  ");
+    }
+    out.push_str(line);
+    out.push('\n');
+}
+
+fn write_tree_paragraphs(out: &mut String, paragraphs: &[Paragraph], depth: usize) {
+    for paragraph in paragraphs {
+        write_tree_paragraph(out, paragraph, depth);
+    }
+}
+
+fn write_tree_paragraph(out: &mut String, paragraph: &Paragraph, depth: usize) {
+    let mut line = format!("{:?}", paragraph.paragraph_type());
+    match paragraph {
+        Paragraph::Text { lead: true, .. } => line.push_str(" lead"),
+        Paragraph::Header1 { anchor, .. }
+        | Paragraph::Header2 { anchor, .. }
+        | Paragraph::Header3 { anchor, .. } => {
+            if let Some(anchor) = anchor {
+                line.push_str(&format!(" #{anchor}"));
+            }
+        }
+        Paragraph::CodeBlock {
+            language: Some(language),
+            ..
+        } => line.push_str(&format!(" {language}")),
+        Paragraph::OrderedList {
+            delimiter: ListDelimiter::Parenthesis,
+            ..
+        } => line.push_str(" 1)"),
+        Paragraph::Admonition { kind, .. } => line.push_str(&format!(" {}", kind.marker())),
+        Paragraph::Raw { format, .. } => line.push_str(&format!(" {format}")),
+        _ => {}
+    }
+    write_tree_line(out, depth, &line);
+
+    match paragraph {
+        Paragraph::Text { content, .. }
+        | Paragraph::Header1 { content, .. }
+        | Paragraph::Header2 { content, .. }
+        | Paragraph::Header3 { content, .. }
+        | Paragraph::CodeBlock { content, .. } => write_tree_spans(out, content, depth + 1),
+        Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
+            for entry in entries {
+                write_tree_line(out, depth + 1, "Item");
+                write_tree_paragraphs(out, entry, depth + 2);
+            }
+        }
+        Paragraph::Checklist { items } => write_tree_checklist(out, items, depth + 1),
+        Paragraph::Quote { children } | Paragraph::Admonition { children, .. } => {
+            write_tree_paragraphs(out, children, depth + 1)
+        }
+        Paragraph::Table { rows, caption, .. } => {
+            if !caption.is_empty() {
+                write_tree_line(out, depth + 1, "Caption");
+                write_tree_spans(out, caption, depth + 2);
+            }
+            for row in rows {
+                write_tree_line(out, depth + 1, "Row");
+                for cell in &row.cells {
+                    let kind = if cell.is_header { "HeaderCell" } else { "Cell" };
+                    write_tree_line(out, depth + 2, kind);
+                    write_tree_spans(out, &cell.content, depth + 3);
+                }
+            }
+        }
+        Paragraph::DefinitionList { items } => {
+            for item in items {
+                write_tree_line(out, depth + 1, "Term");
+                write_tree_spans(out, &item.term, depth + 2);
+                write_tree_line(out, depth + 1, "Definition");
+                write_tree_paragraphs(out, &item.definition, depth + 2);
+            }
+        }
+        Paragraph::Details { summary, children } => {
+            write_tree_line(out, depth + 1, "Summary");
+            write_tree_spans(out, summary, depth + 2);
+            write_tree_paragraphs(out, children, depth + 1);
+        }
+        Paragraph::Raw { content, .. } => write_tree_line(out, depth + 1, &format!("{content:?}")),
+        Paragraph::HorizontalRule => {}
+    }
+}
+
+fn write_tree_checklist(out: &mut String, items: &[ChecklistItem], depth: usize) {
+    for item in items {
+        let line = if item.checked { "Item [x]" } else { "Item [ ]" };
+        write_tree_line(out, depth, line);
+        write_tree_spans(out, &item.content, depth + 1);
+        write_tree_checklist(out, &item.children, depth + 1);
+    }
+}
+
+fn write_tree_spans(out: &mut String, spans: &[Span], depth: usize) {
+    for span in spans {
+        let mut line = match span.style {
+            InlineStyle::None => "Text".to_string(),
+            style => format!("{style:?}"),
+        };
+        if !span.text.is_empty() {
+            line.push_str(&format!(" {:?}", span.text));
+        }
+        if let Some(target) = &span.link_target {
+            line.push_str(&format!(" -> {target:?}"));
+        }
+        write_tree_line(out, depth, &line);
+        write_tree_spans(out, &span.children, depth + 1);
+    }
 }
 
 impl Default for Document {
@@ -68,9 +549,33 @@ impl Default for Document {
     }
 }
 
+/// Renders the document as plain ASCII text, like [`Formatter::new_ascii`].
+///
+/// ```
+/// use tdoc::formatter::Formatter;
+/// use tdoc::Document;
+///
+/// let doc = Document::from_markdown("# Title\n\nSome *text*.").unwrap();
+///
+/// let mut output = Vec::new();
+/// Formatter::new_ascii(&mut output).write_document(&doc).unwrap();
+/// assert_eq!(format!("{doc}"), String::from_utf8(output).unwrap());
+/// ```
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut output = Vec::new();
+        Formatter::new_ascii(&mut output)
+            .write_document(self)
+            .map_err(|_| fmt::Error)?;
+        f.write_str(&String::from_utf8_lossy(&output))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metadata::Value;
+    use crate::test_helpers::*;
     use crate::{Paragraph, Span};
 
     #[test]
@@ -94,4 +599,187 @@ mod tests {
         assert!(!doc.is_empty());
         assert_eq!(doc.paragraphs.len(), 1);
     }
+
+    #[test]
+    fn test_append_keeps_first_metadata() {
+        let mut first = Metadata::default();
+        first.insert("title".to_string(), Value::String("First".to_string()));
+        let mut second = Metadata::default();
+        second.insert("title".to_string(), Value::String("Second".to_string()));
+
+        let mut doc = Document::new().with_paragraphs(vec![Paragraph::new_text()]);
+        doc.append(Document::new().with_metadata(first.clone()));
+        doc.append(
+            Document::new()
+                .with_metadata(second)
+                .with_paragraphs(vec![Paragraph::new_text(), Paragraph::new_text()]),
+        );
+
+        assert_eq!(doc.paragraphs.len(), 3);
+        assert_eq!(doc.metadata, Some(first));
+    }
+
+    #[test]
+    fn test_retain_headers() {
+        let mut doc = doc(vec![
+            h1_("Title"),
+            p__("Intro"),
+            h2_("Usage"),
+            quote_(vec![h2_("Quoted")]),
+            p__("Body"),
+            h3_("Details"),
+        ]);
+        doc.retain(|paragraph| {
+            matches!(
+                paragraph.paragraph_type(),
+                ParagraphType::Header1 | ParagraphType::Header2 | ParagraphType::Header3
+            )
+        });
+        assert_eq!(doc.paragraphs, [h1_("Title"), h2_("Usage"), h3_("Details")]);
+
+        let doc = doc.take(2);
+        assert_eq!(doc.paragraphs, [h1_("Title"), h2_("Usage")]);
+        assert_eq!(doc.take(5).paragraphs.len(), 2);
+    }
+
+    #[test]
+    fn test_table_of_contents_slugs() {
+        let heading =
+            |paragraph: Paragraph, text: &str| paragraph.with_content(vec![Span::new_text(text)]);
+        let doc = Document::new().with_paragraphs(vec![
+            heading(Paragraph::new_header1(), "Hello,  World!"),
+            heading(Paragraph::new_text(), "Body"),
+            heading(Paragraph::new_header3(), "Über uns -- Team"),
+            heading(Paragraph::new_header2(), "Hello World"),
+        ]);
+
+        let toc = doc.table_of_contents();
+        let summary: Vec<_> = toc
+            .iter()
+            .map(|entry| (entry.level, entry.title.as_str(), entry.slug.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, "Hello, World!", "hello-world"),
+                (3, "Über uns -- Team", "über-uns-team"),
+                (2, "Hello World", "hello-world-1"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_table_of_contents_prefers_heading_anchors() {
+        let doc = Document::new().with_paragraphs(vec![
+            Paragraph::new_header1().with_content(vec![Span::new_text("Intro")]),
+            Paragraph::new_header2()
+                .with_content(vec![Span::new_text("Details")])
+                .with_anchor("intro"),
+        ]);
+
+        let slugs: Vec<_> = doc
+            .table_of_contents()
+            .into_iter()
+            .map(|entry| entry.slug)
+            .collect();
+        assert_eq!(slugs, ["intro-1", "intro"]);
+    }
+
+    #[test]
+    fn test_normalized_ignores_insignificant_whitespace() {
+        let messy = doc(vec![
+            p_(vec![
+                span("Some "),
+                span("text "),
+                b_(vec![span(""), span("bold")]),
+                span(""),
+                span(" \n"),
+            ]),
+            ul_(vec![li_(vec![p_(vec![
+                span("item"),
+                i_(vec![]),
+                span("  "),
+            ])])]),
+            code_block__("keep  \n"),
+        ]);
+        let clean = doc(vec![
+            p_(vec![span("Some text "), b__("bold")]),
+            ul_(vec![li_(vec![p__("item")])]),
+            code_block__("keep  \n"),
+        ]);
+
+        assert_ne!(messy, clean);
+        assert_eq!(messy.normalized(), clean.normalized());
+        assert_eq!(clean.normalized(), clean);
+    }
+
+    #[test]
+    fn test_strip_links() {
+        let mut document = doc(vec![
+            p_(vec![
+                span("Read "),
+                link_text__("https://example.com/docs", "Docs"),
+                span(" or "),
+                link__("https://example.com"),
+            ]),
+            quote_(vec![p_(vec![b_(vec![link_(
+                "https://example.com/x",
+                vec![i__("styled")],
+            )])])]),
+        ]);
+        document.strip_links();
+
+        assert_eq!(
+            document,
+            doc(vec![
+                p_(vec![
+                    span("Read "),
+                    span("Docs"),
+                    span(" or "),
+                    span("https://example.com"),
+                ]),
+                quote_(vec![p_(vec![b_(vec![i__("styled")])])]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_tree_string() {
+        let document = doc(vec![
+            h1_("Intro"),
+            p_(vec![
+                span("Read "),
+                b_(vec![link_text__("https://example.com", "this")]),
+            ]),
+            ul_(vec![li_(vec![p__("one")]), li_(vec![p__("two")])]),
+            checklist_(vec![task_(true, "done", vec![task__(false, "todo")])]),
+        ]);
+
+        assert_eq!(
+            document.tree_string(),
+            [
+                "Header1",
+                "  Text \"Intro\"",
+                "Text",
+                "  Text \"Read \"",
+                "  Bold",
+                "    Link -> \"https://example.com\"",
+                "      Text \"this\"",
+                "UnorderedList",
+                "  Item",
+                "    Text",
+                "      Text \"one\"",
+                "  Item",
+                "    Text",
+                "      Text \"two\"",
+                "Checklist",
+                "  Item [x]",
+                "    Text \"done\"",
+                "    Item [ ]",
+                "      Text \"todo\"",
+                "",
+            ]
+            .join("\n")
+        );
+    }
 }
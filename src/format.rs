@@ -0,0 +1,257 @@
+//! Detect the format of a document and parse it with the matching module.
+//!
+//! [`parse_auto`] is the entry point for input of unknown origin: it looks at
+//! the beginning of the stream, picks one of the [`Format`]s and hands the
+//! complete stream to that format's parser.
+
+use crate::{ftml, gemini, html, json, markdown, Document};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::io::{Cursor, Read};
+
+/// Number of bytes [`parse_auto`] inspects to guess the format of its input.
+pub const SNIFF_LENGTH: u64 = 8 * 1024;
+
+/// Parser whose element tables tell FTML tags from other markup.
+static FTML_PARSER: Lazy<ftml::Parser> = Lazy::new(ftml::Parser::new);
+
+static TAG_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"</?([a-zA-Z][a-zA-Z0-9]*)[\s/>]").expect("valid tag regex"));
+
+static COMMENT_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<!--.*?(?:-->|$)").expect("valid comment regex"));
+
+static PRE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<pre[\s>].*?(?:</pre>|$)").expect("valid pre regex"));
+
+/// Lines opening a Markdown heading, list, quote or code fence.
+static MARKDOWN_BLOCK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^(?:#{1,6}[ \t]|[-*+][ \t]|>[ \t]|```|~~~|\d+[.)][ \t])")
+        .expect("valid Markdown block regex")
+});
+
+/// The document formats that can be parsed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Format {
+    Ftml,
+    Html,
+    Markdown,
+    Gemini,
+    Json,
+}
+
+impl Format {
+    /// Returns the format conventionally stored under the file extension
+    /// `extension` (without the dot), if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::Format;
+    ///
+    /// assert_eq!(Format::from_extension("MD"), Some(Format::Markdown));
+    /// assert_eq!(Format::from_extension("txt"), None);
+    /// ```
+    pub fn from_extension(extension: &str) -> Option<Format> {
+        match extension.to_ascii_lowercase().as_str() {
+            "ftml" => Some(Format::Ftml),
+            "html" | "htm" => Some(Format::Html),
+            "md" | "markdown" => Some(Format::Markdown),
+            "gmi" | "gemini" => Some(Format::Gemini),
+            "json" => Some(Format::Json),
+            _ => None,
+        }
+    }
+
+    /// Guesses the format of `sample`, the beginning of a document.
+    ///
+    /// Comments are ignored. Markup is HTML when it carries a doctype,
+    /// `<html>` scaffolding or any tag the FTML parser does not know, and FTML
+    /// otherwise, unless Markdown blocks such as headings or lists follow it,
+    /// as in a README opening with a centered `<p align="center">` logo. Text
+    /// with Gemini link lines (`=> `) but no Markdown links is Gemini; any
+    /// other text is treated as Markdown. Empty input stays FTML, the
+    /// historical default.
+    /// JSON is never guessed, as it is an interchange format rather than one
+    /// written by hand.
+    pub fn sniff(sample: &str) -> Format {
+        let uncommented = COMMENT_REGEX.replace_all(sample, "");
+        let trimmed = uncommented.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.is_empty() {
+            return Format::Ftml;
+        }
+
+        let lower = trimmed.to_ascii_lowercase();
+        if lower.starts_with("<!doctype") || lower.contains("<html") || lower.contains("<body") {
+            return Format::Html;
+        }
+
+        let tags: Vec<&str> = TAG_REGEX
+            .captures_iter(&lower)
+            .filter_map(|capture| capture.get(1).map(|name| name.as_str()))
+            .collect();
+        // Markup either starts right away or makes up much of the text.
+        let tag_dense = tags.len() >= 3 && tags.len() * 40 >= lower.len();
+        let markdown_blocks = MARKDOWN_BLOCK_REGEX.is_match(&PRE_REGEX.replace_all(&lower, ""));
+        if !tags.is_empty() && (lower.starts_with('<') || tag_dense) && !markdown_blocks {
+            return if tags.iter().all(|tag| FTML_PARSER.knows_element(tag)) {
+                Format::Ftml
+            } else {
+                Format::Html
+            };
+        }
+
+        let has_gemini_links = trimmed.lines().any(|line| line.starts_with("=>"));
+        if has_gemini_links && !trimmed.contains("](") {
+            return Format::Gemini;
+        }
+        Format::Markdown
+    }
+
+    /// Parses `reader` as a document of this format.
+    pub fn parse<R: Read>(self, reader: R) -> crate::Result<Document> {
+        match self {
+            Format::Ftml => Ok(ftml::parse(reader)?),
+            Format::Html => html::parse(reader),
+            Format::Markdown => markdown::parse(reader),
+            Format::Gemini => gemini::parse(reader),
+            Format::Json => json::parse(reader),
+        }
+    }
+}
+
+/// Reads the first [`SNIFF_LENGTH`] bytes of `reader` and guesses its format
+/// with [`Format::sniff`]. Returns the format along with a reader yielding
+/// the complete input again.
+pub fn detect<R: Read>(mut reader: R) -> std::io::Result<(Format, impl Read)> {
+    let mut head = Vec::new();
+    reader.by_ref().take(SNIFF_LENGTH).read_to_end(&mut head)?;
+    let format = Format::sniff(&String::from_utf8_lossy(&head));
+    Ok((format, Cursor::new(head).chain(reader)))
+}
+
+/// Parses a document of any supported [`Format`].
+///
+/// A `hint`, such as the format belonging to a file extension (see
+/// [`Format::from_extension`]), is trusted as is. Without one, the format is
+/// guessed from the beginning of the input.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tdoc::{parse_auto, Format, ParagraphType};
+///
+/// let document = parse_auto(Cursor::new("# Title\n\n- item\n"), None).unwrap();
+/// assert_eq!(document.paragraphs[1].paragraph_type(), ParagraphType::UnorderedList);
+///
+/// let document = parse_auto(Cursor::new("# Title"), Some(Format::Gemini)).unwrap();
+/// assert_eq!(document.paragraphs[0].paragraph_type(), ParagraphType::Header1);
+/// ```
+pub fn parse_auto<R: Read>(reader: R, hint: Option<Format>) -> crate::Result<Document> {
+    match hint {
+        Some(format) => format.parse(reader),
+        None => {
+            let (format, reader) = detect(reader)?;
+            format.parse(reader)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_sniff() {
+        let cases = [
+            ("", Format::Ftml),
+            (
+                "<p>Hello <b>world</b></p>\n<ul><li><p>x</p></li></ul>",
+                Format::Ftml,
+            ),
+            ("<!DOCTYPE html>\n<title>x</title><p>y</p>", Format::Html),
+            ("<html><body><p>y</p></body></html>", Format::Html),
+            ("<div class=\"post\"><p>Hi</p></div>", Format::Html),
+            ("Intro\n<p>a</p><p>b</p><p>c</p>", Format::Ftml),
+            (
+                "<ul><li><input type=\"checkbox\" checked /><p>done</p></li></ul>",
+                Format::Ftml,
+            ),
+            ("<p>She said <q>hi</q>.</p>", Format::Ftml),
+            (
+                "# Title\n\n=> gemini://example.org Example\n",
+                Format::Gemini,
+            ),
+            (
+                "# Title\n\nSee [docs](https://example.com).\n\n=> not a link\n",
+                Format::Markdown,
+            ),
+            ("# Title\n\n- one\n- **two**\n", Format::Markdown),
+            (
+                "Just some text with a <br> in it and more words.\n",
+                Format::Markdown,
+            ),
+            ("<!-- generated -->\n<p>Hello</p>", Format::Ftml),
+            ("<!-- generated -->\nSome *text*.\n", Format::Markdown),
+            ("<pre>\n# not a heading\n</pre>", Format::Ftml),
+        ];
+        for (sample, expected) in cases {
+            assert_eq!(Format::sniff(sample), expected, "{sample:?}");
+        }
+    }
+
+    #[test]
+    fn test_sniff_markdown_with_inline_html() {
+        let readme = "<!-- badges -->\n\
+                      <p align=\"center\">\n  <b>tdoc</b>\n</p>\n\n\
+                      # tdoc\n\nA **document** library.\n\n- one\n- two\n";
+        assert_eq!(Format::sniff(readme), Format::Markdown);
+
+        let readme = "<p align=\"center\">\n  <img src=\"logo.png\">\n</p>\n\n## Usage\n";
+        assert_eq!(Format::sniff(readme), Format::Markdown);
+    }
+
+    #[test]
+    fn test_parse_auto_detects_sample_documents() {
+        let samples = [
+            ("tests/data/ftml/testdocument.ftml", Format::Ftml),
+            ("tests/data/html/openbsd-innovations.html", Format::Html),
+            ("tests/data/markdown/progit1-en.md", Format::Markdown),
+            ("tests/data/gemini/comprehensive_test.gmi", Format::Gemini),
+        ];
+        for (path, format) in samples {
+            let input = fs::read(path).unwrap();
+            let (detected, _) = detect(Cursor::new(&input)).unwrap();
+            assert_eq!(detected, format, "{path}");
+
+            let expected = format.parse(Cursor::new(&input)).unwrap();
+            let parsed = parse_auto(Cursor::new(&input), None).unwrap();
+            assert_eq!(parsed, expected, "{path}");
+        }
+    }
+
+    #[test]
+    fn test_parse_auto_detects_written_ftml() {
+        let document = crate::test_helpers::every_type_document();
+        let mut output = Vec::new();
+        ftml::write(&mut output, &document).unwrap();
+
+        assert_eq!(
+            Format::sniff(&String::from_utf8_lossy(&output)),
+            Format::Ftml
+        );
+        assert_eq!(
+            parse_auto(Cursor::new(&output), None).unwrap(),
+            ftml::parse(Cursor::new(&output)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_auto_follows_hint() {
+        let input = "# Title\n\n=> gemini://example.org Example\n";
+        let document = parse_auto(Cursor::new(input), Some(Format::Markdown)).unwrap();
+        assert_eq!(document, markdown::parse(Cursor::new(input)).unwrap());
+    }
+}
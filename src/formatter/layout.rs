@@ -0,0 +1,384 @@
+//! Width measurement and word wrapping for text that may embed the ANSI SGR
+//! and OSC 8 hyperlink escape sequences produced by the [`Formatter`].
+//!
+//! [`Formatter`]: super::Formatter
+
+use super::{
+    FormattingStyle, Osc8Link, StyleTags, ANSI_ESCAPE_REGEX, DEFAULT_FOREGROUND,
+    FOREGROUND_COLOR_REGEX, OSC8_END, OSC8_SEQUENCE_REGEX,
+};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Measures the display width of `text`, skipping SGR (`ESC [ ... m`) and
+/// OSC 8 hyperlink sequences. The string is scanned once without allocating,
+/// as this runs for every token measured while wrapping.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::formatter::layout::display_width;
+///
+/// assert_eq!(display_width("\x1b[1mbold\x1b[22m"), 4);
+/// assert_eq!(display_width("漢字"), 4);
+/// ```
+pub fn display_width(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    let mut width = 0;
+    let mut segment_start = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == 0x1b {
+            if let Some(len) = escape_sequence_len(&bytes[i..]) {
+                width += UnicodeWidthStr::width(&text[segment_start..i]);
+                i += len;
+                segment_start = i;
+                continue;
+            }
+        }
+        i += 1;
+    }
+    width + UnicodeWidthStr::width(&text[segment_start..])
+}
+
+/// Returns the byte length of the SGR or OSC 8 sequence starting `bytes`, if
+/// there is one.
+fn escape_sequence_len(bytes: &[u8]) -> Option<usize> {
+    match bytes.get(1)? {
+        b'[' => {
+            let params = bytes[2..]
+                .iter()
+                .take_while(|b| b.is_ascii_digit() || **b == b';')
+                .count();
+            (bytes.get(2 + params) == Some(&b'm')).then_some(3 + params)
+        }
+        b']' if bytes[2..].starts_with(b"8;") => {
+            let body = bytes[4..].iter().position(|b| *b == 0x1b)?;
+            (bytes.get(5 + body) == Some(&b'\\')).then_some(6 + body)
+        }
+        _ => None,
+    }
+}
+
+/// Lays out a line rendered left to right for right-to-left reading: its
+/// `prefix` (margin plus quote and list markers) is mirrored onto the right
+/// and the rest is right-aligned so the line ends at column `width`. Lines
+/// holding nothing but whitespace come out empty.
+pub(super) fn mirror_line(line: &str, prefix: &str, width: usize) -> String {
+    let content = &line[prefix.len()..];
+    let markers = mirror_prefix(prefix);
+    if content.is_empty() && markers.trim().is_empty() {
+        return String::new();
+    }
+    let used = display_width(content) + display_width(&markers);
+    format!(
+        "{}{}{}",
+        " ".repeat(width.saturating_sub(used)),
+        content,
+        markers
+    )
+}
+
+/// Reverses `prefix` character by character, swapping brackets so `[ ]`
+/// stays a checkbox, while numbers keep their digit order.
+fn mirror_prefix(prefix: &str) -> String {
+    let mut mirrored = String::with_capacity(prefix.len());
+    let mut digits = String::new();
+    for ch in prefix.chars().rev() {
+        if ch.is_ascii_digit() {
+            digits.insert(0, ch);
+            continue;
+        }
+        mirrored.push_str(&digits);
+        digits.clear();
+        mirrored.push(match ch {
+            '[' => ']',
+            ']' => '[',
+            '(' => ')',
+            ')' => '(',
+            other => other,
+        });
+    }
+    mirrored.push_str(&digits);
+    mirrored
+}
+
+/// Word-wraps already-formatted text (which may embed ANSI/OSC8 escape
+/// sequences) to the given visible `width`, returning one string per
+/// physical line. Active styles and hyperlinks are closed at the end of a
+/// line and reopened at the start of the next so each line stands on its
+/// own — important because table cells are padded and bordered
+/// independently. Words wider than `width` are hard-broken.
+///
+/// `style` tells which escape sequences open a style, and whether hyperlinks
+/// are emitted at all.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::formatter::layout::wrap;
+/// use tdoc::formatter::FormattingStyle;
+///
+/// let text = "plain \x1b[1mbold words\x1b[22m end";
+/// let lines = wrap(text, 11, &FormattingStyle::ansi());
+/// assert_eq!(
+///     lines,
+///     vec!["plain \x1b[1mbold\x1b[22m", "\x1b[1mwords\x1b[22m end"]
+/// );
+/// ```
+pub fn wrap(text: &str, width: usize, style: &FormattingStyle) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines: Vec<String> = Vec::new();
+    let mut active_styles: Vec<StyleTags> = Vec::new();
+    let mut active_osc_links: Vec<Osc8Link> = Vec::new();
+
+    let mut current = String::new();
+    let mut current_width = 0usize;
+    let mut pending_whitespace = 0usize;
+
+    for (is_whitespace, token) in tokenize(text) {
+        if is_whitespace {
+            pending_whitespace += token.chars().count();
+            continue;
+        }
+
+        let mut word = token;
+        loop {
+            let word_width = display_width(&word);
+            let whitespace_width = if current_width == 0 {
+                0
+            } else {
+                pending_whitespace
+            };
+
+            if current_width + whitespace_width + word_width <= width {
+                if whitespace_width > 0 {
+                    current.push_str(&" ".repeat(whitespace_width));
+                    current_width += whitespace_width;
+                }
+                pending_whitespace = 0;
+                current.push_str(&word);
+                current_width += word_width;
+                update_active_styles(&word, style, &mut active_styles);
+                update_active_osc_links(&word, style, &mut active_osc_links);
+                break;
+            }
+
+            // The word does not fit. If the line already has content, end
+            // it and retry the word on a fresh line.
+            if current_width > 0 {
+                current.push_str(&close_active(style, &active_styles, &active_osc_links));
+                lines.push(std::mem::take(&mut current));
+                current = open_active(style, &active_styles, &active_osc_links);
+                current_width = 0;
+                pending_whitespace = 0;
+                continue;
+            }
+
+            // The line is empty yet the word is still too wide: hard-break
+            // it so the column never overflows.
+            let (head, tail) = split_at_width(&word, width);
+            if head.is_empty() {
+                // Safety valve: never loop forever on unsplittable input.
+                current.push_str(&word);
+                update_active_styles(&word, style, &mut active_styles);
+                update_active_osc_links(&word, style, &mut active_osc_links);
+                current_width = display_width(&current);
+                pending_whitespace = 0;
+                break;
+            }
+            update_active_styles(&head, style, &mut active_styles);
+            update_active_osc_links(&head, style, &mut active_osc_links);
+            let mut finished = head;
+            finished.push_str(&close_active(style, &active_styles, &active_osc_links));
+            lines.push(finished);
+            current = open_active(style, &active_styles, &active_osc_links);
+            current_width = 0;
+            pending_whitespace = 0;
+            word = tail;
+        }
+    }
+
+    if current_width > 0 || lines.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+/// Builds the escape sequence that closes every currently-active style and
+/// hyperlink, mirroring `Formatter::write_line_break` but as a string.
+fn close_active(
+    style: &FormattingStyle,
+    active_styles: &[StyleTags],
+    active_osc_links: &[Osc8Link],
+) -> String {
+    let mut out = String::new();
+    for tags in active_styles.iter().rev() {
+        out.push_str(&tags.end);
+    }
+    if style.enable_osc8_hyperlinks {
+        for _ in active_osc_links.iter().rev() {
+            out.push_str(OSC8_END);
+        }
+    }
+    out
+}
+
+/// Builds the escape sequence that re-opens every currently-active
+/// hyperlink and style at the start of a continuation line.
+fn open_active(
+    style: &FormattingStyle,
+    active_styles: &[StyleTags],
+    active_osc_links: &[Osc8Link],
+) -> String {
+    let mut out = String::new();
+    if style.enable_osc8_hyperlinks {
+        for link in active_osc_links {
+            out.push_str(&link.start_sequence());
+        }
+    }
+    for tags in active_styles {
+        out.push_str(&tags.begin);
+    }
+    out
+}
+
+/// Splits `text` so the head occupies at most `max` visible columns,
+/// returning `(head, tail)`. Embedded escape sequences are copied without
+/// counting toward the width and are never split apart.
+pub(super) fn split_at_width(text: &str, max: usize) -> (String, String) {
+    let chars: Vec<char> = text.chars().collect();
+    let mut head = String::new();
+    let mut visible = 0usize;
+    let mut i = 0usize;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        if ch == '\x1b' {
+            let start = i;
+            i += 1; // ESC
+            if i < chars.len() && chars[i] == '[' {
+                i += 1;
+                while i < chars.len() {
+                    let c = chars[i];
+                    i += 1;
+                    if c.is_ascii_alphabetic() {
+                        break;
+                    }
+                }
+            } else if i < chars.len() && chars[i] == ']' {
+                i += 1;
+                while i < chars.len() {
+                    let c = chars[i];
+                    if c == '\x07' {
+                        i += 1;
+                        break;
+                    }
+                    if c == '\x1b' {
+                        i += 1;
+                        if i < chars.len() && chars[i] == '\\' {
+                            i += 1;
+                        }
+                        break;
+                    }
+                    i += 1;
+                }
+            }
+            head.extend(&chars[start..i]);
+            continue;
+        }
+
+        let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
+        if visible + char_width > max && visible > 0 {
+            break;
+        }
+        head.push(ch);
+        visible += char_width;
+        i += 1;
+        if visible >= max {
+            break;
+        }
+    }
+
+    let tail: String = chars[i..].iter().collect();
+    (head, tail)
+}
+
+/// Splits `text` into alternating runs of whitespace and non-whitespace,
+/// each tagged with whether it is whitespace.
+pub(super) fn tokenize(text: &str) -> Vec<(bool, String)> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut current_kind: Option<bool> = None;
+
+    for ch in text.chars() {
+        let is_whitespace = ch.is_whitespace();
+        match current_kind {
+            Some(kind) if kind == is_whitespace => current.push(ch),
+            Some(kind) => {
+                tokens.push((kind, std::mem::take(&mut current)));
+                current.push(ch);
+                current_kind = Some(is_whitespace);
+            }
+            None => {
+                current.push(ch);
+                current_kind = Some(is_whitespace);
+            }
+        }
+    }
+
+    if let Some(kind) = current_kind {
+        tokens.push((kind, current));
+    }
+
+    tokens
+}
+
+/// Tracks which styles are open after `text` is written, so they can be
+/// closed and re-opened around line breaks. A foreground color replaces
+/// the active one, matching how terminals treat SGR color changes.
+pub(super) fn update_active_styles(
+    text: &str,
+    style: &FormattingStyle,
+    active_styles: &mut Vec<StyleTags>,
+) {
+    for capture in ANSI_ESCAPE_REGEX.find_iter(text) {
+        let sequence = capture.as_str();
+        if let Some(tags) = style
+            .text_styles
+            .values()
+            .find(|tags| tags.begin == sequence)
+        {
+            active_styles.push(tags.clone());
+        } else if FOREGROUND_COLOR_REGEX.is_match(sequence) {
+            active_styles.retain(|tags| tags.end != DEFAULT_FOREGROUND);
+            active_styles.push(StyleTags::new(sequence, DEFAULT_FOREGROUND));
+        } else if let Some(idx) = active_styles.iter().rposition(|tags| tags.end == sequence) {
+            active_styles.remove(idx);
+        }
+    }
+}
+
+pub(super) fn update_active_osc_links(
+    text: &str,
+    style: &FormattingStyle,
+    active_osc_links: &mut Vec<Osc8Link>,
+) {
+    if !style.enable_osc8_hyperlinks {
+        return;
+    }
+
+    for capture in OSC8_SEQUENCE_REGEX.captures_iter(text) {
+        let params = capture.get(1).map(|m| m.as_str()).unwrap_or("");
+        let target = capture.get(2).map(|m| m.as_str()).unwrap_or("");
+        if target.is_empty() {
+            let _ = active_osc_links.pop();
+        } else {
+            let id = params
+                .split(':')
+                .find_map(|param| param.strip_prefix("id="))
+                .map(|value| value.to_string());
+            active_osc_links.push(Osc8Link::new(id, target.to_string()));
+        }
+    }
+}
@@ -1,22 +1,43 @@
 //! Render documents to formatted plain text suitable for terminals or logs.
 
-use crate::{ChecklistItem, Document, InlineStyle, Paragraph, ParagraphType, Span, TableRow};
+use crate::{
+    AdmonitionKind, Alignment, ChecklistItem, ChecklistState, Color, DefinitionItem, Document,
+    InlineStyle, Paragraph, ParagraphType, Span,
+};
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::io::Write;
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+use std::rc::Rc;
+use std::sync::Arc;
+
+pub mod layout;
+
+use layout::display_width;
 
 const DEFAULT_WRAP_WIDTH: usize = 72;
 const DEFAULT_QUOTE_PREFIX: &str = "| ";
 const DEFAULT_UNORDERED_LIST_ITEM_PREFIX: &str = " • ";
+const DEFAULT_LIST_INDENT: usize = 1;
+/// Indentation of a definition below its term.
+const DEFINITION_INDENT: &str = "    ";
+/// SGR sequence restoring the terminal's default foreground color.
+const DEFAULT_FOREGROUND: &str = "\x1b[39m";
+/// SGR sequence shading inline code with the palette's bright black.
+const CODE_BACKGROUND: &str = "\x1b[100m";
+/// SGR sequence restoring the terminal's default background color.
+const DEFAULT_BACKGROUND: &str = "\x1b[49m";
+/// OSC 8 sequence closing the innermost open hyperlink.
+const OSC8_END: &str = "\x1b]8;;\x1b\\";
 
 static ANSI_ESCAPE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\x1b\[[0-9;]*m").expect("valid ANSI escape regex"));
 static OSC8_SEQUENCE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"\x1b]8;([^;]*);([^\x1b]*)\x1b\\").expect("valid OSC8 regex"));
-static OSC8_ESCAPE_REGEX: Lazy<Regex> =
-    Lazy::new(|| Regex::new(r"\x1b]8;[^\x1b]*\x1b\\").expect("valid OSC8 escape regex"));
+static FOREGROUND_COLOR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\x1b\[(3[0-7]|38;[0-9;]+)m$").expect("valid foreground color regex")
+});
 
 #[derive(Clone)]
 /// Opening and closing escape sequences for a particular inline style.
@@ -44,6 +65,110 @@ pub enum LinkIndexFormat {
     Bracketed,
 }
 
+/// Reading direction of the rendered text.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Direction {
+    /// Lines start at the left margin, with markers on the left.
+    #[default]
+    Ltr,
+    /// Lines are right-aligned to [`FormattingStyle::wrap_width`], with list
+    /// and quote markers and the left padding mirrored onto the right.
+    Rtl,
+}
+
+/// How code block lines wider than the available width are rendered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodeOverflow {
+    /// Continue the line on the next ones.
+    #[default]
+    Wrap,
+    /// Cut the line off and end it with `…`, keeping the layout of
+    /// e.g. ASCII diagrams intact.
+    Truncate,
+}
+
+/// The last line written when output is cut off at
+/// [`FormattingStyle::max_lines`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TruncationIndicator {
+    /// A lone `…`.
+    #[default]
+    Ellipsis,
+    /// The number of lines left out, e.g. `(12 more lines)`. Unlike the other
+    /// indicators, this lays out the rest of the document to count them.
+    LineCount,
+    /// The given text.
+    Text(String),
+}
+
+impl TruncationIndicator {
+    /// The indicator's text for output missing `omitted` lines.
+    pub fn text(&self, omitted: usize) -> String {
+        match self {
+            TruncationIndicator::Ellipsis => "…".to_string(),
+            TruncationIndicator::LineCount if omitted == 1 => "(1 more line)".to_string(),
+            TruncationIndicator::LineCount => format!("({} more lines)", omitted),
+            TruncationIndicator::Text(text) => text.clone(),
+        }
+    }
+}
+
+/// How one kind of admonition is rendered: a bold label line above the body,
+/// with `prefix` in front of every line of both.
+///
+/// [`AdmonitionStyle::ascii`] labels the body with plain text such as
+/// `NOTE:`, while [`AdmonitionStyle::ansi`] colors the label.
+#[derive(Clone)]
+pub struct AdmonitionStyle {
+    pub prefix: String,
+    pub label: String,
+    /// Styling wrapped around the label in addition to bold, e.g. a
+    /// foreground color.
+    pub label_style: Option<StyleTags>,
+}
+
+impl AdmonitionStyle {
+    /// A plain-text style labeling the body with e.g. `NOTE:`.
+    pub fn ascii(kind: AdmonitionKind) -> Self {
+        let prefix = match kind {
+            AdmonitionKind::Warning | AdmonitionKind::Caution => "! ",
+            _ => "| ",
+        };
+        Self {
+            prefix: prefix.to_string(),
+            label: format!("{}:", kind.marker()),
+            label_style: None,
+        }
+    }
+
+    /// A style drawing a box-drawing bar and coloring the label the way
+    /// GitHub colors its alerts.
+    pub fn ansi(kind: AdmonitionKind) -> Self {
+        let (prefix, color) = match kind {
+            AdmonitionKind::Note => ("│ ", "\x1b[34m"),
+            AdmonitionKind::Tip => ("│ ", "\x1b[32m"),
+            AdmonitionKind::Important => ("│ ", "\x1b[35m"),
+            AdmonitionKind::Warning => ("┃ ", "\x1b[33m"),
+            AdmonitionKind::Caution => ("┃ ", "\x1b[31m"),
+        };
+        Self {
+            prefix: prefix.to_string(),
+            label: kind.marker().to_string(),
+            label_style: Some(StyleTags::new(color, DEFAULT_FOREGROUND)),
+        }
+    }
+}
+
+/// Builds the [`FormattingStyle::admonition_styles`] for every kind.
+fn admonition_styles(
+    style: fn(AdmonitionKind) -> AdmonitionStyle,
+) -> HashMap<AdmonitionKind, AdmonitionStyle> {
+    AdmonitionKind::ALL
+        .into_iter()
+        .map(|kind| (kind, style(kind)))
+        .collect()
+}
+
 /// Glyphs used to draw the lines and junctions of a rendered table grid.
 ///
 /// Two presets are provided: [`TableBorders::ascii`] uses the portable `+`,
@@ -101,6 +226,30 @@ impl TableBorders {
     }
 }
 
+/// Blank lines written around headings, indexed by heading level (`[0]` for
+/// [`ParagraphType::Header1`] through `[2]` for [`ParagraphType::Header3`]).
+///
+/// The blank lines between two paragraphs are the larger of what the first
+/// one wants after it and the second one wants before it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HeadingSpacing {
+    pub before: [usize; 3],
+    pub after: [usize; 3],
+}
+
+impl Default for HeadingSpacing {
+    fn default() -> Self {
+        Self {
+            before: [3, 3, 2],
+            after: [3, 2, 1],
+        }
+    }
+}
+
+/// Predicate deciding whether a link target gets a footnote; see
+/// [`FormattingStyle::footnote_filter`].
+pub type FootnoteFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
 #[derive(Clone)]
 /// High-level configuration that influences how the [`Formatter`] renders output.
 pub struct FormattingStyle {
@@ -108,6 +257,10 @@ pub struct FormattingStyle {
     pub text_styles: HashMap<InlineStyle, StyleTags>,
     pub quote_prefix: String,
     pub unordered_list_item_prefix: String,
+    /// Spaces between a list item's marker (the trimmed
+    /// `unordered_list_item_prefix`, ` 1.` or `[ ]`) and its content. Wrapped
+    /// lines and nested content are aligned with the content.
+    pub list_indent: usize,
     pub wrap_width: usize,
     pub left_padding: usize,
     /// When set, wrap link text in OSC 8 control sequences so supporting terminals emit clickable hyperlinks.
@@ -116,8 +269,60 @@ pub struct FormattingStyle {
     pub link_index_format: LinkIndexFormat,
     /// When true, numbered link references are emitted after each section.
     pub link_footnotes: bool,
+    /// When true, link references are collected for the whole document and
+    /// emitted once at its end instead of after each section.
+    pub footnotes_at_end: bool,
+    /// When true, footnote targets wider than the available width are broken
+    /// between characters onto several lines, aligned with the first one.
+    /// Otherwise they overflow the line, keeping them easy to copy.
+    pub wrap_footnote_urls: bool,
+    /// Decides per link target whether it gets a footnote when
+    /// `link_footnotes` is enabled; targets for which it returns `false` are
+    /// rendered without an index. `None` gives every link a footnote.
+    pub footnote_filter: Option<FootnoteFilter>,
     /// Glyphs used to draw table borders.
     pub table_borders: TableBorders,
+    /// Blank lines around headings.
+    pub heading_spacing: HeadingSpacing,
+    /// When true (and `link_footnotes` is enabled), abbreviation expansions are
+    /// numbered and listed alongside the link references.
+    pub abbreviation_footnotes: bool,
+    /// When true, [`InlineStyle::Color`] spans are rendered with SGR
+    /// foreground color sequences; otherwise their text is written uncolored.
+    pub enable_colors: bool,
+    /// Plain-text markers for inline styles without an entry in
+    /// `text_styles`, e.g. `~~` around strikethrough on terminals lacking
+    /// SGR 9. Empty by default; see [`FormattingStyle::ascii_markers`].
+    pub fallback_markers: HashMap<InlineStyle, StyleTags>,
+    /// Styling wrapped around heading text in addition to bold, e.g. a
+    /// foreground color. `None` by default.
+    pub heading_style: Option<StyleTags>,
+    /// When true, [`InlineStyle::Quotation`] spans are wrapped in typographic
+    /// quotation marks (`“…”`, nested `‘…’`) instead of ASCII `"…"` and `'…'`.
+    pub typographic_quotes: bool,
+    /// When true, headings are prefixed with hierarchical section numbers
+    /// such as `1`, `1.1` and `1.1.1`.
+    pub number_headings: bool,
+    /// Reading direction; [`Direction::Rtl`] suits Arabic or Hebrew text.
+    pub direction: Direction,
+    /// When true, checklists are followed by a line such as `(3/5 done)`
+    /// counting all items, nested ones included.
+    pub checklist_progress: bool,
+    /// Upper bound for the number of dashes in the lines framing code
+    /// blocks, which otherwise span the whole `wrap_width`. `None` by default.
+    pub max_code_fence_width: Option<usize>,
+    /// Whether long code block lines are wrapped or truncated.
+    pub code_overflow: CodeOverflow,
+    /// Label and prefix of each admonition kind. Kinds without an entry are
+    /// rendered with [`AdmonitionStyle::ascii`].
+    pub admonition_styles: HashMap<AdmonitionKind, AdmonitionStyle>,
+    /// Upper bound for the number of lines a document is rendered to, e.g.
+    /// for a preview pane. Longer output is cut off, its last line replaced
+    /// by `truncation_indicator`, and rendering stops at the next paragraph.
+    /// `None` by default.
+    pub max_lines: Option<usize>,
+    /// Line ending output cut off at `max_lines`.
+    pub truncation_indicator: TruncationIndicator,
 }
 
 impl Default for FormattingStyle {
@@ -127,12 +332,30 @@ impl Default for FormattingStyle {
             text_styles: HashMap::new(),
             quote_prefix: DEFAULT_QUOTE_PREFIX.to_string(),
             unordered_list_item_prefix: DEFAULT_UNORDERED_LIST_ITEM_PREFIX.to_string(),
+            list_indent: DEFAULT_LIST_INDENT,
             wrap_width: DEFAULT_WRAP_WIDTH,
             left_padding: 0,
             enable_osc8_hyperlinks: false,
             link_index_format: LinkIndexFormat::default(),
             link_footnotes: true,
+            footnotes_at_end: false,
+            wrap_footnote_urls: false,
+            footnote_filter: None,
             table_borders: TableBorders::ascii(),
+            heading_spacing: HeadingSpacing::default(),
+            abbreviation_footnotes: false,
+            enable_colors: false,
+            fallback_markers: HashMap::new(),
+            heading_style: None,
+            typographic_quotes: false,
+            number_headings: false,
+            direction: Direction::Ltr,
+            checklist_progress: false,
+            max_code_fence_width: None,
+            code_overflow: CodeOverflow::Wrap,
+            admonition_styles: admonition_styles(AdmonitionStyle::ascii),
+            max_lines: None,
+            truncation_indicator: TruncationIndicator::default(),
         }
     }
 }
@@ -143,6 +366,40 @@ impl FormattingStyle {
         Self::default()
     }
 
+    /// Creates a plain ASCII style that keeps strikethrough and highlights
+    /// visible without escape sequences, as `~~struck~~` and `*highlighted*`.
+    pub fn ascii_markers() -> Self {
+        Self {
+            fallback_markers: Self::default_fallback_markers(),
+            ..Self::ascii()
+        }
+    }
+
+    /// Creates a plain ASCII style that marks emphasis the way Markdown
+    /// source does, as `*bold*`, `_italic_`, `~struck~` and `` `code` ``, for
+    /// logs and plain-text email.
+    pub fn markdown_markers() -> Self {
+        let mut markers = HashMap::new();
+        markers.insert(InlineStyle::Bold, StyleTags::new("*", "*"));
+        markers.insert(InlineStyle::Italic, StyleTags::new("_", "_"));
+        markers.insert(InlineStyle::Strike, StyleTags::new("~", "~"));
+        markers.insert(InlineStyle::Code, StyleTags::new("`", "`"));
+        Self {
+            fallback_markers: markers,
+            ..Self::ascii()
+        }
+    }
+
+    /// The markers installed by [`FormattingStyle::ascii_markers`]. Assign them
+    /// to `fallback_markers` to use them with another style, for example an
+    /// ANSI style with the strikethrough escape removed.
+    pub fn default_fallback_markers() -> HashMap<InlineStyle, StyleTags> {
+        let mut markers = HashMap::new();
+        markers.insert(InlineStyle::Strike, StyleTags::new("~~", "~~"));
+        markers.insert(InlineStyle::Highlight, StyleTags::new("*", "*"));
+        markers
+    }
+
     /// Creates a style that emits ANSI escape codes for bold, italic, and other emphasis.
     pub fn ansi() -> Self {
         let mut text_styles = HashMap::new();
@@ -157,20 +414,64 @@ impl FormattingStyle {
             StyleTags::new("\x1b[4m", "\x1b[24m"),
         );
         text_styles.insert(InlineStyle::Strike, StyleTags::new("\x1b[9m", "\x1b[29m"));
+        text_styles.insert(InlineStyle::Keyboard, StyleTags::new("\x1b[7m", "\x1b[27m"));
+        text_styles.insert(
+            InlineStyle::Code,
+            StyleTags::new(CODE_BACKGROUND, DEFAULT_BACKGROUND),
+        );
 
         Self {
             reset_styles: "\x1b[0m".to_string(),
             text_styles,
             quote_prefix: DEFAULT_QUOTE_PREFIX.to_string(),
             unordered_list_item_prefix: DEFAULT_UNORDERED_LIST_ITEM_PREFIX.to_string(),
+            list_indent: DEFAULT_LIST_INDENT,
             wrap_width: DEFAULT_WRAP_WIDTH,
             left_padding: 0,
             enable_osc8_hyperlinks: true,
             link_index_format: LinkIndexFormat::default(),
             link_footnotes: true,
+            footnotes_at_end: false,
+            wrap_footnote_urls: false,
+            footnote_filter: None,
             table_borders: TableBorders::unicode(),
+            heading_spacing: HeadingSpacing::default(),
+            abbreviation_footnotes: false,
+            enable_colors: true,
+            fallback_markers: HashMap::new(),
+            heading_style: None,
+            typographic_quotes: true,
+            number_headings: false,
+            direction: Direction::Ltr,
+            checklist_progress: false,
+            max_code_fence_width: None,
+            code_overflow: CodeOverflow::Wrap,
+            admonition_styles: admonition_styles(AdmonitionStyle::ansi),
+            max_lines: None,
+            truncation_indicator: TruncationIndicator::default(),
         }
     }
+
+    /// Creates an ANSI style that colors headings, inline code and links for
+    /// terminals with a dark background.
+    pub fn ansi_dark() -> Self {
+        Self::ansi().with_colors("\x1b[33m", "\x1b[32m", "\x1b[36m")
+    }
+
+    /// Creates an ANSI style that colors headings, inline code and links for
+    /// terminals with a light background.
+    pub fn ansi_light() -> Self {
+        Self::ansi().with_colors("\x1b[35m", "\x1b[31m", "\x1b[34m")
+    }
+
+    fn with_colors(mut self, heading: &str, code: &str, link: &str) -> Self {
+        self.heading_style = Some(StyleTags::new(heading, DEFAULT_FOREGROUND));
+        self.text_styles
+            .insert(InlineStyle::Code, StyleTags::new(code, DEFAULT_FOREGROUND));
+        self.text_styles
+            .insert(InlineStyle::Link, StyleTags::new(link, DEFAULT_FOREGROUND));
+        self
+    }
 }
 
 /// Pretty-prints [`Document`] trees using the supplied [`FormattingStyle`].
@@ -196,12 +497,34 @@ pub struct Formatter<W: Write> {
     link_indices: HashMap<String, usize>,
     next_link_index: usize,
     next_hyperlink_id: usize,
+    /// OSC 8 ids handed out in the current document, by link target, so
+    /// every occurrence of a target forms one logical hyperlink.
+    hyperlink_ids: HashMap<String, String>,
+    /// Foreground sequence of the enclosing [`InlineStyle::Color`] span while
+    /// its content is collected, restored when a nested color ends.
+    current_color: Option<String>,
+    /// Number of [`InlineStyle::Quotation`] spans enclosing the content being
+    /// collected; nested quotations alternate between double and single marks.
+    quote_depth: usize,
+    /// Section counters for the three heading levels, used when
+    /// [`FormattingStyle::number_headings`] is set.
+    heading_counters: [usize; 3],
+    /// Every line prefix written, collected while rendering into a buffer
+    /// that is mirrored for [`Direction::Rtl`] afterwards.
+    line_prefixes: Option<HashSet<String>>,
+    /// Link footnotes written by the last `write_document` call.
+    written_links: Vec<(usize, String)>,
+    /// Set once output exceeds [`FormattingStyle::max_lines`], so no further
+    /// paragraphs are rendered.
+    budget_spent: Option<Rc<Cell<bool>>>,
 }
 
 #[derive(Clone, Debug)]
 struct LinkReference {
     index: usize,
     target: String,
+    /// `false` for abbreviation expansions, which are not hyperlinked.
+    is_link: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -217,6 +540,216 @@ impl Osc8Link {
             target: target.into(),
         }
     }
+
+    /// The OSC 8 sequence opening this hyperlink.
+    fn start_sequence(&self) -> String {
+        let params = self
+            .id
+            .as_ref()
+            .map(|id| format!("id={}", id))
+            .unwrap_or_default();
+        format!("\x1b]8;{};{}\x1b\\", params, self.target)
+    }
+}
+
+/// The prefixes a paragraph is written with, describing where it sits in a
+/// rendered document. Used with [`render_paragraph`] and
+/// [`Formatter::write_paragraph_block`] to re-render one paragraph on its own.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ParagraphContext {
+    /// Prefix of the paragraph's first line.
+    pub prefix: String,
+    /// Prefix of every following line.
+    pub continuation_prefix: String,
+    /// Prefix of blank lines inside the paragraph, e.g. between list entries.
+    pub blank_line_prefix: String,
+}
+
+impl ParagraphContext {
+    /// The context of a paragraph at the top level of a document rendered
+    /// with `style`.
+    pub fn top_level(style: &FormattingStyle) -> Self {
+        let indent = " ".repeat(style.left_padding);
+        Self {
+            prefix: indent.clone(),
+            continuation_prefix: indent.clone(),
+            blank_line_prefix: indent,
+        }
+    }
+}
+
+/// Renders a single paragraph as the text block it occupies within a full
+/// document render, so an edited paragraph can be spliced into a cached
+/// render. The blank lines separating the paragraph from its neighbours are
+/// not part of the block. Link indices start at 1 and the footnotes are left
+/// out; with [`FormattingStyle::number_headings`], a heading is numbered as
+/// if it were the first of the document.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::formatter::{render_paragraph, FormattingStyle, ParagraphContext};
+/// use tdoc::{Paragraph, Span};
+///
+/// let style = FormattingStyle::ascii();
+/// let context = ParagraphContext::top_level(&style);
+/// let paragraph = Paragraph::new_text().with_content(vec![Span::new_text("Hello")]);
+/// assert_eq!(render_paragraph(&paragraph, style, &context), "Hello\n");
+/// ```
+pub fn render_paragraph(
+    paragraph: &Paragraph,
+    style: FormattingStyle,
+    context: &ParagraphContext,
+) -> String {
+    let mut output = Vec::new();
+    Formatter::new(&mut output, style)
+        .write_paragraph_block(paragraph, context)
+        .expect("writing to a Vec cannot fail");
+    String::from_utf8(output).expect("formatter output is valid UTF-8")
+}
+
+/// A pending step of the paragraph traversal. Nested content is pushed onto
+/// an explicit stack rather than rendered recursively, so deeply nested
+/// documents cannot overflow the call stack.
+enum RenderTask<'a> {
+    Paragraphs(ParagraphRun<'a>),
+    BlankLines {
+        prefix: String,
+        count: usize,
+    },
+    DefinitionTerm {
+        term: &'a [Span],
+        prefix: String,
+        continuation_prefix: String,
+    },
+    ChecklistItem {
+        item: &'a ChecklistItem,
+        prefix: String,
+        continuation_prefix: String,
+    },
+    ChecklistProgress {
+        items: &'a [ChecklistItem],
+        prefix: String,
+    },
+}
+
+/// A run of sibling paragraphs together with the prefixes they are written
+/// with. `next` and `previous_type` track progress so the run can be resumed
+/// once the children of the paragraph just written are done.
+struct ParagraphRun<'a> {
+    paragraphs: Vec<&'a Paragraph>,
+    next: usize,
+    previous_type: Option<ParagraphType>,
+    first_line_prefixes: Vec<String>,
+    default_first_line_prefix: String,
+    continuation_prefix: String,
+    blank_line_prefix: String,
+}
+
+impl<'a> ParagraphRun<'a> {
+    fn new(
+        paragraphs: &'a [Paragraph],
+        first_line_prefixes: Vec<String>,
+        default_first_line_prefix: impl Into<String>,
+        continuation_prefix: impl Into<String>,
+        blank_line_prefix: impl Into<String>,
+    ) -> Self {
+        // Raw pass-through paragraphs target other formats; plain-text output
+        // never shows them.
+        let paragraphs = paragraphs
+            .iter()
+            .filter(|paragraph| paragraph.paragraph_type() != ParagraphType::Raw)
+            .collect();
+        Self {
+            paragraphs,
+            next: 0,
+            previous_type: None,
+            first_line_prefixes,
+            default_first_line_prefix: default_first_line_prefix.into(),
+            continuation_prefix: continuation_prefix.into(),
+            blank_line_prefix: blank_line_prefix.into(),
+        }
+    }
+}
+
+/// Passes the first `max_lines - 1` lines on to the wrapped writer and
+/// holds back the last one, which is only written if the output ends there.
+/// Everything beyond is counted and dropped.
+struct LineLimit<'a> {
+    inner: &'a mut dyn Write,
+    passed: usize,
+    kept: usize,
+    held: Vec<u8>,
+    holdable: usize,
+    held_lines: usize,
+    dropped_lines: usize,
+    spent: Rc<Cell<bool>>,
+}
+
+impl<'a> LineLimit<'a> {
+    fn new(inner: &'a mut dyn Write, max_lines: usize, spent: Rc<Cell<bool>>) -> Self {
+        Self {
+            inner,
+            passed: 0,
+            kept: max_lines.saturating_sub(1),
+            held: Vec::new(),
+            holdable: max_lines.min(1),
+            held_lines: 0,
+            dropped_lines: 0,
+            spent,
+        }
+    }
+
+    /// Writes the held back line if the output fit. Otherwise returns the
+    /// number of lines left out, counting the one the indicator replaces.
+    fn finish(self) -> std::io::Result<Option<usize>> {
+        if self.spent.get() {
+            return Ok(Some(self.held_lines + self.dropped_lines));
+        }
+        self.inner.write_all(&self.held)?;
+        Ok(None)
+    }
+}
+
+fn count_lines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+impl Write for LineLimit<'_> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut rest = buf;
+        while self.passed < self.kept && !rest.is_empty() {
+            let end = rest
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(rest.len(), |newline| newline + 1);
+            self.inner.write_all(&rest[..end])?;
+            if rest[end - 1] == b'\n' {
+                self.passed += 1;
+            }
+            rest = &rest[end..];
+        }
+        if self.spent.get() {
+            self.dropped_lines += count_lines(rest);
+            return Ok(buf.len());
+        }
+        for (index, &byte) in rest.iter().enumerate() {
+            if byte == b'\n' {
+                if self.held_lines == self.holdable {
+                    self.spent.set(true);
+                    self.dropped_lines += count_lines(&rest[index..]);
+                    break;
+                }
+                self.held_lines += 1;
+            }
+            self.held.push(byte);
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
 }
 
 impl<W: Write> Formatter<W> {
@@ -229,6 +762,13 @@ impl<W: Write> Formatter<W> {
             link_indices: HashMap::new(),
             next_link_index: 1,
             next_hyperlink_id: 1,
+            hyperlink_ids: HashMap::new(),
+            current_color: None,
+            quote_depth: 0,
+            heading_counters: [0; 3],
+            written_links: Vec::new(),
+            line_prefixes: None,
+            budget_spent: None,
         }
     }
 
@@ -244,9 +784,26 @@ impl<W: Write> Formatter<W> {
 
     /// Writes the entire document into the wrapped writer.
     pub fn write_document(&mut self, document: &Document) -> std::io::Result<()> {
-        self.next_hyperlink_id = 1;
+        if let Some(max_lines) = self.style.max_lines {
+            return self.write_truncated(document, max_lines);
+        }
+        if self.style.direction == Direction::Rtl && self.line_prefixes.is_none() {
+            return self.write_mirrored(|formatter| formatter.write_document(document));
+        }
+
+        // Ids keep counting across documents so links of separate documents
+        // written to the same terminal are never grouped together.
+        self.hyperlink_ids.clear();
+        self.heading_counters = [0; 3];
+        self.written_links.clear();
         let indent = " ".repeat(self.style.left_padding);
-        self.write_paragraphs(&document.paragraphs, &indent, &indent, &indent)?;
+        self.write_paragraphs(ParagraphRun::new(
+            &document.paragraphs,
+            Vec::new(),
+            indent.as_str(),
+            indent.as_str(),
+            indent.as_str(),
+        ))?;
         let _ = self.flush_pending_links(&indent)?;
 
         // Write reset styles if we have any
@@ -254,78 +811,248 @@ impl<W: Write> Formatter<W> {
             write!(self.writer, "{}", self.style.reset_styles)?;
         }
 
-        Ok(())
+        self.writer.flush()
     }
 
-    fn write_paragraphs(
+    /// Writes `paragraph` with the prefixes of `context`, without the blank
+    /// lines that separate it from its siblings. See [`render_paragraph`].
+    pub fn write_paragraph_block(
         &mut self,
-        paragraphs: &[Paragraph],
-        prefix: &str,
-        continuation_prefix: &str,
-        blank_line_prefix: &str,
+        paragraph: &Paragraph,
+        context: &ParagraphContext,
     ) -> std::io::Result<()> {
-        self.write_paragraphs_with_prefixes(
-            paragraphs,
-            &[],
-            prefix,
-            continuation_prefix,
-            blank_line_prefix,
-        )
+        if self.style.direction == Direction::Rtl && self.line_prefixes.is_none() {
+            return self
+                .write_mirrored(|formatter| formatter.write_paragraph_block(paragraph, context));
+        }
+
+        self.heading_counters = [0; 3];
+        let tasks = self.write_paragraph(
+            paragraph,
+            &context.prefix,
+            &context.continuation_prefix,
+            &context.blank_line_prefix,
+        )?;
+        self.render(tasks)?;
+
+        // Footnotes belong to the surrounding section, so they are dropped
+        // and the next block numbers its links from 1 again.
+        self.pending_links.clear();
+        self.link_indices.clear();
+        self.next_link_index = 1;
+        Ok(())
     }
 
-    fn write_paragraphs_with_prefixes(
+    /// Renders left to right into a buffer via `write`, then writes every
+    /// line of it mirrored for right-to-left reading.
+    fn write_mirrored(
         &mut self,
-        paragraphs: &[Paragraph],
-        first_line_prefixes: &[&str],
-        default_first_line_prefix: &str,
-        continuation_prefix: &str,
-        blank_line_prefix: &str,
+        write: impl FnOnce(&mut Formatter<Vec<u8>>) -> std::io::Result<()>,
     ) -> std::io::Result<()> {
-        let mut previous_type: Option<ParagraphType> = None;
-
-        for (idx, paragraph) in paragraphs.iter().enumerate() {
-            let paragraph_type = paragraph.paragraph_type();
-            let flushed_links = if matches!(
-                paragraph_type,
-                ParagraphType::Header1 | ParagraphType::Header2 | ParagraphType::Header3
-            ) {
-                self.flush_pending_links(blank_line_prefix)?
-            } else {
-                false
-            };
-            let previous_after = previous_type
-                .map(|ty| self.blank_lines_after(ty))
-                .unwrap_or(0);
-            let mut blank_lines = self.blank_lines_before(previous_type, paragraph_type);
-            if flushed_links && blank_lines > 0 {
-                blank_lines -= 1;
-            }
-            self.write_blank_lines_with_prefix(blank_line_prefix, previous_after.max(blank_lines))?;
-            let paragraph_prefix = if idx < first_line_prefixes.len() {
-                first_line_prefixes[idx]
-            } else {
-                default_first_line_prefix
+        let mut buffered = Formatter::new(Vec::new(), self.style.clone());
+        buffered.next_hyperlink_id = self.next_hyperlink_id;
+        buffered.line_prefixes = Some(HashSet::new());
+        write(&mut buffered)?;
+        self.next_hyperlink_id = buffered.next_hyperlink_id;
+        self.written_links = buffered.written_links;
+
+        // Match the longest prefix first, so `| | ` wins over `| `.
+        let mut prefixes: Vec<String> = buffered
+            .line_prefixes
+            .unwrap_or_default()
+            .into_iter()
+            .collect();
+        prefixes.sort_by_key(|prefix| std::cmp::Reverse(prefix.len()));
+
+        let output = String::from_utf8_lossy(&buffered.writer);
+        for line in output.split_inclusive('\n') {
+            let Some(line) = line.strip_suffix('\n') else {
+                // The trailing style reset.
+                write!(self.writer, "{}", line)?;
+                continue;
             };
+            let prefix = prefixes
+                .iter()
+                .find(|prefix| line.starts_with(prefix.as_str()))
+                .map_or("", String::as_str);
+            let mirrored = layout::mirror_line(line, prefix, self.style.wrap_width);
+            writeln!(self.writer, "{}", mirrored)?;
+        }
+        Ok(())
+    }
 
-            self.write_paragraph(
-                paragraph,
-                paragraph_prefix,
-                continuation_prefix,
-                blank_line_prefix,
-            )?;
-            previous_type = Some(paragraph_type);
+    /// Writes at most `max_lines` lines of `document`. When it does not fit,
+    /// the last line written is the [`TruncationIndicator`].
+    fn write_truncated(&mut self, document: &Document, max_lines: usize) -> std::io::Result<()> {
+        let mut style = self.style.clone();
+        style.max_lines = None;
+        let spent = Rc::new(Cell::new(false));
+        let mut limited = Formatter::new(
+            LineLimit::new(&mut self.writer, max_lines, Rc::clone(&spent)),
+            style,
+        );
+        limited.next_hyperlink_id = self.next_hyperlink_id;
+        if self.style.truncation_indicator != TruncationIndicator::LineCount {
+            limited.budget_spent = Some(spent);
         }
+        limited.write_document(document)?;
+        self.next_hyperlink_id = limited.next_hyperlink_id;
+        self.written_links = limited.written_links;
 
-        if let Some(last_type) = previous_type {
-            self.write_blank_lines_with_prefix(
-                blank_line_prefix,
-                self.blank_lines_after(last_type),
-            )?;
+        let Some(omitted) = limited.writer.finish()? else {
+            return self.writer.flush();
+        };
+        if max_lines > 0 {
+            let indicator = self.style.truncation_indicator.text(omitted);
+            let indent = " ".repeat(self.style.left_padding);
+            writeln!(self.writer, "{}{}", indent, indicator)?;
+        }
+        if !self.style.reset_styles.is_empty() {
+            write!(self.writer, "{}", self.style.reset_styles)?;
         }
+        self.writer.flush()
+    }
+
+    /// Remembers `prefixes` as line prefixes when rendering for
+    /// [`Direction::Rtl`].
+    fn note_prefixes(&mut self, prefixes: &[&str]) {
+        if let Some(line_prefixes) = &mut self.line_prefixes {
+            for prefix in prefixes {
+                if !line_prefixes.contains(*prefix) {
+                    line_prefixes.insert(prefix.to_string());
+                }
+            }
+        }
+    }
+
+    fn write_paragraphs(&mut self, run: ParagraphRun<'_>) -> std::io::Result<()> {
+        self.render(vec![RenderTask::Paragraphs(run)])
+    }
+
+    /// Runs `tasks` in order, along with the nested tasks they produce.
+    fn render(&mut self, mut tasks: Vec<RenderTask<'_>>) -> std::io::Result<()> {
+        tasks.reverse();
+        let mut stack = tasks;
+        while let Some(task) = stack.pop() {
+            match task {
+                RenderTask::Paragraphs(mut run) => {
+                    let Some(paragraph) = run.paragraphs.get(run.next).copied() else {
+                        if let Some(last_type) = run.previous_type {
+                            self.write_blank_lines_with_prefix(
+                                &run.blank_line_prefix,
+                                self.blank_lines_after(last_type),
+                            )?;
+                        }
+                        continue;
+                    };
+
+                    if self.budget_spent.as_ref().is_some_and(|spent| spent.get()) {
+                        return Ok(());
+                    }
 
+                    // Everything before this paragraph is complete; hand it
+                    // on, so piped output appears while the rest renders.
+                    self.writer.flush()?;
+
+                    let paragraph_type = paragraph.paragraph_type();
+                    let flushed_links = if !self.style.footnotes_at_end
+                        && matches!(
+                            paragraph_type,
+                            ParagraphType::Header1
+                                | ParagraphType::Header2
+                                | ParagraphType::Header3
+                        ) {
+                        self.flush_pending_links(&run.blank_line_prefix)?
+                    } else {
+                        false
+                    };
+                    let previous_after = run
+                        .previous_type
+                        .map(|ty| self.blank_lines_after(ty))
+                        .unwrap_or(0);
+                    let mut blank_lines =
+                        self.blank_lines_before(run.previous_type, paragraph_type);
+                    if flushed_links && blank_lines > 0 {
+                        blank_lines -= 1;
+                    }
+                    self.write_blank_lines_with_prefix(
+                        &run.blank_line_prefix,
+                        previous_after.max(blank_lines),
+                    )?;
+                    let paragraph_prefix = run
+                        .first_line_prefixes
+                        .get(run.next)
+                        .unwrap_or(&run.default_first_line_prefix);
+
+                    let children = self.write_paragraph(
+                        paragraph,
+                        paragraph_prefix,
+                        &run.continuation_prefix,
+                        &run.blank_line_prefix,
+                    )?;
+                    run.next += 1;
+                    run.previous_type = Some(paragraph_type);
+                    stack.push(RenderTask::Paragraphs(run));
+                    stack.extend(children.into_iter().rev());
+                }
+                RenderTask::BlankLines { prefix, count } => {
+                    self.write_blank_lines_with_prefix(&prefix, count)?;
+                }
+                RenderTask::DefinitionTerm {
+                    term,
+                    prefix,
+                    continuation_prefix,
+                } => {
+                    let term = Span::new_styled(InlineStyle::Bold).with_children(term.to_vec());
+                    self.write_text_paragraph(&[term], &prefix, &continuation_prefix)?;
+                }
+                RenderTask::ChecklistItem {
+                    item,
+                    prefix,
+                    continuation_prefix,
+                } => {
+                    let continuation =
+                        self.write_checklist_item(item, &prefix, &continuation_prefix)?;
+                    stack.extend(item.children.iter().rev().map(|child| {
+                        RenderTask::ChecklistItem {
+                            item: child,
+                            prefix: continuation.clone(),
+                            continuation_prefix: continuation.clone(),
+                        }
+                    }));
+                }
+                RenderTask::ChecklistProgress { items, prefix } => {
+                    let (done, total) = checklist_progress(items);
+                    writeln!(self.writer, "{}({}/{} done)", prefix, done, total)?;
+                }
+            }
+        }
         Ok(())
     }
 
+    /// Returns the `(index, target)` pairs of the link footnotes emitted by
+    /// the last [`write_document`](Self::write_document) call, in output
+    /// order. Numbering restarts after each heading, so an index can repeat;
+    /// abbreviation footnotes are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::formatter::Formatter;
+    /// use tdoc::Document;
+    ///
+    /// let doc = Document::from_markdown("See [docs](https://example.com/docs).").unwrap();
+    /// let mut formatter = Formatter::new_ascii(Vec::new());
+    /// formatter.write_document(&doc).unwrap();
+    /// assert_eq!(
+    ///     formatter.written_links(),
+    ///     [(1, "https://example.com/docs".to_string())]
+    /// );
+    /// ```
+    pub fn written_links(&self) -> &[(usize, String)] {
+        &self.written_links
+    }
+
     fn flush_pending_links(&mut self, prefix: &str) -> std::io::Result<bool> {
         if !self.style.link_footnotes {
             if !self.pending_links.is_empty() {
@@ -358,14 +1085,46 @@ impl<W: Write> Formatter<W> {
             .unwrap_or(1);
 
         for link in &links {
+            if link.is_link {
+                self.written_links.push((link.index, link.target.clone()));
+            }
             let label = self.link_label(link.index, max_label_width);
             let first_prefix = format!("{}{}", prefix, label);
             let continuation_prefix = format!("{}{}", prefix, " ".repeat(label.chars().count()));
-            let footnote_text = if self.style.enable_osc8_hyperlinks {
-                let hyperlink = self.next_osc8_link(&link.target);
-                self.osc8_wrap(&hyperlink, &link.target)
+            self.note_prefixes(&[&first_prefix, &continuation_prefix]);
+            let display = if link.is_link {
+                footnote_display(&link.target)
             } else {
-                link.target.clone()
+                &link.target
+            };
+            let hyperlink = (self.style.enable_osc8_hyperlinks && link.is_link)
+                .then(|| self.next_osc8_link(&link.target));
+            let available_width = self
+                .style
+                .wrap_width
+                .saturating_sub(first_prefix.chars().count())
+                .max(1);
+            if self.style.wrap_footnote_urls && display.chars().count() > available_width {
+                // Targets rarely contain whitespace to wrap at, so break them
+                // between any two characters instead.
+                for (idx, chunk) in split_at_width(display, available_width).enumerate() {
+                    let line_prefix = if idx == 0 {
+                        &first_prefix
+                    } else {
+                        &continuation_prefix
+                    };
+                    let text = match &hyperlink {
+                        Some(hyperlink) => self.osc8_wrap(hyperlink, chunk),
+                        None => chunk.to_string(),
+                    };
+                    writeln!(self.writer, "{}{}", line_prefix, text)?;
+                }
+                continue;
+            }
+
+            let footnote_text = match &hyperlink {
+                Some(hyperlink) => self.osc8_wrap(hyperlink, display),
+                None => display.to_string(),
             };
             let parts = vec![footnote_text];
             self.write_wrapped_text(&parts, &first_prefix, &continuation_prefix)?;
@@ -376,22 +1135,35 @@ impl<W: Write> Formatter<W> {
         Ok(true)
     }
 
-    fn write_paragraph(
+    /// Writes the parts of `paragraph` that precede its nested content and
+    /// returns the tasks rendering that content, in output order.
+    fn write_paragraph<'a>(
         &mut self,
-        paragraph: &Paragraph,
+        paragraph: &'a Paragraph,
         prefix: &str,
         continuation_prefix: &str,
         blank_line_prefix: &str,
-    ) -> std::io::Result<()> {
+    ) -> std::io::Result<Vec<RenderTask<'a>>> {
+        self.note_prefixes(&[prefix, continuation_prefix, blank_line_prefix]);
+        let mut tasks = Vec::new();
         match paragraph.paragraph_type() {
             ParagraphType::Header1 => {
-                self.write_header1_paragraph(paragraph.content(), prefix)?;
+                let content = self.numbered_heading_content(paragraph.content(), 1);
+                self.write_header1_paragraph(&content, prefix)?;
             }
             ParagraphType::Header2 => {
-                self.write_header2_paragraph(paragraph.content(), prefix)?;
+                let content = self.numbered_heading_content(paragraph.content(), 2);
+                self.write_header2_paragraph(&content, prefix)?;
             }
             ParagraphType::Header3 => {
-                self.write_header3_paragraph(paragraph.content(), prefix)?;
+                let content = self.numbered_heading_content(paragraph.content(), 3);
+                self.write_header3_paragraph(&content, prefix)?;
+            }
+            ParagraphType::Text if paragraph.is_lead() => {
+                // Lead paragraphs stand out from the body text in bold.
+                let lead =
+                    Span::new_styled(InlineStyle::Bold).with_children(paragraph.content().to_vec());
+                self.write_text_paragraph(&[lead], prefix, continuation_prefix)?;
             }
             ParagraphType::Text => {
                 self.write_text_paragraph(paragraph.content(), prefix, continuation_prefix)?;
@@ -418,44 +1190,69 @@ impl<W: Write> Formatter<W> {
                         Some(ParagraphType::Text)
                     );
 
-                if list_context {
+                let run = if list_context {
                     let quote_prefix =
                         format!("{}{}", continuation_prefix, self.style.quote_prefix);
-
-                    // Maintain owned storage for custom prefixes so borrowed slices stay valid.
-                    let owned_prefixes = [quote_prefix, quote_continuation.clone()];
-
-                    let default_first_prefix = owned_prefixes[0].as_str();
-                    let continuation = owned_prefixes[1].as_str();
-                    let first_line_prefixes = [prefix];
-
-                    self.write_paragraphs_with_prefixes(
+                    ParagraphRun::new(
                         children,
-                        &first_line_prefixes,
-                        default_first_prefix,
-                        continuation,
+                        vec![prefix.to_string()],
+                        quote_prefix,
+                        quote_continuation,
                         continuation_prefix,
-                    )?;
+                    )
                 } else {
                     let quote_prefix = format!("{}{}", prefix, self.style.quote_prefix);
-
-                    self.write_paragraphs(
+                    ParagraphRun::new(
                         children,
-                        &quote_prefix,
-                        &quote_continuation,
-                        &quote_prefix,
-                    )?;
+                        Vec::new(),
+                        quote_prefix.clone(),
+                        quote_continuation,
+                        quote_prefix,
+                    )
+                };
+                tasks.push(RenderTask::Paragraphs(run));
+            }
+            ParagraphType::Admonition => {
+                let kind = paragraph.admonition_kind().unwrap_or_default();
+                let style = self
+                    .style
+                    .admonition_styles
+                    .get(&kind)
+                    .cloned()
+                    .unwrap_or_else(|| AdmonitionStyle::ascii(kind));
+                let label_prefix = format!("{}{}", prefix, style.prefix);
+                let body_prefix = format!("{}{}", continuation_prefix, style.prefix);
+                self.note_prefixes(&[&label_prefix, &body_prefix]);
+
+                let mut label = self.apply_bold(&style.label);
+                if let Some(tags) = &style.label_style {
+                    label = format!("{}{}{}", tags.begin, label, tags.end);
                 }
+                writeln!(self.writer, "{}{}", label_prefix, label)?;
+                tasks.push(RenderTask::Paragraphs(ParagraphRun::new(
+                    paragraph.children(),
+                    Vec::new(),
+                    body_prefix.clone(),
+                    body_prefix.clone(),
+                    body_prefix,
+                )));
             }
             ParagraphType::UnorderedList => {
                 for (idx, entry) in paragraph.entries().iter().enumerate() {
                     if idx > 0 {
-                        self.write_blank_lines_with_prefix(blank_line_prefix, 1)?;
+                        tasks.push(RenderTask::BlankLines {
+                            prefix: blank_line_prefix.to_string(),
+                            count: 1,
+                        });
                     }
 
                     let base_prefix = continuation_prefix;
-                    let bullet_prefix =
-                        format!("{}{}", base_prefix, self.style.unordered_list_item_prefix);
+                    let bullet_prefix = format!(
+                        "{}{}{}",
+                        base_prefix,
+                        self.style.unordered_list_item_prefix.trim_end(),
+                        " ".repeat(self.style.list_indent)
+                    );
                     let bullet_continuation = {
                         let desired_width = bullet_prefix.chars().count();
                         let current_width = base_prefix.chars().count();
@@ -466,19 +1263,22 @@ impl<W: Write> Formatter<W> {
                         continuation
                     };
 
-                    self.write_paragraphs_with_prefixes(
+                    tasks.push(RenderTask::Paragraphs(ParagraphRun::new(
                         entry,
-                        &[bullet_prefix.as_str()],
-                        &bullet_continuation,
-                        &bullet_continuation,
-                        &bullet_continuation,
-                    )?;
+                        vec![bullet_prefix],
+                        bullet_continuation.clone(),
+                        bullet_continuation.clone(),
+                        bullet_continuation,
+                    )));
                 }
             }
             ParagraphType::OrderedList => {
                 for (i, entry) in paragraph.entries().iter().enumerate() {
                     if i > 0 {
-                        self.write_blank_lines_with_prefix(blank_line_prefix, 1)?;
+                        tasks.push(RenderTask::BlankLines {
+                            prefix: blank_line_prefix.to_string(),
+                            count: 1,
+                        });
                     }
 
                     let base_prefix = continuation_prefix;
@@ -488,7 +1288,12 @@ impl<W: Write> Formatter<W> {
                         base_prefix
                     };
 
-                    let bullet_prefix = format!("{}{:2}. ", numbering_prefix, i + 1);
+                    let bullet_prefix = format!(
+                        "{}{:2}.{}",
+                        numbering_prefix,
+                        i + 1,
+                        " ".repeat(self.style.list_indent)
+                    );
                     let bullet_continuation = {
                         let desired_width = bullet_prefix.chars().count();
                         let current_width = base_prefix.chars().count();
@@ -499,28 +1304,69 @@ impl<W: Write> Formatter<W> {
                         continuation
                     };
 
-                    self.write_paragraphs_with_prefixes(
+                    tasks.push(RenderTask::Paragraphs(ParagraphRun::new(
                         entry,
-                        &[bullet_prefix.as_str()],
-                        &bullet_continuation,
-                        &bullet_continuation,
-                        &bullet_continuation,
-                    )?;
+                        vec![bullet_prefix],
+                        bullet_continuation.clone(),
+                        bullet_continuation.clone(),
+                        bullet_continuation,
+                    )));
+                }
+            }
+            ParagraphType::Checklist => {
+                tasks.extend(paragraph.checklist_items().iter().map(|item| {
+                    RenderTask::ChecklistItem {
+                        item,
+                        prefix: continuation_prefix.to_string(),
+                        continuation_prefix: continuation_prefix.to_string(),
+                    }
+                }));
+                if self.style.checklist_progress {
+                    tasks.push(RenderTask::ChecklistProgress {
+                        items: paragraph.checklist_items(),
+                        prefix: continuation_prefix.to_string(),
+                    });
                 }
             }
-            ParagraphType::Checklist => self.write_checklist_items(
-                paragraph.checklist_items(),
-                continuation_prefix,
-                continuation_prefix,
-            )?,
             ParagraphType::Table => {
-                self.write_table_paragraph(paragraph.rows(), prefix, continuation_prefix)?;
+                self.write_table_paragraph(paragraph, prefix, continuation_prefix)?;
             }
+            ParagraphType::Details => {
+                // Render the section expanded, with the summary as a bold
+                // heading-like line.
+                let summary = paragraph.summary();
+                let mut body_prefix = prefix;
+                if !summary.is_empty() {
+                    let summary =
+                        Span::new_styled(InlineStyle::Bold).with_children(summary.to_vec());
+                    self.write_text_paragraph(&[summary], prefix, continuation_prefix)?;
+                    if !paragraph.children().is_empty() {
+                        self.write_blank_lines_with_prefix(blank_line_prefix, 1)?;
+                    }
+                    body_prefix = continuation_prefix;
+                }
+                tasks.push(RenderTask::Paragraphs(ParagraphRun::new(
+                    paragraph.children(),
+                    vec![body_prefix.to_string()],
+                    continuation_prefix,
+                    continuation_prefix,
+                    blank_line_prefix,
+                )));
+            }
+            ParagraphType::DefinitionList => Self::push_definition_list(
+                &mut tasks,
+                paragraph.definition_items(),
+                prefix,
+                continuation_prefix,
+                blank_line_prefix,
+            ),
             ParagraphType::HorizontalRule => {
                 self.write_horizontal_rule(prefix)?;
             }
+            // Filtered out by `ParagraphRun::new`.
+            ParagraphType::Raw => {}
         }
-        Ok(())
+        Ok(tasks)
     }
 
     /// Renders a horizontal rule: a dim, centered run of Unicode line
@@ -534,7 +1380,7 @@ impl<W: Write> Formatter<W> {
 
         let prefix_width = prefix.chars().count();
         let available_width = self.style.wrap_width.saturating_sub(prefix_width);
-        let rule_width = self.visible_width(&rule);
+        let rule_width = display_width(&rule);
         let padding = available_width.saturating_sub(rule_width) / 2;
 
         // The ANSI style carries a non-empty reset sequence; plain ASCII does
@@ -558,10 +1404,11 @@ impl<W: Write> Formatter<W> {
 
     fn write_table_paragraph(
         &mut self,
-        rows: &[TableRow],
+        table: &Paragraph,
         prefix: &str,
         continuation_prefix: &str,
     ) -> std::io::Result<()> {
+        let rows = table.rows();
         if rows.is_empty() {
             return Ok(());
         }
@@ -571,6 +1418,13 @@ impl<W: Write> Formatter<W> {
             return Ok(());
         }
 
+        // The caption sits directly above the top border.
+        let mut prefix = prefix;
+        if !table.caption().is_empty() {
+            self.write_text_paragraph(table.caption(), prefix, continuation_prefix)?;
+            prefix = continuation_prefix;
+        }
+
         // Pre-render each cell to its formatted string representation. Newlines
         // within cell content are flattened to spaces; line breaks are
         // re-introduced later by wrapping each cell to its column width.
@@ -613,7 +1467,7 @@ impl<W: Write> Formatter<W> {
         let mut minimum = vec![0usize; column_count];
         for row in &cell_text {
             for (col, text) in row.iter().enumerate() {
-                let w = self.visible_width(text);
+                let w = display_width(text);
                 if w > natural[col] {
                     natural[col] = w;
                 }
@@ -649,7 +1503,7 @@ impl<W: Write> Formatter<W> {
                 } else {
                     text.clone()
                 };
-                wrapped_row.push(self.wrap_formatted_to_width(&styled, widths[col]));
+                wrapped_row.push(layout::wrap(&styled, widths[col], &self.style));
             }
             wrapped.push(wrapped_row);
         }
@@ -700,9 +1554,21 @@ impl<W: Write> Formatter<W> {
                 write!(self.writer, "{}{}", border_prefix, vertical)?;
                 for (col, cell_lines) in wrapped_row.iter().enumerate() {
                     let text = cell_lines.get(line_idx).map(String::as_str).unwrap_or("");
-                    let visible = self.visible_width(text);
+                    let visible = display_width(text);
                     let pad = widths[col].saturating_sub(visible);
-                    write!(self.writer, " {}{} {}", text, " ".repeat(pad), vertical)?;
+                    let (before, after) = match table.column_alignment(col) {
+                        Alignment::Left => (0, pad),
+                        Alignment::Center => (pad / 2, pad - pad / 2),
+                        Alignment::Right => (pad, 0),
+                    };
+                    write!(
+                        self.writer,
+                        " {}{}{} {}",
+                        " ".repeat(before),
+                        text,
+                        " ".repeat(after),
+                        vertical
+                    )?;
                 }
                 writeln!(self.writer)?;
             }
@@ -720,214 +1586,67 @@ impl<W: Write> Formatter<W> {
     /// Returns the visible width of the widest whitespace-delimited word in
     /// `text`, ignoring any embedded ANSI/OSC8 escape sequences.
     fn longest_word_width(&self, text: &str) -> usize {
-        self.tokenize_for_wrap(text)
+        layout::tokenize(text)
             .into_iter()
             .filter(|(is_whitespace, _)| !is_whitespace)
-            .map(|(_, token)| self.visible_width(&token))
+            .map(|(_, token)| display_width(&token))
             .max()
             .unwrap_or(0)
     }
 
-    /// Word-wraps already-formatted text (which may embed ANSI/OSC8 escape
-    /// sequences) to the given visible `width`, returning one string per
-    /// physical line. Active styles and hyperlinks are closed at the end of a
-    /// line and reopened at the start of the next so each line stands on its
-    /// own — important because table cells are padded and bordered
-    /// independently. Words wider than `width` are hard-broken.
-    fn wrap_formatted_to_width(&self, text: &str, width: usize) -> Vec<String> {
-        let width = width.max(1);
-        let mut lines: Vec<String> = Vec::new();
-        let mut active_styles: Vec<InlineStyle> = Vec::new();
-        let mut active_osc_links: Vec<Osc8Link> = Vec::new();
-
-        let mut current = String::new();
-        let mut current_width = 0usize;
-        let mut pending_whitespace = 0usize;
-
-        for (is_whitespace, token) in self.tokenize_for_wrap(text) {
-            if is_whitespace {
-                pending_whitespace += token.chars().count();
-                continue;
-            }
-
-            let mut word = token;
-            loop {
-                let word_width = self.visible_width(&word);
-                let whitespace_width = if current_width == 0 {
-                    0
-                } else {
-                    pending_whitespace
-                };
-
-                if current_width + whitespace_width + word_width <= width {
-                    if whitespace_width > 0 {
-                        current.push_str(&" ".repeat(whitespace_width));
-                        current_width += whitespace_width;
-                    }
-                    pending_whitespace = 0;
-                    current.push_str(&word);
-                    current_width += word_width;
-                    self.update_active_styles_from_text(&word, &mut active_styles);
-                    self.update_active_osc_links_from_text(&word, &mut active_osc_links);
-                    break;
-                }
-
-                // The word does not fit. If the line already has content, end
-                // it and retry the word on a fresh line.
-                if current_width > 0 {
-                    current.push_str(&self.close_active(&active_styles, &active_osc_links));
-                    lines.push(std::mem::take(&mut current));
-                    current = self.open_active(&active_styles, &active_osc_links);
-                    current_width = 0;
-                    pending_whitespace = 0;
-                    continue;
-                }
-
-                // The line is empty yet the word is still too wide: hard-break
-                // it so the column never overflows.
-                let (head, tail) = self.split_at_visible_width(&word, width);
-                if head.is_empty() {
-                    // Safety valve: never loop forever on unsplittable input.
-                    current.push_str(&word);
-                    self.update_active_styles_from_text(&word, &mut active_styles);
-                    self.update_active_osc_links_from_text(&word, &mut active_osc_links);
-                    current_width = self.visible_width(&current);
-                    pending_whitespace = 0;
-                    break;
-                }
-                self.update_active_styles_from_text(&head, &mut active_styles);
-                self.update_active_osc_links_from_text(&head, &mut active_osc_links);
-                let mut finished = head;
-                finished.push_str(&self.close_active(&active_styles, &active_osc_links));
-                lines.push(finished);
-                current = self.open_active(&active_styles, &active_osc_links);
-                current_width = 0;
-                pending_whitespace = 0;
-                word = tail;
-            }
-        }
-
-        if current_width > 0 || lines.is_empty() {
-            lines.push(current);
-        }
-
-        lines
-    }
-
-    /// Builds the escape sequence that closes every currently-active style and
-    /// hyperlink, mirroring [`Self::write_line_break`] but as a string.
-    fn close_active(&self, active_styles: &[InlineStyle], active_osc_links: &[Osc8Link]) -> String {
-        let mut out = String::new();
-        for style in active_styles.iter().rev() {
-            if let Some(tags) = self.style.text_styles.get(style) {
-                out.push_str(&tags.end);
-            }
-        }
-        if self.style.enable_osc8_hyperlinks {
-            for _ in active_osc_links.iter().rev() {
-                out.push_str(&self.osc8_end());
-            }
-        }
-        out
-    }
-
-    /// Builds the escape sequence that re-opens every currently-active
-    /// hyperlink and style at the start of a continuation line.
-    fn open_active(&self, active_styles: &[InlineStyle], active_osc_links: &[Osc8Link]) -> String {
-        let mut out = String::new();
-        if self.style.enable_osc8_hyperlinks {
-            for link in active_osc_links {
-                out.push_str(&self.osc8_start(link));
-            }
-        }
-        for style in active_styles {
-            if let Some(tags) = self.style.text_styles.get(style) {
-                out.push_str(&tags.begin);
-            }
-        }
-        out
-    }
-
-    /// Splits `text` so the head occupies at most `max` visible columns,
-    /// returning `(head, tail)`. Embedded escape sequences are copied without
-    /// counting toward the width and are never split apart.
-    fn split_at_visible_width(&self, text: &str, max: usize) -> (String, String) {
-        let chars: Vec<char> = text.chars().collect();
-        let mut head = String::new();
-        let mut visible = 0usize;
-        let mut i = 0usize;
-
-        while i < chars.len() {
-            let ch = chars[i];
-            if ch == '\x1b' {
-                let start = i;
-                i += 1; // ESC
-                if i < chars.len() && chars[i] == '[' {
-                    i += 1;
-                    while i < chars.len() {
-                        let c = chars[i];
-                        i += 1;
-                        if c.is_ascii_alphabetic() {
-                            break;
-                        }
-                    }
-                } else if i < chars.len() && chars[i] == ']' {
-                    i += 1;
-                    while i < chars.len() {
-                        let c = chars[i];
-                        if c == '\x07' {
-                            i += 1;
-                            break;
-                        }
-                        if c == '\x1b' {
-                            i += 1;
-                            if i < chars.len() && chars[i] == '\\' {
-                                i += 1;
-                            }
-                            break;
-                        }
-                        i += 1;
-                    }
-                }
-                head.extend(&chars[start..i]);
-                continue;
-            }
-
-            let char_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-            if visible + char_width > max && visible > 0 {
-                break;
-            }
-            head.push(ch);
-            visible += char_width;
-            i += 1;
-            if visible >= max {
-                break;
-            }
-        }
-
-        let tail: String = chars[i..].iter().collect();
-        (head, tail)
-    }
-
-    fn write_checklist_items(
-        &mut self,
-        items: &[ChecklistItem],
+    /// Queues each term in bold, followed by its definition indented by
+    /// [`DEFINITION_INDENT`].
+    fn push_definition_list<'a>(
+        tasks: &mut Vec<RenderTask<'a>>,
+        items: &'a [DefinitionItem],
         prefix: &str,
         continuation_prefix: &str,
-    ) -> std::io::Result<()> {
-        for item in items {
-            self.write_checklist_item(item, prefix, continuation_prefix)?;
+        blank_line_prefix: &str,
+    ) {
+        let indent = format!("{}{}", continuation_prefix, DEFINITION_INDENT);
+        for (idx, item) in items.iter().enumerate() {
+            if idx > 0 {
+                tasks.push(RenderTask::BlankLines {
+                    prefix: blank_line_prefix.to_string(),
+                    count: 1,
+                });
+            }
+            let term_prefix = if idx == 0 {
+                prefix
+            } else {
+                continuation_prefix
+            };
+            if !item.term.is_empty() {
+                tasks.push(RenderTask::DefinitionTerm {
+                    term: &item.term,
+                    prefix: term_prefix.to_string(),
+                    continuation_prefix: continuation_prefix.to_string(),
+                });
+            }
+            tasks.push(RenderTask::Paragraphs(ParagraphRun::new(
+                &item.definition,
+                Vec::new(),
+                indent.as_str(),
+                indent.as_str(),
+                indent.as_str(),
+            )));
         }
-        Ok(())
     }
 
+    /// Writes the checkbox line of `item` and returns the prefix its nested
+    /// items are written with.
     fn write_checklist_item(
         &mut self,
         item: &ChecklistItem,
         prefix: &str,
         continuation_prefix: &str,
-    ) -> std::io::Result<()> {
-        let marker = if item.checked { "[✓] " } else { "[ ] " };
+    ) -> std::io::Result<String> {
+        let checkbox = match item.state() {
+            ChecklistState::Unchecked => "[ ]",
+            ChecklistState::Checked => "[✓]",
+            ChecklistState::Mixed => "[~]",
+        };
+        let marker = format!("{}{}", checkbox, " ".repeat(self.style.list_indent));
         let first_prefix = format!("{}{}", prefix, marker);
         let continuation = format!(
             "{}{}",
@@ -935,20 +1654,11 @@ impl<W: Write> Formatter<W> {
             " ".repeat(marker.chars().count())
         );
 
+        self.note_prefixes(&[&first_prefix, &continuation]);
         self.write_checklist_text(item, &first_prefix, &continuation)?;
         writeln!(self.writer)?;
 
-        if !item.children.is_empty() {
-            let child_prefix = continuation.clone();
-            let child_continuation = continuation.clone();
-            self.write_checklist_items(
-                &item.children,
-                child_prefix.as_str(),
-                child_continuation.as_str(),
-            )?;
-        }
-
-        Ok(())
+        Ok(continuation)
     }
 
     fn write_code_block_paragraph(
@@ -987,23 +1697,18 @@ impl<W: Write> Formatter<W> {
             return Ok(());
         }
 
-        let mut remaining = line;
-        while !remaining.is_empty() {
-            let mut end_idx = 0;
-            for (count, (idx, ch)) in remaining.char_indices().enumerate() {
-                if count >= available_width {
-                    break;
-                }
-                end_idx = idx + ch.len_utf8();
-            }
-
-            if end_idx == 0 {
-                end_idx = remaining.len();
+        if self.style.code_overflow == CodeOverflow::Truncate {
+            if line.chars().count() <= available_width {
+                writeln!(self.writer, "{}{}", continuation_prefix, line)?;
+            } else {
+                let kept: String = line.chars().take(available_width - 1).collect();
+                writeln!(self.writer, "{}{}…", continuation_prefix, kept)?;
             }
+            return Ok(());
+        }
 
-            let (chunk, rest) = remaining.split_at(end_idx);
+        for chunk in split_at_width(line, available_width) {
             writeln!(self.writer, "{}{}", continuation_prefix, chunk)?;
-            remaining = rest;
         }
 
         Ok(())
@@ -1012,7 +1717,11 @@ impl<W: Write> Formatter<W> {
     fn write_code_block_fence(&mut self, prefix: &str) -> std::io::Result<()> {
         const MIN_FENCE_WIDTH: usize = 4;
         let available_width = self.style.wrap_width.saturating_sub(prefix.chars().count());
-        let dash_count = available_width.max(MIN_FENCE_WIDTH);
+        let clamped_width = match self.style.max_code_fence_width {
+            Some(max_width) => available_width.min(max_width),
+            None => available_width,
+        };
+        let dash_count = clamped_width.max(MIN_FENCE_WIDTH);
         writeln!(self.writer, "{}{}", prefix, "-".repeat(dash_count))
     }
 
@@ -1034,6 +1743,7 @@ impl<W: Write> Formatter<W> {
     }
 
     fn write_blank_lines_with_prefix(&mut self, prefix: &str, count: usize) -> std::io::Result<()> {
+        self.note_prefixes(&[prefix]);
         for _ in 0..count {
             if prefix.is_empty() {
                 writeln!(self.writer)?;
@@ -1049,10 +1759,11 @@ impl<W: Write> Formatter<W> {
         previous_type: Option<ParagraphType>,
         current_type: ParagraphType,
     ) -> usize {
+        let spacing = &self.style.heading_spacing;
         match current_type {
-            ParagraphType::Header1 => 3,
-            ParagraphType::Header2 => 3,
-            ParagraphType::Header3 => 2,
+            ParagraphType::Header1 => spacing.before[0],
+            ParagraphType::Header2 => spacing.before[1],
+            ParagraphType::Header3 => spacing.before[2],
             ParagraphType::HorizontalRule => 2,
             _ => match previous_type {
                 Some(_) => 1,
@@ -1062,10 +1773,11 @@ impl<W: Write> Formatter<W> {
     }
 
     fn blank_lines_after(&self, paragraph_type: ParagraphType) -> usize {
+        let spacing = &self.style.heading_spacing;
         match paragraph_type {
-            ParagraphType::Header1 => 3,
-            ParagraphType::Header2 => 2,
-            ParagraphType::Header3 => 1,
+            ParagraphType::Header1 => spacing.after[0],
+            ParagraphType::Header2 => spacing.after[1],
+            ParagraphType::Header3 => spacing.after[2],
             ParagraphType::HorizontalRule => 2,
             _ => 0,
         }
@@ -1091,11 +1803,41 @@ impl<W: Write> Formatter<W> {
             return Ok((String::new(), 0));
         }
 
-        let bold_text = self.apply_bold(&trimmed);
-        let visible_width = self.visible_width(&bold_text);
+        let mut bold_text = self.apply_bold(&trimmed);
+        if let Some(tags) = &self.style.heading_style {
+            bold_text = format!("{}{}{}", tags.begin, bold_text, tags.end);
+        }
+        let visible_width = display_width(&bold_text);
         Ok((bold_text, visible_width))
     }
 
+    /// Returns the content of a heading at `level`, prefixed with its section
+    /// number when [`FormattingStyle::number_headings`] is set. Levels above
+    /// the first heading seen are left out, so a document starting at
+    /// `Header2` is numbered `1`, `2`, … rather than `0.1`, `0.2`.
+    fn numbered_heading_content(&mut self, spans: &[Span], level: usize) -> Vec<Span> {
+        if !self.style.number_headings {
+            return spans.to_vec();
+        }
+
+        self.heading_counters[level - 1] += 1;
+        for counter in &mut self.heading_counters[level..] {
+            *counter = 0;
+        }
+        let counters = &self.heading_counters[..level];
+        let first = counters.iter().position(|&n| n > 0).unwrap_or(0);
+        let number = counters[first..]
+            .iter()
+            .map(usize::to_string)
+            .collect::<Vec<_>>()
+            .join(".");
+
+        let mut content = Vec::with_capacity(spans.len() + 1);
+        content.push(Span::new_text(format!("{} ", number)));
+        content.extend_from_slice(spans);
+        content
+    }
+
     fn write_header1_paragraph(&mut self, spans: &[Span], prefix: &str) -> std::io::Result<()> {
         let (bold_text, visible_width) = self.render_heading_text(spans)?;
 
@@ -1226,10 +1968,46 @@ impl<W: Write> Formatter<W> {
             return self.collect_link_text(span, parts);
         }
 
+        if span.style == InlineStyle::Raw {
+            return Ok(());
+        }
+
+        if matches!(
+            span.style,
+            InlineStyle::Superscript | InlineStyle::Subscript
+        ) {
+            return self.collect_script_text(span, parts);
+        }
+
+        if span.style == InlineStyle::Abbreviation {
+            return self.collect_abbreviation_text(span, parts);
+        }
+
+        if span.style == InlineStyle::Color {
+            return self.collect_colored_text(span, parts);
+        }
+
+        if span.style == InlineStyle::Quotation {
+            return self.collect_quotation_text(span, parts);
+        }
+
+        // Without a terminal style for key names, bracket them (`[Ctrl]`).
+        if span.style == InlineStyle::Keyboard && self.inline_tags(span.style).is_none() {
+            parts.push("[".to_string());
+            if !span.text.is_empty() {
+                self.push_text_fragment(parts, &span.text);
+            }
+            for child in &span.children {
+                self.collect_formatted_text(child, parts)?;
+            }
+            parts.push("]".to_string());
+            return Ok(());
+        }
+
         if span.children.is_empty() {
             self.push_text_fragment(parts, &span.text);
         } else {
-            if let Some(style_tags) = self.style.text_styles.get(&span.style) {
+            if let Some(style_tags) = self.inline_tags(span.style) {
                 parts.push(style_tags.begin.clone());
             }
 
@@ -1241,7 +2019,7 @@ impl<W: Write> Formatter<W> {
                 self.collect_formatted_text(child, parts)?;
             }
 
-            if let Some(style_tags) = self.style.text_styles.get(&span.style) {
+            if let Some(style_tags) = self.inline_tags(span.style) {
                 parts.push(style_tags.end.clone());
             }
         }
@@ -1249,6 +2027,126 @@ impl<W: Write> Formatter<W> {
         Ok(())
     }
 
+    /// Returns the tags wrapping `style`: its terminal styling if the style
+    /// defines one, otherwise its plain-text fallback marker.
+    fn inline_tags(&self, style: InlineStyle) -> Option<&StyleTags> {
+        self.style
+            .text_styles
+            .get(&style)
+            .or_else(|| self.style.fallback_markers.get(&style))
+    }
+
+    /// Renders superscript and subscript spans using Unicode script
+    /// characters when every character has one, falling back to `^(...)` and
+    /// `_(...)` otherwise.
+    fn collect_script_text(&mut self, span: &Span, parts: &mut Vec<String>) -> std::io::Result<()> {
+        let superscript = span.style == InlineStyle::Superscript;
+        if is_unstyled(span) {
+            let mut text = String::new();
+            Self::collect_visible_text(span, &mut text);
+            if let Some(converted) = to_script_chars(&text, superscript) {
+                self.push_text_fragment(parts, &converted);
+                return Ok(());
+            }
+        }
+
+        parts.push(if superscript { "^(" } else { "_(" }.to_string());
+        if !span.text.is_empty() {
+            self.push_text_fragment(parts, &span.text);
+        }
+        for child in &span.children {
+            self.collect_formatted_text(child, parts)?;
+        }
+        parts.push(")".to_string());
+        Ok(())
+    }
+
+    /// Renders an abbreviation inline, followed by a footnote marker for its
+    /// expansion when `abbreviation_footnotes` is enabled.
+    fn collect_abbreviation_text(
+        &mut self,
+        span: &Span,
+        parts: &mut Vec<String>,
+    ) -> std::io::Result<()> {
+        if !span.text.is_empty() {
+            self.push_text_fragment(parts, &span.text);
+        }
+        for child in &span.children {
+            self.collect_formatted_text(child, parts)?;
+        }
+
+        if let Some(expansion) = &span.link_target {
+            if self.style.abbreviation_footnotes && self.style.link_footnotes {
+                let index = self.register_footnote(expansion, false);
+                parts.push(self.inline_link_index(index));
+            }
+        }
+        Ok(())
+    }
+
+    /// Renders an inline quotation between quotation marks, alternating
+    /// double and single marks for nested quotations.
+    fn collect_quotation_text(
+        &mut self,
+        span: &Span,
+        parts: &mut Vec<String>,
+    ) -> std::io::Result<()> {
+        let (open, close) = match (self.style.typographic_quotes, self.quote_depth % 2) {
+            (true, 0) => ("“", "”"),
+            (true, _) => ("‘", "’"),
+            (false, 0) => ("\"", "\""),
+            (false, _) => ("'", "'"),
+        };
+        parts.push(open.to_string());
+        if !span.text.is_empty() {
+            self.push_text_fragment(parts, &span.text);
+        }
+        self.quote_depth += 1;
+        for child in &span.children {
+            self.collect_formatted_text(child, parts)?;
+        }
+        self.quote_depth -= 1;
+        parts.push(close.to_string());
+        Ok(())
+    }
+
+    /// Renders a colored span, switching the foreground color around its
+    /// content. A nested color hands back to the enclosing one when it ends.
+    fn collect_colored_text(
+        &mut self,
+        span: &Span,
+        parts: &mut Vec<String>,
+    ) -> std::io::Result<()> {
+        let begin = span
+            .color
+            .filter(|_| self.style.enable_colors)
+            .map(foreground_sequence);
+        let outer = match &begin {
+            Some(begin) => {
+                parts.push(begin.clone());
+                self.current_color.replace(begin.clone())
+            }
+            None => self.current_color.clone(),
+        };
+
+        if !span.text.is_empty() {
+            self.push_text_fragment(parts, &span.text);
+        }
+        for child in &span.children {
+            self.collect_formatted_text(child, parts)?;
+        }
+
+        if begin.is_some() {
+            parts.push(
+                outer
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_FOREGROUND.to_string()),
+            );
+            self.current_color = outer;
+        }
+        Ok(())
+    }
+
     fn collect_link_text(&mut self, span: &Span, parts: &mut Vec<String>) -> std::io::Result<()> {
         let Some(target) = span.link_target.as_ref() else {
             if !span.text.is_empty() {
@@ -1266,44 +2164,45 @@ impl<W: Write> Formatter<W> {
             None
         };
 
+        let link_tags = self.style.text_styles.get(&InlineStyle::Link).cloned();
+
+        let scheme = link_scheme(target);
+
         if !span.has_content() {
+            let address = scheme.map_or(target.as_str(), |(_, address)| address);
             let display = if let Some(link) = &hyperlink {
-                self.osc8_wrap(link, target)
+                self.osc8_wrap(link, address)
             } else {
-                target.clone()
+                address.to_string()
             };
-            self.push_text_fragment(parts, &display);
-            return Ok(());
-        }
-
-        if Self::is_mailto_with_matching_description(span, target) {
-            if let Some(link) = &hyperlink {
-                parts.push(self.osc8_start(link));
-            }
-
-            if !span.text.is_empty() {
-                self.push_text_fragment(parts, &span.text);
+            if let Some(tags) = &link_tags {
+                parts.push(tags.begin.clone());
             }
-
-            for child in &span.children {
-                self.collect_formatted_text(child, parts)?;
-            }
-
-            if hyperlink.is_some() {
-                parts.push(self.osc8_end());
+            self.push_text_fragment(parts, &display);
+            if let Some(tags) = &link_tags {
+                parts.push(tags.end.clone());
             }
-
             return Ok(());
         }
 
-        let footnote_index = if self.style.link_footnotes {
-            Some(self.register_numbered_link(target))
-        } else {
-            None
+        // A description repeating the address needs neither a footnote nor
+        // the address inline.
+        let mut inline_address = None;
+        let footnote_index = match scheme {
+            Some((_, address)) if Self::description_matches(span, address) => None,
+            Some((scheme, address)) if !scheme.footnote => {
+                inline_address = Some(address);
+                None
+            }
+            _ if self.style.link_footnotes => self.register_numbered_link(target),
+            _ => None,
         };
 
         if let Some(link) = &hyperlink {
-            parts.push(self.osc8_start(link));
+            parts.push(link.start_sequence());
+        }
+        if let Some(tags) = &link_tags {
+            parts.push(tags.begin.clone());
         }
 
         if !span.text.is_empty() {
@@ -1314,21 +2213,23 @@ impl<W: Write> Formatter<W> {
             self.collect_formatted_text(child, parts)?;
         }
 
+        if let Some(tags) = &link_tags {
+            parts.push(tags.end.clone());
+        }
         if hyperlink.is_some() {
-            parts.push(self.osc8_end());
+            parts.push(OSC8_END.to_string());
         }
 
         if let Some(index) = footnote_index {
             parts.push(self.inline_link_index(index));
         }
+        if let Some(address) = inline_address {
+            self.push_text_fragment(parts, &format!(" ({})", address));
+        }
         Ok(())
     }
 
-    fn is_mailto_with_matching_description(span: &Span, target: &str) -> bool {
-        let Some(address) = target.strip_prefix("mailto:") else {
-            return false;
-        };
-
+    fn description_matches(span: &Span, address: &str) -> bool {
         let mut description = String::new();
         Self::collect_visible_text(span, &mut description);
 
@@ -1369,12 +2270,28 @@ impl<W: Write> Formatter<W> {
     }
 
     fn next_osc8_link(&mut self, target: &str) -> Osc8Link {
-        let id = self.next_hyperlink_id.to_string();
-        self.next_hyperlink_id += 1;
+        let id = match self.hyperlink_ids.get(target) {
+            Some(id) => id.clone(),
+            None => {
+                let id = self.next_hyperlink_id.to_string();
+                self.next_hyperlink_id += 1;
+                self.hyperlink_ids.insert(target.to_string(), id.clone());
+                id
+            }
+        };
         Osc8Link::new(Some(id), target.to_string())
     }
 
-    fn register_numbered_link(&mut self, target: &str) -> usize {
+    fn register_numbered_link(&mut self, target: &str) -> Option<usize> {
+        if let Some(filter) = &self.style.footnote_filter {
+            if !filter(target) {
+                return None;
+            }
+        }
+        Some(self.register_footnote(target, true))
+    }
+
+    fn register_footnote(&mut self, target: &str, is_link: bool) -> usize {
         if let Some(&index) = self.link_indices.get(target) {
             return index;
         }
@@ -1384,27 +2301,15 @@ impl<W: Write> Formatter<W> {
         self.pending_links.push(LinkReference {
             index,
             target: target.to_string(),
+            is_link,
         });
         self.link_indices.insert(target.to_string(), index);
         index
     }
 
-    fn osc8_start(&self, link: &Osc8Link) -> String {
-        let params = link
-            .id
-            .as_ref()
-            .map(|id| format!("id={}", id))
-            .unwrap_or_default();
-        format!("\x1b]8;{};{}\x1b\\", params, link.target)
-    }
-
-    fn osc8_end(&self) -> String {
-        "\x1b]8;;\x1b\\".to_string()
-    }
-
     fn osc8_wrap(&self, link: &Osc8Link, text: &str) -> String {
         if self.style.enable_osc8_hyperlinks {
-            format!("{}{}{}", self.osc8_start(link), text, self.osc8_end())
+            format!("{}{}{}", link.start_sequence(), text, OSC8_END)
         } else {
             text.to_string()
         }
@@ -1464,7 +2369,7 @@ impl<W: Write> Formatter<W> {
             }
         }
 
-        let mut active_styles: Vec<InlineStyle> = Vec::new();
+        let mut active_styles: Vec<StyleTags> = Vec::new();
         let mut active_osc_links: Vec<Osc8Link> = Vec::new();
 
         if has_forced_breaks {
@@ -1586,7 +2491,7 @@ impl<W: Write> Formatter<W> {
             return;
         }
 
-        let tokens = self.tokenize_for_wrap(trimmed_text);
+        let tokens = layout::tokenize(trimmed_text);
 
         if tokens.is_empty() {
             if current_width > 0 {
@@ -1601,7 +2506,7 @@ impl<W: Write> Formatter<W> {
                 continue;
             }
 
-            let word_width = self.visible_width(&token);
+            let word_width = display_width(&token);
             let whitespace_width = if current_width == 0 {
                 0
             } else {
@@ -1635,40 +2540,12 @@ impl<W: Write> Formatter<W> {
         }
     }
 
-    fn tokenize_for_wrap(&self, text: &str) -> Vec<(bool, String)> {
-        let mut tokens = Vec::new();
-        let mut current = String::new();
-        let mut current_kind: Option<bool> = None;
-
-        for ch in text.chars() {
-            let is_whitespace = ch.is_whitespace();
-            match current_kind {
-                Some(kind) if kind == is_whitespace => current.push(ch),
-                Some(kind) => {
-                    tokens.push((kind, std::mem::take(&mut current)));
-                    current.push(ch);
-                    current_kind = Some(is_whitespace);
-                }
-                None => {
-                    current.push(ch);
-                    current_kind = Some(is_whitespace);
-                }
-            }
-        }
-
-        if let Some(kind) = current_kind {
-            tokens.push((kind, current));
-        }
-
-        tokens
-    }
-
     fn write_wrapped_line(
         &mut self,
         text: &str,
         initial_width: usize,
         continuation_prefix: &str,
-        active_styles: &mut Vec<InlineStyle>,
+        active_styles: &mut Vec<StyleTags>,
         active_osc_links: &mut Vec<Osc8Link>,
     ) -> std::io::Result<()> {
         if text.is_empty() {
@@ -1689,7 +2566,7 @@ impl<W: Write> Formatter<W> {
             line_width += 1;
         }
 
-        let tokens = self.tokenize_for_wrap(trimmed_text);
+        let tokens = layout::tokenize(trimmed_text);
 
         if tokens.is_empty() {
             if !current_line.is_empty() {
@@ -1706,7 +2583,7 @@ impl<W: Write> Formatter<W> {
                 continue;
             }
 
-            let word_width = self.visible_width(&token);
+            let word_width = display_width(&token);
             let whitespace_width = if current_line.is_empty() {
                 0
             } else {
@@ -1732,8 +2609,8 @@ impl<W: Write> Formatter<W> {
 
             current_line.push_str(&token);
             line_width += word_width;
-            self.update_active_styles_from_text(&token, active_styles);
-            self.update_active_osc_links_from_text(&token, active_osc_links);
+            layout::update_active_styles(&token, &self.style, active_styles);
+            layout::update_active_osc_links(&token, &self.style, active_osc_links);
         }
 
         if !current_line.is_empty() {
@@ -1747,7 +2624,7 @@ impl<W: Write> Formatter<W> {
     fn write_line_break(
         &mut self,
         continuation_prefix: &str,
-        active_styles: &[InlineStyle],
+        active_styles: &[StyleTags],
         active_osc_links: &[Osc8Link],
     ) -> std::io::Result<()> {
         self.write_style_resets(active_styles)?;
@@ -1759,11 +2636,9 @@ impl<W: Write> Formatter<W> {
         Ok(())
     }
 
-    fn write_style_resets(&mut self, active_styles: &[InlineStyle]) -> std::io::Result<()> {
-        for style in active_styles.iter().rev() {
-            if let Some(tags) = self.style.text_styles.get(style) {
-                write!(self.writer, "{}", tags.end)?;
-            }
+    fn write_style_resets(&mut self, active_styles: &[StyleTags]) -> std::io::Result<()> {
+        for tags in active_styles.iter().rev() {
+            write!(self.writer, "{}", tags.end)?;
         }
         Ok(())
     }
@@ -1771,17 +2646,15 @@ impl<W: Write> Formatter<W> {
     fn write_osc8_resets(&mut self, active_osc_links: &[Osc8Link]) -> std::io::Result<()> {
         if self.style.enable_osc8_hyperlinks {
             for _ in active_osc_links.iter().rev() {
-                write!(self.writer, "{}", self.osc8_end())?;
+                write!(self.writer, "{}", OSC8_END)?;
             }
         }
         Ok(())
     }
 
-    fn reapply_active_styles(&mut self, active_styles: &[InlineStyle]) -> std::io::Result<()> {
-        for style in active_styles {
-            if let Some(tags) = self.style.text_styles.get(style) {
-                write!(self.writer, "{}", tags.begin)?;
-            }
+    fn reapply_active_styles(&mut self, active_styles: &[StyleTags]) -> std::io::Result<()> {
+        for tags in active_styles {
+            write!(self.writer, "{}", tags.begin)?;
         }
         Ok(())
     }
@@ -1789,71 +2662,110 @@ impl<W: Write> Formatter<W> {
     fn reapply_osc8_links(&mut self, active_osc_links: &[Osc8Link]) -> std::io::Result<()> {
         if self.style.enable_osc8_hyperlinks {
             for link in active_osc_links {
-                write!(self.writer, "{}", self.osc8_start(link))?;
+                write!(self.writer, "{}", link.start_sequence())?;
             }
         }
         Ok(())
     }
+}
 
-    fn update_active_styles_from_text(&self, text: &str, active_styles: &mut Vec<InlineStyle>) {
-        for capture in ANSI_ESCAPE_REGEX.find_iter(text) {
-            let sequence = capture.as_str();
-            if let Some(style) = self.find_style_start(sequence) {
-                active_styles.push(style);
-            } else if let Some(style) = self.find_style_end(sequence) {
-                if let Some(idx) = active_styles.iter().rposition(|s| *s == style) {
-                    active_styles.remove(idx);
-                }
-            }
-        }
+/// Returns the SGR sequence selecting `color` as the foreground color.
+fn foreground_sequence(color: Color) -> String {
+    match color {
+        Color::Named(index) => format!("\x1b[{}m", 30 + index.min(7)),
+        Color::Rgb(r, g, b) => format!("\x1b[38;2;{};{};{}m", r, g, b),
     }
+}
 
-    fn update_active_osc_links_from_text(&self, text: &str, active_osc_links: &mut Vec<Osc8Link>) {
-        if !self.style.enable_osc8_hyperlinks {
-            return;
-        }
+/// Returns `true` when neither the span's children nor their descendants carry
+/// any styling of their own.
+fn is_unstyled(span: &Span) -> bool {
+    span.children
+        .iter()
+        .all(|child| child.style == InlineStyle::None && is_unstyled(child))
+}
 
-        for capture in OSC8_SEQUENCE_REGEX.captures_iter(text) {
-            let params = capture.get(1).map(|m| m.as_str()).unwrap_or("");
-            let target = capture.get(2).map(|m| m.as_str()).unwrap_or("");
-            if target.is_empty() {
-                let _ = active_osc_links.pop();
-            } else {
-                let id = params
-                    .split(':')
-                    .find_map(|param| param.strip_prefix("id="))
-                    .map(|value| value.to_string());
-                active_osc_links.push(Osc8Link::new(id, target.to_string()));
-            }
-        }
-    }
+/// Counts the items of a checklist, nested ones included, as `(done, total)`.
+/// An item is done when its box is rendered checked.
+fn checklist_progress(items: &[ChecklistItem]) -> (usize, usize) {
+    items.iter().fold((0, 0), |(done, total), item| {
+        let (nested_done, nested_total) = checklist_progress(&item.children);
+        let checked = usize::from(item.state() == ChecklistState::Checked);
+        (done + checked + nested_done, total + 1 + nested_total)
+    })
+}
 
-    fn find_style_start(&self, sequence: &str) -> Option<InlineStyle> {
-        self.style.text_styles.iter().find_map(|(style, tags)| {
-            if tags.begin == sequence {
-                Some(*style)
-            } else {
-                None
-            }
-        })
+/// Maps `text` to Unicode superscript or subscript characters, or returns
+/// `None` if any character has no such form.
+fn to_script_chars(text: &str, superscript: bool) -> Option<String> {
+    if text.is_empty() {
+        return None;
     }
-
-    fn find_style_end(&self, sequence: &str) -> Option<InlineStyle> {
-        self.style.text_styles.iter().find_map(|(style, tags)| {
-            if tags.end == sequence {
-                Some(*style)
+    text.chars()
+        .map(|ch| {
+            if superscript {
+                superscript_char(ch)
             } else {
-                None
+                subscript_char(ch)
             }
         })
-    }
+        .collect()
+}
 
-    fn visible_width(&self, text: &str) -> usize {
-        // Remove ANSI escape sequences for width calculation
-        let without_ansi = ANSI_ESCAPE_REGEX.replace_all(text, "");
-        let visible_text = OSC8_ESCAPE_REGEX.replace_all(&without_ansi, "");
-        UnicodeWidthStr::width(visible_text.as_ref())
-    }
+fn superscript_char(ch: char) -> Option<char> {
+    Some(match ch {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'i' => 'ⁱ',
+        'n' => 'ⁿ',
+        _ => return None,
+    })
+}
+
+fn subscript_char(ch: char) -> Option<char> {
+    Some(match ch {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
 }
 
 /// Chooses a rendered width for each table column so the whole table fits
@@ -1951,6 +2863,64 @@ fn proportional_split(amount: usize, weights: &[usize]) -> Vec<usize> {
     out
 }
 
+/// A URI scheme whose links are shown by their bare address, without the
+/// scheme prefix.
+struct LinkScheme {
+    prefix: &'static str,
+    /// Whether a link described by something other than its address gets a
+    /// footnote. Otherwise the address follows the description inline.
+    footnote: bool,
+}
+
+/// Schemes with dedicated rendering. Links of any other scheme are
+/// footnoted with their full target.
+const LINK_SCHEMES: &[LinkScheme] = &[
+    LinkScheme {
+        prefix: "mailto:",
+        footnote: true,
+    },
+    LinkScheme {
+        prefix: "tel:",
+        footnote: false,
+    },
+];
+
+/// Looks up the scheme of `target` in [`LINK_SCHEMES`], returning it along
+/// with the address following the prefix.
+fn link_scheme(target: &str) -> Option<(&'static LinkScheme, &str)> {
+    LINK_SCHEMES.iter().find_map(|scheme| {
+        let address = target.get(..scheme.prefix.len()).and_then(|prefix| {
+            prefix
+                .eq_ignore_ascii_case(scheme.prefix)
+                .then(|| &target[scheme.prefix.len()..])
+        })?;
+        Some((scheme, address))
+    })
+}
+
+/// Splits `text` into pieces of at most `width` characters.
+fn split_at_width(text: &str, width: usize) -> impl Iterator<Item = &str> {
+    let mut remaining = text;
+    std::iter::from_fn(move || {
+        if remaining.is_empty() {
+            return None;
+        }
+        let end = remaining
+            .char_indices()
+            .nth(width.max(1))
+            .map_or(remaining.len(), |(idx, _)| idx);
+        let (chunk, rest) = remaining.split_at(end);
+        remaining = rest;
+        Some(chunk)
+    })
+}
+
+/// Returns the text a link footnote shows for `target`: the bare address for
+/// the schemes in [`LINK_SCHEMES`], the target itself otherwise.
+fn footnote_display(target: &str) -> &str {
+    link_scheme(target).map_or(target, |(_, address)| address)
+}
+
 /// Ensures no column is allocated zero width, stealing a column from the
 /// currently widest column where possible.
 fn enforce_floor_one(widths: &mut [usize]) {
@@ -1975,6 +2945,7 @@ mod tests {
     use crate::{TableCell, TableRow};
     use std::io::Cursor;
     use std::time::{Duration, Instant};
+    use unicode_width::UnicodeWidthStr;
 
     // ----- Width-aware table rendering -------------------------------------
 
@@ -2005,6 +2976,9 @@ mod tests {
         String::from_utf8(output).unwrap()
     }
 
+    static OSC8_ESCAPE_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"\x1b]8;[^\x1b]*\x1b\\").expect("valid OSC8 escape regex"));
+
     /// Visible width of a line, ignoring ANSI/OSC8 escape sequences.
     fn visible_line_width(line: &str) -> usize {
         let without_ansi = ANSI_ESCAPE_REGEX.replace_all(line, "");
@@ -2073,6 +3047,51 @@ mod tests {
         assert_eq!(rendered, expected);
     }
 
+    #[test]
+    fn table_sizes_columns_by_display_width() {
+        // Columns are as wide as their widest cell on screen: CJK characters
+        // take up two columns and `ü` one, whatever their length in bytes.
+        let rendered = render_table_ascii(
+            vec![
+                trow(vec![th("City"), th("Country")]),
+                trow(vec![td("東京"), td("日本")]),
+                trow(vec![td("Zürich"), td("Schweiz")]),
+            ],
+            72,
+        );
+        let expected = "+--------+---------+\n\
+                        | City   | Country |\n\
+                        +--------+---------+\n\
+                        | 東京   | 日本    |\n\
+                        +--------+---------+\n\
+                        | Zürich | Schweiz |\n\
+                        +--------+---------+\n";
+        assert_eq!(rendered, expected);
+    }
+
+    #[test]
+    fn table_renders_caption_and_column_alignment() {
+        let table = Paragraph::new_table()
+            .with_rows(vec![
+                trow(vec![th("Item"), th("Price")]),
+                trow(vec![td("Coffee"), td("3.50")]),
+                trow(vec![td("Cake"), td("12.00")]),
+            ])
+            .with_caption(vec![Span::new_text("Menu")])
+            .with_alignments(vec![Alignment::Left, Alignment::Right]);
+        let mut style = FormattingStyle::ascii();
+        style.wrap_width = 72;
+        let expected = "Menu\n\
+                        +--------+-------+\n\
+                        | Item   | Price |\n\
+                        +--------+-------+\n\
+                        | Coffee |  3.50 |\n\
+                        +--------+-------+\n\
+                        | Cake   | 12.00 |\n\
+                        +--------+-------+\n";
+        assert_eq!(render_doc(doc(vec![table]), style), expected);
+    }
+
     #[test]
     fn table_uses_box_drawing_borders_in_ansi() {
         // Terminal output draws the grid with Unicode box-drawing characters,
@@ -2374,6 +3393,281 @@ mod tests {
         assert!(!result.contains("\x1b["));
     }
 
+    #[test]
+    fn test_superscript_and_subscript_use_unicode_characters() {
+        let doc = doc(vec![p_(vec![
+            span("E = mc"),
+            sup__("2"),
+            span(", H"),
+            sub__("2"),
+            span("O"),
+        ])]);
+        let result = render_doc(doc, FormattingStyle::ascii());
+        assert_eq!(result, "E = mc², H₂O\n");
+    }
+
+    #[test]
+    fn test_superscript_and_subscript_fall_back_to_ascii() {
+        let doc = doc(vec![p_(vec![
+            span("May 4"),
+            sup__("th"),
+            span(", x"),
+            sub__("i,j"),
+        ])]);
+        let result = render_doc(doc, FormattingStyle::ascii());
+        assert_eq!(result, "May 4^(th), x_(i,j)\n");
+    }
+
+    #[test]
+    fn test_keyboard_is_bracketed_in_ascii_and_reversed_in_ansi() {
+        let doc = doc(vec![p_(vec![
+            span("Press "),
+            kbd__("Ctrl"),
+            span("+"),
+            kbd__("C"),
+        ])]);
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ascii()),
+            "Press [Ctrl]+[C]\n"
+        );
+        assert_eq!(
+            render_doc(doc, FormattingStyle::ansi()),
+            "Press \x1b[7mCtrl\x1b[27m+\x1b[7mC\x1b[27m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_inline_code_is_shaded_in_ansi() {
+        let doc = doc(vec![p_(vec![
+            span("Run "),
+            code__("cargo test --workspace"),
+            span(" now"),
+        ])]);
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ascii()),
+            "Run cargo test --workspace now\n"
+        );
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ansi()),
+            "Run \x1b[100mcargo test --workspace\x1b[49m now\n\x1b[0m"
+        );
+
+        let mut style = FormattingStyle::ansi();
+        style.wrap_width = 12;
+        assert_eq!(
+            render_doc(doc, style),
+            "Run \x1b[100mcargo\x1b[49m\n\x1b[100mtest\x1b[49m\n\x1b[100m--workspace\x1b[49m\nnow\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_quotations_alternate_marks_when_nested() {
+        let doc = doc(vec![p_(vec![
+            span("She said "),
+            q_(vec![span("he wrote "), q__("hi"), span(" twice")]),
+            span("."),
+        ])]);
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ascii()),
+            "She said \"he wrote 'hi' twice\".\n"
+        );
+        assert_eq!(
+            render_doc(doc, FormattingStyle::ansi()),
+            "She said “he wrote ‘hi’ twice”.\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_ascii_markers_keep_strike_and_highlight_visible() {
+        let doc = doc(vec![p_(vec![
+            s__("old"),
+            span(" and "),
+            mark__("new"),
+            span(" but "),
+            b__("bold"),
+        ])]);
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ascii()),
+            "old and new but bold\n"
+        );
+        assert_eq!(
+            render_doc(doc, FormattingStyle::ascii_markers()),
+            "~~old~~ and *new* but bold\n"
+        );
+    }
+
+    #[test]
+    fn test_markdown_markers() {
+        let doc = doc(vec![p_(vec![
+            b__("bold"),
+            span(", "),
+            i__("italic"),
+            span(", "),
+            s__("gone"),
+            span(" and "),
+            code__("code"),
+            span(" "),
+            link_text__("https://example.com", "site"),
+        ])]);
+        assert_eq!(
+            render_doc(doc, FormattingStyle::markdown_markers()),
+            "*bold*, _italic_, ~gone~ and `code` site¹\n\n¹ https://example.com\n"
+        );
+    }
+
+    #[test]
+    fn test_fallback_markers_only_apply_without_terminal_styling() {
+        let doc = doc(vec![p_(vec![s__("old"), span(" "), mark__("new")])]);
+        let mut style = FormattingStyle::ansi();
+        style.text_styles.remove(&InlineStyle::Strike);
+        style.fallback_markers = FormattingStyle::default_fallback_markers();
+        assert_eq!(
+            render_doc(doc, style),
+            "~~old~~ \x1b[7mnew\x1b[27m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_color_emits_and_resets_foreground() {
+        let doc = doc(vec![p_(vec![
+            span("Status: "),
+            color__("red", "failed"),
+            span(", "),
+            color__("#00ff00", "passed"),
+        ])]);
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ascii()),
+            "Status: failed, passed\n"
+        );
+        assert_eq!(
+            render_doc(doc, FormattingStyle::ansi()),
+            "Status: \x1b[31mfailed\x1b[39m, \x1b[38;2;0;255;0mpassed\x1b[39m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_dark_theme_colors_headings_code_and_links() {
+        let mut style = FormattingStyle::ansi_dark();
+        style.enable_osc8_hyperlinks = false;
+        style.link_footnotes = false;
+        let doc = doc(vec![
+            h3_("Title"),
+            p_(vec![
+                span("Run "),
+                code__("make"),
+                span(" or see "),
+                link_text__("https://example.com", "docs"),
+            ]),
+        ]);
+        assert_eq!(
+            render_doc(doc, style),
+            "\n\n\x1b[33m\x1b[1mTitle\x1b[22m\x1b[39m\n\
+             -----\n\n\
+             Run \x1b[32mmake\x1b[39m or see \x1b[36mdocs\x1b[39m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_definition_list_bolds_term_and_indents_definition() {
+        let list = Paragraph::new_definition_list().with_definition_items(vec![
+            DefinitionItem::new()
+                .with_term(vec![span("Term")])
+                .with_definition(vec![p_(vec![span("Meaning")])]),
+            DefinitionItem::new().with_term(vec![span("Other")]),
+        ]);
+        assert_eq!(
+            render_doc(doc(vec![list.clone()]), FormattingStyle::ascii()),
+            "Term\n    Meaning\n\nOther\n"
+        );
+        assert_eq!(
+            render_doc(doc(vec![list]), FormattingStyle::ansi()),
+            "\x1b[1mTerm\x1b[22m\n    Meaning\n\n\x1b[1mOther\x1b[22m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_details_renders_bold_summary_before_body() {
+        let details = Paragraph::new_details()
+            .with_summary(vec![span("More")])
+            .with_children(vec![p_(vec![span("Body")])]);
+        assert_eq!(
+            render_doc(doc(vec![details]), FormattingStyle::ansi()),
+            "\x1b[1mMore\x1b[22m\n\nBody\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_admonition_renders_label_above_body() {
+        let admonition = || {
+            Paragraph::new_admonition(AdmonitionKind::Warning)
+                .with_children(vec![p__("be careful"), p__("really")])
+        };
+        assert_eq!(
+            render_doc(doc(vec![admonition()]), FormattingStyle::ascii()),
+            "! WARNING:\n! be careful\n! \n! really\n"
+        );
+        assert_eq!(
+            render_doc(doc(vec![admonition()]), FormattingStyle::ansi()),
+            "┃ \x1b[33m\x1b[1mWARNING\x1b[22m\x1b[39m\n┃ be careful\n┃ \n┃ really\n\x1b[0m"
+        );
+
+        let mut style = FormattingStyle::ascii();
+        style.admonition_styles.insert(
+            AdmonitionKind::Note,
+            AdmonitionStyle {
+                prefix: "i ".to_string(),
+                label: "Note".to_string(),
+                label_style: Some(StyleTags::new("<", ">")),
+            },
+        );
+        let note = Paragraph::new_admonition(AdmonitionKind::Note).with_children(vec![p__("hi")]);
+        assert_eq!(render_doc(doc(vec![note]), style), "i <Note>\ni hi\n");
+    }
+
+    #[test]
+    fn test_nested_color_hands_back_to_outer_color() {
+        let outer = Span::new_colored(Color::Named(4)).with_children(vec![
+            span("a "),
+            color__("red", "b"),
+            span(" c"),
+        ]);
+        let doc = doc(vec![p_(vec![outer])]);
+        assert_eq!(
+            render_doc(doc, FormattingStyle::ansi()),
+            "\x1b[34ma \x1b[31mb\x1b[34m c\x1b[39m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_color_is_restored_across_wrapped_lines() {
+        let doc = doc(vec![p_(vec![color__("green", "one two three")])]);
+        let mut style = FormattingStyle::ansi();
+        style.wrap_width = 8;
+        assert_eq!(
+            render_doc(doc, style),
+            "\x1b[32mone two\x1b[39m\n\x1b[32mthree\x1b[39m\n\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn test_abbreviation_renders_inline_with_optional_footnote() {
+        let doc = doc(vec![p_(vec![
+            abbr__("HyperText Markup Language", "HTML"),
+            span(" rocks"),
+        ])]);
+        assert_eq!(
+            render_doc(doc.clone(), FormattingStyle::ascii()),
+            "HTML rocks\n"
+        );
+
+        let mut style = FormattingStyle::ascii();
+        style.abbreviation_footnotes = true;
+        assert_eq!(
+            render_doc(doc, style),
+            "HTML¹ rocks\n\n¹ HyperText Markup Language\n"
+        );
+    }
+
     #[test]
     fn test_ansi_formatting() {
         let mut output = Vec::new();
@@ -2467,9 +3761,114 @@ mod tests {
         formatter.write_document(&doc).unwrap();
         let result = String::from_utf8(output).unwrap();
 
-        assert!(
-            result.contains("\x1b[27m\n| \x1b[7m"),
-            "Expected quote prefix to remain unstyled around forced line breaks"
+        assert!(
+            result.contains("\x1b[27m\n| \x1b[7m"),
+            "Expected quote prefix to remain unstyled around forced line breaks"
+        );
+    }
+
+    #[test]
+    fn test_max_lines_cuts_output_with_indicator() {
+        let doc = doc((1..=10).map(|n| p__(&format!("Paragraph {n}"))).collect());
+
+        let mut style = FormattingStyle::ascii();
+        style.max_lines = Some(5);
+        style.truncation_indicator = TruncationIndicator::LineCount;
+        assert_eq!(
+            render_doc(doc.clone(), style.clone()),
+            "Paragraph 1\n\nParagraph 2\n\n(15 more lines)\n"
+        );
+
+        style.max_lines = Some(18);
+        assert_eq!(
+            render_doc(doc.clone(), style.clone()),
+            "Paragraph 1\n\nParagraph 2\n\nParagraph 3\n\nParagraph 4\n\nParagraph 5\n\n\
+             Paragraph 6\n\nParagraph 7\n\nParagraph 8\n\nParagraph 9\n(2 more lines)\n"
+        );
+        style.max_lines = Some(5);
+
+        style.truncation_indicator = TruncationIndicator::Ellipsis;
+        style.left_padding = 2;
+        assert_eq!(
+            render_doc(doc.clone(), style.clone()),
+            "  Paragraph 1\n  \n  Paragraph 2\n  \n  …\n"
+        );
+
+        style.max_lines = Some(19);
+        assert_eq!(
+            render_doc(doc.clone(), style.clone()),
+            render_doc(
+                doc,
+                FormattingStyle {
+                    max_lines: None,
+                    ..style
+                }
+            )
+        );
+    }
+
+    #[test]
+    fn test_max_lines_stops_rendering_at_the_budget() {
+        struct FlushCounter {
+            output: Vec<u8>,
+            flushes: usize,
+        }
+
+        impl Write for FlushCounter {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.output.extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                self.flushes += 1;
+                Ok(())
+            }
+        }
+
+        let doc = doc((1..=10_000)
+            .map(|n| p__(&format!("Paragraph {n}")))
+            .collect());
+        let mut style = FormattingStyle::ascii();
+        style.max_lines = Some(5);
+
+        let mut formatter = Formatter::new(
+            FlushCounter {
+                output: Vec::new(),
+                flushes: 0,
+            },
+            style,
+        );
+        formatter.write_document(&doc).unwrap();
+        let FlushCounter { output, flushes } = formatter.writer;
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Paragraph 1\n\nParagraph 2\n\n…\n"
+        );
+        // The fourth paragraph starts before the blank line ahead of it runs
+        // over the budget; the last two flushes end the document.
+        assert_eq!(flushes, 6);
+    }
+
+    #[test]
+    fn test_wrap_footnote_urls() {
+        let target = "https://example.com/a/very/long/path/to/some/document.html";
+        let doc = doc(vec![p_(vec![link_text__(target, "Docs")])]);
+
+        let mut style = FormattingStyle::ascii();
+        style.wrap_width = 20;
+        let unwrapped = render_doc(doc.clone(), style.clone());
+        assert!(unwrapped.contains(&format!("¹ {target}\n")));
+
+        style.wrap_footnote_urls = true;
+        assert_eq!(
+            render_doc(doc, style),
+            "Docs¹\n\n\
+             ¹ https://example.co\n  \
+             m/a/very/long/path\n  \
+             /to/some/document.\n  \
+             html\n"
         );
     }
 
@@ -2539,11 +3938,173 @@ mod tests {
             "\x1b]8;id=2;https://example.com/plain\x1b\\https://example.com/plain\x1b]8;;\x1b\\"
         ));
         assert!(result.contains(
-            "¹ \x1b]8;id=3;https://example.com/docs\x1b\\https://example.com/docs\x1b]8;;\x1b\\"
+            "¹ \x1b]8;id=1;https://example.com/docs\x1b\\https://example.com/docs\x1b]8;;\x1b\\"
         ));
         assert!(result.ends_with("\x1b[0m"));
     }
 
+    #[test]
+    fn test_compact_heading_spacing() {
+        let doc = doc(vec![
+            p__("Intro"),
+            h1_("One"),
+            p__("Body"),
+            h2_("Two"),
+            h3_("Three"),
+            p__("End"),
+        ]);
+
+        let mut style = FormattingStyle::ascii();
+        style.heading_spacing = HeadingSpacing {
+            before: [1, 1, 1],
+            after: [1, 0, 0],
+        };
+        let mut output = Vec::new();
+        Formatter::new(&mut output, style)
+            .write_document(&doc)
+            .unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        let lines: Vec<&str> = result.lines().map(str::trim).collect();
+        assert_eq!(
+            lines,
+            ["Intro", "", "One", "", "Body", "", "Two", "===", "", "Three", "-----", "", "End"]
+        );
+    }
+
+    #[test]
+    fn test_written_links_match_footnotes() {
+        let doc = doc(vec![
+            p_(vec![
+                link_text__("https://example.com/a", "A"),
+                span(" "),
+                link_text__("https://example.com/b", "B"),
+                span(" "),
+                link_text__("https://example.com/a", "A again"),
+                span(" "),
+                abbr__("HyperText Markup Language", "HTML"),
+            ]),
+            h2_("Next"),
+            p_(vec![link_text__("https://example.com/c", "C")]),
+        ]);
+
+        let mut style = FormattingStyle::ascii();
+        style.abbreviation_footnotes = true;
+        let mut formatter = Formatter::new(Vec::new(), style);
+        formatter.write_document(&doc).unwrap();
+        let links = formatter.written_links().to_vec();
+        let result = String::from_utf8(formatter.writer).unwrap();
+
+        assert_eq!(
+            links,
+            [
+                (1, "https://example.com/a".to_string()),
+                (2, "https://example.com/b".to_string()),
+                (1, "https://example.com/c".to_string()),
+            ]
+        );
+        for footnote in [
+            "¹ https://example.com/a",
+            "² https://example.com/b",
+            "¹ https://example.com/c",
+        ] {
+            assert!(result.contains(footnote), "missing {footnote:?}");
+        }
+        assert!(result.contains("³ HyperText Markup Language"));
+    }
+
+    #[test]
+    fn test_footnotes_at_end() {
+        let doc = doc(vec![
+            p_(vec![link_text__("https://example.com/a", "A")]),
+            h2_("Next"),
+            p_(vec![link_text__("https://example.com/b", "B")]),
+        ]);
+
+        let mut style = FormattingStyle::ascii();
+        style.footnotes_at_end = true;
+        let mut formatter = Formatter::new(Vec::new(), style);
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(formatter.writer).unwrap();
+
+        assert_eq!(
+            result,
+            "A¹\n\n\n\nNext\n====\n\n\nB²\n\n¹ https://example.com/a\n² https://example.com/b\n"
+        );
+    }
+
+    /// Records the output handed on by every `flush` call.
+    #[derive(Default)]
+    struct FlushRecorder {
+        buffer: Vec<u8>,
+        flushed: Vec<String>,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.buffer.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushed
+                .push(String::from_utf8_lossy(&self.buffer).into_owned());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_paragraphs_are_flushed_as_they_complete() {
+        let doc = doc(vec![
+            p__("One"),
+            ul_(vec![li_(vec![p__("Two")])]),
+            p__("Three"),
+        ]);
+
+        let mut recorder = FlushRecorder::default();
+        Formatter::new_ascii(&mut recorder)
+            .write_document(&doc)
+            .unwrap();
+
+        let first_flush_with = |text: &str| {
+            recorder
+                .flushed
+                .iter()
+                .position(|output| output.contains(text))
+                .unwrap_or_else(|| panic!("{text:?} never flushed"))
+        };
+        let one = first_flush_with("One");
+        let two = first_flush_with("Two");
+        let three = first_flush_with("Three");
+        assert!(one < two && two < three);
+        assert_eq!(three, recorder.flushed.len() - 1);
+    }
+
+    #[test]
+    fn test_repeated_link_target_reuses_osc8_id() {
+        let doc = doc(vec![p_(vec![
+            link_text__("https://example.com/a", "first"),
+            span(" "),
+            link_text__("https://example.com/b", "other"),
+            span(" "),
+            link_text__("https://example.com/a", "again"),
+        ])]);
+
+        let mut style = FormattingStyle::ansi();
+        style.link_footnotes = false;
+        let mut formatter = Formatter::new(Vec::new(), style);
+        formatter.write_document(&doc).unwrap();
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(formatter.writer).unwrap();
+
+        assert!(result.contains("\x1b]8;id=1;https://example.com/a\x1b\\first"));
+        assert!(result.contains("\x1b]8;id=2;https://example.com/b\x1b\\other"));
+        assert!(result.contains("\x1b]8;id=1;https://example.com/a\x1b\\again"));
+        // The second document gets fresh ids.
+        assert!(result.contains("\x1b]8;id=3;https://example.com/a\x1b\\first"));
+        assert!(result.contains("\x1b]8;id=3;https://example.com/a\x1b\\again"));
+    }
+
     #[test]
     fn test_ansi_links_without_footnotes_when_disabled() {
         let doc = doc(vec![
@@ -2655,6 +4216,76 @@ mod tests {
         assert!(result.contains("¹ https://example.com/docs"));
     }
 
+    #[test]
+    fn test_named_mailto_link_footnotes_bare_address() {
+        let doc = doc(vec![p_(vec![
+            span("Ask "),
+            link_text__("mailto:alice@example.com", "Alice"),
+            span("."),
+        ])]);
+
+        let mut formatter = Formatter::new_ascii(Vec::new());
+        formatter.write_document(&doc).unwrap();
+        let links = formatter.written_links().to_vec();
+        let result = String::from_utf8(formatter.writer).unwrap();
+
+        assert_eq!(result, "Ask Alice¹.\n\n¹ alice@example.com\n");
+        assert_eq!(links, [(1, "mailto:alice@example.com".to_string())]);
+    }
+
+    #[test]
+    fn test_tel_links_show_number_inline() {
+        let doc = doc(vec![p_(vec![
+            link_text__("tel:+15551234", "Call us"),
+            span(" or dial "),
+            link_text__("tel:+15551234", "+15551234"),
+            span("."),
+        ])]);
+
+        let mut formatter = Formatter::new_ascii(Vec::new());
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(formatter.writer).unwrap();
+
+        assert_eq!(result, "Call us (+15551234) or dial +15551234.\n");
+    }
+
+    #[test]
+    fn test_unknown_scheme_links_footnote_full_target() {
+        let doc = doc(vec![p_(vec![link_text__(
+            "gemini://example.com/",
+            "Capsule",
+        )])]);
+
+        let mut formatter = Formatter::new_ascii(Vec::new());
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(formatter.writer).unwrap();
+
+        assert_eq!(result, "Capsule¹\n\n¹ gemini://example.com/\n");
+    }
+
+    #[test]
+    fn test_footnote_filter_skips_rejected_targets() {
+        let doc = doc(vec![p_(vec![
+            span("Fetch "),
+            link_text__("ftp://example.com/file", "it"),
+            span(" or visit "),
+            link_text__("https://example.com/docs", "Docs"),
+            span("."),
+        ])]);
+
+        let mut style = FormattingStyle::ascii();
+        style.footnote_filter = Some(Arc::new(|target: &str| !target.starts_with("ftp:")));
+        let mut output = Vec::new();
+        Formatter::new(&mut output, style)
+            .write_document(&doc)
+            .unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert!(result.contains("Fetch it or visit Docs¹."));
+        assert!(!result.contains("ftp:"));
+        assert!(result.contains("¹ https://example.com/docs"));
+    }
+
     #[test]
     fn test_ansi_mailto_links_skip_indices() {
         let doc = doc(vec![p_(vec![
@@ -2680,7 +4311,7 @@ mod tests {
         assert!(!result.contains("support@example.com\x1b]8;;\x1b\\¹"));
         assert!(result.contains("\x1b]8;id=2;https://example.com/docs\x1b\\Docs"));
         assert!(result.contains(
-            "¹ \x1b]8;id=3;https://example.com/docs\x1b\\https://example.com/docs\x1b]8;;\x1b\\"
+            "¹ \x1b]8;id=2;https://example.com/docs\x1b\\https://example.com/docs\x1b]8;;\x1b\\"
         ));
         assert!(result.ends_with("\x1b[0m"));
     }
@@ -2709,7 +4340,7 @@ mod tests {
         assert!(result.contains("\x1b]8;id=1;https://example.com/docs\x1b\\Docs"));
         assert!(result.contains("\x1b]8;;\x1b\\[1]"));
         assert!(result.contains(
-            "[1] \x1b]8;id=3;https://example.com/docs\x1b\\https://example.com/docs\x1b]8;;\x1b\\"
+            "[1] \x1b]8;id=1;https://example.com/docs\x1b\\https://example.com/docs\x1b]8;;\x1b\\"
         ));
     }
 
@@ -2844,6 +4475,156 @@ mod tests {
         assert!(result.contains(" 1. First\n\n 2. Second"));
     }
 
+    #[test]
+    fn test_list_indent() {
+        let mut output = Vec::new();
+        let mut formatter = Formatter::new_ascii(&mut output);
+        formatter.style.list_indent = 3;
+
+        let doc = doc(vec![
+            ul_(vec![li_(vec![
+                p__("Bullet"),
+                ol_(vec![li_(vec![p__("Nested")])]),
+            ])]),
+            checklist_(vec![task__(true, "Done")]),
+        ]);
+
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert_eq!(
+            result,
+            " •   Bullet\n     \n      1.   Nested\n\n[✓]   Done\n"
+        );
+    }
+
+    #[test]
+    fn test_checklist_parent_with_mixed_children() {
+        let mut output = Vec::new();
+        let mut formatter = Formatter::new_ascii(&mut output);
+
+        let doc = doc(vec![checklist_(vec![task_(
+            false,
+            "Release",
+            vec![task__(true, "Notes"), task__(false, "Tag")],
+        )])]);
+
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert_eq!(result, "[~] Release\n    [✓] Notes\n    [ ] Tag\n");
+    }
+
+    #[test]
+    fn test_code_overflow_truncate() {
+        let mut output = Vec::new();
+        let mut formatter = Formatter::new_ascii(&mut output);
+        formatter.style.wrap_width = 10;
+        formatter.style.code_overflow = CodeOverflow::Truncate;
+
+        let doc = doc(vec![code_block__("+--------------+\n| box |")]);
+
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert_eq!(result, "----------\n+--------…\n| box |\n----------\n");
+    }
+
+    #[test]
+    fn test_max_code_fence_width() {
+        let mut output = Vec::new();
+        let mut formatter = Formatter::new_ascii(&mut output);
+        formatter.style.wrap_width = 200;
+        formatter.style.max_code_fence_width = Some(10);
+
+        let doc = doc(vec![code_block__("let x = 1;")]);
+
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert_eq!(result, "----------\nlet x = 1;\n----------\n");
+    }
+
+    #[test]
+    fn test_checklist_progress() {
+        let mut output = Vec::new();
+        let mut formatter = Formatter::new_ascii(&mut output);
+        formatter.style.checklist_progress = true;
+
+        let doc = doc(vec![
+            ul_(vec![li_(vec![
+                p__("Tasks"),
+                checklist_(vec![
+                    task__(true, "One"),
+                    task__(false, "Two"),
+                    task_(
+                        false,
+                        "Three",
+                        vec![task__(true, "Four"), task__(true, "Five")],
+                    ),
+                ]),
+            ])]),
+            p__("After"),
+        ]);
+
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert_eq!(
+            result,
+            concat!(
+                " • Tasks\n",
+                "   \n",
+                "   [✓] One\n",
+                "   [ ] Two\n",
+                "   [ ] Three\n",
+                "       [✓] Four\n",
+                "       [✓] Five\n",
+                "   (3/5 done)\n",
+                "\n",
+                "After\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_number_headings() {
+        let mut output = Vec::new();
+        let mut formatter = Formatter::new_ascii(&mut output);
+        formatter.style.number_headings = true;
+
+        let doc = doc(vec![
+            h2_("Preface"),
+            h1_("Intro"),
+            h2_("Scope"),
+            h3_("Details"),
+            h2_("Terms"),
+            h1_("Usage"),
+            h3_("Flags"),
+        ]);
+
+        formatter.write_document(&doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+        let headings: Vec<&str> = result
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_digit()))
+            .collect();
+
+        assert_eq!(
+            headings,
+            vec![
+                "1 Preface",
+                "1 Intro",
+                "1.1 Scope",
+                "1.1.1 Details",
+                "1.2 Terms",
+                "2 Usage",
+                "2.0.1 Flags",
+            ]
+        );
+    }
+
     #[test]
     fn test_list_item_multiple_paragraphs() {
         let mut output = Vec::new();
@@ -3446,4 +5227,155 @@ mod tests {
         );
         assert!(!output.is_empty());
     }
+
+    #[test]
+    fn renders_deeply_nested_quotes_without_recursion() {
+        let depth = 1000;
+        let mut paragraph = p__("deep");
+        for _ in 0..depth {
+            paragraph = quote_(vec![paragraph]);
+        }
+        let doc = doc(vec![paragraph]);
+
+        // A small stack would overflow long before this depth if rendering
+        // recursed once per nesting level.
+        let (output, _doc) = std::thread::Builder::new()
+            .stack_size(256 * 1024)
+            .spawn(move || {
+                let mut output = Vec::new();
+                Formatter::new_ascii(&mut output)
+                    .write_document(&doc)
+                    .unwrap();
+                // Hand the document back so it is dropped on this thread.
+                (output, doc)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        let result = String::from_utf8(output).unwrap();
+
+        assert_eq!(result, format!("{}deep\n", "| ".repeat(depth)));
+    }
+
+    #[test]
+    fn standalone_paragraph_matches_full_render() {
+        let list = ul_(vec![li_(vec![p__("one")]), li_(vec![p__("two")])]);
+        let quote = quote_(vec![p__("Quoted text"), list.clone()]);
+        let doc = doc(vec![p__("First"), quote.clone(), p__("Last")]);
+        let mut style = FormattingStyle::ascii();
+        style.left_padding = 2;
+
+        let mut output = Vec::new();
+        Formatter::new(&mut output, style.clone())
+            .write_document(&doc)
+            .unwrap();
+        let full = String::from_utf8(output).unwrap();
+
+        let top_level = ParagraphContext::top_level(&style);
+        let block = render_paragraph(&quote, style.clone(), &top_level);
+        assert_eq!(full, format!("  First\n  \n{}  \n  Last\n", block));
+
+        let nested = ParagraphContext {
+            prefix: "  | ".to_string(),
+            continuation_prefix: "  | ".to_string(),
+            blank_line_prefix: "  | ".to_string(),
+        };
+        let block = render_paragraph(&list, style, &nested);
+        assert!(block.starts_with("  |  • one"), "{block:?}");
+        assert!(full.contains(&block), "{block:?} not in {full:?}");
+    }
+
+    #[test]
+    fn right_to_left_lines_are_right_aligned() {
+        let mut style = FormattingStyle::ascii();
+        style.wrap_width = 24;
+        style.direction = Direction::Rtl;
+        let doc = doc(vec![
+            p__("שלום עולם, זהו משפט ארוך מספיק כדי לגלוש לשורה נוספת"),
+            quote_(vec![p__("ציטוט")]),
+            ol_(vec![li_(vec![p__("פריט")])]),
+        ]);
+
+        let mut output = Vec::new();
+        Formatter::new(&mut output, style.clone())
+            .write_document(&doc)
+            .unwrap();
+        let result = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = result.lines().filter(|line| !line.is_empty()).collect();
+
+        assert!(lines.len() > 4, "{result}");
+        for line in &lines {
+            assert_eq!(display_width(line), 24, "{line:?}");
+        }
+        assert_eq!(lines[0].trim_start(), "שלום עולם, זהו משפט ארוך");
+        assert!(lines.contains(&"                 ציטוט |"), "{result}");
+        assert!(lines.contains(&"                פריט .1 "), "{result}");
+
+        style.left_padding = 2;
+        let mut output = Vec::new();
+        Formatter::new(&mut output, style)
+            .write_document(&doc)
+            .unwrap();
+        let result = String::from_utf8(output).unwrap();
+        for line in result.lines().filter(|line| !line.is_empty()) {
+            assert_eq!(display_width(line), 24, "{line:?}");
+            assert!(line.ends_with("  "), "{line:?}");
+        }
+        assert!(result.contains("ציטוט |  \n"), "{result}");
+    }
+
+    #[test]
+    fn test_display_width_matches_regex_stripping() {
+        let samples = [
+            "",
+            "plain",
+            "\x1b[1mbold\x1b[22m",
+            "\x1b[38;2;1;2;3mrgb\x1b[39m text",
+            "\x1b]8;id=1;https://example.com\x1b\\link\x1b]8;;\x1b\\",
+            "wide 漢字 and emoji 😀",
+            "\x1b[1m\x1b]8;;x\x1b\\grüße\x1b]8;;\x1b\\\x1b[0m",
+            "stray \x1b escape",
+            "\x1b[12",
+            "\x1b]8;unterminated",
+            "\x1b]7;not osc8\x1b\\",
+            "\x1b[1m\x1b]8;;https://example.com\x1b\\Überschrift\x1b]8;;\x1b\\\x1b[22m",
+        ];
+        for sample in samples {
+            assert_eq!(
+                display_width(sample),
+                visible_line_width(sample),
+                "{sample:?}"
+            );
+        }
+    }
+
+    /// Compares the speed of both width computations. Timings are unreliable
+    /// in debug builds and next to other tests, so run it on its own with
+    /// `cargo test --release -- --ignored display_width_benchmark`.
+    #[test]
+    #[ignore = "timing comparison, run explicitly in release builds"]
+    fn test_display_width_benchmark() {
+        let token = "\x1b[1m\x1b]8;;https://example.com\x1b\\Überschrift\x1b]8;;\x1b\\\x1b[22m";
+        let iterations = 20_000;
+
+        let started = std::time::Instant::now();
+        let mut scanned = 0;
+        for _ in 0..iterations {
+            scanned += display_width(std::hint::black_box(token));
+        }
+        let scan_time = started.elapsed();
+
+        let started = std::time::Instant::now();
+        let mut stripped = 0;
+        for _ in 0..iterations {
+            stripped += visible_line_width(std::hint::black_box(token));
+        }
+        let regex_time = started.elapsed();
+
+        assert_eq!(scanned, stripped);
+        assert!(
+            scan_time < regex_time,
+            "scan took {scan_time:?}, regex stripping took {regex_time:?}"
+        );
+    }
 }
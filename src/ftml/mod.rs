@@ -32,5 +32,5 @@
 pub mod parser;
 pub mod writer;
 
-pub use parser::{parse, ParseError, Parser};
-pub use writer::{write, Writer};
+pub use parser::{parse, parse_lenient, parse_streaming, ParseError, Parser, Position};
+pub use writer::{write, write_with_options, WriteOptions, Writer};
@@ -1,8 +1,11 @@
 //! FTML parser that transforms HTML-like markup into [`Document`](crate::Document) trees.
 
+use crate::line_endings::{self, NormalizedReader};
 use crate::{ChecklistItem, Document, InlineStyle, Paragraph, ParagraphType, Span};
 use regex::Regex;
-use std::collections::HashMap;
+use std::borrow::Borrow;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
 use std::io::Read;
 use thiserror::Error;
 
@@ -47,6 +50,88 @@ pub enum ParseError {
     UnexpectedClosingTag(String),
     #[error("No closing tag for {0}")]
     NoClosingTag(InlineStyle),
+    #[error("Unclosed paragraph of type {0}")]
+    UnclosedParagraph(ParagraphType),
+    #[error("{position}: {source}")]
+    At {
+        position: Position,
+        source: Box<ParseError>,
+    },
+}
+
+impl ParseError {
+    /// Returns the location in the input at which the error was detected.
+    pub fn position(&self) -> Option<Position> {
+        match self {
+            ParseError::At { position, .. } => Some(*position),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error without any attached location.
+    pub fn inner(&self) -> &ParseError {
+        match self {
+            ParseError::At { source, .. } => source.inner(),
+            other => other,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A location inside FTML source text.
+///
+/// `offset` is a byte offset into the input, while `line` and `column` are
+/// 1-based and count Unicode scalar values.
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    /// Computes the line and column for a byte offset into `input`.
+    pub fn from_offset(input: &str, offset: usize) -> Self {
+        let mut offset = offset.min(input.len());
+        while !input.is_char_boundary(offset) {
+            offset -= 1;
+        }
+        let before = &input[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|idx| idx + 1).unwrap_or(0);
+        let column = before[line_start..].chars().count() + 1;
+        Self {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    /// Treats `self` as the start of a text in which `local` was measured and
+    /// returns the combined location.
+    fn followed_by(self, local: Position) -> Position {
+        Position {
+            offset: self.offset + local.offset,
+            line: self.line + local.line - 1,
+            column: if local.line == 1 {
+                self.column + local.column - 1
+            } else {
+                local.column
+            },
+        }
+    }
+}
+
+fn invalid_utf8() -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
 }
 
 // Simple tokenizer for FTML parsing
@@ -64,35 +149,95 @@ enum Token {
     SelfClosingTag(Tag),
 }
 
-struct Tokenizer {
+/// Number of bytes requested from the underlying reader per refill.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+struct Tokenizer<'a> {
+    // Window of input that has been read but not yet discarded. All offsets
+    // below are relative to its start.
     input: String,
     pos: usize,
+    // Start of the most recently returned token, used for error positions.
+    token_start: usize,
     putback_token: Option<(Token, usize, usize)>,
+    // Remaining input, or `None` once it is exhausted.
+    source: Option<Box<dyn Read + 'a>>,
+    // Trailing bytes of a UTF-8 sequence split across two reads.
+    pending: Vec<u8>,
+    // Location of `input[0]` within the whole stream.
+    origin: Position,
+    // Set when a scan ran into the end of `input`, meaning the token might
+    // continue in data that has not been read yet.
+    hit_end: bool,
+    error: Option<std::io::Error>,
 }
 
-impl Tokenizer {
+impl<'a> Tokenizer<'a> {
     fn new(input: String) -> Self {
         Self {
             input,
             pos: 0,
+            token_start: 0,
             putback_token: None,
+            source: None,
+            pending: Vec::new(),
+            origin: Position {
+                offset: 0,
+                line: 1,
+                column: 1,
+            },
+            hit_end: false,
+            error: None,
         }
     }
 
+    fn from_reader<R: Read + 'a>(reader: R) -> Self {
+        let mut tokenizer = Self::new(String::new());
+        tokenizer.source = Some(Box::new(NormalizedReader::new(reader)));
+        tokenizer
+    }
+
     fn next(&mut self) -> Option<Token> {
+        self.next_with_pos().map(|(token, _)| token)
+    }
+
+    fn next_with_pos(&mut self) -> Option<(Token, usize)> {
         // Return putback token if available and restore position
-        if let Some((token, _start_pos, end_pos)) = self.putback_token.take() {
+        if let Some((token, start_pos, end_pos)) = self.putback_token.take() {
             self.pos = end_pos;
-            return Some(token);
+            self.token_start = start_pos;
+            return Some((token, start_pos));
+        }
+
+        self.discard_consumed();
+
+        loop {
+            let scan_start = self.pos;
+            self.hit_end = false;
+            let token = self.scan_token();
+            if !self.hit_end {
+                return token.map(|t| (t, self.token_start));
+            }
+            if !self.fill() {
+                if self.error.is_some() {
+                    return None;
+                }
+                return token.map(|t| (t, self.token_start));
+            }
+            // More input arrived; rescan the token that was cut off.
+            self.pos = scan_start;
         }
+    }
 
+    fn scan_token(&mut self) -> Option<Token> {
         self.skip_whitespace_between_tags();
 
+        self.token_start = self.pos;
         if self.pos >= self.input.len() {
+            self.hit_end = true;
             return None;
         }
 
-        // Parse token
         if self.input.get(self.pos..self.pos + 1) == Some("<") {
             self.parse_tag()
         } else {
@@ -100,32 +245,75 @@ impl Tokenizer {
         }
     }
 
-    fn next_with_pos(&mut self) -> Option<(Token, usize)> {
-        // Return putback token if available and restore position
-        if let Some((token, start_pos, end_pos)) = self.putback_token.take() {
-            self.pos = end_pos;
-            return Some((token, start_pos));
-        }
+    fn putback(&mut self, token: Token, start_pos: usize) {
+        self.putback_token = Some((token, start_pos, self.pos));
+    }
 
-        self.skip_whitespace_between_tags();
+    /// Reads the next chunk from the source into `input`. Returns `false` once
+    /// the source is exhausted or failed.
+    fn fill(&mut self) -> bool {
+        let Some(source) = self.source.as_mut() else {
+            return false;
+        };
 
-        if self.pos >= self.input.len() {
-            return None;
+        let mut chunk = vec![0; READ_CHUNK_SIZE];
+        let read = loop {
+            match source.read(&mut chunk) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    self.error = Some(err);
+                    self.source = None;
+                    return false;
+                }
+            }
+        };
+
+        if read == 0 {
+            self.source = None;
+            if !self.pending.is_empty() {
+                self.error = Some(invalid_utf8());
+            }
+            return false;
         }
 
-        // Save position before parsing token
-        let token_start_pos = self.pos;
-        let token = if self.input.get(self.pos..self.pos + 1) == Some("<") {
-            self.parse_tag()
-        } else {
-            self.parse_text()
+        self.pending.extend_from_slice(&chunk[..read]);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            // An incomplete sequence at the end is finished by the next read.
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => {
+                self.error = Some(invalid_utf8());
+                self.source = None;
+                return false;
+            }
         };
+        let text = std::str::from_utf8(&self.pending[..valid]).expect("validated above");
+        self.input.push_str(text);
+        self.pending.drain(..valid);
+        true
+    }
+
+    /// Drops input that has already been tokenized, so that streaming input
+    /// is only ever held in memory a chunk at a time.
+    fn discard_consumed(&mut self) {
+        if self.source.is_none() || self.pos < READ_CHUNK_SIZE {
+            return;
+        }
+        self.origin = self.position(self.pos);
+        self.input.drain(..self.pos);
+        self.pos = 0;
+        self.token_start = 0;
+    }
 
-        token.map(|t| (t, token_start_pos))
+    /// Returns the stream location of an offset into the current window.
+    fn position(&self, offset: usize) -> Position {
+        self.origin
+            .followed_by(Position::from_offset(&self.input, offset))
     }
 
-    fn putback(&mut self, token: Token, start_pos: usize) {
-        self.putback_token = Some((token, start_pos, self.pos));
+    fn end_position(&self) -> Position {
+        self.position(self.input.len())
     }
 
     fn skip_whitespace_between_tags(&mut self) {
@@ -180,6 +368,7 @@ impl Tokenizer {
         }
 
         if end_pos >= self.input.len() {
+            self.hit_end = true;
             return None;
         }
 
@@ -299,6 +488,10 @@ impl Tokenizer {
             }
         }
 
+        if self.pos >= self.input.len() {
+            self.hit_end = true;
+        }
+
         let text = &self.input[start..self.pos];
         if text.is_empty() {
             None
@@ -359,6 +552,11 @@ impl Parser {
         inline_elements.insert("s".to_string(), InlineStyle::Strike);
         inline_elements.insert("mark".to_string(), InlineStyle::Highlight);
         inline_elements.insert("code".to_string(), InlineStyle::Code);
+        inline_elements.insert("sup".to_string(), InlineStyle::Superscript);
+        inline_elements.insert("sub".to_string(), InlineStyle::Subscript);
+        inline_elements.insert("kbd".to_string(), InlineStyle::Keyboard);
+        inline_elements.insert("abbr".to_string(), InlineStyle::Abbreviation);
+        inline_elements.insert("q".to_string(), InlineStyle::Quotation);
         inline_elements.insert("a".to_string(), InlineStyle::Link);
 
         Self {
@@ -368,27 +566,171 @@ impl Parser {
         }
     }
 
+    /// Returns `true` for the elements this parser understands: paragraph
+    /// wrappers, inline styles and the list item, line break and checkbox
+    /// elements.
+    pub fn knows_element(&self, name: &str) -> bool {
+        self.wrapper_elements.contains_key(name)
+            || self.inline_elements.contains_key(name)
+            || matches!(name, "li" | "br" | "input")
+    }
+
     /// Parses a string slice into a [`Document`].
+    ///
+    /// Errors raised while processing the markup are wrapped in
+    /// [`ParseError::At`], carrying the [`Position`] of the offending token.
     pub fn parse_string(&self, input: &str) -> Result<Document, ParseError> {
-        let mut tokenizer = Tokenizer::new(input.to_string());
+        let paragraphs = ParagraphStream::new(
+            self,
+            Tokenizer::new(line_endings::normalize(input).into_owned()),
+        )
+        .collect::<Result<Vec<_>, _>>()?;
+        Ok(Document::new().with_paragraphs(paragraphs))
+    }
+
+    /// Parses a string slice into a [`Document`], recovering from malformed markup.
+    ///
+    /// Instead of aborting on the first problem, offending tokens are skipped
+    /// and unclosed or mismatched blocks are closed on a best-effort basis. Every
+    /// problem encountered is returned as a diagnostic alongside the document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::ftml::Parser;
+    ///
+    /// let parser = Parser::new();
+    /// let (document, diagnostics) =
+    ///     parser.parse_string_lenient("stray text<p>Hello!</p><ul><li><p>open");
+    /// assert_eq!(document.paragraphs.len(), 2);
+    /// assert_eq!(diagnostics.len(), 2);
+    /// ```
+    pub fn parse_string_lenient(&self, input: &str) -> (Document, Vec<ParseError>) {
+        let mut diagnostics = Vec::new();
+        let document = self
+            .parse_tokens_lenient(
+                Tokenizer::new(line_endings::normalize(input).into_owned()),
+                &mut diagnostics,
+            )
+            .unwrap_or_default();
+        (document, diagnostics)
+    }
+
+    /// Runs the tokenizer to completion, recording every problem in
+    /// `diagnostics`. Only I/O failures of the underlying reader are returned
+    /// as errors.
+    fn parse_tokens_lenient(
+        &self,
+        mut tokenizer: Tokenizer,
+        diagnostics: &mut Vec<ParseError>,
+    ) -> Result<Document, ParseError> {
         let mut document = Document::new();
         let mut breadcrumbs: Vec<Paragraph> = Vec::new();
         let mut list_item_level = 0;
 
         while let Some(token) = tokenizer.next() {
-            self.process_token(
+            let result = self.process_token(
                 token,
                 &mut document,
                 &mut breadcrumbs,
                 &mut list_item_level,
                 &mut tokenizer,
-            )?;
+            );
+            let Err(err) = result else {
+                continue;
+            };
+
+            let err = ParseError::At {
+                position: tokenizer.position(tokenizer.token_start),
+                source: Box::new(err),
+            };
+            if let ParseError::MismatchedClosingTag { expected, .. } = err.inner() {
+                self.close_until(*expected, &mut breadcrumbs, &mut document, diagnostics);
+            }
+            diagnostics.push(err);
+            self.skip_to_block_boundary(&mut tokenizer, &breadcrumbs);
+        }
+
+        if let Some(err) = tokenizer.error.take() {
+            return Err(err.into());
+        }
+
+        while let Some(current) = breadcrumbs.last() {
+            let current_type = current.paragraph_type();
+            diagnostics.push(ParseError::At {
+                position: tokenizer.end_position(),
+                source: Box::new(ParseError::UnclosedParagraph(current_type)),
+            });
+            self.close_or_discard(current_type, &mut breadcrumbs, &mut document, diagnostics);
         }
 
         normalize_entity_whitespace(&mut document);
         Ok(document)
     }
 
+    /// Discards tokens until the next block-level start tag or an end tag that
+    /// closes an open paragraph, so that a single broken node does not cascade
+    /// into follow-up errors.
+    fn skip_to_block_boundary(&self, tokenizer: &mut Tokenizer, breadcrumbs: &[Paragraph]) {
+        while let Some((token, token_pos)) = tokenizer.next_with_pos() {
+            let is_boundary = match &token {
+                Token::StartTag(tag) => {
+                    tag.name == "li" || self.wrapper_elements.contains_key(&tag.name)
+                }
+                Token::EndTag(tag_name) => match self.wrapper_elements.get(tag_name) {
+                    Some(&closing) => breadcrumbs
+                        .iter()
+                        .any(|paragraph| paragraph.paragraph_type().matches_closing_tag(closing)),
+                    None => false,
+                },
+                _ => false,
+            };
+            if is_boundary {
+                tokenizer.putback(token, token_pos);
+                return;
+            }
+        }
+    }
+
+    /// Closes open paragraphs up to and including one matching `closing`, if
+    /// any such paragraph is open. Used to recover from mismatched end tags.
+    fn close_until(
+        &self,
+        closing: ParagraphType,
+        breadcrumbs: &mut Vec<Paragraph>,
+        document: &mut Document,
+        diagnostics: &mut Vec<ParseError>,
+    ) {
+        if !breadcrumbs
+            .iter()
+            .any(|paragraph| paragraph.paragraph_type().matches_closing_tag(closing))
+        {
+            return;
+        }
+
+        while let Some(current) = breadcrumbs.last() {
+            let current_type = current.paragraph_type();
+            self.close_or_discard(current_type, breadcrumbs, document, diagnostics);
+            if current_type.matches_closing_tag(closing) {
+                break;
+            }
+        }
+    }
+
+    fn close_or_discard(
+        &self,
+        paragraph_type: ParagraphType,
+        breadcrumbs: &mut Vec<Paragraph>,
+        document: &mut Document,
+        diagnostics: &mut Vec<ParseError>,
+    ) {
+        if let Err(err) = self.process_end_paragraph(paragraph_type, breadcrumbs, document) {
+            // `process_end_paragraph` only fails after popping the paragraph when
+            // it cannot be attached to its parent, so it is dropped here.
+            diagnostics.push(err);
+        }
+    }
+
     fn process_token(
         &self,
         token: Token,
@@ -458,6 +800,7 @@ impl Parser {
                 } else if let Some(&paragraph_type) = self.wrapper_elements.get(&tag_name) {
                     self.process_start_paragraph(
                         paragraph_type,
+                        &tag,
                         document,
                         breadcrumbs,
                         list_item_level,
@@ -504,6 +847,7 @@ impl Parser {
     fn process_start_paragraph(
         &self,
         paragraph_type: ParagraphType,
+        tag: &Tag,
         document: &mut Document,
         breadcrumbs: &mut Vec<Paragraph>,
         _list_item_level: &mut i32,
@@ -518,7 +862,7 @@ impl Parser {
             } else {
                 self.read_content(tokenizer, paragraph_type.html_tag())?
             };
-            paragraph = paragraph.with_content(content);
+            paragraph = with_code_language(paragraph.with_content(content), tag);
             self.add_paragraph_to_current_context(&paragraph, document, breadcrumbs)?;
         } else {
             // For non-leaf paragraphs, add to breadcrumbs
@@ -726,9 +1070,12 @@ impl Parser {
                         let mut paragraph = Paragraph::new(paragraph_type);
 
                         if paragraph_type.is_leaf() {
-                            let content =
-                                self.read_content(tokenizer, paragraph_type.html_tag())?;
-                            paragraph = paragraph.with_content(content);
+                            let content = if paragraph_type == ParagraphType::CodeBlock {
+                                self.read_code_block_content(tokenizer, paragraph_type.html_tag())?
+                            } else {
+                                self.read_content(tokenizer, paragraph_type.html_tag())?
+                            };
+                            paragraph = with_code_language(paragraph.with_content(content), &tag);
                             paragraphs.push(paragraph);
                         } else {
                             breadcrumbs.push((paragraph, paragraphs.len()));
@@ -768,7 +1115,7 @@ impl Parser {
                     if checklist_state.is_some() || parent_is_checklist {
                         let collapsed =
                             self.collapse_whitespace(&text, inline_spans.is_empty(), false);
-                        let decoded = self.decode_entities(&collapsed);
+                        let decoded = unescape_ftml_text(&collapsed);
                         if !decoded.is_empty() {
                             inline_spans.push(Span::new_text(decoded));
                         }
@@ -807,7 +1154,9 @@ impl Parser {
 
         for paragraph in paragraphs {
             match paragraph {
-                Paragraph::Text { content: mut spans } => {
+                Paragraph::Text {
+                    content: mut spans, ..
+                } => {
                     if spans.is_empty() {
                         continue;
                     }
@@ -907,7 +1256,7 @@ impl Parser {
                     return Ok(buffer);
                 }
                 Token::Text(text) => {
-                    buffer.push_str(&self.decode_entities(&text));
+                    buffer.push_str(&unescape_ftml_text(&text));
                 }
                 Token::SelfClosingTag(tag) => {
                     if tag.name == "br" {
@@ -964,7 +1313,7 @@ impl Parser {
             match token {
                 Token::EndTag(tag_name) if tag_name == end_tag => {
                     if !buffer.is_empty() {
-                        spans.push(Span::new_text(self.decode_entities(
+                        spans.push(Span::new_text(unescape_ftml_text(
                             &self.collapse_whitespace(&buffer, spans.is_empty(), true),
                         )));
                         buffer.clear();
@@ -974,7 +1323,7 @@ impl Parser {
                 Token::SelfClosingTag(tag) => {
                     if tag.name == "br" {
                         if !buffer.is_empty() {
-                            let decoded = self.decode_entities(&buffer);
+                            let decoded = unescape_ftml_text(&buffer);
                             spans.push(Span::new_text(self.collapse_whitespace(
                                 &decoded,
                                 spans.is_empty(),
@@ -994,7 +1343,7 @@ impl Parser {
                     let tag_name = tag.name.clone();
                     if let Some(&style) = self.inline_elements.get(&tag_name) {
                         if !buffer.is_empty() {
-                            spans.push(Span::new_text(self.decode_entities(
+                            spans.push(Span::new_text(unescape_ftml_text(
                                 &self.collapse_whitespace(&buffer, spans.is_empty(), false),
                             )));
                             buffer.clear();
@@ -1005,7 +1354,7 @@ impl Parser {
                         // This is a structural element that should be handled by parent context
                         // Put the token back and return the content we've read so far
                         if !buffer.is_empty() {
-                            spans.push(Span::new_text(self.decode_entities(
+                            spans.push(Span::new_text(unescape_ftml_text(
                                 &self.collapse_whitespace(&buffer, spans.is_empty(), false),
                             )));
                         }
@@ -1038,7 +1387,7 @@ impl Parser {
                     } else if self.wrapper_elements.contains_key(&tag_name) {
                         // This is a parent structure ending - put it back for parent to handle
                         if !buffer.is_empty() {
-                            spans.push(Span::new_text(self.decode_entities(&buffer)));
+                            spans.push(Span::new_text(unescape_ftml_text(&buffer)));
                         }
                         tokenizer.putback(Token::EndTag(tag_name), token_pos);
                         return Ok(spans);
@@ -1050,7 +1399,7 @@ impl Parser {
         }
 
         if !buffer.is_empty() {
-            let decoded = self.decode_entities(&buffer);
+            let decoded = unescape_ftml_text(&buffer);
             let buffer_has_leading_entity = buffer.starts_with('&');
             let buffer_has_trailing_entity = buffer.ends_with(';') && buffer.contains('&');
 
@@ -1079,11 +1428,14 @@ impl Parser {
     ) -> Result<Span, ParseError> {
         let end_tag = start_tag.name.clone();
         let mut span = Span::new_styled(style);
-        if style == InlineStyle::Link {
-            if let Some(target) = start_tag.attributes.get("href") {
-                let decoded = self.decode_entities(target);
-                span = span.with_link_target(decoded);
-            }
+        let target_attribute = match style {
+            InlineStyle::Link => Some("href"),
+            InlineStyle::Abbreviation => Some("title"),
+            _ => None,
+        };
+        if let Some(target) = target_attribute.and_then(|name| start_tag.attributes.get(name)) {
+            let decoded = unescape_ftml_text(target);
+            span = span.with_link_target(decoded);
         }
 
         let mut children = Vec::new();
@@ -1093,8 +1445,9 @@ impl Parser {
             match token {
                 Token::EndTag(tag_name) if tag_name == end_tag => {
                     if !buffer.is_empty() {
-                        let text = self.decode_entities(&buffer);
-                        let normalized = self.normalize_span_whitespace(&text);
+                        let text = unescape_ftml_text(&buffer);
+                        let normalized =
+                            self.normalize_span_whitespace(&text, children.is_empty(), true);
                         children.push(Span::new_text(normalized));
                         buffer.clear();
                     }
@@ -1105,8 +1458,9 @@ impl Parser {
                 Token::SelfClosingTag(tag) => {
                     if tag.name == "br" {
                         if !buffer.is_empty() {
-                            let text = self.decode_entities(&buffer);
-                            let normalized = self.normalize_span_whitespace(&text);
+                            let text = unescape_ftml_text(&buffer);
+                            let normalized =
+                                self.normalize_span_whitespace(&text, children.is_empty(), true);
                             children.push(Span::new_text(format!("{}\n", normalized)));
                             buffer.clear();
                         } else if let Some(last) = children.last_mut() {
@@ -1129,8 +1483,9 @@ impl Parser {
                     let tag_name = tag.name.clone();
                     if let Some(&child_style) = self.inline_elements.get(&tag_name) {
                         if !buffer.is_empty() {
-                            let text = self.decode_entities(&buffer);
-                            let normalized = self.normalize_span_whitespace(&text);
+                            let text = unescape_ftml_text(&buffer);
+                            let normalized =
+                                self.normalize_span_whitespace(&text, children.is_empty(), false);
                             children.push(Span::new_text(normalized));
                             buffer.clear();
                         }
@@ -1184,7 +1539,11 @@ impl Parser {
             .replace(NBSP_PLACEHOLDER, "\u{00A0}")
     }
 
-    fn normalize_span_whitespace(&self, s: &str) -> String {
+    /// Collapses formatting whitespace in text inside an inline element.
+    /// `first` and `last` tell whether the text starts or ends the element;
+    /// only there is a run containing a line break dropped entirely, while
+    /// between text and a nested tag it still separates words.
+    fn normalize_span_whitespace(&self, s: &str, first: bool, last: bool) -> String {
         // Only normalize if the text contains newlines (indicating HTML formatting whitespace)
         if s.contains('\n') {
             const FIGURE_SPACE_PLACEHOLDER: char = '\u{E000}';
@@ -1195,10 +1554,19 @@ impl Parser {
             let mut masked = s.replace('\u{2005}', FIGURE_SPACE_PLACEHOLDER_STR);
             masked = masked.replace('\u{00A0}', NBSP_PLACEHOLDER_STR);
 
-            // Collapse all whitespace (including newlines) to single spaces and trim
+            // Collapse all whitespace (including newlines) to single spaces.
+            let leading_break =
+                first && masked[..masked.len() - masked.trim_start().len()].contains('\n');
+            let trailing_break = last && masked[masked.trim_end().len()..].contains('\n');
             let collapsed = self.space_regex.replace_all(&masked, " ");
+            let mut collapsed: &str = &collapsed;
+            if leading_break {
+                collapsed = collapsed.trim_start();
+            }
+            if trailing_break {
+                collapsed = collapsed.trim_end();
+            }
             collapsed
-                .trim()
                 .replace(FIGURE_SPACE_PLACEHOLDER, "\u{2005}")
                 .replace(NBSP_PLACEHOLDER, "\u{00A0}")
         } else {
@@ -1207,16 +1575,6 @@ impl Parser {
         }
     }
 
-    fn decode_entities(&self, s: &str) -> String {
-        s.replace("&emsp14;", "\u{2005}")
-            .replace("&nbsp;", "\u{00A0}")
-            .replace("&lt;", "<")
-            .replace("&gt;", ">")
-            .replace("&amp;", "&")
-            .replace("&quot;", "\"")
-            .replace("&apos;", "'")
-    }
-
     fn trim_whitespace_with_entities(
         &self,
         mut spans: Vec<Span>,
@@ -1310,6 +1668,59 @@ enum TrimTrailingAction {
 const FIGURE_SPACE: char = '\u{2005}';
 const NON_BREAKING_SPACE: char = '\u{00A0}';
 
+/// Sets the language of a code block read from `tag`, given as a
+/// `language-*` class: `<pre class="language-rust">`. Other paragraphs are
+/// returned unchanged.
+fn with_code_language(paragraph: Paragraph, tag: &Tag) -> Paragraph {
+    if paragraph.paragraph_type() != ParagraphType::CodeBlock {
+        return paragraph;
+    }
+    let language = tag.attributes.get("class").and_then(|class| {
+        class
+            .split_whitespace()
+            .filter_map(|name| name.strip_prefix("language-"))
+            .find(|language| !language.is_empty())
+    });
+    match language {
+        Some(language) => paragraph.with_language(language),
+        None => paragraph,
+    }
+}
+
+/// Decodes the entities FTML knows, reversing the writer's escaping of markup
+/// characters. Works in a single pass, so that escaped ampersands
+/// (`&amp;quot;`) are not decoded a second time.
+fn unescape_ftml_text(s: &str) -> String {
+    const ENTITIES: &[(&str, &str)] = &[
+        ("&emsp14;", "\u{2005}"),
+        ("&nbsp;", "\u{00A0}"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&amp;", "&"),
+        ("&quot;", "\""),
+        ("&apos;", "'"),
+    ];
+
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(idx) = rest.find('&') {
+        result.push_str(&rest[..idx]);
+        rest = &rest[idx..];
+        match ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity)) {
+            Some((entity, decoded)) => {
+                result.push_str(decoded);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
 fn is_preserved_entity_space(ch: char) -> bool {
     matches!(ch, FIGURE_SPACE | NON_BREAKING_SPACE)
 }
@@ -1390,39 +1801,56 @@ fn normalize_entity_whitespace(document: &mut Document) {
 }
 
 fn normalize_paragraph_spaces(paragraph: &mut Paragraph) {
-    // `HorizontalRule` is a leaf but carries no inline content, so it has no
-    // spans to normalize.
-    if paragraph.is_leaf() && !matches!(paragraph, Paragraph::HorizontalRule) {
+    // `HorizontalRule` and `Raw` are leaves but carry no inline content, so
+    // they have no spans to normalize.
+    if paragraph.is_leaf()
+        && !matches!(paragraph, Paragraph::HorizontalRule | Paragraph::Raw { .. })
+    {
         normalize_spans_spaces(paragraph.content_mut());
     }
 
     match paragraph {
-        Paragraph::Quote { children } => {
+        Paragraph::Quote { children } | Paragraph::Admonition { children, .. } => {
+            for child in children {
+                normalize_paragraph_spaces(child);
+            }
+        }
+        Paragraph::Details { summary, children } => {
+            normalize_spans_spaces(summary);
             for child in children {
                 normalize_paragraph_spaces(child);
             }
         }
-        Paragraph::OrderedList { entries } | Paragraph::UnorderedList { entries } => {
+        Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
             for entry in entries {
                 for item in entry {
                     normalize_paragraph_spaces(item);
                 }
             }
         }
-        Paragraph::Table { rows } => {
+        Paragraph::Table { rows, .. } => {
             for row in rows {
                 for cell in &mut row.cells {
                     normalize_spans_spaces(&mut cell.content);
                 }
             }
         }
+        Paragraph::DefinitionList { items } => {
+            for item in items {
+                normalize_spans_spaces(&mut item.term);
+                for child in &mut item.definition {
+                    normalize_paragraph_spaces(child);
+                }
+            }
+        }
         Paragraph::Checklist { .. }
         | Paragraph::Text { .. }
         | Paragraph::Header1 { .. }
         | Paragraph::Header2 { .. }
         | Paragraph::Header3 { .. }
         | Paragraph::CodeBlock { .. }
-        | Paragraph::HorizontalRule => {}
+        | Paragraph::HorizontalRule
+        | Paragraph::Raw { .. } => {}
     }
 }
 
@@ -1442,8 +1870,87 @@ fn normalize_spans_spaces(spans: &mut [Span]) {
     }
 }
 
+/// Yields top-level paragraphs as soon as their closing tag has been read.
+struct ParagraphStream<'a, P> {
+    parser: P,
+    tokenizer: Tokenizer<'a>,
+    // Receives paragraphs completed at the top level.
+    document: Document,
+    breadcrumbs: Vec<Paragraph>,
+    list_item_level: i32,
+    ready: VecDeque<Paragraph>,
+    error: Option<ParseError>,
+    finished: bool,
+}
+
+impl<'a, P: Borrow<Parser>> ParagraphStream<'a, P> {
+    fn new(parser: P, tokenizer: Tokenizer<'a>) -> Self {
+        Self {
+            parser,
+            tokenizer,
+            document: Document::new(),
+            breadcrumbs: Vec::new(),
+            list_item_level: 0,
+            ready: VecDeque::new(),
+            error: None,
+            finished: false,
+        }
+    }
+}
+
+impl<P: Borrow<Parser>> Iterator for ParagraphStream<'_, P> {
+    type Item = Result<Paragraph, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(mut paragraph) = self.ready.pop_front() {
+                normalize_paragraph_spaces(&mut paragraph);
+                return Some(Ok(paragraph));
+            }
+            if let Some(err) = self.error.take() {
+                return Some(Err(err));
+            }
+            if self.finished {
+                return None;
+            }
+
+            let Some(token) = self.tokenizer.next() else {
+                self.finished = true;
+                self.error = self.tokenizer.error.take().map(ParseError::from);
+                continue;
+            };
+
+            let result = self.parser.borrow().process_token(
+                token,
+                &mut self.document,
+                &mut self.breadcrumbs,
+                &mut self.list_item_level,
+                &mut self.tokenizer,
+            );
+            if let Some(err) = self.tokenizer.error.take() {
+                // Whatever was built from the truncated input is incomplete.
+                self.document.paragraphs.clear();
+                self.finished = true;
+                self.error = Some(err.into());
+                continue;
+            }
+            self.ready.extend(self.document.paragraphs.drain(..));
+            if let Err(err) = result {
+                self.finished = true;
+                self.error = Some(ParseError::At {
+                    position: self.tokenizer.position(self.tokenizer.token_start),
+                    source: Box::new(err),
+                });
+            }
+        }
+    }
+}
+
 /// Parses FTML content from any [`Read`] implementor.
 ///
+/// This collects the paragraphs produced by [`parse_streaming`] into a
+/// [`Document`].
+///
 /// # Examples
 ///
 /// ```
@@ -1454,12 +1961,58 @@ fn normalize_spans_spaces(spans: &mut [Span]) {
 /// let document = ftml::parse(&mut input).unwrap();
 /// assert_eq!(document.paragraphs.len(), 1);
 /// ```
-pub fn parse<R: Read>(mut reader: R) -> Result<Document, ParseError> {
-    let mut input = String::new();
-    reader.read_to_string(&mut input)?;
+pub fn parse<R: Read>(reader: R) -> Result<Document, ParseError> {
+    let paragraphs = parse_streaming(reader).collect::<Result<Vec<_>, _>>()?;
+    Ok(Document::new().with_paragraphs(paragraphs))
+}
 
-    let parser = Parser::new();
-    parser.parse_string(&input)
+/// Parses FTML content incrementally, yielding each top-level paragraph as
+/// soon as it is complete.
+///
+/// The reader is consumed in chunks and input that has already been
+/// tokenized is discarded, so memory use is bounded by the largest top-level
+/// paragraph rather than by the size of the input. Parsing stops after the
+/// first error, which is yielded as the final item.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tdoc::ftml;
+///
+/// let input = Cursor::new("<h1>Title</h1><p>Body</p>");
+/// let mut paragraphs = ftml::parse_streaming(input);
+/// assert_eq!(paragraphs.next().unwrap().unwrap().content()[0].text, "Title");
+/// assert_eq!(paragraphs.next().unwrap().unwrap().content()[0].text, "Body");
+/// assert!(paragraphs.next().is_none());
+/// ```
+pub fn parse_streaming<'a, R: Read + 'a>(
+    reader: R,
+) -> impl Iterator<Item = Result<Paragraph, ParseError>> + 'a {
+    ParagraphStream::new(Parser::new(), Tokenizer::from_reader(reader))
+}
+
+/// Parses FTML content from any [`Read`] implementor, recovering from malformed markup.
+///
+/// See [`Parser::parse_string_lenient`] for the recovery strategy. Only I/O
+/// failures abort parsing; every other problem is reported as a diagnostic.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tdoc::ftml;
+///
+/// let input = Cursor::new("<p>Fine</p><blockquote>oops</blockquote><p>Also fine</p>");
+/// let (document, diagnostics) = ftml::parse_lenient(input).unwrap();
+/// assert_eq!(document.paragraphs.len(), 3);
+/// assert_eq!(diagnostics.len(), 1);
+/// ```
+pub fn parse_lenient<R: Read>(reader: R) -> Result<(Document, Vec<ParseError>), ParseError> {
+    let mut diagnostics = Vec::new();
+    let document =
+        Parser::new().parse_tokens_lenient(Tokenizer::from_reader(reader), &mut diagnostics)?;
+    Ok((document, diagnostics))
 }
 
 #[cfg(test)]
@@ -1653,6 +2206,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_wrapped_span_keeps_space_before_nested_tag() {
+        let input = "<p><mark>one\n  two <i>three</i>\n</mark></p>";
+        let doc = parse(Cursor::new(input)).unwrap();
+
+        let mark = &doc.paragraphs[0].content()[0];
+        assert_eq!(mark.children[0].text, "one two ");
+        assert_eq!(mark.children[1].children[0].text, "three");
+        assert_eq!(mark.children.len(), 2);
+    }
+
+    #[test]
+    fn test_entities_are_decoded_once() {
+        let input = "<p>&amp;quot; &amp;lt;p&gt; &amp;amp;</p>";
+        let doc = parse(Cursor::new(input)).unwrap();
+        assert_eq!(doc.paragraphs[0].content()[0].text, "&quot; &lt;p> &amp;");
+    }
+
     #[test]
     fn test_whitespace_edge_in_span() {
         fn doc(s: &str) -> Document {
@@ -1685,12 +2256,94 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error_reports_line_and_column() {
+        let input = "<p>one</p>\n<p>two</p>\n  <blockquote>three</blockquote>\n";
+        let err = parse(Cursor::new(input)).unwrap_err();
+
+        let position = err.position().expect("error should carry a position");
+        assert_eq!(position.line, 3);
+        assert_eq!(position.column, 15);
+        assert!(matches!(err.inner(), ParseError::UnexpectedTextContent(_)));
+        assert!(err.to_string().starts_with("3:15: "));
+    }
+
+    #[test]
+    fn test_lenient_parse_keeps_well_formed_paragraphs() {
+        let input = "<h1>Title</h1>\n\
+            <p>First</p>\n\
+            <blockquote>stray text<p>Quoted</p></blockquote>\n\
+            <p>Unknown <blink>tag</blink></p>\n\
+            <p>Last</p>\n\
+            <ul><li><p>Unclosed";
+        assert!(parse(Cursor::new(input)).is_err());
+
+        let (doc, diagnostics) = parse_lenient(Cursor::new(input)).unwrap();
+        assert_eq!(
+            doc.paragraphs[..2],
+            ftml! { h1 { "Title" } p { "First" } }.paragraphs[..]
+        );
+        assert_eq!(
+            doc.paragraphs
+                .iter()
+                .map(|p| p.paragraph_type())
+                .collect::<Vec<_>>(),
+            vec![
+                ParagraphType::Header1,
+                ParagraphType::Text,
+                ParagraphType::Quote,
+                ParagraphType::Text,
+                ParagraphType::UnorderedList,
+            ]
+        );
+        assert_eq!(doc.paragraphs[3], ftml! { p { "Last" } }.paragraphs[0]);
+        assert_eq!(diagnostics.len(), 3);
+        assert!(diagnostics.iter().all(|err| err.position().is_some()));
+        assert!(matches!(
+            diagnostics[2].inner(),
+            ParseError::UnclosedParagraph(ParagraphType::UnorderedList)
+        ));
+    }
+
+    #[test]
+    fn test_lenient_parse_closes_mismatched_blocks() {
+        let input = "<blockquote><ul><li><p>Item</p></li></blockquote><p>After</p>";
+        let (doc, diagnostics) = parse_lenient(Cursor::new(input)).unwrap();
+
+        assert_eq!(
+            doc,
+            ftml! {
+                quote { ul { li { p { "Item" } } } }
+                p { "After" }
+            }
+        );
+        assert_eq!(diagnostics.len(), 1);
+        assert!(matches!(
+            diagnostics[0].inner(),
+            ParseError::MismatchedClosingTag { .. }
+        ));
+    }
+
+    #[test]
+    fn test_position_from_offset_counts_characters() {
+        let input = "<p>äö</p>\n<p>x";
+        let position = Position::from_offset(input, input.find("x").unwrap());
+        assert_eq!(
+            position,
+            Position {
+                offset: 15,
+                line: 2,
+                column: 4
+            }
+        );
+    }
+
     #[test]
     fn test_parse_code_block_no_trailing_newline() {
         let input = "<pre>\nhello\nworld\n</pre>";
         let parsed = parse(Cursor::new(input)).unwrap();
         assert_eq!(parsed.paragraphs.len(), 1);
-        if let crate::Paragraph::CodeBlock { content } = &parsed.paragraphs[0] {
+        if let crate::Paragraph::CodeBlock { content, .. } = &parsed.paragraphs[0] {
             assert_eq!(content.len(), 1);
             // Trailing newline should be stripped
             assert_eq!(content[0].text, "hello\nworld");
@@ -1698,4 +2351,92 @@ mod tests {
             panic!("Expected code block");
         }
     }
+
+    /// Produces `count` FTML paragraphs on demand, without ever holding the
+    /// whole input in memory.
+    struct GeneratedInput {
+        count: usize,
+        next: usize,
+        pending: Vec<u8>,
+    }
+
+    impl Read for GeneratedInput {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            while self.pending.is_empty() && self.next < self.count {
+                self.pending = format!("<p>Paragraph {} with <b>grüne</b> text.</p>\n", self.next)
+                    .into_bytes();
+                self.next += 1;
+            }
+            let len = buf.len().min(self.pending.len());
+            buf[..len].copy_from_slice(&self.pending[..len]);
+            self.pending.drain(..len);
+            Ok(len)
+        }
+    }
+
+    /// Hands out its input one byte at a time.
+    struct ByteByByte<'a>(&'a [u8]);
+
+    impl Read for ByteByByte<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_streaming_parses_large_input_in_bounded_memory() {
+        let count = 100_000;
+        let input = GeneratedInput {
+            count,
+            next: 0,
+            pending: Vec::new(),
+        };
+        let parser = Parser::new();
+        let mut stream = ParagraphStream::new(&parser, Tokenizer::from_reader(input));
+
+        let mut parsed = 0;
+        while let Some(paragraph) = stream.next() {
+            let paragraph = paragraph.unwrap();
+            assert_eq!(
+                paragraph.content()[0].text,
+                format!("Paragraph {} with ", parsed)
+            );
+            assert_eq!(paragraph.content()[1].children[0].text, "grüne");
+            assert!(stream.tokenizer.input.len() <= 2 * READ_CHUNK_SIZE);
+            parsed += 1;
+        }
+        assert_eq!(parsed, count);
+    }
+
+    #[test]
+    fn test_streaming_handles_reads_split_inside_characters() {
+        let input = "<h1>Grüße 😀</h1>\n<ul><li><p>ä</p></li></ul><pre>\n  ö\n</pre>";
+        let expected = Parser::new().parse_string(input).unwrap();
+        let streamed = parse(ByteByByte(input.as_bytes())).unwrap();
+        assert_eq!(streamed, expected);
+    }
+
+    #[test]
+    fn test_streaming_rejects_invalid_utf8() {
+        let mut paragraphs = parse_streaming(ByteByByte(b"<p>ok</p><p>\xff</p>"));
+        assert!(paragraphs.next().unwrap().is_ok());
+        assert!(matches!(paragraphs.next(), Some(Err(ParseError::Io(_)))));
+        assert!(paragraphs.next().is_none());
+    }
+
+    #[test]
+    fn test_streaming_error_position_spans_discarded_input() {
+        let mut input = "<p>äö</p>\n".repeat(20_000);
+        input.push_str("<p>x</blockquote>");
+        let expected = Parser::new().parse_string(&input).unwrap_err().position();
+        let err = parse(Cursor::new(input.as_bytes())).unwrap_err();
+        assert!(expected.is_some());
+        assert_eq!(err.position(), expected);
+        assert_eq!(err.position().unwrap().line, 20_001);
+    }
 }
@@ -3,7 +3,8 @@
 //! For HTML output that preserves table structure, see [`crate::html::write`].
 
 use crate::{
-    ChecklistItem, Document, InlineStyle, Paragraph, ParagraphType, Span, TableCell, TableRow,
+    Alignment, ChecklistItem, DefinitionItem, Document, InlineStyle, Paragraph, ParagraphType,
+    Span, TableCell,
 };
 use regex::Regex;
 use std::collections::HashMap;
@@ -13,7 +14,8 @@ use std::io::{self, Write};
 ///
 /// `Writer` focuses on producing readable markup that preserves semantic tags
 /// such as lists, block quotes, and inline styles. It defaults to two-space
-/// indentation and an 80 character wrap width.
+/// indentation and an 80 character wrap width; see [`Writer::with_options`]
+/// to change either.
 ///
 /// FTML has no table syntax, so tables are flattened into individual `<p>`
 /// paragraphs. Use [`Writer::new_html`] (or [`crate::html::write`]) to retain
@@ -47,6 +49,28 @@ pub struct Writer {
     any_space_regex: Regex,
 }
 
+/// Layout settings for [`write_with_options`] and [`Writer::with_options`].
+///
+/// The defaults match [`write`]. Setting `indent_width` to 0 and `wrap_width`
+/// to `None` gives compact output with every paragraph on a single line.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Spaces written per nesting level. Defaults to 2.
+    pub indent_width: usize,
+    /// Column after which long text is continued on the next line, or `None`
+    /// to never wrap. Defaults to 80.
+    pub wrap_width: Option<usize>,
+}
+
+impl Default for WriteOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 2,
+            wrap_width: Some(80),
+        }
+    }
+}
+
 impl Default for Writer {
     fn default() -> Self {
         Self::new()
@@ -66,6 +90,13 @@ impl Writer {
         Self::with_tables(true)
     }
 
+    /// Applies the indentation and wrapping settings from `options`.
+    pub fn with_options(mut self, options: &WriteOptions) -> Self {
+        self.indentation = " ".repeat(options.indent_width);
+        self.max_width = options.wrap_width.unwrap_or(usize::MAX);
+        self
+    }
+
     fn with_tables(emit_tables: bool) -> Self {
         let mut style_tags = HashMap::new();
         style_tags.insert(InlineStyle::Bold, "b".to_string());
@@ -74,6 +105,16 @@ impl Writer {
         style_tags.insert(InlineStyle::Strike, "s".to_string());
         style_tags.insert(InlineStyle::Highlight, "mark".to_string());
         style_tags.insert(InlineStyle::Code, "code".to_string());
+        style_tags.insert(InlineStyle::Superscript, "sup".to_string());
+        style_tags.insert(InlineStyle::Subscript, "sub".to_string());
+        style_tags.insert(InlineStyle::Keyboard, "kbd".to_string());
+        style_tags.insert(InlineStyle::Abbreviation, "abbr".to_string());
+        style_tags.insert(InlineStyle::Quotation, "q".to_string());
+        if emit_tables {
+            // Strict FTML has no generic inline container; only HTML output
+            // keeps colors, as `<span style="color: …">`.
+            style_tags.insert(InlineStyle::Color, "span".to_string());
+        }
 
         Self {
             indentation: "  ".to_string(),
@@ -118,8 +159,21 @@ impl Writer {
     /// can only arrive via conversion from Markdown or HTML) are skipped; the
     /// HTML writer keeps them as `<hr />`. Skipping them before the separator
     /// logic runs avoids emitting a stray blank line in their place.
+    ///
+    /// Raw paragraphs are likewise skipped unless they target this writer's
+    /// format (see [`Writer::raw_format`]).
     fn should_skip(&self, paragraph: &Paragraph) -> bool {
-        !self.emit_tables && paragraph.paragraph_type() == ParagraphType::HorizontalRule
+        (!self.emit_tables && paragraph.paragraph_type() == ParagraphType::HorizontalRule)
+            || !paragraph.is_emitted_in(self.raw_format())
+    }
+
+    /// The format name whose raw content this writer passes through verbatim.
+    fn raw_format(&self) -> &'static str {
+        if self.emit_tables {
+            "html"
+        } else {
+            "ftml"
+        }
     }
 
     fn write_paragraph<W: Write>(
@@ -132,7 +186,20 @@ impl Writer {
         let tag = paragraph_type.html_tag();
 
         if paragraph_type == ParagraphType::Table {
-            return self.write_table_paragraph(writer, paragraph.rows(), level);
+            return self.write_table_paragraph(writer, paragraph, level);
+        }
+
+        if paragraph_type == ParagraphType::DefinitionList {
+            return self.write_definition_list(writer, paragraph.definition_items(), level);
+        }
+
+        if let Paragraph::Admonition { kind, children } = paragraph {
+            let quote = Paragraph::admonition_as_quote(*kind, children);
+            return self.write_paragraph(writer, &quote, level);
+        }
+
+        if paragraph_type == ParagraphType::Details && !self.emit_tables {
+            return self.write_flattened_details(writer, paragraph, level);
         }
 
         if paragraph_type == ParagraphType::HorizontalRule {
@@ -147,11 +214,38 @@ impl Writer {
             return Ok(());
         }
 
+        if let Paragraph::Raw { content, .. } = paragraph {
+            if paragraph.is_emitted_in(self.raw_format()) {
+                self.write_indent(writer, level)?;
+                writer.write_all(content.as_bytes())?;
+                if !content.ends_with('\n') {
+                    writeln!(writer)?;
+                }
+            }
+            return Ok(());
+        }
+
         if paragraph_type.is_leaf() {
             if paragraph_type == ParagraphType::CodeBlock {
-                self.write_code_block_paragraph(writer, paragraph.content(), level)
+                self.write_code_block_paragraph(
+                    writer,
+                    paragraph.content(),
+                    paragraph.language(),
+                    level,
+                )
             } else {
-                self.write_leaf_paragraph(writer, paragraph.content(), tag, level)
+                match paragraph.anchor() {
+                    // Strict FTML has no attributes on headings or paragraphs.
+                    Some(anchor) if self.emit_tables => {
+                        let tag = format!("{} id=\"{}\"", tag, self.encode_attribute(anchor));
+                        self.write_leaf_paragraph(writer, paragraph.content(), &tag, level)
+                    }
+                    _ if self.emit_tables && paragraph.is_lead() => {
+                        let tag = format!("{} class=\"lead\"", tag);
+                        self.write_leaf_paragraph(writer, paragraph.content(), &tag, level)
+                    }
+                    _ => self.write_leaf_paragraph(writer, paragraph.content(), tag, level),
+                }
             }
         } else {
             self.write_indent(writer, level)?;
@@ -182,6 +276,10 @@ impl Writer {
                     writeln!(writer, "</li>")?;
                 }
             } else {
+                if paragraph_type == ParagraphType::Details && !paragraph.summary().is_empty() {
+                    self.write_leaf_paragraph(writer, paragraph.summary(), "summary", level + 1)?;
+                }
+
                 let mut first = true;
                 for child in paragraph.children() {
                     if self.should_skip(child) {
@@ -205,6 +303,7 @@ impl Writer {
         &self,
         writer: &mut W,
         content: &[Span],
+        language: Option<&str>,
         level: usize,
     ) -> io::Result<()> {
         let mut code_text = self.collect_code_text(content);
@@ -215,13 +314,20 @@ impl Writer {
         let needs_newline_after_tag = code_text.is_empty() || !code_text.starts_with('\n');
 
         self.write_indent(writer, level)?;
-        write!(writer, "<pre>")?;
+        match language {
+            Some(language) => write!(
+                writer,
+                "<pre class=\"language-{}\">",
+                self.encode_attribute(language)
+            )?,
+            None => write!(writer, "<pre>")?,
+        }
         if needs_newline_after_tag {
             writeln!(writer)?;
         }
 
         if !code_text.is_empty() {
-            let encoded = self.encode_pre_text(&code_text);
+            let encoded = escape_ftml_text(&code_text);
             writer.write_all(encoded.as_bytes())?;
         }
 
@@ -229,10 +335,13 @@ impl Writer {
             writeln!(writer)?;
         }
 
-        self.write_indent(writer, level)?;
+        // The closing tag is not indented: any whitespace before it would
+        // become part of the preformatted text.
         writeln!(writer, "</pre>")
     }
 
+    /// Writes a paragraph of inline content. `tag` may include attributes
+    /// (`h2 id="x"`); the closing tag repeats only the element name.
     fn write_leaf_paragraph<W: Write>(
         &self,
         writer: &mut W,
@@ -257,33 +366,37 @@ impl Writer {
         writeln!(writer)?;
 
         self.write_indent(writer, level)?;
-        writeln!(writer, "</{}>", tag)
+        writeln!(writer, "</{}>", element_name(tag))
     }
 
     fn write_table_paragraph<W: Write>(
         &self,
         writer: &mut W,
-        rows: &[TableRow],
+        table: &Paragraph,
         level: usize,
     ) -> io::Result<()> {
         if self.emit_tables {
-            self.write_html_table(writer, rows, level)
+            self.write_html_table(writer, table, level)
         } else {
-            self.write_flattened_table(writer, rows, level)
+            self.write_flattened_table(writer, table, level)
         }
     }
 
     fn write_flattened_table<W: Write>(
         &self,
         writer: &mut W,
-        rows: &[TableRow],
+        table: &Paragraph,
         level: usize,
     ) -> io::Result<()> {
-        // FTML has no table syntax. Flatten each non-empty cell into its own
-        // `<p>` paragraph so the content survives the round-trip even though
-        // the table structure is lost.
+        // FTML has no table syntax. Flatten the caption and each non-empty
+        // cell into its own `<p>` paragraph so the content survives the
+        // round-trip even though the table structure is lost.
         let mut first = true;
-        for row in rows {
+        if !table.caption().is_empty() {
+            self.write_leaf_paragraph(writer, table.caption(), "p", level)?;
+            first = false;
+        }
+        for row in table.rows() {
             for cell in &row.cells {
                 if cell.content.iter().all(|span| span.is_content_empty()) {
                     continue;
@@ -301,17 +414,21 @@ impl Writer {
     fn write_html_table<W: Write>(
         &self,
         writer: &mut W,
-        rows: &[TableRow],
+        table: &Paragraph,
         level: usize,
     ) -> io::Result<()> {
         self.write_indent(writer, level)?;
         writeln!(writer, "<table>")?;
 
-        for row in rows {
+        if !table.caption().is_empty() {
+            self.write_leaf_paragraph(writer, table.caption(), "caption", level + 1)?;
+        }
+
+        for row in table.rows() {
             self.write_indent(writer, level + 1)?;
             writeln!(writer, "<tr>")?;
-            for cell in &row.cells {
-                self.write_table_cell(writer, cell, level + 2)?;
+            for (col, cell) in row.cells.iter().enumerate() {
+                self.write_table_cell(writer, cell, table.column_alignment(col), level + 2)?;
             }
             self.write_indent(writer, level + 1)?;
             writeln!(writer, "</tr>")?;
@@ -325,13 +442,20 @@ impl Writer {
         &self,
         writer: &mut W,
         cell: &TableCell,
+        alignment: Alignment,
         level: usize,
     ) -> io::Result<()> {
-        let tag = if cell.is_header { "th" } else { "td" };
+        let name = if cell.is_header { "th" } else { "td" };
+        let tag = match alignment {
+            Alignment::Left => name.to_string(),
+            Alignment::Center => format!("{} style=\"text-align: center\"", name),
+            Alignment::Right => format!("{} style=\"text-align: right\"", name),
+        };
+        let tag = tag.as_str();
 
         if cell.content.is_empty() {
             self.write_indent(writer, level)?;
-            writeln!(writer, "<{}></{}>", tag, tag)?;
+            writeln!(writer, "<{}></{}>", tag, name)?;
             return Ok(());
         }
 
@@ -350,7 +474,70 @@ impl Writer {
         writeln!(writer)?;
 
         self.write_indent(writer, level)?;
-        writeln!(writer, "</{}>", tag)
+        writeln!(writer, "</{}>", name)
+    }
+
+    /// FTML has no collapsible sections: write the summary as a bold
+    /// paragraph followed by the body, at the level of the section itself.
+    fn write_flattened_details<W: Write>(
+        &self,
+        writer: &mut W,
+        paragraph: &Paragraph,
+        level: usize,
+    ) -> io::Result<()> {
+        let mut first = true;
+        if !paragraph.summary().is_empty() {
+            let summary =
+                Span::new_styled(InlineStyle::Bold).with_children(paragraph.summary().to_vec());
+            self.write_leaf_paragraph(writer, &[summary], "p", level)?;
+            first = false;
+        }
+        for child in paragraph.children() {
+            if self.should_skip(child) {
+                continue;
+            }
+            if !first {
+                writeln!(writer)?;
+            }
+            first = false;
+            self.write_paragraph(writer, child, level)?;
+        }
+        Ok(())
+    }
+
+    /// Writes `<dl>` markup in HTML mode. FTML has no definition lists, so
+    /// there each term becomes a bold paragraph heading a `<ul>` item.
+    fn write_definition_list<W: Write>(
+        &self,
+        writer: &mut W,
+        items: &[DefinitionItem],
+        level: usize,
+    ) -> io::Result<()> {
+        if !self.emit_tables {
+            let list = Paragraph::definition_list_as_unordered_list(items);
+            return self.write_paragraph(writer, &list, level);
+        }
+
+        self.write_indent(writer, level)?;
+        writeln!(writer, "<dl>")?;
+
+        for item in items {
+            if !item.term.is_empty() {
+                self.write_leaf_paragraph(writer, &item.term, "dt", level + 1)?;
+            }
+            self.write_indent(writer, level + 1)?;
+            writeln!(writer, "<dd>")?;
+            for child in &item.definition {
+                if !self.should_skip(child) {
+                    self.write_paragraph(writer, child, level + 2)?;
+                }
+            }
+            self.write_indent(writer, level + 1)?;
+            writeln!(writer, "</dd>")?;
+        }
+
+        self.write_indent(writer, level)?;
+        writeln!(writer, "</dl>")
     }
 
     fn write_checklist_item<W: Write>(
@@ -447,7 +634,7 @@ impl Writer {
             result.push_str(&self.render_span_simple(span, idx == 0, idx == content.len() - 1));
         }
 
-        result.push_str(&format!("</{}>\n", tag));
+        result.push_str(&format!("</{}>\n", element_name(tag)));
         result
     }
 
@@ -456,11 +643,18 @@ impl Writer {
             return self.render_link_simple(span, first, last);
         }
 
+        if span.style == InlineStyle::Raw {
+            return span
+                .raw_content_for(self.raw_format())
+                .unwrap_or_default()
+                .to_string();
+        }
+
         let mut result = String::new();
 
         if !span.children.is_empty() {
             if let Some(tag) = self.style_tags.get(&span.style) {
-                result.push_str(&format!("<{}>", tag));
+                result.push_str(&self.opening_tag(span, tag));
             }
 
             for child in &span.children {
@@ -533,6 +727,9 @@ impl Writer {
     }
 
     fn append_span_text(span: &Span, buffer: &mut String) {
+        if span.style == InlineStyle::Raw {
+            return;
+        }
         if !span.text.is_empty() {
             buffer.push_str(&span.text);
         }
@@ -541,19 +738,6 @@ impl Writer {
         }
     }
 
-    fn encode_pre_text(&self, text: &str) -> String {
-        let mut encoded = String::new();
-        for ch in text.chars() {
-            match ch {
-                '&' => encoded.push_str("&amp;"),
-                '<' => encoded.push_str("&lt;"),
-                '>' => encoded.push_str("&gt;"),
-                _ => encoded.push(ch),
-            }
-        }
-        encoded
-    }
-
     fn write_span<W: Write>(
         &self,
         writer: &mut W,
@@ -566,8 +750,15 @@ impl Writer {
             return self.write_link_span(writer, span, level, first, last);
         }
 
+        if span.style == InlineStyle::Raw {
+            if let Some(raw) = span.raw_content_for(self.raw_format()) {
+                writer.write_all(raw.as_bytes())?;
+            }
+            return Ok(());
+        }
+
         if let Some(tag) = self.style_tags.get(&span.style) {
-            write!(writer, "<{}>", tag)?;
+            write!(writer, "{}", self.opening_tag(span, tag))?;
         }
 
         if span.children.is_empty() {
@@ -587,6 +778,20 @@ impl Writer {
         Ok(())
     }
 
+    /// Returns the opening tag for a styled span, carrying an abbreviation's
+    /// expansion in its `title` attribute and a color in a `style` attribute.
+    fn opening_tag(&self, span: &Span, tag: &str) -> String {
+        match (&span.style, &span.link_target, span.color) {
+            (InlineStyle::Abbreviation, Some(title), _) => {
+                format!("<{} title=\"{}\">", tag, self.encode_attribute(title))
+            }
+            (InlineStyle::Color, _, Some(color)) => {
+                format!("<{} style=\"color: {}\">", tag, color)
+            }
+            _ => format!("<{}>", tag),
+        }
+    }
+
     fn write_link_span<W: Write>(
         &self,
         writer: &mut W,
@@ -662,7 +867,10 @@ impl Writer {
     }
 
     fn encode_entities(&self, text: &str, first: bool, last: bool) -> String {
-        let mut result = text.replace('\u{2005}', "&emsp14;");
+        // Escape markup characters first, before the space entities below
+        // introduce ampersands of their own.
+        let mut result = escape_ftml_text(text);
+        result = result.replace('\u{2005}', "&emsp14;");
         result = result.replace('\u{00A0}', "&nbsp;");
 
         // Handle spaces at start/end and multiple spaces
@@ -705,9 +913,6 @@ impl Writer {
             })
             .to_string();
 
-        // Encode HTML entities
-        result = result.replace('<', "&lt;");
-
         result
     }
 
@@ -748,12 +953,63 @@ impl Writer {
     }
 }
 
+/// Escapes the characters that are significant in FTML markup (`&`, `<` and
+/// `>`) so that `text` is read back verbatim. Undone by the parser's
+/// `unescape_ftml_text`.
+fn escape_ftml_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Returns the element name of a start tag's contents (`h2` for `h2 id="x"`).
+fn element_name(tag: &str) -> &str {
+    tag.split_whitespace().next().unwrap_or(tag)
+}
+
 /// Convenience helper that writes using a fresh [`Writer`] with default settings.
 pub fn write<W: Write>(writer: &mut W, document: &Document) -> io::Result<()> {
     let w = Writer::new();
     w.write(writer, document)
 }
 
+/// Writes like [`write`], with the layout given by [`WriteOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::{ftml, Document, Paragraph, Span};
+///
+/// let item = Paragraph::new_text().with_content(vec![Span::new_text("Item")]);
+/// let list = Paragraph::new_unordered_list().with_entries(vec![vec![item]]);
+/// let document = Document::new().with_paragraphs(vec![list]);
+///
+/// let options = ftml::WriteOptions {
+///     indent_width: 0,
+///     wrap_width: None,
+/// };
+/// let mut output = Vec::new();
+/// ftml::write_with_options(&mut output, &document, &options).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "<ul>\n<li>\n<p>Item</p>\n</li>\n</ul>\n"
+/// );
+/// ```
+pub fn write_with_options<W: Write>(
+    writer: &mut W,
+    document: &Document,
+    options: &WriteOptions,
+) -> io::Result<()> {
+    Writer::new().with_options(options).write(writer, document)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -787,6 +1043,24 @@ mod tests {
         assert_eq!(result, "<p>This is <b>bold</b> text.</p>\n");
     }
 
+    #[test]
+    fn test_superscript_and_subscript_round_trip() {
+        let doc = ftml! { p { "x", sup { "2" }, " H", sub { "2" }, "O" } };
+
+        let result = Writer::new().write_to_string(&doc).unwrap();
+        assert_eq!(result, "<p>x<sup>2</sup> H<sub>2</sub>O</p>\n");
+        assert_eq!(crate::ftml::parse(result.as_bytes()).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_abbreviation_round_trip() {
+        let doc = ftml! { p { abbr { "World Wide Web" "WWW" } } };
+
+        let result = Writer::new().write_to_string(&doc).unwrap();
+        assert_eq!(result, "<p><abbr title=\"World Wide Web\">WWW</abbr></p>\n");
+        assert_eq!(crate::ftml::parse(result.as_bytes()).unwrap(), doc);
+    }
+
     #[test]
     fn test_link_text() {
         let link_span = Span::new_styled(InlineStyle::Link)
@@ -839,6 +1113,25 @@ mod tests {
         assert_eq!(html, "<p>A</p>\n\n<hr />\n\n<p>B</p>\n");
     }
 
+    #[test]
+    fn test_raw_content_only_reaches_matching_writer() {
+        let doc = Document::new().with_paragraphs(vec![
+            Paragraph::new_text()
+                .with_content(vec![Span::new_text("A "), Span::new_raw("html", "<wbr>")]),
+            Paragraph::new_raw("html", "<video src=\"clip.mp4\"></video>"),
+            Paragraph::new_text().with_content(vec![Span::new_text("B")]),
+        ]);
+
+        let ftml = Writer::new().write_to_string(&doc).unwrap();
+        assert_eq!(ftml, "<p>A </p>\n\n<p>B</p>\n");
+
+        let html = Writer::new_html().write_to_string(&doc).unwrap();
+        assert_eq!(
+            html,
+            "<p>A <wbr></p>\n\n<video src=\"clip.mp4\"></video>\n\n<p>B</p>\n"
+        );
+    }
+
     #[test]
     fn test_header() {
         let paragraph = Paragraph::new_header1().with_content(vec![Span::new_text("Header")]);
@@ -898,6 +1191,24 @@ mod tests {
         assert_eq!(result, "<p>Name</p>\n\n<p>Age</p>\n\n<p>Alice</p>\n");
     }
 
+    #[test]
+    fn test_markup_characters_in_text_round_trip() {
+        let doc = ftml! {
+            p { "if a < b && b > c then <p> &amp; &lt;" b { "x<y>" } }
+            code { "<div class=\"x\">&nbsp;</div>\n  a -> b" }
+            quote { p { link { "https://example.com/?a=1&b=<2>" "<a> & <b>" } } }
+        };
+
+        let result = Writer::new().write_to_string(&doc).unwrap();
+        assert!(result.contains(
+            "if a &lt; b &amp;&amp; b &gt; c then &lt;p&gt; &amp;amp; &amp;lt;<b>x&lt;y&gt;</b>\n"
+        ));
+        assert!(
+            result.contains("&lt;div class=\"x\"&gt;&amp;nbsp;&lt;/div&gt;\n  a -&gt; b\n</pre>")
+        );
+        assert_eq!(ftml::parse(result.as_bytes()).unwrap(), doc);
+    }
+
     #[test]
     fn test_whitespace_handling() {
         fn w(doc: Document) -> String {
@@ -927,4 +1238,46 @@ mod tests {
             "<p>\n  <a href=\"https://www.cnn.com/terms\">Terms of Use </a> | <a href=\"https://www.cnn.com/privacy\">Privacy Policy </a> | <a href=\"https://www.cnn.com/ad-choices\">Ad Choices </a> | Cookie Settings&emsp14;\n</p>\n"
         );
     }
+
+    #[test]
+    fn test_compact_and_pretty_output_parse_to_same_document() {
+        let doc = ftml! {
+            p { "A paragraph that is long enough to be wrapped onto more than one line by the pretty writer." }
+            ul {
+                li { p { "One" } }
+                li { p { "Two, with " b { "bold" } " text and enough words to need wrapping at all." } }
+            }
+        };
+
+        let pretty = Writer::new()
+            .with_options(&WriteOptions {
+                indent_width: 4,
+                wrap_width: Some(40),
+            })
+            .write_to_string(&doc)
+            .unwrap();
+        let compact = Writer::new()
+            .with_options(&WriteOptions {
+                indent_width: 0,
+                wrap_width: None,
+            })
+            .write_to_string(&doc)
+            .unwrap();
+
+        assert!(pretty.starts_with("<p>\n    A paragraph that is long enough to\n    be wrapped"));
+        assert!(pretty.contains("\n    <li>\n        <p>One</p>\n    </li>\n"));
+        assert_eq!(
+            compact,
+            "<p>A paragraph that is long enough to be wrapped onto more than one line by the pretty writer.</p>\n\n\
+             <ul>\n<li>\n<p>One</p>\n</li>\n\n<li>\n<p>Two, with <b>bold</b> text and enough words to need wrapping at all.</p>\n</li>\n</ul>\n"
+        );
+        assert_eq!(Writer::new().write_to_string(&doc).unwrap(), {
+            let mut output = Vec::new();
+            write_with_options(&mut output, &doc, &WriteOptions::default()).unwrap();
+            String::from_utf8(output).unwrap()
+        });
+
+        assert_eq!(ftml::parse(pretty.as_bytes()).unwrap(), doc);
+        assert_eq!(ftml::parse(compact.as_bytes()).unwrap(), doc);
+    }
 }
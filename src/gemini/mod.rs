@@ -4,7 +4,8 @@
 //! This module provides bidirectional conversion between Gemini text
 //! and FTML documents.
 
-use crate::{Document, InlineStyle, Paragraph, ParagraphType, Span};
+use crate::line_endings::NormalizedReader;
+use crate::{ChecklistItem, Document, InlineStyle, Paragraph, ParagraphType, Span};
 use std::io::{BufRead, BufReader, Read, Write};
 
 /// Parses Gemini text into a [`Document`].
@@ -19,7 +20,7 @@ use std::io::{BufRead, BufReader, Read, Write};
 /// assert_eq!(doc.paragraphs[0].paragraph_type(), ParagraphType::Header1);
 /// ```
 pub fn parse<R: Read>(reader: R) -> crate::Result<Document> {
-    let buf_reader = BufReader::new(reader);
+    let buf_reader = BufReader::new(NormalizedReader::new(reader));
     let mut builder = GeminiBuilder::new();
 
     for line in buf_reader.lines() {
@@ -81,49 +82,28 @@ impl GeminiBuilder {
             self.flush_quote();
             let rest = rest.trim_start();
             if let Some((url, description)) = parse_link_line(rest) {
-                let span = if description.is_empty() {
-                    Span::new_styled(InlineStyle::Link).with_link_target(url.to_string())
-                } else {
-                    Span::new_styled(InlineStyle::Link)
-                        .with_link_target(url.to_string())
-                        .with_children(vec![Span::new_text(description)])
-                };
+                let span = Span::link_with_text(url, description);
                 let paragraph = Paragraph::new_text().with_content(vec![span]);
                 self.paragraphs.push(paragraph);
             }
             return;
         }
 
-        // Heading lines
-        if let Some(rest) = line.strip_prefix("###") {
-            self.flush_list();
-            self.flush_quote();
-            let content = rest.trim();
-            if !content.is_empty() {
-                let paragraph = Paragraph::new(ParagraphType::Header3)
-                    .with_content(vec![Span::new_text(content)]);
-                self.paragraphs.push(paragraph);
-            }
-            return;
-        }
-        if let Some(rest) = line.strip_prefix("##") {
-            self.flush_list();
-            self.flush_quote();
-            let content = rest.trim();
-            if !content.is_empty() {
-                let paragraph = Paragraph::new(ParagraphType::Header2)
-                    .with_content(vec![Span::new_text(content)]);
-                self.paragraphs.push(paragraph);
-            }
-            return;
-        }
-        if let Some(rest) = line.strip_prefix('#') {
+        // Heading lines. Gemtext only knows three levels, so deeper markers
+        // such as `####` are clamped to a level-3 heading.
+        let level = line.bytes().take_while(|&b| b == b'#').count();
+        if level > 0 {
             self.flush_list();
             self.flush_quote();
-            let content = rest.trim();
+            let content = line[level..].trim();
             if !content.is_empty() {
-                let paragraph = Paragraph::new(ParagraphType::Header1)
-                    .with_content(vec![Span::new_text(content)]);
+                let paragraph_type = match level {
+                    1 => ParagraphType::Header1,
+                    2 => ParagraphType::Header2,
+                    _ => ParagraphType::Header3,
+                };
+                let paragraph =
+                    Paragraph::new(paragraph_type).with_content(vec![Span::new_text(content)]);
                 self.paragraphs.push(paragraph);
             }
             return;
@@ -214,16 +194,14 @@ impl GeminiBuilder {
     }
 }
 
+/// Splits the text after `=>` into the URL and the optional description,
+/// which are separated by any run of spaces and tabs.
 fn parse_link_line(rest: &str) -> Option<(&str, &str)> {
-    // Format: URL [DESCRIPTION]
-    // Find first whitespace to separate URL from description
-    if let Some(idx) = rest.find(char::is_whitespace) {
-        let url = &rest[..idx];
-        let description = rest[idx..].trim();
-        Some((url, description))
-    } else {
+    let rest = rest.trim_start_matches([' ', '\t']);
+    match rest.split_once([' ', '\t']) {
+        Some((url, description)) => Some((url, description.trim())),
         // No description, just URL
-        Some((rest, ""))
+        None => Some((rest.trim_end(), "")),
     }
 }
 
@@ -243,48 +221,57 @@ fn parse_link_line(rest: &str) -> Option<(&str, &str)> {
 /// assert_eq!(String::from_utf8(output).unwrap(), "Hello\n");
 /// ```
 pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<()> {
-    let mut first = true;
-    for paragraph in &document.paragraphs {
-        if !first {
+    write_paragraphs(writer, &document.paragraphs)
+}
+
+/// Writes a run of block paragraphs, separated by blank lines.
+///
+/// Gemtext is line-oriented and has no block delimiters, so blank lines are
+/// what keep blocks apart: every heading is preceded by one, and lists,
+/// quotes and flattened tables are followed by one, so the next line is not
+/// read as a continuation. Text lines are spaced the same way, which is how
+/// gemtext paragraphs are conventionally written.
+fn write_paragraphs<W: Write>(writer: &mut W, paragraphs: &[Paragraph]) -> std::io::Result<()> {
+    let visible = paragraphs.iter().filter(|p| p.is_emitted_in("gemini"));
+    for (i, paragraph) in visible.enumerate() {
+        if i > 0 {
             writeln!(writer)?;
         }
         write_paragraph(writer, paragraph)?;
-        first = false;
     }
     Ok(())
 }
 
 fn write_paragraph<W: Write>(writer: &mut W, paragraph: &Paragraph) -> std::io::Result<()> {
     match paragraph {
-        Paragraph::Text { content } => {
+        Paragraph::Text { content, .. } => {
             write_text_paragraph(writer, content)?;
         }
-        Paragraph::Header1 { content } => {
+        Paragraph::Header1 { content, .. } => {
             write!(writer, "# ")?;
             write_spans_plain(writer, content)?;
             writeln!(writer)?;
         }
-        Paragraph::Header2 { content } => {
+        Paragraph::Header2 { content, .. } => {
             write!(writer, "## ")?;
             write_spans_plain(writer, content)?;
             writeln!(writer)?;
         }
-        Paragraph::Header3 { content } => {
+        Paragraph::Header3 { content, .. } => {
             write!(writer, "### ")?;
             write_spans_plain(writer, content)?;
             writeln!(writer)?;
         }
-        Paragraph::CodeBlock { content } => {
+        Paragraph::CodeBlock { content, .. } => {
             writeln!(writer, "```")?;
             write_spans_plain(writer, content)?;
             writeln!(writer, "```")?;
         }
-        Paragraph::Quote { children } => {
-            for child in children {
-                write_quoted_paragraph(writer, child)?;
-            }
+        Paragraph::Quote { children } => write_quote_children(writer, children)?,
+        Paragraph::Admonition { kind, children } => {
+            write_paragraph(writer, &Paragraph::admonition_as_quote(*kind, children))?
         }
-        Paragraph::UnorderedList { entries } | Paragraph::OrderedList { entries } => {
+        Paragraph::UnorderedList { entries } | Paragraph::OrderedList { entries, .. } => {
             for entry in entries {
                 write!(writer, "* ")?;
                 for (i, p) in entry.iter().enumerate() {
@@ -298,21 +285,9 @@ fn write_paragraph<W: Write>(writer: &mut W, paragraph: &Paragraph) -> std::io::
         }
         Paragraph::Checklist { items } => {
             // Gemini doesn't have native checklist support, render as unordered list
-            for item in items {
-                let marker = if item.checked { "[x]" } else { "[ ]" };
-                write!(writer, "* {} ", marker)?;
-                write_spans_plain(writer, &item.content)?;
-                writeln!(writer)?;
-
-                // Render nested children as indented items
-                for child in &item.children {
-                    write!(writer, "* {} ", if child.checked { "[x]" } else { "[ ]" })?;
-                    write_spans_plain(writer, &child.content)?;
-                    writeln!(writer)?;
-                }
-            }
+            write_checklist_items(writer, items, 0)?;
         }
-        Paragraph::Table { rows } => {
+        Paragraph::Table { rows, .. } => {
             // Gemini has no native table syntax; flatten each non-empty cell
             // into a plain text line so the content survives the round-trip.
             for row in rows {
@@ -325,11 +300,62 @@ fn write_paragraph<W: Write>(writer: &mut W, paragraph: &Paragraph) -> std::io::
                 }
             }
         }
+        Paragraph::Details { summary, children } => {
+            // Gemtext can't collapse content; show the summary as a heading
+            // line above the body.
+            if !summary.is_empty() {
+                write!(writer, "### ")?;
+                write_spans_plain(writer, summary)?;
+                writeln!(writer)?;
+                if children.iter().any(|p| p.is_emitted_in("gemini")) {
+                    writeln!(writer)?;
+                }
+            }
+            write_paragraphs(writer, children)?;
+        }
+        Paragraph::DefinitionList { items } => {
+            // Gemtext has no definition lists: put each term on its own line,
+            // followed by a line per definition paragraph.
+            for item in items {
+                if !item.term.is_empty() {
+                    write_spans_plain(writer, &item.term)?;
+                    writeln!(writer)?;
+                }
+                for p in &item.definition {
+                    write_paragraph_inline(writer, p)?;
+                    writeln!(writer)?;
+                }
+            }
+        }
         Paragraph::HorizontalRule => {
             // Gemtext has no thematic-break construct. Degrade to a plain-text
             // divider line so a human reader still sees the separation.
             writeln!(writer, "---")?;
         }
+        Paragraph::Raw { content, .. } => {
+            // Only gemtext raw content reaches this point (see `write`).
+            writer.write_all(content.as_bytes())?;
+            if !content.ends_with('\n') {
+                writeln!(writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes checklist items as `* [x]` lines, indenting nested items by two
+/// spaces per level.
+fn write_checklist_items<W: Write>(
+    writer: &mut W,
+    items: &[ChecklistItem],
+    depth: usize,
+) -> std::io::Result<()> {
+    for item in items {
+        let marker = if item.checked { "[x]" } else { "[ ]" };
+        write!(writer, "{}* {} ", "  ".repeat(depth), marker)?;
+        write_spans_plain(writer, &item.content)?;
+        writeln!(writer)?;
+        write_checklist_items(writer, &item.children, depth + 1)?;
     }
     Ok(())
 }
@@ -357,20 +383,29 @@ fn write_text_paragraph<W: Write>(writer: &mut W, content: &[Span]) -> std::io::
     Ok(())
 }
 
+/// Writes the paragraphs of a quote, separated by empty `>` lines so they
+/// stay apart without ending the quote.
+fn write_quote_children<W: Write>(writer: &mut W, children: &[Paragraph]) -> std::io::Result<()> {
+    let visible = children.iter().filter(|p| p.is_emitted_in("gemini"));
+    for (i, child) in visible.enumerate() {
+        if i > 0 {
+            writeln!(writer, ">")?;
+        }
+        write_quoted_paragraph(writer, child)?;
+    }
+    Ok(())
+}
+
 fn write_quoted_paragraph<W: Write>(writer: &mut W, paragraph: &Paragraph) -> std::io::Result<()> {
     match paragraph {
-        Paragraph::Text { content } => {
+        Paragraph::Text { content, .. } => {
             // Split content by newlines and prefix each with >
             let text = collect_plain_text_from_spans(content);
             for line in text.lines() {
                 writeln!(writer, "> {}", line)?;
             }
         }
-        Paragraph::Quote { children } => {
-            for child in children {
-                write_quoted_paragraph(writer, child)?;
-            }
-        }
+        Paragraph::Quote { children } => write_quote_children(writer, children)?,
         _ => {
             write!(writer, "> ")?;
             write_paragraph_inline(writer, paragraph)?;
@@ -382,15 +417,18 @@ fn write_quoted_paragraph<W: Write>(writer: &mut W, paragraph: &Paragraph) -> st
 
 fn write_paragraph_inline<W: Write>(writer: &mut W, paragraph: &Paragraph) -> std::io::Result<()> {
     match paragraph {
-        Paragraph::Text { content }
-        | Paragraph::Header1 { content }
-        | Paragraph::Header2 { content }
-        | Paragraph::Header3 { content } => {
+        Paragraph::Text { content, .. }
+        | Paragraph::Header1 { content, .. }
+        | Paragraph::Header2 { content, .. }
+        | Paragraph::Header3 { content, .. } => {
             write_spans_plain(writer, content)?;
         }
-        Paragraph::CodeBlock { content } => {
+        Paragraph::CodeBlock { content, .. } => {
             write_spans_plain(writer, content)?;
         }
+        Paragraph::Raw { content, .. } if paragraph.is_emitted_in("gemini") => {
+            writer.write_all(content.trim_end_matches('\n').as_bytes())?;
+        }
         _ => {}
     }
     Ok(())
@@ -404,13 +442,26 @@ fn write_spans_plain<W: Write>(writer: &mut W, spans: &[Span]) -> std::io::Resul
 }
 
 fn write_span_plain<W: Write>(writer: &mut W, span: &Span) -> std::io::Result<()> {
+    if span.style == InlineStyle::Raw {
+        if let Some(raw) = span.raw_content_for("gemini") {
+            writer.write_all(raw.as_bytes())?;
+        }
+        return Ok(());
+    }
     // Gemini doesn't support inline formatting, so we flatten everything
+    let quoted = span.style == InlineStyle::Quotation;
+    if quoted {
+        writer.write_all(b"\"")?;
+    }
     if !span.text.is_empty() {
         writer.write_all(span.text.as_bytes())?;
     }
     for child in &span.children {
         write_span_plain(writer, child)?;
     }
+    if quoted {
+        writer.write_all(b"\"")?;
+    }
     Ok(())
 }
 
@@ -433,12 +484,23 @@ fn collect_plain_text_from_spans(spans: &[Span]) -> String {
 }
 
 fn collect_plain_text(span: &Span, buffer: &mut String) {
+    if span.style == InlineStyle::Raw {
+        buffer.push_str(span.raw_content_for("gemini").unwrap_or_default());
+        return;
+    }
+    let quoted = span.style == InlineStyle::Quotation;
+    if quoted {
+        buffer.push('"');
+    }
     if !span.text.is_empty() {
         buffer.push_str(&span.text);
     }
     for child in &span.children {
         collect_plain_text(child, buffer);
     }
+    if quoted {
+        buffer.push('"');
+    }
 }
 
 #[cfg(test)]
@@ -495,6 +557,29 @@ mod tests {
         assert_eq!(span.children[0].text, "Example Site");
     }
 
+    #[test]
+    fn test_parse_link_separated_by_tabs_and_spaces() {
+        for input in [
+            "=> https://example.com\tDescription",
+            "=> https://example.com    Description",
+            "=>\thttps://example.com \t Description\t",
+        ] {
+            let parsed = parse(Cursor::new(input)).unwrap();
+            assert_eq!(
+                parsed,
+                doc(vec![p_(vec![link_text__(
+                    "https://example.com",
+                    "Description"
+                )])]),
+                "{input:?}"
+            );
+        }
+        assert_eq!(
+            parse_link_line("https://example.com\t"),
+            Some(("https://example.com", ""))
+        );
+    }
+
     #[test]
     fn test_parse_link_without_description() {
         let input = "=> https://example.com";
@@ -531,7 +616,7 @@ mod tests {
             Paragraph::Quote { children } => {
                 assert_eq!(children.len(), 1);
                 match &children[0] {
-                    Paragraph::Text { content } => {
+                    Paragraph::Text { content, .. } => {
                         let text = collect_plain_text_from_spans(content);
                         assert_eq!(text, "This is a quote\nspanning multiple lines");
                     }
@@ -548,7 +633,7 @@ mod tests {
         let parsed = parse(Cursor::new(input)).unwrap();
         assert_eq!(parsed.paragraphs.len(), 1);
         match &parsed.paragraphs[0] {
-            Paragraph::CodeBlock { content } => {
+            Paragraph::CodeBlock { content, .. } => {
                 let text = collect_plain_text_from_spans(content);
                 assert_eq!(text, "fn main() {\n    println!(\"Hello\");\n}\n");
             }
@@ -574,6 +659,41 @@ mod tests {
         assert_eq!(result, "# Main Header\n");
     }
 
+    #[test]
+    fn test_parse_link_with_description_equal_to_url() {
+        let parsed = parse(Cursor::new("=> https://x https://x")).unwrap();
+        assert_eq!(parsed, doc(vec![p_(vec![link__("https://x")])]));
+    }
+
+    #[test]
+    fn test_parse_deep_heading_clamps_to_level_three() {
+        let parsed = parse(Cursor::new("#### x\n###### Deeper")).unwrap();
+        assert_eq!(parsed, doc(vec![h3_("x"), h3_("Deeper")]));
+    }
+
+    #[test]
+    fn test_write_nested_checklist() {
+        let mut output = Vec::new();
+        let doc = doc(vec![checklist_(vec![
+            task_(
+                false,
+                "Release",
+                vec![task_(
+                    true,
+                    "Changelog",
+                    vec![task__(true, "Draft"), task__(false, "Review")],
+                )],
+            ),
+            task__(true, "Announce"),
+        ])]);
+        write(&mut output, &doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+        assert_eq!(
+            result,
+            "* [ ] Release\n  * [x] Changelog\n    * [x] Draft\n    * [ ] Review\n* [x] Announce\n"
+        );
+    }
+
     #[test]
     fn test_write_link() {
         let mut output = Vec::new();
@@ -607,6 +727,36 @@ mod tests {
         assert_eq!(result, "> This is quoted.\n");
     }
 
+    #[test]
+    fn test_write_separates_headings_and_lists() {
+        let mut output = Vec::new();
+        let doc = doc(vec![
+            p__("Intro"),
+            h2_("Items"),
+            ul_(vec![li_(vec![p__("One")]), li_(vec![p__("Two")])]),
+            p__("After the list"),
+        ]);
+        write(&mut output, &doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+        assert_eq!(
+            result,
+            "Intro\n\n## Items\n\n* One\n* Two\n\nAfter the list\n"
+        );
+    }
+
+    #[test]
+    fn test_write_spacing_inside_quotes_and_details() {
+        let mut output = Vec::new();
+        let doc = doc(vec![
+            quote_(vec![p__("First"), p__("Second")]),
+            Paragraph::new_details()
+                .with_children(vec![ul_(vec![li_(vec![p__("Item")])]), h3_("Heading")]),
+        ]);
+        write(&mut output, &doc).unwrap();
+        let result = String::from_utf8(output).unwrap();
+        assert_eq!(result, "> First\n>\n> Second\n\n* Item\n\n### Heading\n");
+    }
+
     #[test]
     fn test_write_code_block() {
         let mut output = Vec::new();
@@ -1,4 +1,32 @@
+//! A small, lossless tokenizer for HTML and XML.
+//!
+//! [`Tokenizer`] splits markup into [`Token`]s without building a tree or
+//! validating the structure, so it copes with fragments and broken markup
+//! alike. Concatenating the [`Token::raw`] text of every token reproduces the
+//! input exactly. [`StreamTokenizer`] yields the same tokens for input read
+//! from any [`Read`] implementor.
+//!
+//! # Examples
+//!
+//! Collect the link targets of a snippet:
+//!
+//! ```
+//! use tdoc::html::gockl::{Token, Tokenizer};
+//!
+//! let html = r#"<p>See <a href="https://example.com">this</a> and <a href=/about>that</a>.</p>"#;
+//!
+//! let mut links = Vec::new();
+//! for token in Tokenizer::new(html) {
+//!     let token = token.unwrap();
+//!     if token.as_element().is_some_and(|element| element.name() == "a") {
+//!         links.extend(token.attribute("href"));
+//!     }
+//! }
+//! assert_eq!(links, ["https://example.com", "/about"]);
+//! ```
+
 use std::cmp::min;
+use std::io::{self, Read};
 
 const SPACE_CHARS: &str = " \t\r\n";
 
@@ -27,18 +55,29 @@ fn find_byte(haystack: &[u8], start: usize, value: u8) -> Option<usize> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// Splits an in-memory string into [`Token`]s.
+///
+/// Use it as an [`Iterator`] or call [`Tokenizer::next_token`] directly.
 pub struct Tokenizer<'a> {
     input: &'a str,
     bytes: &'a [u8],
     position: usize,
+    /// Whether `input` is only a prefix of the document, as when driven by a
+    /// [`StreamTokenizer`].
+    partial: bool,
+    /// Set when a token could only be cut short because `input` is partial.
+    incomplete: bool,
 }
 
 impl<'a> Tokenizer<'a> {
+    /// Creates a tokenizer positioned at the start of `input`.
     pub fn new(input: &'a str) -> Self {
         Self {
             input,
             bytes: input.as_bytes(),
             position: 0,
+            partial: false,
+            incomplete: false,
         }
     }
 
@@ -50,6 +89,8 @@ impl<'a> Tokenizer<'a> {
             return slice_to_string(self.bytes, start, end_pos);
         }
 
+        // The terminator might still follow in input that is yet to be read.
+        self.incomplete |= self.partial;
         self.shift_until('<')
     }
 
@@ -168,6 +209,8 @@ impl<'a> Tokenizer<'a> {
         end <= self.bytes.len() && &self.bytes[start..end] == next.as_bytes()
     }
 
+    /// Returns the next token, or [`TokenizerError::Eof`] once the input is
+    /// exhausted.
     pub fn next_token(&mut self) -> Result<Token, TokenizerError> {
         let len = self.bytes.len();
         if self.position >= len {
@@ -215,6 +258,10 @@ impl<'a> Tokenizer<'a> {
             }
         }
 
+        // Too little input is left to tell a tag from text, which might change
+        // once more input arrives.
+        self.incomplete |= self.partial && bytes[self.position] == b'<';
+
         let raw = self.shift_until('<');
         if raw.is_empty() {
             return Err(TokenizerError::Eof);
@@ -223,19 +270,172 @@ impl<'a> Tokenizer<'a> {
     }
 }
 
+/// Number of bytes a [`StreamTokenizer`] requests from its reader at once.
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Tokenizer that pulls its input from a [`Read`] in buffered chunks.
+///
+/// It yields the same tokens as [`Tokenizer`] does for the complete input,
+/// while only holding the unconsumed part of the current chunk in memory. A
+/// token that runs into the end of the buffered data is re-read once more
+/// input has arrived.
+///
+/// Read failures end the token stream like the end of input does; check
+/// [`StreamTokenizer::take_error`] afterwards to tell the two apart.
+pub struct StreamTokenizer<R> {
+    reader: Option<R>,
+    buffer: String,
+    // Start of the unconsumed input within `buffer`.
+    start: usize,
+    // Trailing bytes of a UTF-8 sequence split across two reads.
+    pending: Vec<u8>,
+    error: Option<io::Error>,
+}
+
+impl<R: Read> StreamTokenizer<R> {
+    /// Creates a tokenizer that reads from `reader` on demand.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(reader),
+            buffer: String::new(),
+            start: 0,
+            pending: Vec::new(),
+            error: None,
+        }
+    }
+
+    /// Returns the next token, or [`TokenizerError::Eof`] once the input is
+    /// exhausted or reading failed.
+    pub fn next_token(&mut self) -> Result<Token, TokenizerError> {
+        loop {
+            let window = &self.buffer[self.start..];
+            let mut tokenizer = Tokenizer::new(window);
+            tokenizer.partial = self.reader.is_some();
+            let result = tokenizer.next_token();
+            let needs_more =
+                tokenizer.partial && (tokenizer.incomplete || tokenizer.position >= window.len());
+            let consumed = tokenizer.position;
+
+            if needs_more {
+                if !self.fill() && self.error.is_some() {
+                    return Err(TokenizerError::Eof);
+                }
+                continue;
+            }
+
+            self.start += consumed;
+            if self.start >= READ_CHUNK_SIZE {
+                self.buffer.drain(..self.start);
+                self.start = 0;
+            }
+            return result;
+        }
+    }
+
+    /// Returns the error that cut the token stream short, if reading failed.
+    pub fn take_error(&mut self) -> Option<io::Error> {
+        self.error.take()
+    }
+
+    /// Appends the next chunk of the reader to `buffer`. Returns `false` once
+    /// the reader is exhausted or failed, discarding unconsumed input in the
+    /// latter case.
+    fn fill(&mut self) -> bool {
+        let Some(reader) = self.reader.as_mut() else {
+            return false;
+        };
+
+        let mut chunk = vec![0; READ_CHUNK_SIZE];
+        let read = loop {
+            match reader.read(&mut chunk) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return self.fail(err),
+            }
+        };
+
+        if read == 0 {
+            self.reader = None;
+            if !self.pending.is_empty() {
+                return self.fail(invalid_utf8());
+            }
+            return false;
+        }
+
+        self.pending.extend_from_slice(&chunk[..read]);
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(text) => text.len(),
+            // An incomplete sequence at the end is finished by the next read.
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => return self.fail(invalid_utf8()),
+        };
+        let text = std::str::from_utf8(&self.pending[..valid]).expect("validated above");
+        self.buffer.push_str(text);
+        self.pending.drain(..valid);
+        true
+    }
+
+    fn fail(&mut self, err: io::Error) -> bool {
+        self.error = Some(err);
+        self.reader = None;
+        self.start = self.buffer.len();
+        false
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Result<Token, TokenizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok(token) => Some(Ok(token)),
+            Err(TokenizerError::Eof) => None,
+        }
+    }
+}
+
+impl<R: Read> Iterator for StreamTokenizer<R> {
+    type Item = Result<Token, TokenizerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok(token) => Some(Ok(token)),
+            Err(TokenizerError::Eof) => None,
+        }
+    }
+}
+
+fn invalid_utf8() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "stream did not contain valid UTF-8",
+    )
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// A piece of markup. Every variant keeps the exact source text it was read
+/// from, available through [`Token::raw`].
 pub enum Token {
+    /// Character data between tags, with entities left undecoded.
     Text(String),
+    /// A `<![CDATA[...]]>` section.
     CData(String),
+    /// A `<!-- ... -->` comment.
     Comment(String),
+    /// A `<!...>` declaration such as `<!DOCTYPE html>`.
     Directive(String),
+    /// A `<?...?>` processing instruction.
     ProcInst(String),
+    /// An opening tag such as `<p class="x">`.
     StartElement(StartElementToken),
+    /// A closing tag such as `</p>`.
     EndElement(EndElementToken),
+    /// A self-closing tag such as `<br/>`.
     EmptyElement(EmptyElementToken),
 }
 
 impl Token {
+    /// Returns the source text of the token.
     pub fn raw(&self) -> &str {
         match self {
             Token::Text(raw)
@@ -249,6 +449,8 @@ impl Token {
         }
     }
 
+    /// Returns the token as an element tag, or `None` for text, comments and
+    /// other non-element tokens.
     pub fn as_element(&self) -> Option<ElementToken<'_>> {
         match self {
             Token::StartElement(tok) => Some(ElementToken::Start(tok)),
@@ -257,9 +459,25 @@ impl Token {
             _ => None,
         }
     }
+
+    /// Returns the attributes of a start or empty element tag in source
+    /// order. Every other token has none.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        self.as_element()
+            .map(|element| element.attributes())
+            .unwrap_or_default()
+    }
+
+    /// Returns the value of the attribute called `name`, compared
+    /// case-insensitively.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        self.as_element()
+            .and_then(|element| element.attribute(name))
+    }
 }
 
 #[derive(Debug)]
+/// A borrowed view of any of the three element tag tokens.
 pub enum ElementToken<'a> {
     Start(&'a StartElementToken),
     Empty(&'a EmptyElementToken),
@@ -267,6 +485,7 @@ pub enum ElementToken<'a> {
 }
 
 impl<'a> ElementToken<'a> {
+    /// Returns the tag name as written in the source.
     pub fn name(&self) -> &str {
         match self {
             ElementToken::Start(tok) => tok.name(),
@@ -274,22 +493,45 @@ impl<'a> ElementToken<'a> {
             ElementToken::End(tok) => tok.name(),
         }
     }
+
+    /// Returns the attributes of the tag; closing tags have none.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        match self {
+            ElementToken::Start(tok) => tok.attributes(),
+            ElementToken::Empty(tok) => tok.attributes(),
+            ElementToken::End(_) => Vec::new(),
+        }
+    }
+
+    /// Returns the value of the attribute called `name`, compared
+    /// case-insensitively.
+    pub fn attribute(&self, name: &str) -> Option<String> {
+        match self {
+            ElementToken::Start(tok) => tok.attribute(name),
+            ElementToken::Empty(tok) => tok.attribute(name),
+            ElementToken::End(_) => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// An opening tag such as `<p class="x">`.
 pub struct StartElementToken {
     raw: String,
 }
 
 impl StartElementToken {
+    /// Wraps the source text of a tag.
     pub fn new<S: Into<String>>(raw: S) -> Self {
         Self { raw: raw.into() }
     }
 
+    /// Returns the source text of the tag.
     pub fn raw(&self) -> &str {
         &self.raw
     }
 
+    /// Returns the tag name as written in the source.
     pub fn name(&self) -> &str {
         if self.raw.len() <= 1 {
             return "";
@@ -302,6 +544,7 @@ impl StartElementToken {
         }
     }
 
+    /// Returns the attributes of the tag in source order.
     pub fn attributes(&self) -> Vec<Attribute> {
         if self.raw.len() <= 1 {
             return Vec::new();
@@ -314,6 +557,8 @@ impl StartElementToken {
         get_attributes(content)
     }
 
+    /// Returns the value of the attribute called `name`, compared
+    /// case-insensitively.
     pub fn attribute(&self, name: &str) -> Option<String> {
         if self.raw.len() < 2 {
             return None;
@@ -328,19 +573,23 @@ impl StartElementToken {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// A closing tag such as `</p>`.
 pub struct EndElementToken {
     raw: String,
 }
 
 impl EndElementToken {
+    /// Wraps the source text of a tag.
     pub fn new<S: Into<String>>(raw: S) -> Self {
         Self { raw: raw.into() }
     }
 
+    /// Returns the source text of the tag.
     pub fn raw(&self) -> &str {
         &self.raw
     }
 
+    /// Returns the tag name as written in the source.
     pub fn name(&self) -> &str {
         if self.raw.len() <= 2 {
             ""
@@ -367,19 +616,23 @@ impl EndElementToken {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// A self-closing tag such as `<img src="x.png"/>`.
 pub struct EmptyElementToken {
     raw: String,
 }
 
 impl EmptyElementToken {
+    /// Wraps the source text of a tag.
     pub fn new<S: Into<String>>(raw: S) -> Self {
         Self { raw: raw.into() }
     }
 
+    /// Returns the source text of the tag.
     pub fn raw(&self) -> &str {
         &self.raw
     }
 
+    /// Returns the tag name as written in the source.
     pub fn name(&self) -> &str {
         if self.raw.len() <= 1 {
             return "";
@@ -398,6 +651,7 @@ impl EmptyElementToken {
         }
     }
 
+    /// Returns the attributes of the tag in source order.
     pub fn attributes(&self) -> Vec<Attribute> {
         if self.raw.len() <= 2 {
             return Vec::new();
@@ -407,6 +661,8 @@ impl EmptyElementToken {
         get_attributes(content)
     }
 
+    /// Returns the value of the attribute called `name`, compared
+    /// case-insensitively.
     pub fn attribute(&self, name: &str) -> Option<String> {
         if self.raw.len() <= 2 {
             return None;
@@ -418,6 +674,8 @@ impl EmptyElementToken {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// A single attribute of an element tag. Quotes around the value are removed;
+/// entities are left undecoded.
 pub struct Attribute {
     pub name: String,
     pub content: String,
@@ -459,22 +717,6 @@ impl<'a> AttributeTokenizer<'a> {
         }
     }
 
-    fn shift_until(&mut self, next: &str) -> String {
-        if self.position >= self.bytes.len() {
-            return String::new();
-        }
-
-        if let Some(pos) = find_subslice(self.bytes, self.position + 1, next.as_bytes()) {
-            let start = self.position;
-            self.position = pos;
-            return slice_to_string(self.bytes, start, pos);
-        }
-
-        let start = self.position;
-        self.position = self.bytes.len();
-        slice_to_string(self.bytes, start, self.bytes.len())
-    }
-
     fn shift_until_space(&mut self) -> String {
         if self.position >= self.bytes.len() {
             return String::new();
@@ -547,10 +789,25 @@ impl<'a> AttributeTokenizer<'a> {
             return None;
         }
 
-        let key = self.shift_until("=");
-        if self.position < self.input.len() {
+        let start = self.position;
+        while self.position < self.bytes.len() {
+            let ch = self.bytes[self.position];
+            if ch == b'=' || SPACE_CHARS.as_bytes().contains(&ch) {
+                break;
+            }
             self.position += 1;
         }
+        let key = slice_to_string(self.bytes, start, self.position);
+        let _ = self.eat_space();
+
+        // Boolean attributes such as `checked` carry no value.
+        if self.bytes.get(self.position) != Some(&b'=') {
+            return Some(Attribute {
+                name: key,
+                content: String::new(),
+            });
+        }
+        self.position += 1;
         let _ = self.eat_space();
 
         if self.position >= self.input.len() {
@@ -568,7 +825,9 @@ impl<'a> AttributeTokenizer<'a> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+/// Reasons a tokenizer stops producing tokens.
 pub enum TokenizerError {
+    /// The input is exhausted.
     Eof,
 }
 
@@ -875,6 +1134,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_valueless_attributes() {
+        let input = EmptyElementToken::new(r#"<input checked type="checkbox" disabled />"#);
+        assert_eq!(input.attribute("checked"), Some(String::new()));
+        assert_eq!(input.attribute("type"), Some("checkbox".to_string()));
+        assert_eq!(input.attribute("disabled"), Some(String::new()));
+    }
+
     #[test]
     fn test_getting_attributes_by_name() {
         struct AttribTest {
@@ -1088,4 +1355,108 @@ mod tests {
             other => panic!("expected start element, got {other:?}"),
         }
     }
+
+    /// Hands out its input in chunks of at most `chunk` bytes per read.
+    struct ChunkedReader<'a> {
+        data: &'a [u8],
+        chunk: usize,
+    }
+
+    impl Read for ChunkedReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.chunk.min(buf.len()).min(self.data.len());
+            buf[..len].copy_from_slice(&self.data[..len]);
+            self.data = &self.data[len..];
+            Ok(len)
+        }
+    }
+
+    fn tokens(data: &str) -> Vec<Token> {
+        let mut result = Vec::new();
+        let mut tokenizer = Tokenizer::new(data);
+        while let Ok(token) = tokenizer.next_token() {
+            result.push(token);
+        }
+        result
+    }
+
+    fn streamed_tokens(data: &str, chunk: usize) -> Vec<Token> {
+        let mut result = Vec::new();
+        let mut tokenizer = StreamTokenizer::new(ChunkedReader {
+            data: data.as_bytes(),
+            chunk,
+        });
+        while let Ok(token) = tokenizer.next_token() {
+            result.push(token);
+        }
+        assert!(tokenizer.take_error().is_none());
+        result
+    }
+
+    #[test]
+    fn test_stream_tokenizer_matches_in_memory_tokenizer() {
+        for (name, info) in DOCUMENTS.iter() {
+            for chunk in [1, 2, 3, 7, 64, READ_CHUNK_SIZE] {
+                assert_eq!(
+                    streamed_tokens(info.data, chunk),
+                    tokens(info.data),
+                    "document '{name}' differs when read in chunks of {chunk} bytes"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_stream_tokenizer_on_svg_document() {
+        let (_, svg) = DOCUMENTS
+            .iter()
+            .find(|(name, _)| *name == "simple-svg")
+            .expect("svg sample");
+        let expected = tokens(svg.data);
+        assert!(expected
+            .iter()
+            .any(|token| matches!(token, Token::ProcInst(_))));
+        assert!(expected
+            .iter()
+            .any(|token| matches!(token, Token::EndElement(_))));
+
+        for chunk in 1..=16 {
+            assert_eq!(streamed_tokens(svg.data, chunk), expected);
+        }
+    }
+
+    #[test]
+    fn test_stream_tokenizer_spans_many_chunks() {
+        let comment = format!("<!-- {} < not a tag -->", "x".repeat(3 * READ_CHUNK_SIZE));
+        let data = format!("<p>Grüße</p>{comment}<br/>{}", "ä".repeat(READ_CHUNK_SIZE));
+        assert_eq!(streamed_tokens(&data, 1000), tokens(&data));
+    }
+
+    #[test]
+    fn test_stream_tokenizer_reports_invalid_utf8() {
+        let mut tokenizer = StreamTokenizer::new(&b"<p>\xff</p>"[..]);
+        assert_eq!(tokenizer.next_token(), Err(TokenizerError::Eof));
+        assert_eq!(
+            tokenizer.take_error().map(|err| err.kind()),
+            Some(io::ErrorKind::InvalidData)
+        );
+    }
+
+    #[test]
+    fn test_token_attributes_on_all_variants() {
+        let tokens: Vec<Token> = Tokenizer::new(r#"<a href="x">y</a><img src=z.png/>"#)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(tokens[0].attribute("HREF").as_deref(), Some("x"));
+        assert!(tokens[1].attributes().is_empty());
+        assert!(tokens[2].attributes().is_empty());
+        assert_eq!(
+            tokens[3].attributes(),
+            vec![Attribute {
+                name: "src".into(),
+                content: "z.png".into()
+            }]
+        );
+    }
 }
@@ -8,13 +8,16 @@
 pub mod gockl;
 
 use crate::ftml::Writer;
+use crate::inline::trim_trailing_inline_whitespace;
+use crate::line_endings::NormalizedReader;
 use crate::{
-    ChecklistItem, Document, InlineStyle, Paragraph, ParagraphType, Span, TableCell, TableRow,
+    Alignment, ChecklistItem, Color, DefinitionItem, Document, InlineStyle, Paragraph,
+    ParagraphType, Span, TableCell, TableRow, DEFAULT_MAX_DEPTH,
 };
-use gockl::{StartElementToken, Token, Tokenizer, TokenizerError};
+use gockl::{ElementToken, StartElementToken, StreamTokenizer, Token, TokenizerError};
 use html_escape::decode_html_entities;
 use std::cell::RefCell;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 use std::io::{Read, Write};
 use std::rc::Rc;
 use thiserror::Error;
@@ -40,21 +43,85 @@ struct SpanOutcome {
 /// let document = html::parse(html).unwrap();
 /// assert_eq!(document.paragraphs.len(), 1);
 /// ```
-pub fn parse<R: Read>(mut reader: R) -> crate::Result<Document> {
-    let mut input = String::new();
-    reader.read_to_string(&mut input)?;
+pub fn parse<R: Read>(reader: R) -> crate::Result<Document> {
+    parse_with_options(reader, &ParseOptions::default())
+}
+
+/// Elements dropped, together with their contents, unless
+/// [`ParseOptions::skip_tags`] says otherwise.
+pub const DEFAULT_SKIP_TAGS: &[&str] = &["title", "style", "script", "nav"];
+
+/// Options for [`parse_with_options`].
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Keep embedded elements the document model cannot represent (`<iframe>`,
+    /// `<video>`, `<svg>`, …) verbatim as [`InlineStyle::Raw`] spans targeting
+    /// `html`, instead of reducing them to their fallback text.
+    pub preserve_raw: bool,
+    /// Lowercase names of the elements that are dropped along with everything
+    /// inside them. Defaults to [`DEFAULT_SKIP_TAGS`].
+    pub skip_tags: HashSet<String>,
+    /// The deepest nesting of block containers (quotes, lists, …) accepted.
+    /// Deeper documents fail to parse. Defaults to [`DEFAULT_MAX_DEPTH`].
+    pub max_depth: usize,
+    /// Keep empty `<p></p>` elements as empty text paragraphs, which render
+    /// as blank lines, instead of dropping them.
+    pub keep_empty_paragraphs: bool,
+}
 
-    Parser::new(&input)
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            preserve_raw: false,
+            skip_tags: DEFAULT_SKIP_TAGS
+                .iter()
+                .map(|tag| tag.to_string())
+                .collect(),
+            max_depth: DEFAULT_MAX_DEPTH,
+            keep_empty_paragraphs: false,
+        }
+    }
+}
+
+/// Parses HTML like [`parse`], with the given [`ParseOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tdoc::{html, InlineStyle};
+///
+/// let input = Cursor::new(r#"<p>Watch <iframe src="https://example.test"></iframe></p>"#);
+/// let options = html::ParseOptions {
+///     preserve_raw: true,
+///     ..Default::default()
+/// };
+/// let document = html::parse_with_options(input, &options).unwrap();
+/// let raw = &document.paragraphs[0].content()[1];
+/// assert_eq!(raw.style, InlineStyle::Raw);
+/// assert_eq!(raw.text, r#"<iframe src="https://example.test"></iframe>"#);
+/// ```
+pub fn parse_with_options<R: Read>(reader: R, options: &ParseOptions) -> crate::Result<Document> {
+    Parser::new(Box::new(NormalizedReader::new(reader)), options.clone())
         .parse()
         .map_err(|err| Box::new(err) as Box<dyn std::error::Error + Send + Sync>)
 }
 
 struct Parser<'a> {
-    tokenizer: Tokenizer<'a>,
+    tokenizer: StreamTokenizer<Box<dyn Read + 'a>>,
+    options: ParseOptions,
     document: Vec<ParagraphNode>,
     breadcrumbs: Vec<ParagraphNode>,
     list_item_level: usize,
     skip_stack: Vec<String>,
+    /// The `id` (or `name`) of the heading about to be read by
+    /// [`Parser::read_paragraph`].
+    heading_anchor: Option<String>,
+    /// The language of the code block being read, from a `language-*` class
+    /// on its `<pre>` or inner `<code>` element.
+    code_language: Option<String>,
+    /// Whether the paragraph being read is a `<p class="lead">`.
+    lead_paragraph: bool,
     pending_token: Option<Token>,
     /// Tokens to consume before the live tokenizer. Used to replay a buffered
     /// `<table>` body when it turns out to be layout scaffolding.
@@ -66,13 +133,17 @@ struct Parser<'a> {
 }
 
 impl<'a> Parser<'a> {
-    fn new(input: &'a str) -> Self {
+    fn new(reader: Box<dyn Read + 'a>, options: ParseOptions) -> Self {
         Self {
-            tokenizer: Tokenizer::new(input),
+            tokenizer: StreamTokenizer::new(reader),
+            options,
             document: Vec::new(),
             breadcrumbs: Vec::new(),
             list_item_level: 0,
             skip_stack: Vec::new(),
+            heading_anchor: None,
+            code_language: None,
+            lead_paragraph: false,
             pending_token: None,
             injected: VecDeque::new(),
             replaying: false,
@@ -99,6 +170,9 @@ impl<'a> Parser<'a> {
         while let Ok(token) = self.tokenizer.next_token() {
             self.process_token(token)?;
         }
+        if let Some(err) = self.tokenizer.take_error() {
+            return Err(HtmlError::Io(err));
+        }
 
         let mut paragraphs = self
             .document
@@ -134,6 +208,14 @@ impl<'a> Parser<'a> {
                     return Ok(());
                 }
 
+                if tag == "input" {
+                    // `<input>` is a void element, so it may come without `/>`.
+                    if let Some(checked) = checkbox_state(ElementToken::Start(&start)) {
+                        self.mark_current_list_item_checkbox(checked);
+                    }
+                    return Ok(());
+                }
+
                 if tag == "li" {
                     let parent = match self.parent() {
                         Some(parent) => parent,
@@ -156,11 +238,71 @@ impl<'a> Parser<'a> {
                     return Ok(());
                 }
 
+                if tag == "dt" || tag == "dd" {
+                    let parent = self.definition_list_parent()?;
+                    if tag == "dd" {
+                        // The paragraphs that follow join the current
+                        // definition via `down`.
+                        parent.borrow_mut().ensure_current_definition();
+                        return Ok(());
+                    }
+
+                    let (mut term, extra_token, _) = self.read_content(Some("dt"), None)?;
+                    trim_trailing_line_breaks(&mut term);
+                    trim_trailing_inline_whitespace(&mut term);
+                    parent.borrow_mut().definitions.push(DefinitionBuilder {
+                        term,
+                        definition: Vec::new(),
+                    });
+                    if let Some(token) = extra_token {
+                        return self.process_token(token);
+                    }
+                    return Ok(());
+                }
+
+                if tag == "summary" {
+                    let Some(parent) = self
+                        .parent()
+                        .filter(|node| node.borrow().paragraph_type == ParagraphType::Details)
+                    else {
+                        // A stray summary is just a line of text.
+                        return self.read_paragraph(ParagraphType::Text, Some(tag), None);
+                    };
+
+                    let (mut summary, extra_token, _) = self.read_content(Some("summary"), None)?;
+                    trim_trailing_line_breaks(&mut summary);
+                    trim_trailing_inline_whitespace(&mut summary);
+                    parent.borrow_mut().content.append(&mut summary);
+                    if let Some(token) = extra_token {
+                        return self.process_token(token);
+                    }
+                    return Ok(());
+                }
+
                 if let Some(para_type) = paragraph_type_for(&tag) {
+                    if matches!(
+                        para_type,
+                        ParagraphType::Header1 | ParagraphType::Header2 | ParagraphType::Header3
+                    ) {
+                        self.heading_anchor = start
+                            .attribute("id")
+                            .or_else(|| start.attribute("name"))
+                            .map(decode_html)
+                            .filter(|anchor| !anchor.trim().is_empty());
+                    }
+                    if para_type == ParagraphType::CodeBlock {
+                        self.code_language = code_language(&start);
+                    }
+                    if tag == "p" {
+                        self.lead_paragraph = has_class(&start, "lead");
+                    }
                     return self.read_paragraph(para_type, Some(tag), None);
                 }
 
-                if inline_style_for(&tag).is_some() {
+                if inline_style_for(&tag).is_some()
+                    || self.is_preserved_raw(&tag)
+                    || inline_style_and_target(&tag, &start).0 == InlineStyle::Color
+                {
                     self.pending_token = Some(Token::StartElement(start));
                     return self.read_paragraph(ParagraphType::Text, None, None);
                 }
@@ -184,6 +326,10 @@ impl<'a> Parser<'a> {
                     return Ok(());
                 }
 
+                if matches!(tag.as_str(), "dt" | "dd" | "summary") {
+                    return Ok(());
+                }
+
                 if let Some(para_type) = paragraph_type_for(&tag) {
                     if para_type.is_leaf() {
                         return Ok(());
@@ -201,6 +347,16 @@ impl<'a> Parser<'a> {
             Token::EmptyElement(empty) if lowercase_name(empty.name()) == "hr" => {
                 self.down(ParagraphType::HorizontalRule)?;
             }
+            // A checkbox usually opens its list item, before any text.
+            Token::EmptyElement(empty) if lowercase_name(empty.name()) == "input" => {
+                if let Some(checked) = checkbox_state(ElementToken::Empty(&empty)) {
+                    self.mark_current_list_item_checkbox(checked);
+                }
+            }
+            Token::EmptyElement(empty) if self.is_preserved_raw(&lowercase_name(empty.name())) => {
+                self.pending_token = Some(Token::EmptyElement(empty));
+                return self.read_paragraph(ParagraphType::Text, None, None);
+            }
             _ => {}
         }
 
@@ -214,10 +370,13 @@ impl<'a> Parser<'a> {
         start_text: Option<String>,
     ) -> Result<(), HtmlError> {
         let node = self.down(para_type)?;
+        node.borrow_mut().anchor = self.heading_anchor.take();
+        node.borrow_mut().lead = std::mem::take(&mut self.lead_paragraph);
 
         let (mut content, extra_token, closed) = if para_type == ParagraphType::CodeBlock {
             let (text, token, closed) =
                 self.read_preformatted_content(end_tag.as_deref(), start_text)?;
+            node.borrow_mut().language = self.code_language.take();
             let spans = if text.is_empty() {
                 Vec::new()
             } else {
@@ -248,7 +407,8 @@ impl<'a> Parser<'a> {
             node.borrow_mut().content = content;
         }
 
-        let should_remove_empty = if para_type.is_leaf() {
+        let keep_empty = self.options.keep_empty_paragraphs && end_tag.as_deref() == Some("p");
+        let should_remove_empty = if para_type.is_leaf() && !keep_empty {
             let borrowed = node.borrow();
             borrowed.content.is_empty()
                 && borrowed.children.is_empty()
@@ -315,7 +475,7 @@ impl<'a> Parser<'a> {
             match token {
                 Token::StartElement(start) => {
                     let name = lowercase_name(start.name());
-                    if should_skip_tag(&name) {
+                    if self.should_skip_tag(&name) {
                         continue;
                     }
 
@@ -323,6 +483,10 @@ impl<'a> Parser<'a> {
                         return Ok((buffer, Some(Token::StartElement(start)), false));
                     }
 
+                    if name == "code" && self.code_language.is_none() {
+                        self.code_language = code_language(&start);
+                    }
+
                     let (nested_text, extra_token, closed) =
                         self.read_preformatted_content(Some(&name), None)?;
                     buffer.push_str(&nested_text);
@@ -335,7 +499,7 @@ impl<'a> Parser<'a> {
                 }
                 Token::EmptyElement(empty) => {
                     let name = lowercase_name(empty.name());
-                    if should_skip_tag(&name) {
+                    if self.should_skip_tag(&name) {
                         continue;
                     }
                     if is_line_break_element(&name) {
@@ -403,17 +567,27 @@ impl<'a> Parser<'a> {
             match token {
                 Token::StartElement(start) => {
                     let name = lowercase_name(start.name());
-                    if should_skip_tag(&name) {
+                    if self.should_skip_tag(&name) {
                         continue;
                     }
 
-                    let style = inline_style_for(&name).unwrap_or(InlineStyle::None);
-                    let link_target = if style == InlineStyle::Link {
-                        start.attribute("href")
-                    } else {
-                        None
-                    };
-                    let outcome = self.read_span(style, &name, link_target)?;
+                    if self.is_preserved_raw(&name) {
+                        spans.add(self.read_raw_element(&start, &name));
+                        continue;
+                    }
+
+                    if name == "input" {
+                        if let Some(checked) = checkbox_state(ElementToken::Start(&start)) {
+                            self.mark_current_list_item_checkbox(checked);
+                        }
+                        continue;
+                    }
+
+                    let (style, link_target) = inline_style_and_target(&name, &start);
+                    let mut outcome = self.read_span(style, &name, link_target)?;
+                    if outcome.span.style == InlineStyle::Color {
+                        outcome.span.color = span_color(&name, &start);
+                    }
                     if should_skip_link_span(&outcome.span, outcome.had_visible_text)
                         || should_skip_empty_styled_span(&outcome.span)
                     {
@@ -423,17 +597,17 @@ impl<'a> Parser<'a> {
                 }
                 Token::EmptyElement(empty) => {
                     let name = lowercase_name(empty.name());
-                    if should_skip_tag(&name) {
+                    if self.should_skip_tag(&name) {
+                        continue;
+                    }
+
+                    if self.is_preserved_raw(&name) {
+                        spans.add(Span::new_raw("html", empty.raw()));
                         continue;
                     }
 
                     if name == "input" {
-                        let is_checkbox = empty
-                            .attribute("type")
-                            .map(|value| value.eq_ignore_ascii_case("checkbox"))
-                            .unwrap_or(false);
-                        if is_checkbox {
-                            let checked = empty.attribute("checked").is_some();
+                        if let Some(checked) = checkbox_state(ElementToken::Empty(&empty)) {
                             self.mark_current_list_item_checkbox(checked);
                         }
                         continue;
@@ -485,14 +659,11 @@ impl<'a> Parser<'a> {
             };
 
             if is_line_break(&token) {
-                if let Some(last) = children.last_mut() {
-                    if last.children.is_empty() {
-                        last.text.push('\n');
-                    } else {
-                        children.push(Span::new_text("\n"));
-                    }
-                } else {
-                    children.push(Span::new_text("\n"));
+                // Consecutive breaks collapse, as in `read_content`.
+                match children.last_mut() {
+                    Some(last) if last.ends_with_line_break() => {}
+                    Some(last) if last.children.is_empty() => last.text.push('\n'),
+                    _ => children.push(Span::new_text("\n")),
                 }
                 first = true;
                 continue;
@@ -502,6 +673,9 @@ impl<'a> Parser<'a> {
                 Token::StartElement(start) => {
                     let name = lowercase_name(start.name());
                     if is_block_level(&name) {
+                        // Leave the block start to the caller, which ends the
+                        // paragraph there.
+                        self.pending_token = Some(Token::StartElement(start));
                         let span = build_span(style, children, link_target.clone());
                         return Ok(SpanOutcome {
                             span,
@@ -509,12 +683,14 @@ impl<'a> Parser<'a> {
                         });
                     }
 
-                    let nested_style = inline_style_for(&name).unwrap_or(InlineStyle::None);
-                    let nested_link = if nested_style == InlineStyle::Link {
-                        start.attribute("href")
-                    } else {
-                        None
-                    };
+                    if self.is_preserved_raw(&name) {
+                        children.push(self.read_raw_element(&start, &name));
+                        had_visible_text = true;
+                        first = false;
+                        continue;
+                    }
+
+                    let (nested_style, nested_link) = inline_style_and_target(&name, &start);
                     let outcome = self.read_span(nested_style, &name, nested_link)?;
                     if should_skip_link_span(&outcome.span, outcome.had_visible_text)
                         || should_skip_empty_styled_span(&outcome.span)
@@ -532,6 +708,12 @@ impl<'a> Parser<'a> {
                 Token::EndElement(end) => {
                     let name = lowercase_name(end.name());
                     if name == end_tag || is_block_level(&name) {
+                        // An unclosed style ends with its block. The block's
+                        // end tag is handed back so every enclosing span stops
+                        // there, too, instead of capturing the text after it.
+                        if name != end_tag {
+                            self.pending_token = Some(Token::EndElement(end));
+                        }
                         let span = build_span(style, children, link_target.clone());
                         return Ok(SpanOutcome {
                             span,
@@ -544,16 +726,49 @@ impl<'a> Parser<'a> {
         }
     }
 
+    fn should_skip_tag(&self, tag: &str) -> bool {
+        self.options.skip_tags.contains(tag)
+    }
+
+    fn is_preserved_raw(&self, tag: &str) -> bool {
+        self.options.preserve_raw && is_raw_element(tag)
+    }
+
+    /// Captures an embedded element verbatim, from its (already consumed)
+    /// start tag through the matching end tag, as a raw `html` span.
+    fn read_raw_element(&mut self, start: &StartElementToken, name: &str) -> Span {
+        let mut raw = start.raw().to_string();
+        let mut depth = 1;
+
+        while let Ok(token) = self.pull_token() {
+            raw.push_str(token.raw());
+            match &token {
+                Token::StartElement(nested) if lowercase_name(nested.name()) == name => {
+                    depth += 1;
+                }
+                Token::EndElement(end) if lowercase_name(end.name()) == name => {
+                    depth -= 1;
+                    if depth == 0 {
+                        break;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Span::new_raw("html", raw)
+    }
+
     fn read_table(&mut self, start: &StartElementToken) -> Result<(), HtmlError> {
         // Buffer the whole table once. We need the tokens twice: to build rows
         // for the keep-or-flatten decision, and — if the table is layout
         // scaffolding — to replay the cell contents as ordinary block flow.
         let body = self.collect_table_tokens()?;
-        let rows = self.rows_from_tokens(body.clone())?;
+        let table = self.table_from_tokens(body.clone())?;
 
-        if is_genuine_table(start, &rows) {
+        if is_genuine_table(start, &table.rows) {
             let node = self.down(ParagraphType::Table)?;
-            node.borrow_mut().table_rows = rows;
+            node.borrow_mut().table = table;
             self.up(ParagraphType::Table)?;
         } else {
             // The `<table>` is layout scaffolding (presentational role, a single
@@ -596,9 +811,10 @@ impl<'a> Parser<'a> {
         Ok(buffer)
     }
 
-    /// Builds the table rows from a buffered body without disturbing the live
-    /// token stream. Used only to decide whether the table is genuine.
-    fn rows_from_tokens(&mut self, tokens: Vec<Token>) -> Result<Vec<TableRow>, HtmlError> {
+    /// Builds the table from a buffered body without disturbing the live
+    /// token stream, so the tokens can still be replayed if the table turns
+    /// out to be layout scaffolding.
+    fn table_from_tokens(&mut self, tokens: Vec<Token>) -> Result<TableBuilder, HtmlError> {
         self.run_over_tokens(tokens, |parser| parser.read_table_body())
     }
 
@@ -635,35 +851,39 @@ impl<'a> Parser<'a> {
         result
     }
 
-    fn read_table_body(&mut self) -> Result<Vec<TableRow>, HtmlError> {
-        let mut rows = Vec::new();
+    fn read_table_body(&mut self) -> Result<TableBuilder, HtmlError> {
+        let mut table = TableBuilder::default();
 
         loop {
             let Some(token) = self.next_table_token()? else {
-                return Ok(rows);
+                return Ok(table);
             };
 
             match token {
                 Token::StartElement(start) => {
                     let name = lowercase_name(start.name());
                     match name.as_str() {
-                        "thead" | "tbody" | "tfoot" | "colgroup" | "caption" | "col" => {}
+                        "thead" | "tbody" | "tfoot" | "colgroup" | "col" => {}
+                        "caption" => {
+                            table.caption = self.read_table_cell(false, &name)?.content;
+                        }
                         "tr" => {
-                            let row = self.read_table_row()?;
+                            let row = self.read_table_row(&mut table.alignments)?;
                             if !row.cells.is_empty() {
-                                rows.push(row);
+                                table.rows.push(row);
                             }
                         }
                         "th" | "td" => {
                             // Implicit row for orphan cells.
                             let mut row = TableRow::new();
                             let is_header = name == "th";
+                            record_cell_alignment(&mut table.alignments, 0, &start);
                             let cell = self.read_table_cell(is_header, &name)?;
                             row.cells.push(cell);
-                            let mut trailing = self.read_table_row()?;
+                            let mut trailing = self.read_table_row(&mut table.alignments)?;
                             row.cells.append(&mut trailing.cells);
                             if !row.cells.is_empty() {
-                                rows.push(row);
+                                table.rows.push(row);
                             }
                         }
                         _ => {}
@@ -672,7 +892,7 @@ impl<'a> Parser<'a> {
                 Token::EndElement(end) => {
                     let name = lowercase_name(end.name());
                     if name == "table" {
-                        return Ok(rows);
+                        return Ok(table);
                     }
                 }
                 _ => {}
@@ -680,7 +900,12 @@ impl<'a> Parser<'a> {
         }
     }
 
-    fn read_table_row(&mut self) -> Result<TableRow, HtmlError> {
+    /// Reads the cells of one row. Cells carrying an alignment fill the
+    /// still-unknown entries of `alignments` for their column.
+    fn read_table_row(
+        &mut self,
+        alignments: &mut Vec<Option<Alignment>>,
+    ) -> Result<TableRow, HtmlError> {
         let mut row = TableRow::new();
 
         loop {
@@ -692,12 +917,9 @@ impl<'a> Parser<'a> {
                 Token::StartElement(start) => {
                     let name = lowercase_name(start.name());
                     match name.as_str() {
-                        "th" => {
-                            let cell = self.read_table_cell(true, &name)?;
-                            row.cells.push(cell);
-                        }
-                        "td" => {
-                            let cell = self.read_table_cell(false, &name)?;
+                        "th" | "td" => {
+                            record_cell_alignment(alignments, row.cells.len(), &start);
+                            let cell = self.read_table_cell(name == "th", &name)?;
                             row.cells.push(cell);
                         }
                         "tr" => {
@@ -819,6 +1041,11 @@ impl<'a> Parser<'a> {
     }
 
     fn down(&mut self, para_type: ParagraphType) -> Result<ParagraphNode, HtmlError> {
+        if !para_type.is_leaf() && self.breadcrumbs.len() >= self.options.max_depth {
+            return Err(HtmlError::NestingTooDeep {
+                limit: self.options.max_depth,
+            });
+        }
         let node = Rc::new(RefCell::new(ParagraphBuilder::new(para_type)));
 
         if let Some(parent) = self.parent() {
@@ -845,6 +1072,16 @@ impl<'a> Parser<'a> {
                         .expect("list entry present")
                         .push(Rc::clone(&node));
                 }
+                ParagraphType::DefinitionList => {
+                    let mut parent_mut = parent.borrow_mut();
+                    parent_mut.ensure_current_definition();
+                    parent_mut
+                        .definitions
+                        .last_mut()
+                        .expect("definition present")
+                        .definition
+                        .push(Rc::clone(&node));
+                }
                 _ => parent.borrow_mut().children.push(Rc::clone(&node)),
             }
         } else {
@@ -879,6 +1116,18 @@ impl<'a> Parser<'a> {
         self.breadcrumbs.last().cloned()
     }
 
+    /// Returns the definition list a `<dt>` or `<dd>` belongs to, opening an
+    /// implicit one at the top level.
+    fn definition_list_parent(&mut self) -> Result<ParagraphNode, HtmlError> {
+        let Some(parent) = self.parent() else {
+            return self.down(ParagraphType::DefinitionList);
+        };
+        if parent.borrow().paragraph_type != ParagraphType::DefinitionList {
+            return Err(HtmlError::UnexpectedDefinitionItem);
+        }
+        Ok(parent)
+    }
+
     fn remove_leaf(&mut self, node: &ParagraphNode) {
         if let Some(parent) = self.parent() {
             let parent_type = parent.borrow().paragraph_type;
@@ -896,6 +1145,16 @@ impl<'a> Parser<'a> {
                         }
                     }
                 }
+                ParagraphType::DefinitionList => {
+                    let mut parent_mut = parent.borrow_mut();
+                    if let Some(item) = parent_mut.definitions.last_mut() {
+                        if let Some(last) = item.definition.last() {
+                            if Rc::ptr_eq(last, node) {
+                                item.definition.pop();
+                            }
+                        }
+                    }
+                }
                 _ => {
                     let mut parent_mut = parent.borrow_mut();
                     if let Some(last) = parent_mut.children.last() {
@@ -954,14 +1213,14 @@ impl<'a> Parser<'a> {
         match token {
             Token::StartElement(start) => {
                 let name = lowercase_name(start.name());
-                if should_skip_tag(&name) {
+                if self.should_skip_tag(&name) {
                     self.skip_stack.push(name);
                     return true;
                 }
             }
             Token::EmptyElement(empty) => {
                 let name = lowercase_name(empty.name());
-                if should_skip_tag(&name) {
+                if self.should_skip_tag(&name) {
                     return true;
                 }
             }
@@ -983,11 +1242,17 @@ enum HtmlError {
     },
     #[error("unexpected list item outside list context")]
     UnexpectedListItem,
+    #[error("unexpected definition term or description outside definition list")]
+    UnexpectedDefinitionItem,
     #[error("cannot close {found:?} with {expected:?}")]
     ParagraphCloseMismatch {
         expected: ParagraphType,
         found: ParagraphType,
     },
+    #[error("paragraphs nested more than {limit} levels deep")]
+    NestingTooDeep { limit: usize },
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
 }
 
 #[derive(Debug)]
@@ -997,7 +1262,26 @@ struct ParagraphBuilder {
     content: Vec<Span>,
     entries: Vec<Vec<ParagraphNode>>,
     checklist_states: Vec<Option<bool>>,
-    table_rows: Vec<TableRow>,
+    table: TableBuilder,
+    definitions: Vec<DefinitionBuilder>,
+    anchor: Option<String>,
+    language: Option<String>,
+    lead: bool,
+}
+
+/// The rows, `<caption>` and column alignments read from a `<table>`.
+#[derive(Debug, Default)]
+struct TableBuilder {
+    rows: Vec<TableRow>,
+    caption: Vec<Span>,
+    alignments: Vec<Option<Alignment>>,
+}
+
+/// A `<dt>` term and the paragraphs of its `<dd>` descriptions.
+#[derive(Debug)]
+struct DefinitionBuilder {
+    term: Vec<Span>,
+    definition: Vec<ParagraphNode>,
 }
 
 impl ParagraphBuilder {
@@ -1008,7 +1292,22 @@ impl ParagraphBuilder {
             content: Vec::new(),
             entries: Vec::new(),
             checklist_states: Vec::new(),
-            table_rows: Vec::new(),
+            table: TableBuilder::default(),
+            definitions: Vec::new(),
+            anchor: None,
+            language: None,
+            lead: false,
+        }
+    }
+
+    /// Makes sure there is a definition to receive `<dd>` content, even when
+    /// no `<dt>` preceded it.
+    fn ensure_current_definition(&mut self) {
+        if self.definitions.is_empty() {
+            self.definitions.push(DefinitionBuilder {
+                term: Vec::new(),
+                definition: Vec::new(),
+            });
         }
     }
 
@@ -1075,20 +1374,32 @@ impl ParagraphBuilder {
             Paragraph::new_checklist().with_checklist_items(checklist_items)
         } else {
             match borrowed.paragraph_type {
-                ParagraphType::Text => Paragraph::new_text().with_content(borrowed.content.clone()),
-                ParagraphType::Header1 => {
-                    Paragraph::new_header1().with_content(borrowed.content.clone())
-                }
-                ParagraphType::Header2 => {
-                    Paragraph::new_header2().with_content(borrowed.content.clone())
+                ParagraphType::Text => Paragraph::new_text()
+                    .with_content(borrowed.content.clone())
+                    .with_lead(borrowed.lead),
+                ParagraphType::Header1 => Paragraph::Header1 {
+                    content: borrowed.content.clone(),
+                    anchor: borrowed.anchor.clone(),
+                },
+                ParagraphType::Header2 => Paragraph::Header2 {
+                    content: borrowed.content.clone(),
+                    anchor: borrowed.anchor.clone(),
+                },
+                ParagraphType::Header3 => Paragraph::Header3 {
+                    content: borrowed.content.clone(),
+                    anchor: borrowed.anchor.clone(),
+                },
+                ParagraphType::CodeBlock => Paragraph::CodeBlock {
+                    content: borrowed.content.clone(),
+                    language: borrowed.language.clone(),
+                },
+                // Admonitions are never parsed from HTML.
+                ParagraphType::Quote | ParagraphType::Admonition => {
+                    Paragraph::new_quote().with_children(children)
                 }
-                ParagraphType::Header3 => {
-                    Paragraph::new_header3().with_content(borrowed.content.clone())
-                }
-                ParagraphType::CodeBlock => {
-                    Paragraph::new_code_block().with_content(borrowed.content.clone())
-                }
-                ParagraphType::Quote => Paragraph::new_quote().with_children(children),
+                ParagraphType::Details => Paragraph::new_details()
+                    .with_summary(borrowed.content.clone())
+                    .with_children(children),
                 ParagraphType::OrderedList => Paragraph::new_ordered_list().with_entries(entries),
                 ParagraphType::UnorderedList => {
                     Paragraph::new_unordered_list().with_entries(entries)
@@ -1097,9 +1408,42 @@ impl ParagraphBuilder {
                     Paragraph::new_checklist().with_checklist_items(Vec::new())
                 }
                 ParagraphType::Table => {
-                    Paragraph::new_table().with_rows(borrowed.table_rows.clone())
+                    let table = &borrowed.table;
+                    Paragraph::new_table()
+                        .with_rows(table.rows.clone())
+                        .with_caption(table.caption.clone())
+                        .with_alignments(
+                            table
+                                .alignments
+                                .iter()
+                                .map(|alignment| alignment.unwrap_or_default())
+                                .collect(),
+                        )
+                }
+                ParagraphType::DefinitionList => {
+                    let items = borrowed
+                        .definitions
+                        .iter()
+                        .map(|item| {
+                            DefinitionItem::new()
+                                .with_term(item.term.clone())
+                                .with_definition(
+                                    item.definition
+                                        .iter()
+                                        .map(ParagraphBuilder::to_paragraph)
+                                        .filter(|child| !is_empty_list(child))
+                                        .collect(),
+                                )
+                        })
+                        .filter(|item| {
+                            !item.term.is_empty()
+                                || list_entry_has_meaningful_content(&item.definition)
+                        })
+                        .collect();
+                    Paragraph::new_definition_list().with_definition_items(items)
                 }
                 ParagraphType::HorizontalRule => Paragraph::new_horizontal_rule(),
+                ParagraphType::Raw => Paragraph::new(ParagraphType::Raw),
             }
         }
     }
@@ -1113,11 +1457,21 @@ impl ParagraphBuilder {
                 Paragraph::Checklist { mut items } => {
                     item.children.append(&mut items);
                 }
-                Paragraph::Text { content: mut spans }
-                | Paragraph::Header1 { content: mut spans }
-                | Paragraph::Header2 { content: mut spans }
-                | Paragraph::Header3 { content: mut spans }
-                | Paragraph::CodeBlock { content: mut spans } => {
+                Paragraph::Text {
+                    content: mut spans, ..
+                }
+                | Paragraph::Header1 {
+                    content: mut spans, ..
+                }
+                | Paragraph::Header2 {
+                    content: mut spans, ..
+                }
+                | Paragraph::Header3 {
+                    content: mut spans, ..
+                }
+                | Paragraph::CodeBlock {
+                    content: mut spans, ..
+                } => {
                     if spans.is_empty() {
                         continue;
                     }
@@ -1150,31 +1504,41 @@ fn list_entry_has_meaningful_content(entry: &[Paragraph]) -> bool {
 
 fn is_empty_list(paragraph: &Paragraph) -> bool {
     match paragraph {
-        Paragraph::OrderedList { entries } | Paragraph::UnorderedList { entries } => {
+        Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
             entries.iter().all(|entry| entry.is_empty())
         }
         Paragraph::Checklist { items } => items.is_empty(),
+        Paragraph::DefinitionList { items } => items.is_empty(),
         _ => false,
     }
 }
 
 fn paragraph_has_meaningful_content(paragraph: &Paragraph) -> bool {
     match paragraph {
-        Paragraph::Text { content }
-        | Paragraph::Header1 { content }
-        | Paragraph::Header2 { content }
-        | Paragraph::Header3 { content }
-        | Paragraph::CodeBlock { content } => content.iter().any(|span| !span.is_content_empty()),
+        Paragraph::Text { content, .. }
+        | Paragraph::Header1 { content, .. }
+        | Paragraph::Header2 { content, .. }
+        | Paragraph::Header3 { content, .. }
+        | Paragraph::CodeBlock { content, .. } => {
+            content.iter().any(|span| !span.is_content_empty())
+        }
         Paragraph::Quote { children } => children.iter().any(paragraph_has_meaningful_content),
-        Paragraph::OrderedList { entries } | Paragraph::UnorderedList { entries } => entries
+        Paragraph::Details { summary, children } => {
+            !summary.is_empty() || children.iter().any(paragraph_has_meaningful_content)
+        }
+        // The label of an admonition's kind is shown even without a body.
+        Paragraph::Admonition { .. } => true,
+        Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => entries
             .iter()
             .any(|nested| list_entry_has_meaningful_content(nested)),
         Paragraph::Checklist { items } => !items.is_empty(),
-        Paragraph::Table { rows } => rows
+        Paragraph::DefinitionList { items } => !items.is_empty(),
+        Paragraph::Table { rows, .. } => rows
             .iter()
             .any(|row| row.cells.iter().any(|cell| !cell.content.is_empty())),
         // A horizontal rule is itself the content; it is always meaningful.
         Paragraph::HorizontalRule => true,
+        Paragraph::Raw { content, .. } => !content.is_empty(),
     }
 }
 
@@ -1195,11 +1559,16 @@ impl BufferedSpanList {
         }
     }
 
+    /// Appends a `<br>`. Runs of breaks collapse into one, so that only
+    /// preformatted content keeps blank lines made of consecutive `<br>`s.
     fn add_line_break(&mut self) {
         self.trim_end = true;
         self.flush();
-        self.spans.push(Span::new_text("\n"));
         self.first = true;
+        if self.spans.last().is_some_and(Span::ends_with_line_break) {
+            return;
+        }
+        self.spans.push(Span::new_text("\n"));
     }
 
     fn add(&mut self, span: Span) {
@@ -1339,6 +1708,10 @@ fn build_span(style: InlineStyle, children: Vec<Span>, link_target: Option<Strin
     let mut span = Span::new_styled(style);
     span.children = children;
     span.link_target = link_target;
+    if style == InlineStyle::Abbreviation {
+        // An abbreviation without an expansion carries no extra information.
+        span.link_target = span.link_target.filter(|title| !title.trim().is_empty());
+    }
     span
 }
 
@@ -1376,10 +1749,114 @@ fn paragraph_type_for(tag: &str) -> Option<ParagraphType> {
         "blockquote" => Some(ParagraphType::Quote),
         "ul" => Some(ParagraphType::UnorderedList),
         "ol" => Some(ParagraphType::OrderedList),
+        "dl" => Some(ParagraphType::DefinitionList),
+        "details" => Some(ParagraphType::Details),
+        _ => None,
+    }
+}
+
+/// Returns the inline style and `link_target` for an inline start tag. A
+/// `<span>` whose `style` attribute sets a recognized `color` becomes an
+/// [`InlineStyle::Color`] span; see [`span_color`].
+fn inline_style_and_target(name: &str, start: &StartElementToken) -> (InlineStyle, Option<String>) {
+    if span_color(name, start).is_some() {
+        return (InlineStyle::Color, None);
+    }
+    let style = inline_style_for(name).unwrap_or(InlineStyle::None);
+    let target = target_attribute_for(style).and_then(|name| start.attribute(name));
+    (style, target)
+}
+
+/// Returns the color a `<span>` sets in its `style` attribute, if
+/// [`Color::parse`] understands it.
+fn span_color(name: &str, start: &StartElementToken) -> Option<Color> {
+    if name != "span" {
+        return None;
+    }
+    let css = start.attribute("style")?;
+    css.split(';').find_map(|declaration| {
+        let (property, value) = declaration.split_once(':')?;
+        if !property.trim().eq_ignore_ascii_case("color") {
+            return None;
+        }
+        Color::parse(value)
+    })
+}
+
+/// Sets the alignment of `column` from a cell's `align` attribute or CSS
+/// `text-align` declaration, unless an earlier cell already determined it.
+fn record_cell_alignment(
+    alignments: &mut Vec<Option<Alignment>>,
+    column: usize,
+    start: &StartElementToken,
+) {
+    if alignments.get(column).is_some_and(Option::is_some) {
+        return;
+    }
+    let css_alignment = start.attribute("style").and_then(|css| {
+        css.split(';').find_map(|declaration| {
+            let (property, value) = declaration.split_once(':')?;
+            property
+                .trim()
+                .eq_ignore_ascii_case("text-align")
+                .then(|| value.trim().to_string())
+        })
+    });
+    let alignment = match css_alignment
+        .or_else(|| start.attribute("align"))
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("left") | Some("start") => Alignment::Left,
+        Some("center") => Alignment::Center,
+        Some("right") | Some("end") => Alignment::Right,
+        _ => return,
+    };
+    if alignments.len() <= column {
+        alignments.resize(column + 1, None);
+    }
+    alignments[column] = Some(alignment);
+}
+
+/// Returns whether the element's `class` attribute lists `name`.
+fn has_class(start: &StartElementToken, name: &str) -> bool {
+    start
+        .attribute("class")
+        .is_some_and(|class| class.split_whitespace().any(|class| class == name))
+}
+
+/// Reads a code block's language from a `language-*` or `lang-*` class, as
+/// used by highlight.js, Prism and GitHub.
+fn code_language(start: &StartElementToken) -> Option<String> {
+    let class = start.attribute("class")?;
+    class
+        .split_whitespace()
+        .filter_map(|name| {
+            name.strip_prefix("language-")
+                .or_else(|| name.strip_prefix("lang-"))
+        })
+        .find(|language| !language.is_empty())
+        .map(str::to_string)
+}
+
+/// Returns the attribute whose value is stored as the span's `link_target`.
+fn target_attribute_for(style: InlineStyle) -> Option<&'static str> {
+    match style {
+        InlineStyle::Link => Some("href"),
+        InlineStyle::Abbreviation => Some("title"),
         _ => None,
     }
 }
 
+/// Whether an `<input>` is a checked (`Some(true)`) or unchecked checkbox, or
+/// `None` for any other kind of input.
+fn checkbox_state(input: ElementToken<'_>) -> Option<bool> {
+    input
+        .attribute("type")
+        .filter(|value| value.eq_ignore_ascii_case("checkbox"))
+        .map(|_| input.attribute("checked").is_some())
+}
+
 fn inline_style_for(tag: &str) -> Option<InlineStyle> {
     match tag {
         "b" | "strong" => Some(InlineStyle::Bold),
@@ -1388,15 +1865,28 @@ fn inline_style_for(tag: &str) -> Option<InlineStyle> {
         "s" | "del" | "strike" => Some(InlineStyle::Strike),
         "mark" => Some(InlineStyle::Highlight),
         "code" | "tt" => Some(InlineStyle::Code),
+        "sup" => Some(InlineStyle::Superscript),
+        "sub" => Some(InlineStyle::Subscript),
+        "kbd" => Some(InlineStyle::Keyboard),
+        "abbr" => Some(InlineStyle::Abbreviation),
+        "q" => Some(InlineStyle::Quotation),
         "a" => Some(InlineStyle::Link),
         _ => None,
     }
 }
 
+/// Block-level tags end any inline run. Those without a paragraph type in
+/// [`paragraph_type_for`], such as `<div>` and the sectioning elements, are
+/// transparent: their children are parsed as if the wrapper wasn't there.
 fn is_block_level(tag: &str) -> bool {
     matches!(
         tag,
         "p" | "div"
+            | "article"
+            | "section"
+            | "main"
+            | "header"
+            | "footer"
             | "h1"
             | "h2"
             | "h3"
@@ -1404,6 +1894,11 @@ fn is_block_level(tag: &str) -> bool {
             | "ul"
             | "ol"
             | "li"
+            | "dl"
+            | "dt"
+            | "dd"
+            | "details"
+            | "summary"
             | "hr"
             | "tr"
             | "table"
@@ -1415,8 +1910,12 @@ fn is_block_level(tag: &str) -> bool {
     )
 }
 
-fn should_skip_tag(tag: &str) -> bool {
-    matches!(tag, "title" | "style" | "script" | "nav")
+/// Embedded elements kept verbatim when [`ParseOptions::preserve_raw`] is set.
+fn is_raw_element(tag: &str) -> bool {
+    matches!(
+        tag,
+        "iframe" | "video" | "audio" | "object" | "svg" | "math" | "canvas"
+    )
 }
 
 fn is_line_break(token: &Token) -> bool {
@@ -1471,29 +1970,6 @@ fn trim_trailing_line_breaks_impl(spans: &mut Vec<Span>) -> bool {
     trimmed_any
 }
 
-fn trim_trailing_inline_whitespace(spans: &mut Vec<Span>) {
-    while let Some(last) = spans.last_mut() {
-        if last.style != InlineStyle::None
-            || !last.children.is_empty()
-            || last.link_target.is_some()
-        {
-            break;
-        }
-
-        let trimmed = last.text.trim_end();
-        if trimmed.len() == last.text.len() {
-            break;
-        }
-
-        if trimmed.is_empty() {
-            spans.pop();
-        } else {
-            last.text = trimmed.to_string();
-            break;
-        }
-    }
-}
-
 /// Writes a [`Document`] as HTML markup. Tables are preserved using
 /// `<table>/<tr>/<td>` markup, unlike [`crate::ftml::write`] which flattens
 /// tables to paragraphs because FTML has no table syntax.
@@ -1568,6 +2044,11 @@ li > ul, li > ol { margin-top: 0.25em; margin-bottom: 0; }
 li:has(> input[type="checkbox"]) { list-style: none; }
 li > input[type="checkbox"] { margin: 0 0.4em 0 -1.4em; vertical-align: middle; }
 
+dl { margin-top: 0; margin-bottom: 16px; }
+dt { font-weight: 600; }
+dd { margin: 0 0 0.5em 2em; }
+dd > :last-child { margin-bottom: 0; }
+
 blockquote {
   margin: 0 0 16px 0;
   padding: 0 1em;
@@ -1658,6 +2139,7 @@ pub fn write_document<W: Write>(writer: &mut W, document: &Document) -> std::io:
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_helpers::*;
     use std::io::Cursor;
 
     #[test]
@@ -1742,6 +2224,189 @@ mod tests {
         assert_eq!(paragraph.content()[0].text, "Hello");
     }
 
+    #[test]
+    fn collapses_consecutive_line_breaks_in_text() {
+        let document = parse(Cursor::new("<p>a<br><br>b</p>")).unwrap();
+        assert_eq!(
+            document.paragraphs,
+            vec![Paragraph::new_text().with_content(vec![
+                Span::new_text("a"),
+                Span::new_text("\n"),
+                Span::new_text("b"),
+            ])]
+        );
+
+        let document = parse(Cursor::new("<p><b>a<br><br/>\n<br>b</b></p>")).unwrap();
+        let bold = &document.paragraphs[0].content()[0];
+        assert_eq!(bold.style, InlineStyle::Bold);
+        assert_eq!(bold.children[0].text, "a\n");
+        assert_eq!(bold.children[1].text, "b");
+    }
+
+    #[test]
+    fn keeps_every_line_break_in_preformatted_text() {
+        let document = parse(Cursor::new("<pre>a<br><br>b</pre>")).unwrap();
+        assert_eq!(document.paragraphs[0].content()[0].text, "a\n\nb");
+
+        let document = parse(Cursor::new("<pre>a<br><br></pre>")).unwrap();
+        assert_eq!(document.paragraphs[0].content()[0].text, "a\n\n");
+    }
+
+    #[test]
+    fn rejects_excessive_nesting() {
+        let input = format!("{}deep", "<blockquote>".repeat(10_000));
+        let error = parse(Cursor::new(input)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("paragraphs nested more than {DEFAULT_MAX_DEPTH} levels deep")
+        );
+
+        let input = format!("{}deep", "<blockquote>".repeat(DEFAULT_MAX_DEPTH));
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(document.paragraphs[0].depth(), DEFAULT_MAX_DEPTH + 1);
+    }
+
+    #[test]
+    fn closes_unterminated_inline_styles_at_block_end() {
+        let document = parse(Cursor::new("<p><b>bold</p><p>normal</p>")).unwrap();
+        assert_eq!(document, doc(vec![p_(vec![b__("bold")]), p__("normal")]));
+
+        let document = parse(Cursor::new("<p><i>a <b>b</p>c<div><u>d<p>e</p></div>")).unwrap();
+        assert_eq!(
+            document,
+            doc(vec![
+                p_(vec![i_(vec![span("a "), b__("b")])]),
+                p__("c"),
+                p_(vec![u__("d")]),
+                p__("e"),
+            ])
+        );
+    }
+
+    #[test]
+    fn reads_code_language_from_pre_or_code_class() {
+        let document = parse(Cursor::new(
+            "<pre class=\"language-go\">fmt.Println()</pre>\
+             <pre><code class=\"hljs lang-rust\">fn main() {}</code></pre>\
+             <pre class=\"language-sh\"><code class=\"language-bash\">ls</code></pre>\
+             <pre class=\"wide\">plain</pre>",
+        ))
+        .unwrap();
+
+        let languages: Vec<_> = document
+            .paragraphs
+            .iter()
+            .map(|paragraph| paragraph.language())
+            .collect();
+        assert_eq!(languages, [Some("go"), Some("rust"), Some("sh"), None]);
+        assert_eq!(document.paragraphs[0].content()[0].text, "fmt.Println()");
+    }
+
+    #[test]
+    fn decodes_entities_in_preformatted_text_exactly_once() {
+        let cases = [
+            ("<pre>if (a &lt; b) { }</pre>", "if (a < b) { }"),
+            ("<pre>if (a &#60; b) { }</pre>", "if (a < b) { }"),
+            (
+                "<pre>&lt;p&gt;not a tag&lt;/p&gt;</pre>",
+                "<p>not a tag</p>",
+            ),
+            ("<pre>&amp;lt;</pre>", "&lt;"),
+            ("<pre><code>x &lt;b&gt; y</code></pre>", "x <b> y"),
+        ];
+        for (input, expected) in cases {
+            let document = parse(Cursor::new(input)).unwrap();
+            assert_eq!(document.paragraphs.len(), 1, "{input}");
+            let paragraph = &document.paragraphs[0];
+            assert_eq!(paragraph.paragraph_type(), ParagraphType::CodeBlock);
+            assert_eq!(paragraph.content().len(), 1, "{input}");
+            assert_eq!(paragraph.content()[0].text, expected, "{input}");
+        }
+    }
+
+    #[test]
+    fn treats_sectioning_elements_as_transparent_containers() {
+        let input = "<section><p>One</p><p>Two</p></section>";
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            document.paragraphs,
+            vec![
+                Paragraph::new_text().with_content(vec![Span::new_text("One")]),
+                Paragraph::new_text().with_content(vec![Span::new_text("Two")]),
+            ]
+        );
+
+        let input = "<main><header>Top</header><article>Body <b>text</b></article><footer>End</footer></main>";
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            document.paragraphs,
+            vec![
+                Paragraph::new_text().with_content(vec![Span::new_text("Top")]),
+                Paragraph::new_text().with_content(vec![
+                    Span::new_text("Body "),
+                    Span::new_styled(InlineStyle::Bold).with_children(vec![Span::new_text("text")]),
+                ]),
+                Paragraph::new_text().with_content(vec![Span::new_text("End")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_definition_list() {
+        let input = "<dl>\n  <dt>Term</dt>\n  <dd>The definition.</dd>\n</dl><p>After</p>";
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            document.paragraphs,
+            vec![
+                Paragraph::new_definition_list().with_definition_items(vec![DefinitionItem::new()
+                    .with_term(vec![Span::new_text("Term")])
+                    .with_definition(vec![
+                        Paragraph::new_text().with_content(vec![Span::new_text("The definition.")])
+                    ])]),
+                Paragraph::new_text().with_content(vec![Span::new_text("After")]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_definitions_with_block_content() {
+        let input =
+            "<dl><dt>A</dt><dt>B</dt><dd><p>One</p><ul><li>x</li></ul></dd><dd>Two</dd></dl>";
+        let document = parse(Cursor::new(input)).unwrap();
+        let items = document.paragraphs[0].definition_items();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].term, vec![Span::new_text("A")]);
+        assert!(items[0].definition.is_empty());
+        assert_eq!(items[1].term, vec![Span::new_text("B")]);
+        let types: Vec<ParagraphType> = items[1]
+            .definition
+            .iter()
+            .map(Paragraph::paragraph_type)
+            .collect();
+        assert_eq!(
+            types,
+            vec![
+                ParagraphType::Text,
+                ParagraphType::UnorderedList,
+                ParagraphType::Text
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_details_with_summary() {
+        let input = "<details open><summary>More info</summary><p>Hidden body</p></details>";
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            document.paragraphs,
+            vec![Paragraph::new_details()
+                .with_summary(vec![Span::new_text("More info")])
+                .with_children(vec![
+                    Paragraph::new_text().with_content(vec![Span::new_text("Hidden body")])
+                ])]
+        );
+    }
+
     #[test]
     fn drops_empty_paragraphs_created_by_line_breaks() {
         let input = "<p><br></p><p>World</p>";
@@ -1754,6 +2419,45 @@ mod tests {
         assert_eq!(paragraph.content()[0].text, "World");
     }
 
+    #[test]
+    fn keeps_navigation_when_nav_is_not_skipped() {
+        let input = r#"<nav><ul><li><a href="/">Home</a></li><li><a href="/about">About</a></li></ul></nav><p>Body</p>"#;
+
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(document.paragraphs.len(), 1);
+
+        let mut options = ParseOptions::default();
+        options.skip_tags.remove("nav");
+        options.skip_tags.insert("aside".to_string());
+        let input = format!("{input}<aside><p>Related</p></aside>");
+        let document = parse_with_options(Cursor::new(input), &options).unwrap();
+        assert_eq!(document.paragraphs.len(), 2);
+
+        let list = &document.paragraphs[0];
+        assert_eq!(list.paragraph_type(), ParagraphType::UnorderedList);
+        let targets: Vec<Option<&str>> = list
+            .entries()
+            .iter()
+            .map(|entry| entry[0].content()[0].link_target.as_deref())
+            .collect();
+        assert_eq!(targets, vec![Some("/"), Some("/about")]);
+        assert_eq!(document.paragraphs[1].content()[0].text, "Body");
+    }
+
+    #[test]
+    fn keeps_heading_ids_and_fragment_links() {
+        let input = r##"<p><a href="#usage">Skip to usage</a></p><h2 id="usage">Usage</h2><h3>Options</h3>"##;
+
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(document.paragraphs.len(), 3);
+
+        let link = &document.paragraphs[0].content()[0];
+        assert_eq!(link.link_fragment(), Some("usage"));
+        assert_eq!(document.paragraphs[1].anchor(), Some("usage"));
+        assert_eq!(document.paragraphs[2].anchor(), None);
+        assert_eq!(document.table_of_contents()[1].slug, "options");
+    }
+
     #[test]
     fn parses_links_inside_navigation_container() {
         let input = r#"
@@ -1780,7 +2484,7 @@ mod tests {
 
         let document = parse(Cursor::new(input)).unwrap();
 
-        let mut tokenizer = Tokenizer::new(input);
+        let mut tokenizer = gockl::Tokenizer::new(input);
         let mut seen_links = Vec::new();
         while let Ok(token) = tokenizer.next_token() {
             if let Token::StartElement(start) = token {
@@ -1822,6 +2526,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn lead_class_marks_lead_paragraph() {
+        use crate::formatter::{Formatter, FormattingStyle};
+
+        let input = "<p class=\"intro lead\">Big news</p><p>Details</p>";
+        let document = parse(Cursor::new(input)).unwrap();
+        assert!(document.paragraphs[0].is_lead());
+        assert!(!document.paragraphs[1].is_lead());
+
+        let mut output = Vec::new();
+        Formatter::new(&mut output, FormattingStyle::ansi())
+            .write_document(&document)
+            .unwrap();
+        let rendered = String::from_utf8(output).unwrap();
+        assert!(rendered.starts_with("\x1b[1mBig news\x1b[22m\n"));
+        assert!(rendered.contains("\n\nDetails\n"));
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        let html = String::from_utf8(output).unwrap();
+        assert!(html.contains("<p class=\"lead\">Big news</p>"));
+        assert_eq!(parse(Cursor::new(html)).unwrap(), document);
+    }
+
     #[test]
     fn parses_simple_table_with_header_row() {
         let input = "<table><thead><tr><th>Name</th><th>Age</th></tr></thead>\
@@ -1861,6 +2589,26 @@ mod tests {
         assert_eq!(data_cell.content[1].children[0].text, "world");
     }
 
+    #[test]
+    fn parses_and_writes_table_caption_and_alignment() {
+        let input = "<table><caption>Prices</caption>\
+                     <tr><th>Item</th><th align=\"right\">Price</th></tr>\
+                     <tr><td>Tea</td><td style=\"text-align: right\">3.50</td></tr>\
+                     <tr><td style=\"text-align:center\">Cake</td><td>12.00</td></tr></table>";
+        let document = parse(Cursor::new(input)).unwrap();
+
+        let table = &document.paragraphs[0];
+        assert_eq!(table.caption(), &[Span::new_text("Prices")]);
+        assert_eq!(table.alignments(), &[Alignment::Center, Alignment::Right]);
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        let html = String::from_utf8(output).unwrap();
+        assert!(html.contains("<caption>Prices</caption>"));
+        assert!(html.contains("<th style=\"text-align: right\">Price</th>"));
+        assert_eq!(parse(Cursor::new(html)).unwrap(), document);
+    }
+
     #[test]
     fn parses_table_without_explicit_tbody() {
         let input = "<table><tr><td>A</td><td>B</td></tr><tr><td>C</td><td>D</td></tr></table>";
@@ -1990,6 +2738,148 @@ mod tests {
         assert_eq!(html, "<p>A</p>\n\n<hr />\n\n<p>B</p>\n");
     }
 
+    #[test]
+    fn parses_and_writes_superscript_and_subscript() {
+        let input = "<p>x<sup>2</sup> and H<sub>2</sub>O</p>";
+        let document = parse(Cursor::new(input)).unwrap();
+
+        let content = document.paragraphs[0].content();
+        assert_eq!(content[1].style, InlineStyle::Superscript);
+        assert_eq!(content[3].style, InlineStyle::Subscript);
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n", input));
+    }
+
+    #[test]
+    fn keyboard_round_trips_through_html_and_ftml() {
+        let input = "<p>Press <kbd>Ctrl</kbd>+<kbd>C</kbd></p>";
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            document.paragraphs[0].content()[1].style,
+            InlineStyle::Keyboard
+        );
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n", input));
+
+        let mut ftml = Vec::new();
+        crate::ftml::write(&mut ftml, &document).unwrap();
+        assert_eq!(crate::ftml::parse(ftml.as_slice()).unwrap(), document);
+    }
+
+    #[test]
+    fn parses_abbreviation_title() {
+        let input = "<p>Written in <abbr title=\"HyperText Markup Language\">HTML</abbr>.</p>";
+        let document = parse(Cursor::new(input)).unwrap();
+
+        let span = &document.paragraphs[0].content()[1];
+        assert_eq!(span.style, InlineStyle::Abbreviation);
+        assert_eq!(
+            span.link_target.as_deref(),
+            Some("HyperText Markup Language")
+        );
+        assert_eq!(span.children, vec![Span::new_text("HTML")]);
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), format!("{}\n", input));
+    }
+
+    #[test]
+    fn preserved_raw_iframe_survives_to_html_but_not_plain_text() {
+        let input =
+            "<p>Watch <iframe src=\"https://example.test/v\"><p>Fallback</p></iframe> now</p>";
+        let options = ParseOptions {
+            preserve_raw: true,
+            ..Default::default()
+        };
+        let document = parse_with_options(Cursor::new(input), &options).unwrap();
+        assert_eq!(document.paragraphs.len(), 1);
+        let raw = &document.paragraphs[0].content()[1];
+        assert_eq!(raw.style, InlineStyle::Raw);
+        assert_eq!(raw.raw_format.as_deref(), Some("html"));
+
+        let mut html = Vec::new();
+        write(&mut html, &document).unwrap();
+        assert_eq!(String::from_utf8(html).unwrap(), format!("{}\n", input));
+
+        let mut text = Vec::new();
+        crate::formatter::Formatter::new_ascii(&mut text)
+            .write_document(&document)
+            .unwrap();
+        assert_eq!(String::from_utf8(text).unwrap(), "Watch  now\n");
+    }
+
+    #[test]
+    fn empty_paragraphs_are_kept_on_request() {
+        let input = "<p></p><p>x</p>";
+        let render = |document: &Document| {
+            let mut text = Vec::new();
+            crate::formatter::Formatter::new_ascii(&mut text)
+                .write_document(document)
+                .unwrap();
+            String::from_utf8(text).unwrap()
+        };
+
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(document.paragraphs, vec![p__("x")]);
+        assert_eq!(render(&document), "x\n");
+
+        let options = ParseOptions {
+            keep_empty_paragraphs: true,
+            ..Default::default()
+        };
+        let document = parse_with_options(Cursor::new(input), &options).unwrap();
+        assert_eq!(document.paragraphs, vec![p_(vec![]), p__("x")]);
+        assert_eq!(render(&document), "\n\nx\n");
+
+        let mut html = Vec::new();
+        write(&mut html, &document).unwrap();
+        assert_eq!(
+            parse_with_options(Cursor::new(html), &options).unwrap(),
+            document
+        );
+    }
+
+    #[test]
+    fn embedded_elements_fall_back_to_text_by_default() {
+        let input = "<p>Watch <video src=\"clip.mp4\">your browser lacks video</video></p>";
+        let document = parse(Cursor::new(input)).unwrap();
+        let content = document.paragraphs[0].content();
+        assert!(content.iter().all(|span| span.style != InlineStyle::Raw));
+
+        let mut html = Vec::new();
+        write(&mut html, &document).unwrap();
+        assert_eq!(
+            String::from_utf8(html).unwrap(),
+            "<p>Watch your browser lacks video</p>\n"
+        );
+    }
+
+    #[test]
+    fn parses_and_writes_colored_spans() {
+        let input = r#"<p>A <span style="font-weight: bold; color: #f80">warning</span> and <span style="margin: 0">plain</span></p>"#;
+        let document = parse(Cursor::new(input)).unwrap();
+        let content = document.paragraphs[0].content();
+        assert_eq!(content[1].style, InlineStyle::Color);
+        assert_eq!(content[1].color, Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(content[1].link_target, None);
+        assert!(content
+            .iter()
+            .skip(2)
+            .all(|span| span.style != InlineStyle::Color));
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "<p>A <span style=\"color: #ff8800\">warning</span> and plain</p>\n"
+        );
+    }
+
     #[test]
     fn write_document_embeds_stylesheet_and_body() {
         let doc = parse(Cursor::new("<h1>Title</h1><p>Body</p>")).unwrap();
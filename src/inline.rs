@@ -21,6 +21,24 @@ pub enum InlineStyle {
     Link,
     /// Inline code.
     Code,
+    /// Superscript text (e.g. `<sup>`).
+    Superscript,
+    /// Subscript text (e.g. `<sub>`).
+    Subscript,
+    /// Keyboard input such as key names (e.g. `<kbd>`).
+    Keyboard,
+    /// An abbreviation (`<abbr>`). Its expansion is stored in
+    /// [`Span::link_target`].
+    Abbreviation,
+    /// An inline quotation (`<q>`). Writers add the quotation marks, so the
+    /// content holds only the quoted text.
+    Quotation,
+    /// Verbatim content for one output format. `text` holds the content and
+    /// [`Span::raw_format`] the format name (e.g. `html`); writers for other
+    /// formats drop the span.
+    Raw,
+    /// Colored text. The color is stored in [`Span::color`].
+    Color,
 }
 
 impl fmt::Display for InlineStyle {
@@ -34,17 +52,108 @@ impl fmt::Display for InlineStyle {
             InlineStyle::Highlight => "highlight",
             InlineStyle::Link => "link",
             InlineStyle::Code => "code",
+            InlineStyle::Superscript => "superscript",
+            InlineStyle::Subscript => "subscript",
+            InlineStyle::Keyboard => "keyboard",
+            InlineStyle::Quotation => "quotation",
+            InlineStyle::Abbreviation => "abbreviation",
+            InlineStyle::Raw => "raw",
+            InlineStyle::Color => "color",
         };
         write!(f, "{}", s)
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// A text color as understood by [`InlineStyle::Color`] spans.
+pub enum Color {
+    /// One of the eight basic terminal colors, by ANSI index (`0` is black,
+    /// `1` red, … `7` white).
+    Named(u8),
+    /// A 24-bit RGB color.
+    Rgb(u8, u8, u8),
+}
+
+impl fmt::Display for Color {
+    /// Writes the color as a CSS color value: a keyword for named colors,
+    /// `#rrggbb` otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Color::Named(index) => {
+                const NAMES: [&str; 8] = [
+                    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+                ];
+                write!(f, "{}", NAMES[usize::from(*index) % NAMES.len()])
+            }
+            Color::Rgb(r, g, b) => write!(f, "#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+}
+
+impl Color {
+    /// Parses a CSS color value: a basic color keyword (`red`, `blue`, …),
+    /// `#rgb`, `#rrggbb`, or `rgb(r, g, b)`. Returns `None` for anything else.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+
+        if let Some(hex) = value.strip_prefix('#') {
+            if !hex.is_ascii() {
+                return None;
+            }
+            let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+            return match hex.len() {
+                3 => {
+                    let mut channels = hex.chars().map(|ch| channel(&ch.to_string()));
+                    let (r, g, b) = (channels.next()??, channels.next()??, channels.next()??);
+                    Some(Color::Rgb(r * 17, g * 17, b * 17))
+                }
+                6 => Some(Color::Rgb(
+                    channel(&hex[0..2])?,
+                    channel(&hex[2..4])?,
+                    channel(&hex[4..6])?,
+                )),
+                _ => None,
+            };
+        }
+
+        if let Some(args) = value
+            .strip_prefix("rgb(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let channels = args
+                .split(',')
+                .map(|part| part.trim().parse::<u8>().ok())
+                .collect::<Option<Vec<_>>>()?;
+            return match channels[..] {
+                [r, g, b] => Some(Color::Rgb(r, g, b)),
+                _ => None,
+            };
+        }
+
+        let index = match value.as_str() {
+            "black" => 0,
+            "red" => 1,
+            "green" => 2,
+            "yellow" => 3,
+            "blue" => 4,
+            "magenta" | "fuchsia" => 5,
+            "cyan" | "aqua" => 6,
+            "white" => 7,
+            _ => return None,
+        };
+        Some(Color::Named(index))
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// Inline-level node that holds styled or plain text content.
 ///
 /// Spans can either contain literal text, nested spans (for composite styling),
 /// or a combination of both. When `style` is [`InlineStyle::Link`], the optional
-/// `link_target` is populated with the URL.
+/// `link_target` is populated with the URL; for [`InlineStyle::Abbreviation`]
+/// it holds the expansion. [`InlineStyle::Raw`] spans name their output
+/// format in `raw_format`, and [`InlineStyle::Color`] spans carry their
+/// `color`.
 ///
 /// # Examples
 ///
@@ -52,17 +161,18 @@ impl fmt::Display for InlineStyle {
 /// use tdoc::{InlineStyle, Span};
 ///
 /// let plain = Span::new_text("plain text");
-/// let link = Span::new_styled(InlineStyle::Link)
-///     .with_children(vec![Span::new_text("The Book")])
-///     .with_link_target("https://example.test");
+/// let link = Span::link_with_text("https://example.test", "The Book");
 ///
 /// assert_eq!(plain.text, "plain text");
+/// assert_eq!(link.style, InlineStyle::Link);
 /// assert_eq!(link.link_target.as_deref(), Some("https://example.test"));
 /// ```
 pub struct Span {
     pub style: InlineStyle,
     pub text: String,
     pub link_target: Option<String>,
+    pub raw_format: Option<String>,
+    pub color: Option<Color>,
     pub children: Vec<Span>,
 }
 
@@ -73,6 +183,8 @@ impl Span {
             style: InlineStyle::None,
             text: text.into(),
             link_target: None,
+            raw_format: None,
+            color: None,
             children: Vec::new(),
         }
     }
@@ -83,10 +195,95 @@ impl Span {
             style,
             text: String::new(),
             link_target: None,
+            raw_format: None,
+            color: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// Creates an [`InlineStyle::Link`] span to `target` without a
+    /// description, which is displayed as the target itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{InlineStyle, Span};
+    ///
+    /// let link = Span::link("https://example.test");
+    /// assert_eq!(link.style, InlineStyle::Link);
+    /// assert_eq!(link.link_target.as_deref(), Some("https://example.test"));
+    /// assert!(link.is_content_empty());
+    /// ```
+    pub fn link(target: impl Into<String>) -> Self {
+        Self::new_styled(InlineStyle::Link).with_link_target(target)
+    }
+
+    /// Creates an [`InlineStyle::Link`] span to `target` described by `text`.
+    /// An empty description, or one that merely repeats an absolute URL, is
+    /// dropped (see [`Span::strip_redundant_link_description`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::Span;
+    ///
+    /// let link = Span::link_with_text("https://example.test", "The Book");
+    /// assert_eq!(link.children, vec![Span::new_text("The Book")]);
+    ///
+    /// let link = Span::link_with_text("https://example.test", "https://example.test");
+    /// assert_eq!(link, Span::link("https://example.test"));
+    /// ```
+    pub fn link_with_text(target: impl Into<String>, text: impl Into<String>) -> Self {
+        let text = text.into();
+        let mut span = Self::link(target);
+        if !text.is_empty() {
+            span.children.push(Span::new_text(text));
+            span.strip_redundant_link_description();
+        }
+        span
+    }
+
+    /// Creates an [`InlineStyle::Raw`] span holding `content` verbatim for the
+    /// writer of `format`.
+    pub fn new_raw(format: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            style: InlineStyle::Raw,
+            text: content.into(),
+            link_target: None,
+            raw_format: Some(format.into()),
+            color: None,
             children: Vec::new(),
         }
     }
 
+    /// Returns the fragment if this is an [`InlineStyle::Link`] to an anchor
+    /// in the same document, i.e. `section` for a link to `#section`.
+    pub fn link_fragment(&self) -> Option<&str> {
+        if self.style != InlineStyle::Link {
+            return None;
+        }
+        self.link_target
+            .as_deref()
+            .and_then(|target| target.trim().strip_prefix('#'))
+            .filter(|fragment| !fragment.is_empty())
+    }
+
+    /// Returns the raw content if this is an [`InlineStyle::Raw`] span
+    /// targeting `format` (compared case-insensitively).
+    pub fn raw_content_for(&self, format: &str) -> Option<&str> {
+        match (&self.style, &self.raw_format) {
+            (InlineStyle::Raw, Some(raw_format)) if raw_format.eq_ignore_ascii_case(format) => {
+                Some(&self.text)
+            }
+            _ => None,
+        }
+    }
+
+    /// Creates an [`InlineStyle::Color`] span in `color` without content.
+    pub fn new_colored(color: Color) -> Self {
+        Self::new_styled(InlineStyle::Color).with_color(color)
+    }
+
     /// Replaces the child spans, returning the updated span.
     pub fn with_children(mut self, children: Vec<Span>) -> Self {
         self.children = children;
@@ -109,12 +306,19 @@ impl Span {
         self.text.is_empty() && self.children.is_empty()
     }
 
-    /// Sets the link target for [`InlineStyle::Link`] spans.
+    /// Sets the link target for [`InlineStyle::Link`] spans, or the expansion
+    /// for [`InlineStyle::Abbreviation`] spans.
     pub fn with_link_target(mut self, target: impl Into<String>) -> Self {
         self.link_target = Some(target.into());
         self
     }
 
+    /// Sets the color of [`InlineStyle::Color`] spans.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
     /// Removes redundant link descriptions when they match the target URL.
     pub fn strip_redundant_link_description(&mut self) {
         if self.style != InlineStyle::Link {
@@ -195,6 +399,54 @@ impl fmt::Display for Span {
     }
 }
 
+/// Creates a plain text span, like [`Span::new_text`].
+///
+/// ```
+/// use tdoc::{InlineStyle, Span};
+///
+/// let span = Span::from("Hello");
+/// assert_eq!(span.style, InlineStyle::None);
+/// assert_eq!(span.text, "Hello");
+/// ```
+impl From<&str> for Span {
+    fn from(text: &str) -> Self {
+        Span::new_text(text)
+    }
+}
+
+/// Creates a plain text span, like [`Span::new_text`].
+impl From<String> for Span {
+    fn from(text: String) -> Self {
+        Span::new_text(text)
+    }
+}
+
+/// Removes trailing whitespace from the last plain-text spans of `spans`,
+/// dropping spans that become empty. Styled spans, links and spans with
+/// children end the trimming.
+pub(crate) fn trim_trailing_inline_whitespace(spans: &mut Vec<Span>) {
+    while let Some(last) = spans.last_mut() {
+        if last.style != InlineStyle::None
+            || !last.children.is_empty()
+            || last.link_target.is_some()
+        {
+            break;
+        }
+
+        let trimmed = last.text.trim_end();
+        if trimmed.len() == last.text.len() {
+            break;
+        }
+
+        if trimmed.is_empty() {
+            spans.pop();
+        } else {
+            last.text = trimmed.to_string();
+            break;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,4 +479,23 @@ mod tests {
             Span::new_styled(InlineStyle::Bold).with_children(vec![Span::new_text("hi")]);
         assert_eq!(bold_span.width(), 2);
     }
+
+    #[test]
+    fn test_color_parse() {
+        assert_eq!(Color::parse("Red"), Some(Color::Named(1)));
+        assert_eq!(Color::parse("#f80"), Some(Color::Rgb(255, 136, 0)));
+        assert_eq!(Color::parse(" #0A0b0C "), Some(Color::Rgb(10, 11, 12)));
+        assert_eq!(Color::parse("rgb(1, 2, 3)"), Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(Color::parse("rgb(1, 2)"), None);
+        assert_eq!(Color::parse("#ggg"), None);
+        assert_eq!(Color::parse("currentcolor"), None);
+    }
+
+    #[test]
+    fn test_color_display_parses_back() {
+        for color in [Color::Named(5), Color::Rgb(255, 136, 0)] {
+            assert_eq!(Color::parse(&color.to_string()), Some(color));
+        }
+        assert_eq!(Color::Rgb(10, 11, 12).to_string(), "#0a0b0c");
+    }
 }
@@ -0,0 +1,863 @@
+//! Convert between JSON and [`Document`](crate::Document) trees.
+//!
+//! JSON is a lossless, language-neutral interchange format for tooling. The
+//! schema is versioned and deliberately independent of the in-memory types, so
+//! it stays stable even when the Rust API evolves.
+//!
+//! # Schema (version 1)
+//!
+//! A document is an object with a `version` number, an optional `metadata`
+//! object, and a list of `paragraphs`:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "metadata": { "title": "Notes" },
+//!   "paragraphs": [
+//!     { "type": "header1", "content": [{ "style": "none", "text": "Notes" }] },
+//!     { "type": "text", "content": [
+//!       { "style": "bold", "children": [{ "style": "none", "text": "Hi" }] },
+//!       { "style": "link", "target": "https://example.com" }
+//!     ] }
+//!   ]
+//! }
+//! ```
+//!
+//! Paragraph objects are discriminated by `type`:
+//!
+//! - `text`, `header1`, `header2`, `header3`, `code_block`: inline spans in `content`.
+//!   Text may also be marked `lead`, headings may carry an `anchor` and code
+//!   blocks a `language` string.
+//! - `ordered_list`, `unordered_list`: `entries`, a list of paragraph lists.
+//!   Ordered lists may also carry a `delimiter`, `period` (the default) or
+//!   `parenthesis`.
+//! - `checklist`: `items`, each with `checked`, `content`, and nested `children` items.
+//! - `quote`: nested paragraphs in `children`.
+//! - `details`: a `summary` of inline spans and nested paragraphs in `children`.
+//! - `admonition`: a `kind` (`note`, `tip`, `important`, `warning` or
+//!   `caution`) and nested paragraphs in `children`.
+//! - `table`: `rows`, each with `cells` carrying `header` and `content`.
+//!   Tables may also carry a `caption` of inline spans and `alignments`, one
+//!   of `left`, `center` or `right` per column.
+//! - `definition_list`: `items`, each with inline `term` spans and `definition`
+//!   paragraphs.
+//! - `horizontal_rule`: no further fields.
+//! - `raw`: verbatim `content` for the output `format` it names.
+//!
+//! Spans carry a `style` (`none`, `bold`, `italic`, `highlight`, `underline`,
+//! `strike`, `link`, `code`, `superscript`, `subscript`, `keyboard`,
+//! `abbreviation`, `quotation`, `raw`, or `color`) plus optional `text`, `target`,
+//! `format`, `color`, and `children` fields, which are omitted when empty.
+//! `target` holds a link's URL or an abbreviation's expansion, `format` names
+//! the output format of a raw span, and `color` is a CSS color value.
+
+use crate::metadata::Metadata;
+use crate::{
+    AdmonitionKind, Alignment, ChecklistItem, Color, DefinitionItem, Document, InlineStyle,
+    ListDelimiter, Paragraph, Span, TableCell, TableRow, DEFAULT_MAX_DEPTH,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+
+/// The schema version emitted by [`write`] and accepted by [`parse`].
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Parses a JSON document into a [`Document`].
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tdoc::{json, ParagraphType};
+///
+/// let input = r#"{"version": 1, "paragraphs": [{"type": "text", "content": [{"style": "none", "text": "Hi"}]}]}"#;
+/// let doc = json::parse(Cursor::new(input)).unwrap();
+/// assert_eq!(doc.paragraphs[0].paragraph_type(), ParagraphType::Text);
+/// ```
+pub fn parse<R: Read>(mut reader: R) -> crate::Result<Document> {
+    let mut input = Vec::new();
+    reader.read_to_end(&mut input)?;
+    // serde_json's own limit of 128 levels is hit long before paragraphs are
+    // nested `DEFAULT_MAX_DEPTH` levels deep, so it is replaced by a check
+    // made without recursion.
+    if json_nesting(&input) > MAX_JSON_NESTING {
+        return Err(format!("JSON nested more than {MAX_JSON_NESTING} levels deep").into());
+    }
+    let mut deserializer = serde_json::Deserializer::from_slice(&input);
+    deserializer.disable_recursion_limit();
+    let json = JsonDocument::deserialize(&mut deserializer)?;
+    deserializer.end()?;
+    if json.version != SCHEMA_VERSION {
+        return Err(format!("Unsupported JSON schema version {}", json.version).into());
+    }
+
+    let paragraphs: Vec<Paragraph> = json.paragraphs.into_iter().map(Paragraph::from).collect();
+    if paragraphs
+        .iter()
+        .any(|paragraph| paragraph.depth() > DEFAULT_MAX_DEPTH)
+    {
+        return Err(format!("paragraphs nested more than {DEFAULT_MAX_DEPTH} levels deep").into());
+    }
+    Ok(Document {
+        metadata: json.metadata,
+        paragraphs,
+    })
+}
+
+/// The deepest nesting of JSON arrays and objects [`parse`] accepts. A
+/// paragraph level takes up to four of them (a definition list, its `items`,
+/// an item and its `definition`), and inline spans nest below the innermost
+/// paragraph.
+const MAX_JSON_NESTING: usize = 8 * DEFAULT_MAX_DEPTH;
+
+/// Returns how deeply the arrays and objects of `input` are nested.
+fn json_nesting(input: &[u8]) -> usize {
+    let mut depth = 0usize;
+    let mut max = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for &byte in input {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max
+}
+
+/// Serializes a [`Document`] structure to pretty-printed JSON.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::{Document, Paragraph, Span};
+/// use tdoc::json;
+///
+/// let paragraph = Paragraph::new_text().with_content(vec![Span::new_text("Hello")]);
+/// let document = Document::new().with_paragraphs(vec![paragraph]);
+///
+/// let mut output = Vec::new();
+/// json::write(&mut output, &document).unwrap();
+/// let result = String::from_utf8(output).unwrap();
+/// assert!(result.contains(r#""type": "text""#));
+/// ```
+pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<()> {
+    let json = JsonDocument {
+        version: SCHEMA_VERSION,
+        metadata: document.metadata.clone(),
+        paragraphs: document
+            .paragraphs
+            .iter()
+            .map(JsonParagraph::from)
+            .collect(),
+    };
+    serde_json::to_writer_pretty(&mut *writer, &json).map_err(std::io::Error::other)?;
+    writeln!(writer)
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonDocument {
+    version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<Metadata>,
+    #[serde(default)]
+    paragraphs: Vec<JsonParagraph>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum JsonParagraph {
+    Text {
+        #[serde(default)]
+        content: Vec<JsonSpan>,
+        #[serde(default, skip_serializing_if = "is_false")]
+        lead: bool,
+    },
+    Header1 {
+        #[serde(default)]
+        content: Vec<JsonSpan>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<String>,
+    },
+    Header2 {
+        #[serde(default)]
+        content: Vec<JsonSpan>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<String>,
+    },
+    Header3 {
+        #[serde(default)]
+        content: Vec<JsonSpan>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        anchor: Option<String>,
+    },
+    CodeBlock {
+        #[serde(default)]
+        content: Vec<JsonSpan>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        language: Option<String>,
+    },
+    OrderedList {
+        #[serde(default)]
+        entries: Vec<Vec<JsonParagraph>>,
+        #[serde(default, skip_serializing_if = "JsonDelimiter::is_period")]
+        delimiter: JsonDelimiter,
+    },
+    UnorderedList {
+        #[serde(default)]
+        entries: Vec<Vec<JsonParagraph>>,
+    },
+    Checklist {
+        #[serde(default)]
+        items: Vec<JsonChecklistItem>,
+    },
+    Quote {
+        #[serde(default)]
+        children: Vec<JsonParagraph>,
+    },
+    Table {
+        #[serde(default)]
+        rows: Vec<JsonTableRow>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        caption: Vec<JsonSpan>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        alignments: Vec<JsonAlignment>,
+    },
+    DefinitionList {
+        #[serde(default)]
+        items: Vec<JsonDefinitionItem>,
+    },
+    Details {
+        #[serde(default)]
+        summary: Vec<JsonSpan>,
+        #[serde(default)]
+        children: Vec<JsonParagraph>,
+    },
+    Admonition {
+        kind: JsonAdmonitionKind,
+        #[serde(default)]
+        children: Vec<JsonParagraph>,
+    },
+    HorizontalRule,
+    Raw {
+        format: String,
+        #[serde(default)]
+        content: String,
+    },
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonChecklistItem {
+    checked: bool,
+    #[serde(default)]
+    content: Vec<JsonSpan>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<JsonChecklistItem>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonTableRow {
+    #[serde(default)]
+    cells: Vec<JsonTableCell>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonTableCell {
+    #[serde(default)]
+    header: bool,
+    #[serde(default)]
+    content: Vec<JsonSpan>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonDefinitionItem {
+    #[serde(default)]
+    term: Vec<JsonSpan>,
+    #[serde(default)]
+    definition: Vec<JsonParagraph>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonSpan {
+    style: JsonStyle,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    text: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    target: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    color: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    children: Vec<JsonSpan>,
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JsonStyle {
+    None,
+    Bold,
+    Italic,
+    Highlight,
+    Underline,
+    Strike,
+    Link,
+    Code,
+    Superscript,
+    Subscript,
+    Keyboard,
+    Abbreviation,
+    Quotation,
+    Raw,
+    Color,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JsonDelimiter {
+    #[default]
+    Period,
+    Parenthesis,
+}
+
+impl JsonDelimiter {
+    fn is_period(&self) -> bool {
+        *self == JsonDelimiter::Period
+    }
+}
+
+impl From<ListDelimiter> for JsonDelimiter {
+    fn from(delimiter: ListDelimiter) -> Self {
+        match delimiter {
+            ListDelimiter::Period => JsonDelimiter::Period,
+            ListDelimiter::Parenthesis => JsonDelimiter::Parenthesis,
+        }
+    }
+}
+
+impl From<JsonDelimiter> for ListDelimiter {
+    fn from(delimiter: JsonDelimiter) -> Self {
+        match delimiter {
+            JsonDelimiter::Period => ListDelimiter::Period,
+            JsonDelimiter::Parenthesis => ListDelimiter::Parenthesis,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JsonAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl From<Alignment> for JsonAlignment {
+    fn from(alignment: Alignment) -> Self {
+        match alignment {
+            Alignment::Left => JsonAlignment::Left,
+            Alignment::Center => JsonAlignment::Center,
+            Alignment::Right => JsonAlignment::Right,
+        }
+    }
+}
+
+impl From<JsonAlignment> for Alignment {
+    fn from(alignment: JsonAlignment) -> Self {
+        match alignment {
+            JsonAlignment::Left => Alignment::Left,
+            JsonAlignment::Center => Alignment::Center,
+            JsonAlignment::Right => Alignment::Right,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum JsonAdmonitionKind {
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl From<AdmonitionKind> for JsonAdmonitionKind {
+    fn from(kind: AdmonitionKind) -> Self {
+        match kind {
+            AdmonitionKind::Note => JsonAdmonitionKind::Note,
+            AdmonitionKind::Tip => JsonAdmonitionKind::Tip,
+            AdmonitionKind::Important => JsonAdmonitionKind::Important,
+            AdmonitionKind::Warning => JsonAdmonitionKind::Warning,
+            AdmonitionKind::Caution => JsonAdmonitionKind::Caution,
+        }
+    }
+}
+
+impl From<JsonAdmonitionKind> for AdmonitionKind {
+    fn from(kind: JsonAdmonitionKind) -> Self {
+        match kind {
+            JsonAdmonitionKind::Note => AdmonitionKind::Note,
+            JsonAdmonitionKind::Tip => AdmonitionKind::Tip,
+            JsonAdmonitionKind::Important => AdmonitionKind::Important,
+            JsonAdmonitionKind::Warning => AdmonitionKind::Warning,
+            JsonAdmonitionKind::Caution => AdmonitionKind::Caution,
+        }
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+fn spans_to_json(spans: &[Span]) -> Vec<JsonSpan> {
+    spans.iter().map(JsonSpan::from).collect()
+}
+
+fn spans_from_json(spans: Vec<JsonSpan>) -> Vec<Span> {
+    spans.into_iter().map(Span::from).collect()
+}
+
+fn entries_to_json(entries: &[Vec<Paragraph>]) -> Vec<Vec<JsonParagraph>> {
+    entries
+        .iter()
+        .map(|entry| entry.iter().map(JsonParagraph::from).collect())
+        .collect()
+}
+
+fn entries_from_json(entries: Vec<Vec<JsonParagraph>>) -> Vec<Vec<Paragraph>> {
+    entries
+        .into_iter()
+        .map(|entry| entry.into_iter().map(Paragraph::from).collect())
+        .collect()
+}
+
+impl From<&Paragraph> for JsonParagraph {
+    fn from(paragraph: &Paragraph) -> Self {
+        match paragraph {
+            Paragraph::Text { content, lead } => JsonParagraph::Text {
+                content: spans_to_json(content),
+                lead: *lead,
+            },
+            Paragraph::Header1 { content, anchor } => JsonParagraph::Header1 {
+                content: spans_to_json(content),
+                anchor: anchor.clone(),
+            },
+            Paragraph::Header2 { content, anchor } => JsonParagraph::Header2 {
+                content: spans_to_json(content),
+                anchor: anchor.clone(),
+            },
+            Paragraph::Header3 { content, anchor } => JsonParagraph::Header3 {
+                content: spans_to_json(content),
+                anchor: anchor.clone(),
+            },
+            Paragraph::CodeBlock { content, language } => JsonParagraph::CodeBlock {
+                content: spans_to_json(content),
+                language: language.clone(),
+            },
+            Paragraph::OrderedList { entries, delimiter } => JsonParagraph::OrderedList {
+                entries: entries_to_json(entries),
+                delimiter: (*delimiter).into(),
+            },
+            Paragraph::UnorderedList { entries } => JsonParagraph::UnorderedList {
+                entries: entries_to_json(entries),
+            },
+            Paragraph::Checklist { items } => JsonParagraph::Checklist {
+                items: items.iter().map(JsonChecklistItem::from).collect(),
+            },
+            Paragraph::Quote { children } => JsonParagraph::Quote {
+                children: children.iter().map(JsonParagraph::from).collect(),
+            },
+            Paragraph::Table {
+                rows,
+                caption,
+                alignments,
+            } => JsonParagraph::Table {
+                rows: rows.iter().map(JsonTableRow::from).collect(),
+                caption: spans_to_json(caption),
+                alignments: alignments.iter().map(|&a| a.into()).collect(),
+            },
+            Paragraph::Details { summary, children } => JsonParagraph::Details {
+                summary: spans_to_json(summary),
+                children: children.iter().map(JsonParagraph::from).collect(),
+            },
+            Paragraph::Admonition { kind, children } => JsonParagraph::Admonition {
+                kind: (*kind).into(),
+                children: children.iter().map(JsonParagraph::from).collect(),
+            },
+            Paragraph::DefinitionList { items } => JsonParagraph::DefinitionList {
+                items: items.iter().map(JsonDefinitionItem::from).collect(),
+            },
+            Paragraph::HorizontalRule => JsonParagraph::HorizontalRule,
+            Paragraph::Raw { format, content } => JsonParagraph::Raw {
+                format: format.clone(),
+                content: content.clone(),
+            },
+        }
+    }
+}
+
+impl From<JsonParagraph> for Paragraph {
+    fn from(paragraph: JsonParagraph) -> Self {
+        match paragraph {
+            JsonParagraph::Text { content, lead } => Paragraph::Text {
+                content: spans_from_json(content),
+                lead,
+            },
+            JsonParagraph::Header1 { content, anchor } => Paragraph::Header1 {
+                content: spans_from_json(content),
+                anchor,
+            },
+            JsonParagraph::Header2 { content, anchor } => Paragraph::Header2 {
+                content: spans_from_json(content),
+                anchor,
+            },
+            JsonParagraph::Header3 { content, anchor } => Paragraph::Header3 {
+                content: spans_from_json(content),
+                anchor,
+            },
+            JsonParagraph::CodeBlock { content, language } => Paragraph::CodeBlock {
+                content: spans_from_json(content),
+                language,
+            },
+            JsonParagraph::OrderedList { entries, delimiter } => Paragraph::OrderedList {
+                entries: entries_from_json(entries),
+                delimiter: delimiter.into(),
+            },
+            JsonParagraph::UnorderedList { entries } => Paragraph::UnorderedList {
+                entries: entries_from_json(entries),
+            },
+            JsonParagraph::Checklist { items } => Paragraph::Checklist {
+                items: items.into_iter().map(ChecklistItem::from).collect(),
+            },
+            JsonParagraph::Quote { children } => Paragraph::Quote {
+                children: children.into_iter().map(Paragraph::from).collect(),
+            },
+            JsonParagraph::Table {
+                rows,
+                caption,
+                alignments,
+            } => Paragraph::Table {
+                rows: rows.into_iter().map(TableRow::from).collect(),
+                caption: spans_from_json(caption),
+                alignments: alignments.into_iter().map(Alignment::from).collect(),
+            },
+            JsonParagraph::Details { summary, children } => Paragraph::Details {
+                summary: spans_from_json(summary),
+                children: children.into_iter().map(Paragraph::from).collect(),
+            },
+            JsonParagraph::Admonition { kind, children } => Paragraph::Admonition {
+                kind: kind.into(),
+                children: children.into_iter().map(Paragraph::from).collect(),
+            },
+            JsonParagraph::DefinitionList { items } => Paragraph::DefinitionList {
+                items: items.into_iter().map(DefinitionItem::from).collect(),
+            },
+            JsonParagraph::HorizontalRule => Paragraph::HorizontalRule,
+            JsonParagraph::Raw { format, content } => Paragraph::Raw { format, content },
+        }
+    }
+}
+
+impl From<&ChecklistItem> for JsonChecklistItem {
+    fn from(item: &ChecklistItem) -> Self {
+        Self {
+            checked: item.checked,
+            content: spans_to_json(&item.content),
+            children: item.children.iter().map(JsonChecklistItem::from).collect(),
+        }
+    }
+}
+
+impl From<JsonChecklistItem> for ChecklistItem {
+    fn from(item: JsonChecklistItem) -> Self {
+        ChecklistItem::new(item.checked)
+            .with_content(spans_from_json(item.content))
+            .with_children(item.children.into_iter().map(ChecklistItem::from).collect())
+    }
+}
+
+impl From<&TableRow> for JsonTableRow {
+    fn from(row: &TableRow) -> Self {
+        Self {
+            cells: row
+                .cells
+                .iter()
+                .map(|cell| JsonTableCell {
+                    header: cell.is_header,
+                    content: spans_to_json(&cell.content),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<JsonTableRow> for TableRow {
+    fn from(row: JsonTableRow) -> Self {
+        TableRow::new().with_cells(
+            row.cells
+                .into_iter()
+                .map(|cell| TableCell::new(cell.header).with_content(spans_from_json(cell.content)))
+                .collect(),
+        )
+    }
+}
+
+impl From<&DefinitionItem> for JsonDefinitionItem {
+    fn from(item: &DefinitionItem) -> Self {
+        Self {
+            term: spans_to_json(&item.term),
+            definition: item.definition.iter().map(JsonParagraph::from).collect(),
+        }
+    }
+}
+
+impl From<JsonDefinitionItem> for DefinitionItem {
+    fn from(item: JsonDefinitionItem) -> Self {
+        DefinitionItem::new()
+            .with_term(spans_from_json(item.term))
+            .with_definition(item.definition.into_iter().map(Paragraph::from).collect())
+    }
+}
+
+impl From<&Span> for JsonSpan {
+    fn from(span: &Span) -> Self {
+        Self {
+            style: span.style.into(),
+            text: span.text.clone(),
+            target: span.link_target.clone(),
+            format: span.raw_format.clone(),
+            color: span.color.map(|color| color.to_string()),
+            children: spans_to_json(&span.children),
+        }
+    }
+}
+
+impl From<JsonSpan> for Span {
+    fn from(span: JsonSpan) -> Self {
+        Span {
+            style: span.style.into(),
+            text: span.text,
+            link_target: span.target,
+            raw_format: span.format,
+            color: span.color.as_deref().and_then(Color::parse),
+            children: spans_from_json(span.children),
+        }
+    }
+}
+
+impl From<InlineStyle> for JsonStyle {
+    fn from(style: InlineStyle) -> Self {
+        match style {
+            InlineStyle::None => JsonStyle::None,
+            InlineStyle::Bold => JsonStyle::Bold,
+            InlineStyle::Italic => JsonStyle::Italic,
+            InlineStyle::Highlight => JsonStyle::Highlight,
+            InlineStyle::Underline => JsonStyle::Underline,
+            InlineStyle::Strike => JsonStyle::Strike,
+            InlineStyle::Link => JsonStyle::Link,
+            InlineStyle::Code => JsonStyle::Code,
+            InlineStyle::Superscript => JsonStyle::Superscript,
+            InlineStyle::Subscript => JsonStyle::Subscript,
+            InlineStyle::Keyboard => JsonStyle::Keyboard,
+            InlineStyle::Abbreviation => JsonStyle::Abbreviation,
+            InlineStyle::Quotation => JsonStyle::Quotation,
+            InlineStyle::Raw => JsonStyle::Raw,
+            InlineStyle::Color => JsonStyle::Color,
+        }
+    }
+}
+
+impl From<JsonStyle> for InlineStyle {
+    fn from(style: JsonStyle) -> Self {
+        match style {
+            JsonStyle::None => InlineStyle::None,
+            JsonStyle::Bold => InlineStyle::Bold,
+            JsonStyle::Italic => InlineStyle::Italic,
+            JsonStyle::Highlight => InlineStyle::Highlight,
+            JsonStyle::Underline => InlineStyle::Underline,
+            JsonStyle::Strike => InlineStyle::Strike,
+            JsonStyle::Link => InlineStyle::Link,
+            JsonStyle::Code => InlineStyle::Code,
+            JsonStyle::Superscript => InlineStyle::Superscript,
+            JsonStyle::Subscript => InlineStyle::Subscript,
+            JsonStyle::Keyboard => InlineStyle::Keyboard,
+            JsonStyle::Abbreviation => InlineStyle::Abbreviation,
+            JsonStyle::Quotation => InlineStyle::Quotation,
+            JsonStyle::Raw => InlineStyle::Raw,
+            JsonStyle::Color => InlineStyle::Color,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Value;
+    use crate::test_helpers::*;
+    use crate::{doc, ChecklistItem};
+    use std::io::Cursor;
+
+    fn roundtrip(document: &Document) -> Document {
+        let mut output = Vec::new();
+        write(&mut output, document).unwrap();
+        parse(Cursor::new(output)).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_every_paragraph_type() {
+        let mut document = doc! {
+            h1 { "Title" }
+            h2 { "Subtitle" }
+            h3 { "Section" }
+            p { "Plain " b { "bold" } " " i { "italic" } " " u { "under" } }
+            p { mark { "marked" } " " del { "gone" } " " code { "x = 1" } }
+            p { link { "https://example.com" "Example" } " " link { "https://bare.test" } }
+            code { "fn main() {}\n" }
+            quote { p { "Quoted" } quote { p { "Nested" } } }
+            ul { li { p { "One" } } li { p { "Two" } ol { li { p { "Inner" } } } } }
+            ol { li { p { "First" } } }
+            table {
+                row { th { "Key" } th { "Value" } }
+                row { td { "a" } td { b { "1" } } }
+            }
+            hr {}
+        };
+        document
+            .paragraphs
+            .push(
+                Paragraph::new_checklist().with_checklist_items(vec![ChecklistItem::new(true)
+                    .with_content(spans("Done"))
+                    .with_children(vec![
+                        ChecklistItem::new(false).with_content(spans("Pending"))
+                    ])]),
+            );
+        document.paragraphs.push(
+            Paragraph::new_details()
+                .with_summary(spans("More"))
+                .with_children(vec![Paragraph::new_text().with_content(spans("Hidden"))]),
+        );
+        document
+            .paragraphs
+            .push(Paragraph::new_definition_list().with_definition_items(
+                vec![DefinitionItem::new()
+                .with_term(spans("Term"))
+                .with_definition(vec![
+                    Paragraph::new_text().with_content(spans("Meaning"))
+                ])],
+            ));
+        document.paragraphs.push(
+            ol_(vec![li_(vec![p__("Parenthesized")])])
+                .with_list_delimiter(ListDelimiter::Parenthesis),
+        );
+        document.paragraphs.extend([
+            p__("Lead").with_lead(true),
+            h2_("Anchored").with_anchor("anchored"),
+            code_block__("fn main() {}").with_language("rust"),
+            Paragraph::new_table()
+                .with_rows(vec![TableRow::new().with_cells(vec![
+                    TableCell::new_data().with_content(spans("Left")),
+                    TableCell::new_data().with_content(spans("Center")),
+                    TableCell::new_data().with_content(spans("Right")),
+                ])])
+                .with_caption(spans("Caption"))
+                .with_alignments(vec![Alignment::Left, Alignment::Center, Alignment::Right]),
+            Paragraph::new_admonition(AdmonitionKind::Tip).with_children(vec![p__("Hint")]),
+            Paragraph::new_raw("html", "<video src=\"clip.mp4\"></video>"),
+        ]);
+        // Every inline style, along with each paragraph type once more.
+        document.paragraphs.extend(every_type_document().paragraphs);
+        let mut meta = Metadata::new();
+        meta.insert("title".to_string(), Value::from("Notes"));
+        meta.insert("draft".to_string(), Value::from(true));
+        let document = document.with_metadata(meta);
+
+        assert_eq!(roundtrip(&document), document);
+    }
+
+    #[test]
+    fn test_roundtrip_deeply_nested_document() {
+        let mut quote = p__("Deep");
+        let mut definitions = p__("Deep");
+        for _ in 1..DEFAULT_MAX_DEPTH {
+            quote = quote_(vec![quote]);
+            definitions =
+                Paragraph::new_definition_list().with_definition_items(vec![DefinitionItem::new()
+                    .with_term(spans("Term"))
+                    .with_definition(vec![definitions])]);
+        }
+        // Nested spans take up nearly all of the JSON nesting allowed.
+        let mut span = span("Deep");
+        for _ in 0..390 {
+            span = b_(vec![span]);
+        }
+        let document = doc(vec![quote.clone(), definitions, p_(vec![span])]);
+        assert_eq!(roundtrip(&document), document);
+
+        let mut output = Vec::new();
+        write(&mut output, &doc(vec![quote_(vec![quote])])).unwrap();
+        let error = parse(Cursor::new(output)).unwrap_err();
+        assert!(error.to_string().contains("nested more than"), "{error}");
+
+        let input = "[".repeat(MAX_JSON_NESTING + 1);
+        assert!(parse(Cursor::new(input)).is_err());
+    }
+
+    #[test]
+    fn test_parse_at_nesting_limit_on_default_stack() {
+        // Paragraphs are internally tagged and buffered at every level, so
+        // the costliest input is paragraphs nested as deep as JSON allows.
+        let quotes = (MAX_JSON_NESTING - 2) / 2;
+        let input = format!(
+            r#"{{"version":1,"paragraphs":[{}{}]}}"#,
+            r#"{"type":"quote","children":["#.repeat(quotes),
+            "]}".repeat(quotes)
+        );
+        assert_eq!(json_nesting(input.as_bytes()), MAX_JSON_NESTING);
+
+        let result = std::thread::spawn(move || parse(Cursor::new(input)).map(|_| ()))
+            .join()
+            .expect("parsing overflowed the stack");
+        let error = result.unwrap_err();
+        assert!(
+            error.to_string().contains("paragraphs nested more than"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_write_schema_shape() {
+        let document = doc(vec![p_(vec![link_text__("https://example.com", "Ex")])]);
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+
+        let value: serde_json::Value = serde_json::from_slice(&output).unwrap();
+        assert_eq!(value["version"], 1);
+        assert!(value.get("metadata").is_none());
+        let span = &value["paragraphs"][0]["content"][0];
+        assert_eq!(value["paragraphs"][0]["type"], "text");
+        assert_eq!(span["style"], "link");
+        assert_eq!(span["target"], "https://example.com");
+        assert_eq!(span["children"][0]["text"], "Ex");
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_version() {
+        let result = parse(Cursor::new(r#"{"version": 2, "paragraphs": []}"#));
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,486 @@
+//! Export [`Document`](crate::Document) trees as LaTeX.
+//!
+//! The writer emits a document body fragment — no preamble, `\documentclass`,
+//! or `\begin{document}` — so the output can be `\input` into a larger file.
+//! Links rely on the `hyperref` package, strikethrough on `ulem`, highlights
+//! on `soul`, and colored text on `xcolor`.
+
+use crate::{
+    Alignment, ChecklistItem, Color, DefinitionItem, Document, InlineStyle, Paragraph, Span,
+    TableRow,
+};
+use std::io::Write;
+
+/// Serializes a [`Document`] structure to LaTeX.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::{Document, InlineStyle, Paragraph, Span};
+/// use tdoc::latex;
+///
+/// let paragraph = Paragraph::new_text().with_content(vec![
+///     Span::new_text("100% "),
+///     Span::new_styled(InlineStyle::Bold).with_children(vec![Span::new_text("done")]),
+/// ]);
+/// let document = Document::new().with_paragraphs(vec![paragraph]);
+///
+/// let mut output = Vec::new();
+/// latex::write(&mut output, &document).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "100\\% \\textbf{done}\n");
+/// ```
+pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<()> {
+    write_paragraphs(writer, &document.paragraphs)
+}
+
+fn write_paragraphs<W: Write>(writer: &mut W, paragraphs: &[Paragraph]) -> std::io::Result<()> {
+    let visible = paragraphs.iter().filter(|p| p.is_emitted_in("latex"));
+    for (i, paragraph) in visible.enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        write_paragraph(writer, paragraph)?;
+    }
+    Ok(())
+}
+
+fn write_paragraph<W: Write>(writer: &mut W, paragraph: &Paragraph) -> std::io::Result<()> {
+    match paragraph {
+        Paragraph::Text { content, .. } => {
+            write_spans(writer, content)?;
+            writeln!(writer)?;
+        }
+        Paragraph::Header1 { content, .. } => write_heading(writer, "section", content)?,
+        Paragraph::Header2 { content, .. } => write_heading(writer, "subsection", content)?,
+        Paragraph::Header3 { content, .. } => write_heading(writer, "subsubsection", content)?,
+        Paragraph::CodeBlock { content, .. } => {
+            let mut text = String::new();
+            for span in content {
+                collect_plain_text(span, &mut text);
+            }
+            if text.contains("\\end{verbatim}") {
+                // The terminator would end `verbatim` early, so such code is
+                // typeset escaped, keeping its spaces and line breaks.
+                let escaped = escape_latex(text.trim_end_matches('\n')).replace(' ', "~");
+                writeln!(writer, "\\begin{{flushleft}}")?;
+                writeln!(writer, "\\texttt{{{}}}", escaped)?;
+                writeln!(writer, "\\end{{flushleft}}")?;
+            } else {
+                writeln!(writer, "\\begin{{verbatim}}")?;
+                if !text.is_empty() {
+                    write!(writer, "{}", text)?;
+                    if !text.ends_with('\n') {
+                        writeln!(writer)?;
+                    }
+                }
+                writeln!(writer, "\\end{{verbatim}}")?;
+            }
+        }
+        Paragraph::Quote { children } => {
+            writeln!(writer, "\\begin{{quote}}")?;
+            write_paragraphs(writer, children)?;
+            writeln!(writer, "\\end{{quote}}")?;
+        }
+        Paragraph::Admonition { kind, children } => {
+            write_paragraph(writer, &Paragraph::admonition_as_quote(*kind, children))?
+        }
+        Paragraph::UnorderedList { entries } => write_list(writer, "itemize", entries)?,
+        Paragraph::OrderedList { entries, .. } => write_list(writer, "enumerate", entries)?,
+        Paragraph::Checklist { items } => write_checklist(writer, items)?,
+        Paragraph::Table { rows, .. } => write_table(writer, paragraph, rows)?,
+        Paragraph::DefinitionList { items } => write_description(writer, items)?,
+        Paragraph::Details { summary, children } => {
+            // Print has no collapsible sections; use a run-in heading.
+            if !summary.is_empty() {
+                write!(writer, "\\paragraph{{")?;
+                write_spans(writer, summary)?;
+                writeln!(writer, "}}")?;
+            }
+            write_paragraphs(writer, children)?;
+        }
+        Paragraph::HorizontalRule => {
+            writeln!(writer, "\\noindent\\rule{{\\linewidth}}{{0.4pt}}")?;
+        }
+        Paragraph::Raw { content, .. } => {
+            write!(writer, "{}", content)?;
+            if !content.ends_with('\n') {
+                writeln!(writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_heading<W: Write>(writer: &mut W, command: &str, content: &[Span]) -> std::io::Result<()> {
+    write!(writer, "\\{}{{", command)?;
+    write_spans(writer, content)?;
+    writeln!(writer, "}}")
+}
+
+fn write_list<W: Write>(
+    writer: &mut W,
+    environment: &str,
+    entries: &[Vec<Paragraph>],
+) -> std::io::Result<()> {
+    // An empty list environment is a LaTeX error ("missing \item").
+    if entries.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "\\begin{{{}}}", environment)?;
+    for entry in entries {
+        write!(writer, "\\item ")?;
+        if entry.is_empty() {
+            writeln!(writer)?;
+        } else {
+            write_paragraphs(writer, entry)?;
+        }
+    }
+    writeln!(writer, "\\end{{{}}}", environment)
+}
+
+fn write_description<W: Write>(writer: &mut W, items: &[DefinitionItem]) -> std::io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "\\begin{{description}}")?;
+    for item in items {
+        // Braces keep a `]` in the term from ending the optional argument.
+        write!(writer, "\\item[{{")?;
+        write_spans(writer, &item.term)?;
+        write!(writer, "}}] ")?;
+        if item.definition.is_empty() {
+            writeln!(writer)?;
+        } else {
+            write_paragraphs(writer, &item.definition)?;
+        }
+    }
+    writeln!(writer, "\\end{{description}}")
+}
+
+fn write_checklist<W: Write>(writer: &mut W, items: &[ChecklistItem]) -> std::io::Result<()> {
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(writer, "\\begin{{itemize}}")?;
+    for item in items {
+        let marker = if item.checked { "[x]" } else { "[ ]" };
+        write!(writer, "\\item[{{{}}}] ", marker)?;
+        write_spans(writer, &item.content)?;
+        writeln!(writer)?;
+        write_checklist(writer, &item.children)?;
+    }
+    writeln!(writer, "\\end{{itemize}}")
+}
+
+fn write_table<W: Write>(
+    writer: &mut W,
+    table: &Paragraph,
+    rows: &[TableRow],
+) -> std::io::Result<()> {
+    let column_count = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    if column_count == 0 {
+        return Ok(());
+    }
+
+    // A caption needs a float to attach to; uncaptioned tables stay inline.
+    let caption = table.caption();
+    if !caption.is_empty() {
+        writeln!(writer, "\\begin{{table}}[h]")?;
+        write!(writer, "\\caption{{")?;
+        write_spans(writer, caption)?;
+        writeln!(writer, "}}")?;
+    }
+
+    let columns: String = (0..column_count)
+        .map(|col| match table.column_alignment(col) {
+            Alignment::Left => 'l',
+            Alignment::Center => 'c',
+            Alignment::Right => 'r',
+        })
+        .collect();
+    writeln!(writer, "\\begin{{tabular}}{{{}}}", columns)?;
+    for (idx, row) in rows.iter().enumerate() {
+        for col in 0..column_count {
+            if col > 0 {
+                write!(writer, " & ")?;
+            }
+            let Some(cell) = row.cells.get(col) else {
+                continue;
+            };
+            if cell.is_header {
+                write!(writer, "\\textbf{{")?;
+                write_spans(writer, &cell.content)?;
+                write!(writer, "}}")?;
+            } else {
+                write_spans(writer, &cell.content)?;
+            }
+        }
+        writeln!(writer, " \\\\")?;
+
+        let is_header_row = !row.cells.is_empty() && row.cells.iter().all(|cell| cell.is_header);
+        if idx == 0 && is_header_row {
+            writeln!(writer, "\\hline")?;
+        }
+    }
+    writeln!(writer, "\\end{{tabular}}")?;
+    if !caption.is_empty() {
+        writeln!(writer, "\\end{{table}}")?;
+    }
+    Ok(())
+}
+
+fn write_spans<W: Write>(writer: &mut W, spans: &[Span]) -> std::io::Result<()> {
+    for span in spans {
+        write_span(writer, span)?;
+    }
+    Ok(())
+}
+
+fn write_span<W: Write>(writer: &mut W, span: &Span) -> std::io::Result<()> {
+    match span.style {
+        InlineStyle::None | InlineStyle::Abbreviation => write_span_content(writer, span),
+        InlineStyle::Raw => write!(
+            writer,
+            "{}",
+            span.raw_content_for("latex").unwrap_or_default()
+        ),
+        InlineStyle::Color => match span.color {
+            Some(color) if span.has_content() => {
+                write!(writer, "\\textcolor{}{{", color_argument(color))?;
+                write_span_content(writer, span)?;
+                write!(writer, "}}")
+            }
+            _ => write_span_content(writer, span),
+        },
+        InlineStyle::Link => match &span.link_target {
+            Some(target) if span.has_content() => {
+                write!(writer, "\\href{{{}}}{{", escape_url(target))?;
+                write_span_content(writer, span)?;
+                write!(writer, "}}")
+            }
+            Some(target) => write!(writer, "\\url{{{}}}", escape_url(target)),
+            None => write_span_content(writer, span),
+        },
+        InlineStyle::Code | InlineStyle::Keyboard => {
+            let mut text = String::new();
+            collect_plain_text(span, &mut text);
+            write!(writer, "\\texttt{{{}}}", escape_latex(&text))
+        }
+        InlineStyle::Quotation => {
+            write!(writer, "``")?;
+            write_span_content(writer, span)?;
+            write!(writer, "''")
+        }
+        style => {
+            if !span.has_content() {
+                return Ok(());
+            }
+            write!(writer, "\\{}{{", inline_command(style))?;
+            write_span_content(writer, span)?;
+            write!(writer, "}}")
+        }
+    }
+}
+
+fn write_span_content<W: Write>(writer: &mut W, span: &Span) -> std::io::Result<()> {
+    if !span.text.is_empty() {
+        write!(writer, "{}", escape_latex(&span.text))?;
+    }
+    write_spans(writer, &span.children)
+}
+
+fn inline_command(style: InlineStyle) -> &'static str {
+    match style {
+        InlineStyle::Bold => "textbf",
+        InlineStyle::Italic => "emph",
+        InlineStyle::Underline => "underline",
+        InlineStyle::Strike => "sout",
+        InlineStyle::Highlight => "hl",
+        InlineStyle::Superscript => "textsuperscript",
+        InlineStyle::Subscript => "textsubscript",
+        InlineStyle::None
+        | InlineStyle::Link
+        | InlineStyle::Code
+        | InlineStyle::Keyboard
+        | InlineStyle::Abbreviation
+        | InlineStyle::Quotation
+        | InlineStyle::Raw
+        | InlineStyle::Color => "",
+    }
+}
+
+/// Returns the `\textcolor` color argument(s) selecting `color`.
+fn color_argument(color: Color) -> String {
+    match color {
+        Color::Named(index) => {
+            let names = [
+                "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+            ];
+            format!("{{{}}}", names[usize::from(index.min(7))])
+        }
+        Color::Rgb(r, g, b) => format!("[RGB]{{{},{},{}}}", r, g, b),
+    }
+}
+
+/// Escapes LaTeX's special characters so `text` is typeset literally.
+///
+/// Hard line breaks (`\n`) become `\\` forced line breaks.
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '%' | '&' | '_' | '#' | '$' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\n' => escaped.push_str("\\\\\n"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Escapes the characters `hyperref` cannot take verbatim inside a URL argument.
+fn escape_url(url: &str) -> String {
+    let mut escaped = String::with_capacity(url.len());
+    for ch in url.chars() {
+        if matches!(ch, '%' | '#' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn collect_plain_text(span: &Span, buffer: &mut String) {
+    if span.style == InlineStyle::Raw {
+        return;
+    }
+    if !span.text.is_empty() {
+        buffer.push_str(&span.text);
+    }
+    for child in &span.children {
+        collect_plain_text(child, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::*;
+    use crate::TableCell;
+
+    fn render(document: &Document) -> String {
+        let mut output = Vec::new();
+        write(&mut output, document).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_bold_and_escaped_percent() {
+        let doc = doc(vec![p_(vec![span("Only 5% are "), b__("bold"), span(".")])]);
+        assert_eq!(render(&doc), "Only 5\\% are \\textbf{bold}.\n");
+    }
+
+    #[test]
+    fn test_write_escapes_special_characters() {
+        let doc = doc(vec![p__("a & b_c #1 $2 {x} \\ ~ ^")]);
+        assert_eq!(
+            render(&doc),
+            "a \\& b\\_c \\#1 \\$2 \\{x\\} \\textbackslash{} \\textasciitilde{} \\textasciicircum{}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_headings() {
+        let doc = doc(vec![h1_("One"), h2_("Two"), h3_("Three")]);
+        assert_eq!(
+            render(&doc),
+            "\\section{One}\n\n\\subsection{Two}\n\n\\subsubsection{Three}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_lists_and_quote() {
+        let doc = doc(vec![
+            ul_(vec![
+                li_(vec![p__("First")]),
+                li_(vec![p_(vec![i__("Second")])]),
+            ]),
+            ol_(vec![li_(vec![p__("Only")])]),
+            quote_(vec![p__("Quoted")]),
+        ]);
+        assert_eq!(
+            render(&doc),
+            "\\begin{itemize}\n\\item First\n\\item \\emph{Second}\n\\end{itemize}\n\n\
+             \\begin{enumerate}\n\\item Only\n\\end{enumerate}\n\n\
+             \\begin{quote}\nQuoted\n\\end{quote}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_table_with_caption_and_alignment() {
+        let table = Paragraph::new_table()
+            .with_rows(vec![
+                TableRow::new().with_cells(vec![
+                    TableCell::new_header().with_content(vec![span("Item")]),
+                    TableCell::new_header().with_content(vec![span("Price")]),
+                ]),
+                TableRow::new().with_cells(vec![
+                    TableCell::new_data().with_content(vec![span("Tea")]),
+                    TableCell::new_data().with_content(vec![span("3.50")]),
+                ]),
+            ])
+            .with_caption(vec![span("Prices")])
+            .with_alignments(vec![Alignment::Left, Alignment::Right]);
+        assert_eq!(
+            render(&doc(vec![table])),
+            "\\begin{table}[h]\n\\caption{Prices}\n\\begin{tabular}{lr}\n\
+             \\textbf{Item} & \\textbf{Price} \\\\\n\\hline\n\
+             Tea & 3.50 \\\\\n\\end{tabular}\n\\end{table}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_code_block_is_verbatim() {
+        let doc = doc(vec![code_block__("let x = 50% & {y};")]);
+        assert_eq!(
+            render(&doc),
+            "\\begin{verbatim}\nlet x = 50% & {y};\n\\end{verbatim}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_code_block_containing_verbatim_terminator() {
+        let doc = doc(vec![code_block__(
+            "\\begin{verbatim}\n  x_1\n\\end{verbatim}",
+        )]);
+        assert_eq!(
+            render(&doc),
+            "\\begin{flushleft}\n\
+             \\texttt{\\textbackslash{}begin\\{verbatim\\}\\\\\n\
+             ~~x\\_1\\\\\n\
+             \\textbackslash{}end\\{verbatim\\}}\n\
+             \\end{flushleft}\n"
+        );
+    }
+
+    #[test]
+    fn test_write_links() {
+        let doc = doc(vec![p_(vec![
+            link_text__("https://example.com/a%20b#top", "Example"),
+            span(" "),
+            link__("https://example.com"),
+        ])]);
+        assert_eq!(
+            render(&doc),
+            "\\href{https://example.com/a\\%20b\\#top}{Example} \\url{https://example.com}\n"
+        );
+    }
+}
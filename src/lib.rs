@@ -5,9 +5,15 @@
 //! - [`Document`], [`Paragraph`], and [`Span`], which form an in-memory tree
 //!   representation of document content.
 //! - Format modules (see [`ftml`], [`html`], [`markdown`], and [`gemini`]) that
-//!   provide both parsers and writers for each external format.
+//!   provide both parsers and writers for each external format, plus
+//!   export-only writers such as [`latex`], [`org`], and [`rst`]. The [`json`]
+//!   module offers a lossless interchange format for external tooling.
 //! - A [`formatter`] for rendering the tree to richly styled terminal output.
 //!
+//! The [`links`] module checks where a document's links lead, for building
+//! link checkers. Input of unknown format can be handed to [`parse_auto`],
+//! which guesses the format and picks the matching parser.
+//!
 //! Checklists (Markdown `- [ ]` entries or HTML `<input type="checkbox">`
 //! lists) map to [`ParagraphType::Checklist`] nodes that store [`ChecklistItem`]
 //! children. Nested checklist items are preserved end-to-end so complex task
@@ -18,24 +24,35 @@
 //! tree, and finally render it with [`ftml::Writer`], [`html::Writer`], or
 //! [`formatter::Formatter`].
 
+mod line_endings;
 mod macros;
 
 pub mod document;
+pub mod format;
 pub mod formatter;
 pub mod ftml;
 pub mod gemini;
 pub mod html;
 pub mod inline;
+pub mod json;
+pub mod latex;
+pub mod links;
 pub mod markdown;
 pub mod metadata;
+pub mod org;
 pub mod pager;
 pub mod paragraph;
+pub mod rst;
 pub mod test_helpers;
 
-pub use document::Document;
-pub use inline::{InlineStyle, Span};
+pub use document::{Document, TocEntry};
+pub use format::{parse_auto, Format};
+pub use inline::{Color, InlineStyle, Span};
 pub use pager::*;
-pub use paragraph::{ChecklistItem, Paragraph, ParagraphType, TableCell, TableRow};
+pub use paragraph::{
+    AdmonitionKind, Alignment, ChecklistItem, ChecklistState, DefinitionItem, ListDelimiter,
+    Paragraph, ParagraphType, TableCell, TableRow, DEFAULT_MAX_DEPTH,
+};
 
 /// Convenience result type used across parsing and writing APIs.
 pub type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
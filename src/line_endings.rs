@@ -0,0 +1,95 @@
+//! Normalizes Windows (`\r\n`) and classic Mac (`\r`) line endings to `\n`
+//! where input enters the parsers, so all of them see the same text.
+
+use std::borrow::Cow;
+use std::io::{self, Read};
+
+/// Returns `text` with every `\r\n` and lone `\r` replaced by `\n`.
+pub(crate) fn normalize(text: &str) -> Cow<'_, str> {
+    if text.contains('\r') {
+        Cow::Owned(text.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// A reader yielding the bytes of `inner` with line endings normalized like
+/// [`normalize`], also when a `\r\n` pair is split between two reads.
+pub(crate) struct NormalizedReader<R> {
+    inner: R,
+    after_cr: bool,
+}
+
+impl<R: Read> NormalizedReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            after_cr: false,
+        }
+    }
+}
+
+impl<R: Read> Read for NormalizedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let read = self.inner.read(buf)?;
+            if read == 0 {
+                return Ok(0);
+            }
+
+            // Both bytes are ASCII, so rewriting them in place never splits a
+            // multi-byte character.
+            let mut written = 0;
+            for idx in 0..read {
+                let byte = buf[idx];
+                let after_cr = std::mem::replace(&mut self.after_cr, byte == b'\r');
+                if byte == b'\n' && after_cr {
+                    continue;
+                }
+                buf[written] = if byte == b'\r' { b'\n' } else { byte };
+                written += 1;
+            }
+            // A chunk holding only the `\n` of a split pair is empty now, but
+            // returning 0 would signal the end of the input.
+            if written > 0 {
+                return Ok(written);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads one byte at a time, so every `\r\n` pair is split.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                Some((byte, rest)) if !buf.is_empty() => {
+                    buf[0] = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("a\r\nb\rc\n\r\n"), "a\nb\nc\n\n");
+        assert!(matches!(normalize("a\nb"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_reader_joins_split_pairs() {
+        let mut output = String::new();
+        NormalizedReader::new(ByteReader("a\r\nb\r\r\nü\r".as_bytes()))
+            .read_to_string(&mut output)
+            .unwrap();
+        assert_eq!(output, "a\nb\n\nü\n");
+    }
+}
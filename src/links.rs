@@ -0,0 +1,257 @@
+//! Checks where the links of a [`Document`] lead without following them.
+//!
+//! This is the resolution logic the `tdoc` pager uses to decide which links
+//! it can open, exposed for building link checkers.
+
+use crate::{ChecklistItem, Document, InlineStyle, Paragraph, Span};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// URL schemes tdoc can load documents from.
+const NAVIGABLE_SCHEMES: &[&str] = &["http", "https", "gemini"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Where a document was loaded from. Relative link targets resolve against it.
+pub enum LinkBase {
+    /// A remote document, such as `https://example.com/docs/index.html`.
+    Url(Url),
+    /// A local file. Relative targets resolve against its directory.
+    File(PathBuf),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The outcome of checking a single link, as returned by [`validate_links`].
+pub struct LinkStatus {
+    /// The link target as written in the document.
+    pub target: String,
+    /// Whether the target resolves: an existing heading for `#fragment`
+    /// links, an existing file for local paths, and a URL with a supported
+    /// scheme (accepted by the remote checker, if any) otherwise.
+    pub resolves: bool,
+}
+
+/// Resolves `target` against the URL of the current document, returning the
+/// absolute URL if it uses a scheme tdoc can load.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::links::resolve_url;
+/// use url::Url;
+///
+/// let base = Url::parse("https://example.com/docs/index.html").unwrap();
+/// let resolved = resolve_url(&base, "guide.html").unwrap();
+/// assert_eq!(resolved.as_str(), "https://example.com/docs/guide.html");
+/// assert!(resolve_url(&base, "ftp://example.com/file").is_none());
+/// ```
+pub fn resolve_url(base: &Url, target: &str) -> Option<Url> {
+    let trimmed = target.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Url::options()
+        .base_url(Some(base))
+        .parse(trimmed)
+        .ok()
+        .filter(|url| NAVIGABLE_SCHEMES.contains(&url.scheme()))
+}
+
+/// Resolves `target` against the directory of the local file `base`,
+/// returning the canonical path if it names an existing file.
+pub fn resolve_file(base: &Path, target: &str) -> Option<PathBuf> {
+    let trimmed = target.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    let candidate = if Path::new(trimmed).is_absolute() {
+        PathBuf::from(trimmed)
+    } else {
+        base.parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(trimmed)
+    };
+    std::fs::canonicalize(candidate)
+        .ok()
+        .filter(|resolved| resolved.is_file())
+}
+
+/// Lists every link target in `document` together with whether it resolves
+/// relative to `base`. Remote URLs are only checked for a supported scheme;
+/// use [`validate_links_with`] to actually look them up.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::links::{validate_links, LinkBase};
+/// use tdoc::Document;
+/// use url::Url;
+///
+/// let doc = Document::from_markdown("[a](next.html) and [b](ftp://host/x)").unwrap();
+/// let base = LinkBase::Url(Url::parse("https://example.com/").unwrap());
+/// let results = validate_links(&doc, &base);
+/// assert!(results[0].resolves);
+/// assert!(!results[1].resolves);
+/// ```
+pub fn validate_links(document: &Document, base: &LinkBase) -> Vec<LinkStatus> {
+    validate_links_with(document, base, |_| true)
+}
+
+/// Like [`validate_links`], but asks `remote` whether each remote URL (after
+/// resolving it against `base`) is reachable.
+pub fn validate_links_with(
+    document: &Document,
+    base: &LinkBase,
+    remote: impl Fn(&Url) -> bool,
+) -> Vec<LinkStatus> {
+    let slugs: Vec<String> = document
+        .table_of_contents()
+        .into_iter()
+        .map(|entry| entry.slug)
+        .collect();
+
+    let mut targets = Vec::new();
+    collect_paragraph_links(&document.paragraphs, &mut targets);
+
+    targets
+        .into_iter()
+        .map(|target| {
+            let resolves = target_resolves(&target, base, &slugs, &remote);
+            LinkStatus { target, resolves }
+        })
+        .collect()
+}
+
+fn target_resolves(
+    target: &str,
+    base: &LinkBase,
+    slugs: &[String],
+    remote: &impl Fn(&Url) -> bool,
+) -> bool {
+    let trimmed = target.trim();
+    if let Some(fragment) = trimmed.strip_prefix('#') {
+        return slugs.iter().any(|slug| slug == fragment);
+    }
+
+    match base {
+        LinkBase::Url(base_url) => resolve_url(base_url, trimmed).is_some_and(|url| remote(&url)),
+        LinkBase::File(path) => match Url::parse(trimmed) {
+            Ok(url) => NAVIGABLE_SCHEMES.contains(&url.scheme()) && remote(&url),
+            Err(_) => {
+                let without_fragment = trimmed.split('#').next().unwrap_or_default();
+                resolve_file(path, without_fragment).is_some()
+            }
+        },
+    }
+}
+
+fn collect_paragraph_links(paragraphs: &[Paragraph], targets: &mut Vec<String>) {
+    for paragraph in paragraphs {
+        match paragraph {
+            Paragraph::Text { content, .. }
+            | Paragraph::Header1 { content, .. }
+            | Paragraph::Header2 { content, .. }
+            | Paragraph::Header3 { content, .. } => collect_span_links(content, targets),
+            Paragraph::Quote { children } | Paragraph::Admonition { children, .. } => {
+                collect_paragraph_links(children, targets)
+            }
+            Paragraph::Details { summary, children } => {
+                collect_span_links(summary, targets);
+                collect_paragraph_links(children, targets);
+            }
+            Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
+                for entry in entries {
+                    collect_paragraph_links(entry, targets);
+                }
+            }
+            Paragraph::Checklist { items } => collect_checklist_links(items, targets),
+            Paragraph::Table { rows, caption, .. } => {
+                collect_span_links(caption, targets);
+                for cell in rows.iter().flat_map(|row| &row.cells) {
+                    collect_span_links(&cell.content, targets);
+                }
+            }
+            Paragraph::DefinitionList { items } => {
+                for item in items {
+                    collect_span_links(&item.term, targets);
+                    collect_paragraph_links(&item.definition, targets);
+                }
+            }
+            Paragraph::CodeBlock { .. } | Paragraph::HorizontalRule | Paragraph::Raw { .. } => {}
+        }
+    }
+}
+
+fn collect_checklist_links(items: &[ChecklistItem], targets: &mut Vec<String>) {
+    for item in items {
+        collect_span_links(&item.content, targets);
+        collect_checklist_links(&item.children, targets);
+    }
+}
+
+fn collect_span_links(spans: &[Span], targets: &mut Vec<String>) {
+    for span in spans {
+        if span.style == InlineStyle::Link {
+            if let Some(target) = &span.link_target {
+                targets.push(target.clone());
+            }
+        }
+        collect_span_links(&span.children, targets);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::*;
+
+    #[test]
+    fn test_validate_links_against_files() {
+        let dir = std::env::temp_dir().join(format!("tdoc-links-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("present.md"), "# Present").unwrap();
+
+        let document = doc(vec![
+            h1_("Intro"),
+            p_(vec![
+                link_text__("present.md#section", "present"),
+                link_text__("missing.md", "missing"),
+                link_text__("#intro", "top"),
+                link_text__("#nowhere", "nowhere"),
+                link_text__("https://example.com", "web"),
+            ]),
+        ]);
+        let results = validate_links(&document, &LinkBase::File(dir.join("index.md")));
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let resolves: Vec<(&str, bool)> = results
+            .iter()
+            .map(|status| (status.target.as_str(), status.resolves))
+            .collect();
+        assert_eq!(
+            resolves,
+            vec![
+                ("present.md#section", true),
+                ("missing.md", false),
+                ("#intro", true),
+                ("#nowhere", false),
+                ("https://example.com", true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validate_links_with_remote_checker() {
+        let document = doc(vec![ul_(vec![li_(vec![p_(vec![
+            link_text__("up.html", "up"),
+            link_text__("https://down.example/", "down"),
+            link_text__("mailto:a@b.test", "mail"),
+        ])])])]);
+        let base = LinkBase::Url(Url::parse("https://site.example/docs/").unwrap());
+        let results = validate_links_with(&document, &base, |url| {
+            url.host_str() == Some("site.example")
+        });
+
+        let resolves: Vec<bool> = results.iter().map(|status| status.resolves).collect();
+        assert_eq!(resolves, vec![true, false, false]);
+    }
+}
@@ -244,6 +244,33 @@ macro_rules! __tdoc_build_inline {
         $crate::Span::new_styled($crate::InlineStyle::Code)
             .with_children(__tdoc_inline_nodes!($($inner)*))
     }};
+    (sup { $($inner:tt)* }) => {{
+        $crate::Span::new_styled($crate::InlineStyle::Superscript)
+            .with_children(__tdoc_inline_nodes!($($inner)*))
+    }};
+    (sub { $($inner:tt)* }) => {{
+        $crate::Span::new_styled($crate::InlineStyle::Subscript)
+            .with_children(__tdoc_inline_nodes!($($inner)*))
+    }};
+    (abbr { $title:tt $($rest:tt)* }) => {{
+        $crate::Span::new_styled($crate::InlineStyle::Abbreviation)
+            .with_link_target(__tdoc_link_target!($title))
+            .with_children(__tdoc_parse_link_children!($($rest)*))
+    }};
+    (color { $value:tt $($rest:tt)* }) => {{
+        $crate::Span::new_colored(
+            $crate::Color::parse(__tdoc_link_target!($value)).expect("unrecognized color"),
+        )
+            .with_children(__tdoc_parse_link_children!($($rest)*))
+    }};
+    (kbd { $($inner:tt)* }) => {{
+        $crate::Span::new_styled($crate::InlineStyle::Keyboard)
+            .with_children(__tdoc_inline_nodes!($($inner)*))
+    }};
+    (q { $($inner:tt)* }) => {{
+        $crate::Span::new_styled($crate::InlineStyle::Quotation)
+            .with_children(__tdoc_inline_nodes!($($inner)*))
+    }};
     ($other:ident { $($inner:tt)* }) => {{
         compile_error!(concat!("Unknown inline element: ", stringify!($other)));
     }};
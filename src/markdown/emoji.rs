@@ -0,0 +1,118 @@
+//! Expansion of `:shortcode:` emoji names as used on GitHub and Slack.
+
+use std::borrow::Cow;
+
+/// Known shortcodes and their emoji, sorted by name for binary search.
+static SHORTCODES: &[(&str, &str)] = &[
+    ("+1", "👍"),
+    ("-1", "👎"),
+    ("100", "💯"),
+    ("bug", "🐛"),
+    ("bulb", "💡"),
+    ("clap", "👏"),
+    ("construction", "🚧"),
+    ("cry", "😢"),
+    ("eyes", "👀"),
+    ("fire", "🔥"),
+    ("grin", "😁"),
+    ("grinning", "😀"),
+    ("heart", "❤️"),
+    ("heavy_check_mark", "✔️"),
+    ("hourglass", "⌛"),
+    ("information_source", "ℹ️"),
+    ("joy", "😂"),
+    ("laughing", "😆"),
+    ("lock", "🔒"),
+    ("memo", "📝"),
+    ("no_entry", "⛔"),
+    ("ok_hand", "👌"),
+    ("pencil", "📝"),
+    ("pray", "🙏"),
+    ("question", "❓"),
+    ("rocket", "🚀"),
+    ("sad", "😞"),
+    ("see_no_evil", "🙈"),
+    ("slightly_smiling_face", "🙂"),
+    ("smile", "😄"),
+    ("smiley", "😃"),
+    ("sparkles", "✨"),
+    ("star", "⭐"),
+    ("tada", "🎉"),
+    ("thinking", "🤔"),
+    ("thumbsdown", "👎"),
+    ("thumbsup", "👍"),
+    ("warning", "⚠️"),
+    ("wave", "👋"),
+    ("white_check_mark", "✅"),
+    ("wink", "😉"),
+    ("x", "❌"),
+    ("zap", "⚡"),
+];
+
+/// Returns the emoji for a shortcode name given without its colons.
+fn lookup(name: &str) -> Option<&'static str> {
+    SHORTCODES
+        .binary_search_by(|(candidate, _)| candidate.cmp(&name))
+        .ok()
+        .map(|idx| SHORTCODES[idx].1)
+}
+
+fn is_shortcode_char(ch: char) -> bool {
+    ch.is_ascii_lowercase() || ch.is_ascii_digit() || matches!(ch, '_' | '+' | '-')
+}
+
+/// Replaces every known `:name:` in `text` with its emoji. Unknown names are
+/// left as written.
+pub(super) fn expand_shortcodes(text: &str) -> Cow<'_, str> {
+    if !text.contains(':') {
+        return Cow::Borrowed(text);
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find(':') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+        let emoji = after.find(':').and_then(|end| {
+            let name = &after[..end];
+            if name.is_empty() || !name.chars().all(is_shortcode_char) {
+                return None;
+            }
+            lookup(name).map(|emoji| (emoji, end))
+        });
+        match emoji {
+            Some((emoji, end)) => {
+                result.push_str(emoji);
+                rest = &after[end + 1..];
+            }
+            None => {
+                // The closing colon may open the next shortcode.
+                result.push(':');
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shortcodes_are_sorted() {
+        assert!(SHORTCODES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_expand_shortcodes() {
+        assert_eq!(expand_shortcodes("Ship it :rocket:!"), "Ship it 🚀!");
+        assert_eq!(expand_shortcodes(":+1::tada:"), "👍🎉");
+        assert_eq!(
+            expand_shortcodes("at 10:30 :unknown: a:b"),
+            "at 10:30 :unknown: a:b"
+        );
+        assert_eq!(expand_shortcodes("Time 12:rocket:"), "Time 12🚀");
+    }
+}
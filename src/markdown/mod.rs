@@ -1,12 +1,18 @@
 //! Convert between Markdown text and FTML [`Document`](crate::Document) trees.
 
+mod emoji;
+
+use crate::line_endings;
 use crate::metadata;
 use crate::{
-    ChecklistItem, Document, InlineStyle, Paragraph, ParagraphType, Span, TableCell, TableRow,
+    AdmonitionKind, Alignment, ChecklistItem, DefinitionItem, Document, InlineStyle, ListDelimiter,
+    Paragraph, ParagraphType, Span, TableCell, TableRow, DEFAULT_MAX_DEPTH,
+};
+use pulldown_cmark::{
+    BlockQuoteKind, CodeBlockKind, CowStr, Event, HeadingLevel, Options, Parser, Tag, TagEnd,
 };
-use pulldown_cmark::{Event, HeadingLevel, Options, Parser, Tag, TagEnd};
 use std::borrow::Cow;
-use std::io::{Read, Write};
+use std::io::{Cursor, Read, Write};
 
 /// Parses Markdown into a [`Document`], including YAML metadata (frontmatter) if present.
 ///
@@ -32,9 +38,62 @@ use std::io::{Read, Write};
 /// let meta = doc.metadata.as_ref().unwrap();
 /// assert_eq!(meta.get("title").unwrap().as_str(), Some("Hello"));
 /// ```
-pub fn parse<R: Read>(mut reader: R) -> crate::Result<Document> {
+pub fn parse<R: Read>(reader: R) -> crate::Result<Document> {
+    parse_with_options(reader, &ParseOptions::default())
+}
+
+/// Options for [`parse_with_options`].
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// Replace known emoji shortcodes such as `:rocket:` with the emoji they
+    /// name. Code spans and code blocks are left untouched.
+    pub expand_emoji_shortcodes: bool,
+    /// Convert straight quotes to curly ones, `--` to an en dash, `---` to an
+    /// em dash and `...` to an ellipsis in prose. Code and link targets are
+    /// left untouched.
+    pub smart_punctuation: bool,
+    /// The deepest nesting of block quotes and lists kept. Containers nested
+    /// deeper are dropped and their content is added to the innermost kept
+    /// container. Defaults to [`DEFAULT_MAX_DEPTH`].
+    pub max_depth: usize,
+    /// Keep HTML blocks verbatim as [`Paragraph::Raw`] paragraphs targeting
+    /// `html`, instead of reducing them to the text they contain.
+    pub preserve_raw: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            expand_emoji_shortcodes: false,
+            smart_punctuation: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            preserve_raw: false,
+        }
+    }
+}
+
+/// Parses Markdown like [`parse`], with the given [`ParseOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Cursor;
+/// use tdoc::markdown;
+///
+/// let options = markdown::ParseOptions {
+///     expand_emoji_shortcodes: true,
+///     ..Default::default()
+/// };
+/// let doc = markdown::parse_with_options(Cursor::new("Ship it :rocket:"), &options).unwrap();
+/// assert_eq!(doc.paragraphs[0].content()[0].text, "Ship it 🚀");
+/// ```
+pub fn parse_with_options<R: Read>(
+    mut reader: R,
+    parse_options: &ParseOptions,
+) -> crate::Result<Document> {
     let mut input = String::new();
     reader.read_to_string(&mut input)?;
+    let input = line_endings::normalize(&input);
 
     // Extract metadata (frontmatter) if present
     let (metadata, content) = metadata::extract(&input)?;
@@ -44,8 +103,14 @@ pub fn parse<R: Read>(mut reader: R) -> crate::Result<Document> {
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_WIKILINKS);
     options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_SUPERSCRIPT);
+    options.insert(Options::ENABLE_SUBSCRIPT);
+    options.insert(Options::ENABLE_GFM);
+    if parse_options.smart_punctuation {
+        options.insert(Options::ENABLE_SMART_PUNCTUATION);
+    }
 
-    let mut doc = build_document(content, options);
+    let mut doc = build_document(content, options, parse_options);
     doc.metadata = metadata;
     Ok(doc)
 }
@@ -63,8 +128,12 @@ pub fn parse_without_metadata<R: Read>(mut reader: R) -> crate::Result<Document>
     options.insert(Options::ENABLE_TASKLISTS);
     options.insert(Options::ENABLE_WIKILINKS);
     options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_SUPERSCRIPT);
+    options.insert(Options::ENABLE_SUBSCRIPT);
+    options.insert(Options::ENABLE_GFM);
 
-    Ok(build_document(&input, options))
+    let input = line_endings::normalize(&input);
+    Ok(build_document(&input, options, &ParseOptions::default()))
 }
 
 /// Drives the [`MarkdownBuilder`] over `content`, reconstructing empty
@@ -77,8 +146,8 @@ pub fn parse_without_metadata<R: Read>(mut reader: R) -> crate::Result<Document>
 /// blocks and re-insert one empty [`Paragraph::Text`] per extra blank-line
 /// pair. This mirrors [`write`], which serializes empty paragraphs as blank
 /// lines, so documents round-trip.
-fn build_document(content: &str, options: Options) -> Document {
-    let mut builder = MarkdownBuilder::new();
+fn build_document(content: &str, options: Options, parse_options: &ParseOptions) -> Document {
+    let mut builder = MarkdownBuilder::new(parse_options);
 
     // End offset (into `content`) of the most recent event that maps to real
     // source characters — i.e. any leaf/content event as opposed to a block
@@ -131,24 +200,115 @@ fn build_document(content: &str, options: Options) -> Document {
                 }
             }
         }
+        let event = match event {
+            Event::Text(text) => Event::Text(protect_escaped_marker(
+                text,
+                &content[..range.start],
+                &content[range.clone()],
+            )),
+            event => event,
+        };
+        let list_delimiter = match &event {
+            Event::Start(Tag::List(Some(_))) => Some(ordered_list_delimiter(&content[range])),
+            _ => None,
+        };
         builder.handle_event(event);
+        if let Some(delimiter) = list_delimiter {
+            builder.set_list_delimiter(delimiter);
+        }
     }
 
     builder.finish()
 }
 
+/// Markers of the inline syntax `pulldown-cmark` does not know, which
+/// [`parse_highlights`] and [`parse_scripts`] look for in the parsed text.
+const INLINE_MARKERS: [char; 3] = ['=', '^', '~'];
+
+/// Escaped markers are moved this far into the Private Use Area while the
+/// marker passes run, so they cannot pair up with real ones.
+const ESCAPED_MARKER_OFFSET: u32 = 0xE000;
+
+/// Hides a marker starting `text` from the marker passes when the source
+/// escapes it, as in `\~` or `&#126;`. `pulldown-cmark` removes the escape,
+/// but starts a new text event at the escaped character, so whether `text`
+/// begins with one is told by the source `before` it and its own `source`.
+fn protect_escaped_marker<'a>(text: CowStr<'a>, before: &str, source: &str) -> CowStr<'a> {
+    let Some(first) = text.chars().next().filter(|ch| INLINE_MARKERS.contains(ch)) else {
+        return text;
+    };
+    let backslashes = before.bytes().rev().take_while(|&b| b == b'\\').count();
+    let is_entity = source.starts_with('&') && source != text.as_ref();
+    if backslashes % 2 == 0 && !is_entity {
+        return text;
+    }
+
+    let mut protected = String::with_capacity(text.len() + 2);
+    protected.extend(char::from_u32(ESCAPED_MARKER_OFFSET + first as u32));
+    protected.push_str(&text[first.len_utf8()..]);
+    protected.into()
+}
+
+/// Turns the markers hidden by [`protect_escaped_marker`] back into plain
+/// characters.
+fn restore_escaped_markers(spans: &mut [Span]) {
+    let hidden = INLINE_MARKERS
+        .map(|marker| char::from_u32(ESCAPED_MARKER_OFFSET + marker as u32).unwrap_or(marker));
+    for span in spans {
+        if span.text.contains(hidden) {
+            span.text = span
+                .text
+                .chars()
+                .map(|ch| match hidden.iter().position(|&h| h == ch) {
+                    Some(idx) => INLINE_MARKERS[idx],
+                    None => ch,
+                })
+                .collect();
+        }
+        restore_escaped_markers(&mut span.children);
+    }
+}
+
+/// Reads the delimiter following the number of an ordered list's first
+/// item marker, e.g. `)` in `1) item`.
+fn ordered_list_delimiter(source: &str) -> ListDelimiter {
+    let marker = source.trim_start_matches(|ch: char| ch.is_whitespace() || ch == '>');
+    match marker
+        .trim_start_matches(|ch: char| ch.is_ascii_digit())
+        .chars()
+        .next()
+    {
+        Some(')') => ListDelimiter::Parenthesis,
+        _ => ListDelimiter::Period,
+    }
+}
+
 struct MarkdownBuilder {
     stack: Vec<BlockContext>,
     in_html_comment: bool,
+    expand_emoji_shortcodes: bool,
+    max_depth: usize,
+    /// Quotes and lists currently open beyond `max_depth`, which are not on
+    /// the stack.
+    dropped_containers: usize,
+    preserve_raw: bool,
+    /// Source of the HTML block being read. `pulldown-cmark` hands out one
+    /// [`Event::Html`] per line.
+    html_block: Option<String>,
 }
 
 impl MarkdownBuilder {
-    fn new() -> Self {
+    fn new(parse_options: &ParseOptions) -> Self {
         Self {
             stack: vec![BlockContext::Document {
                 paragraphs: Vec::new(),
             }],
             in_html_comment: false,
+            expand_emoji_shortcodes: parse_options.expand_emoji_shortcodes,
+            max_depth: parse_options.max_depth,
+            dropped_containers: 0,
+            preserve_raw: parse_options.preserve_raw,
+            html_block: None,
         }
     }
 
@@ -171,8 +331,10 @@ impl MarkdownBuilder {
                         let paragraph = if is_checklist {
                             debug_assert!(entries.is_empty());
                             Paragraph::new_checklist().with_checklist_items(checklist_items)
-                        } else if ordered {
-                            Paragraph::new_ordered_list().with_entries(entries)
+                        } else if let Some(delimiter) = ordered {
+                            Paragraph::new_ordered_list()
+                                .with_entries(entries)
+                                .with_list_delimiter(delimiter)
                         } else {
                             Paragraph::new_unordered_list().with_entries(entries)
                         };
@@ -208,13 +370,14 @@ impl MarkdownBuilder {
                             }
                         }
                     }
-                    Some(BlockContext::Quote { children }) => {
+                    Some(BlockContext::Quote { children, .. }) => {
                         let paragraph = Paragraph::new_quote().with_children(children);
                         self.add_paragraph_to_parent(paragraph);
                     }
-                    Some(BlockContext::Table { rows, .. }) => {
-                        let paragraph = Paragraph::new_table().with_rows(rows);
-                        self.add_paragraph_to_parent(paragraph);
+                    Some(BlockContext::Table {
+                        rows, alignments, ..
+                    }) => {
+                        self.finish_table(rows, alignments);
                     }
                     Some(BlockContext::TableRow { cells }) => {
                         if let Some(BlockContext::Table { rows, .. }) = self.stack.last_mut() {
@@ -226,7 +389,7 @@ impl MarkdownBuilder {
                     Some(BlockContext::TableCell { is_header, context }) => {
                         let paragraph = context.finish();
                         let content = match paragraph {
-                            Paragraph::Text { content } => content,
+                            Paragraph::Text { content, .. } => content,
                             _ => Vec::new(),
                         };
                         let cell = TableCell { is_header, content };
@@ -255,11 +418,17 @@ impl MarkdownBuilder {
     }
 
     fn handle_event(&mut self, event: Event<'_>) {
+        if self.drops_container(&event) {
+            return;
+        }
         match event {
             Event::Start(tag) => self.handle_start_tag(tag),
             Event::End(tag_end) => self.handle_end_tag(tag_end),
             Event::Text(text) => self.handle_text(text.as_ref()),
-            Event::Html(html) => self.handle_html(html.as_ref()),
+            Event::Html(html) => match self.html_block.as_mut() {
+                Some(block) => block.push_str(&html),
+                None => self.handle_html(html.as_ref()),
+            },
             Event::InlineHtml(html) => self.handle_html(html.as_ref()),
             Event::Code(text) => self.push_code(text.as_ref()),
             Event::FootnoteReference(reference) => {
@@ -276,6 +445,45 @@ impl MarkdownBuilder {
         }
     }
 
+    /// Tracks quotes and lists nested deeper than `max_depth`, returning
+    /// `true` for the container events to ignore. List items of dropped lists
+    /// are ignored, too; their paragraphs are kept apart.
+    fn drops_container(&mut self, event: &Event<'_>) -> bool {
+        match event {
+            Event::Start(Tag::BlockQuote(_) | Tag::List(_)) => {
+                if self.dropped_containers == 0 && self.nesting_depth() < self.max_depth {
+                    return false;
+                }
+                self.close_open_paragraphs();
+                self.dropped_containers += 1;
+                true
+            }
+            Event::End(TagEnd::BlockQuote(_) | TagEnd::List(_)) if self.dropped_containers > 0 => {
+                self.close_open_paragraphs();
+                self.dropped_containers -= 1;
+                true
+            }
+            Event::Start(Tag::Item) | Event::End(TagEnd::Item) if self.dropped_containers > 0 => {
+                self.close_open_paragraphs();
+                true
+            }
+            Event::TaskListMarker(_) => self.dropped_containers > 0,
+            _ => false,
+        }
+    }
+
+    fn nesting_depth(&self) -> usize {
+        self.stack
+            .iter()
+            .filter(|context| {
+                matches!(
+                    context,
+                    BlockContext::Quote { .. } | BlockContext::List { .. }
+                )
+            })
+            .count()
+    }
+
     fn handle_start_tag(&mut self, tag: Tag<'_>) {
         match tag {
             Tag::Paragraph => {
@@ -290,15 +498,18 @@ impl MarkdownBuilder {
                 };
                 self.start_paragraph(paragraph_type);
             }
-            Tag::BlockQuote(_) => {
+            Tag::BlockQuote(kind) => {
                 self.close_open_paragraphs();
                 self.stack.push(BlockContext::Quote {
+                    admonition: kind.map(admonition_kind),
                     children: Vec::new(),
                 });
             }
             Tag::List(start) => {
                 self.close_open_paragraphs();
-                let ordered = start.is_some();
+                // The actual delimiter is filled in by `set_list_delimiter`,
+                // which sees the source text.
+                let ordered = start.map(|_| ListDelimiter::Period);
                 self.stack.push(BlockContext::List {
                     ordered,
                     entries: Vec::new(),
@@ -324,27 +535,48 @@ impl MarkdownBuilder {
                 self.ensure_paragraph()
                     .start_inline(Span::new_styled(InlineStyle::Strike));
             }
+            Tag::Superscript => {
+                self.ensure_paragraph()
+                    .start_inline(Span::new_styled(InlineStyle::Superscript));
+            }
+            Tag::Subscript => {
+                self.ensure_paragraph()
+                    .start_inline(Span::new_styled(InlineStyle::Subscript));
+            }
             Tag::Link { dest_url, .. } => {
-                let span =
-                    Span::new_styled(InlineStyle::Link).with_link_target(dest_url.into_string());
-                self.ensure_paragraph().start_inline(span);
+                self.ensure_paragraph()
+                    .start_inline(Span::link(dest_url.into_string()));
             }
             Tag::Image { dest_url, .. } => {
-                let span =
-                    Span::new_styled(InlineStyle::Link).with_link_target(dest_url.into_string());
-                self.ensure_paragraph().start_inline(span);
+                self.ensure_paragraph()
+                    .start_inline(Span::link(dest_url.into_string()));
             }
-            Tag::CodeBlock(_) => {
-                self.start_paragraph(ParagraphType::CodeBlock);
+            Tag::CodeBlock(kind) => {
+                let language = match kind {
+                    CodeBlockKind::Fenced(info) => info_string_language(&info),
+                    CodeBlockKind::Indented => None,
+                };
+                self.start_paragraph(ParagraphType::CodeBlock).language = language;
             }
             Tag::FootnoteDefinition(name) => {
                 let paragraph = self.start_paragraph(ParagraphType::Text);
                 paragraph.push_text(&format!("[^{}]: ", name));
             }
-            Tag::Table(_) => {
+            Tag::Table(alignments) => {
                 self.close_open_paragraphs();
+                let alignments = alignments
+                    .into_iter()
+                    .map(|alignment| match alignment {
+                        pulldown_cmark::Alignment::Center => Alignment::Center,
+                        pulldown_cmark::Alignment::Right => Alignment::Right,
+                        pulldown_cmark::Alignment::Left | pulldown_cmark::Alignment::None => {
+                            Alignment::Left
+                        }
+                    })
+                    .collect();
                 self.stack.push(BlockContext::Table {
                     rows: Vec::new(),
+                    alignments,
                     in_head: false,
                 });
             }
@@ -363,15 +595,15 @@ impl MarkdownBuilder {
                 let is_header = self.current_table_in_head();
                 self.stack.push(BlockContext::TableCell {
                     is_header,
-                    context: ParagraphContext::new(ParagraphType::Text),
+                    context: self.new_paragraph_context(ParagraphType::Text),
                 });
             }
-            Tag::HtmlBlock
-            | Tag::DefinitionList
+            Tag::HtmlBlock => {
+                self.html_block = Some(String::new());
+            }
+            Tag::DefinitionList
             | Tag::DefinitionListTitle
             | Tag::DefinitionListDefinition
-            | Tag::Superscript
-            | Tag::Subscript
             | Tag::MetadataBlock(_) => {
                 // Currently unsupported tags.
             }
@@ -385,8 +617,16 @@ impl MarkdownBuilder {
             }
             TagEnd::BlockQuote(_) => {
                 self.close_open_paragraphs();
-                if let Some(BlockContext::Quote { children }) = self.stack.pop() {
-                    let paragraph = Paragraph::new_quote().with_children(children);
+                if let Some(BlockContext::Quote {
+                    admonition,
+                    children,
+                }) = self.stack.pop()
+                {
+                    let paragraph = match admonition {
+                        Some(kind) => Paragraph::new_admonition(kind),
+                        None => Paragraph::new_quote(),
+                    }
+                    .with_children(children);
                     self.add_paragraph_to_parent(paragraph);
                 }
             }
@@ -402,8 +642,10 @@ impl MarkdownBuilder {
                     let paragraph = if is_checklist {
                         debug_assert!(entries.is_empty());
                         Paragraph::new_checklist().with_checklist_items(checklist_items)
-                    } else if ordered {
-                        Paragraph::new_ordered_list().with_entries(entries)
+                    } else if let Some(delimiter) = ordered {
+                        Paragraph::new_ordered_list()
+                            .with_entries(entries)
+                            .with_list_delimiter(delimiter)
                     } else {
                         Paragraph::new_unordered_list().with_entries(entries)
                     };
@@ -453,6 +695,16 @@ impl MarkdownBuilder {
             TagEnd::Strikethrough => {
                 self.current_paragraph_inline_end(InlineStyle::Strike);
             }
+            TagEnd::Superscript => {
+                if let Some(BlockContext::Paragraph(context)) = self.stack.last_mut() {
+                    context.end_script(InlineStyle::Superscript, "^");
+                }
+            }
+            TagEnd::Subscript => {
+                if let Some(BlockContext::Paragraph(context)) = self.stack.last_mut() {
+                    context.end_script(InlineStyle::Subscript, "~");
+                }
+            }
             TagEnd::Link | TagEnd::Image => {
                 self.current_paragraph_inline_end(InlineStyle::Link);
             }
@@ -466,7 +718,7 @@ impl MarkdownBuilder {
                 if let Some(BlockContext::TableCell { is_header, context }) = self.stack.pop() {
                     let paragraph = context.finish();
                     let content = match paragraph {
-                        Paragraph::Text { content } => content,
+                        Paragraph::Text { content, .. } => content,
                         _ => Vec::new(),
                     };
                     let cell = TableCell { is_header, content };
@@ -478,7 +730,9 @@ impl MarkdownBuilder {
             TagEnd::TableRow | TagEnd::TableHead => {
                 if let Some(BlockContext::TableRow { cells }) = self.stack.pop() {
                     if !cells.is_empty() {
-                        if let Some(BlockContext::Table { rows, in_head }) = self.stack.last_mut() {
+                        if let Some(BlockContext::Table { rows, in_head, .. }) =
+                            self.stack.last_mut()
+                        {
                             rows.push(TableRow { cells });
                             if matches!(tag, TagEnd::TableHead) {
                                 *in_head = false;
@@ -488,18 +742,22 @@ impl MarkdownBuilder {
                 }
             }
             TagEnd::Table => {
-                if let Some(BlockContext::Table { rows, .. }) = self.stack.pop() {
-                    let paragraph = Paragraph::new_table().with_rows(rows);
-                    self.add_paragraph_to_parent(paragraph);
+                if let Some(BlockContext::Table {
+                    rows, alignments, ..
+                }) = self.stack.pop()
+                {
+                    self.finish_table(rows, alignments);
+                }
+            }
+            TagEnd::HtmlBlock => {
+                if let Some(html) = self.html_block.take() {
+                    self.finish_html_block(&html);
                 }
             }
-            TagEnd::HtmlBlock
-            | TagEnd::DefinitionList
+            TagEnd::DefinitionList
             | TagEnd::DefinitionListTitle
             | TagEnd::DefinitionListDefinition
-            | TagEnd::MetadataBlock(_)
-            | TagEnd::Superscript
-            | TagEnd::Subscript => {
+            | TagEnd::MetadataBlock(_) => {
                 // Currently unsupported block types; ignore closures.
             }
         }
@@ -562,6 +820,33 @@ impl MarkdownBuilder {
         self.push_text(text.as_ref());
     }
 
+    /// Adds a complete HTML block: verbatim with `preserve_raw`, or else as
+    /// the paragraphs the HTML parser makes of it, which keeps the text and
+    /// drops the tags. Blocks holding only comments are dropped.
+    fn finish_html_block(&mut self, html: &str) {
+        let Some(stripped) = self.strip_html_comments(html) else {
+            return;
+        };
+        if stripped.trim().is_empty() {
+            return;
+        }
+
+        self.close_open_paragraphs();
+        if self.preserve_raw {
+            self.add_paragraph_to_parent(Paragraph::new_raw("html", html.trim_end()));
+            return;
+        }
+
+        match crate::html::parse(Cursor::new(stripped.as_bytes())) {
+            Ok(document) => {
+                for paragraph in document.paragraphs {
+                    self.add_paragraph_to_parent(paragraph);
+                }
+            }
+            Err(_) => self.push_text(stripped.trim()),
+        }
+    }
+
     fn handle_html(&mut self, html: &str) {
         let Some(html) = self.strip_html_comments(html) else {
             return;
@@ -612,6 +897,53 @@ impl MarkdownBuilder {
             return;
         }
 
+        if is_open_tag(&lowercase, "sup") {
+            self.ensure_paragraph()
+                .start_inline(Span::new_styled(InlineStyle::Superscript));
+            return;
+        }
+
+        if is_close_tag(&lowercase, "sup") {
+            self.current_paragraph_inline_end(InlineStyle::Superscript);
+            return;
+        }
+
+        if is_open_tag(&lowercase, "sub") {
+            self.ensure_paragraph()
+                .start_inline(Span::new_styled(InlineStyle::Subscript));
+            return;
+        }
+
+        if is_close_tag(&lowercase, "sub") {
+            self.current_paragraph_inline_end(InlineStyle::Subscript);
+            return;
+        }
+
+        if is_open_tag(&lowercase, "abbr") {
+            let mut span = Span::new_styled(InlineStyle::Abbreviation);
+            if let Some(title) = html_attribute(trimmed, "title").filter(|t| !t.trim().is_empty()) {
+                span = span.with_link_target(title);
+            }
+            self.ensure_paragraph().start_inline(span);
+            return;
+        }
+
+        if is_close_tag(&lowercase, "abbr") {
+            self.current_paragraph_inline_end(InlineStyle::Abbreviation);
+            return;
+        }
+
+        if is_open_tag(&lowercase, "kbd") {
+            self.ensure_paragraph()
+                .start_inline(Span::new_styled(InlineStyle::Keyboard));
+            return;
+        }
+
+        if is_close_tag(&lowercase, "kbd") {
+            self.current_paragraph_inline_end(InlineStyle::Keyboard);
+            return;
+        }
+
         self.push_text(html.as_ref());
     }
 
@@ -666,16 +998,30 @@ impl MarkdownBuilder {
         }
     }
 
+    fn set_list_delimiter(&mut self, delimiter: ListDelimiter) {
+        if let Some(BlockContext::List {
+            ordered: Some(ordered),
+            ..
+        }) = self.stack.last_mut()
+        {
+            *ordered = delimiter;
+        }
+    }
+
     fn push_thematic_break(&mut self) {
         self.close_open_paragraphs();
         self.add_paragraph_to_parent(Paragraph::new_horizontal_rule());
     }
 
+    fn new_paragraph_context(&self, paragraph_type: ParagraphType) -> ParagraphContext {
+        let mut context = ParagraphContext::new(paragraph_type);
+        context.expand_emoji_shortcodes = self.expand_emoji_shortcodes;
+        context
+    }
+
     fn start_paragraph(&mut self, paragraph_type: ParagraphType) -> &mut ParagraphContext {
-        self.stack
-            .push(BlockContext::Paragraph(ParagraphContext::new(
-                paragraph_type,
-            )));
+        let context = self.new_paragraph_context(paragraph_type);
+        self.stack.push(BlockContext::Paragraph(context));
         match self.stack.last_mut() {
             Some(BlockContext::Paragraph(context)) => context,
             _ => unreachable!(),
@@ -736,11 +1082,52 @@ impl MarkdownBuilder {
         self.add_paragraph_to_parent(Paragraph::new_text());
     }
 
+    fn finish_table(&mut self, rows: Vec<TableRow>, alignments: Vec<Alignment>) {
+        let caption = self.take_table_caption();
+        let paragraph = Paragraph::new_table()
+            .with_rows(rows)
+            .with_caption(caption)
+            .with_alignments(alignments);
+        self.add_paragraph_to_parent(paragraph);
+    }
+
+    /// Removes a Pandoc-style `Table: ...` paragraph directly preceding the
+    /// table being finished and returns its remaining content as caption.
+    fn take_table_caption(&mut self) -> Vec<Span> {
+        let siblings = match self.stack.last_mut() {
+            Some(BlockContext::Document { paragraphs }) => paragraphs,
+            Some(BlockContext::Quote { children, .. }) => children,
+            Some(BlockContext::ListItem { paragraphs, .. }) => paragraphs,
+            _ => return Vec::new(),
+        };
+        let Some(Paragraph::Text { content, .. }) = siblings.last() else {
+            return Vec::new();
+        };
+        let is_caption = content.first().is_some_and(|span| {
+            is_plain_text_span(span) && span.text.starts_with(TABLE_CAPTION_PREFIX)
+        });
+        if !is_caption {
+            return Vec::new();
+        }
+
+        let Some(Paragraph::Text { mut content, .. }) = siblings.pop() else {
+            unreachable!("caption paragraph was just inspected");
+        };
+        let first = &mut content[0];
+        first.text = first.text[TABLE_CAPTION_PREFIX.len()..]
+            .trim_start()
+            .to_string();
+        if first.text.is_empty() {
+            content.remove(0);
+        }
+        content
+    }
+
     fn add_paragraph_to_parent(&mut self, paragraph: Paragraph) {
         if let Some(parent) = self.stack.last_mut() {
             match parent {
                 BlockContext::Document { paragraphs } => paragraphs.push(paragraph),
-                BlockContext::Quote { children } => children.push(paragraph),
+                BlockContext::Quote { children, .. } => children.push(paragraph),
                 BlockContext::ListItem {
                     paragraphs: items, ..
                 } => items.push(paragraph),
@@ -777,11 +1164,21 @@ impl MarkdownBuilder {
         for paragraph in paragraphs {
             match paragraph {
                 Paragraph::Checklist { mut items } => item.children.append(&mut items),
-                Paragraph::Text { content: mut spans }
-                | Paragraph::Header1 { content: mut spans }
-                | Paragraph::Header2 { content: mut spans }
-                | Paragraph::Header3 { content: mut spans }
-                | Paragraph::CodeBlock { content: mut spans } => {
+                Paragraph::Text {
+                    content: mut spans, ..
+                }
+                | Paragraph::Header1 {
+                    content: mut spans, ..
+                }
+                | Paragraph::Header2 {
+                    content: mut spans, ..
+                }
+                | Paragraph::Header3 {
+                    content: mut spans, ..
+                }
+                | Paragraph::CodeBlock {
+                    content: mut spans, ..
+                } => {
                     if spans.is_empty() {
                         continue;
                     }
@@ -799,15 +1196,29 @@ impl MarkdownBuilder {
     }
 }
 
+fn admonition_kind(kind: BlockQuoteKind) -> AdmonitionKind {
+    match kind {
+        BlockQuoteKind::Note => AdmonitionKind::Note,
+        BlockQuoteKind::Tip => AdmonitionKind::Tip,
+        BlockQuoteKind::Important => AdmonitionKind::Important,
+        BlockQuoteKind::Warning => AdmonitionKind::Warning,
+        BlockQuoteKind::Caution => AdmonitionKind::Caution,
+    }
+}
+
 enum BlockContext {
     Document {
         paragraphs: Vec<Paragraph>,
     },
     Quote {
+        /// The kind of a GitHub-style alert (`> [!NOTE]`), which turns the
+        /// quote into an admonition.
+        admonition: Option<AdmonitionKind>,
         children: Vec<Paragraph>,
     },
     List {
-        ordered: bool,
+        /// The item number delimiter, for ordered lists.
+        ordered: Option<ListDelimiter>,
         entries: Vec<Vec<Paragraph>>,
         checklist_items: Vec<ChecklistItem>,
         is_checklist: bool,
@@ -819,6 +1230,7 @@ enum BlockContext {
     Paragraph(ParagraphContext),
     Table {
         rows: Vec<TableRow>,
+        alignments: Vec<Alignment>,
         in_head: bool,
     },
     TableRow {
@@ -840,10 +1252,56 @@ fn is_close_tag(tag: &str, name: &str) -> bool {
     tag.starts_with(&prefix) && tag.contains('>')
 }
 
+/// Extracts the entity-decoded value of attribute `name` from a single opening
+/// tag such as `<abbr title="...">`.
+fn html_attribute(tag: &str, name: &str) -> Option<String> {
+    let end = tag.find('>')?;
+    let mut rest = &tag[..end];
+    rest = rest.strip_prefix('<')?;
+    rest = rest.trim_start_matches(|ch: char| !ch.is_whitespace());
+
+    loop {
+        rest = rest.trim_start();
+        let key_end = rest
+            .find(|ch: char| ch == '=' || ch.is_whitespace() || ch == '/')
+            .unwrap_or(rest.len());
+        if key_end == 0 {
+            return None;
+        }
+        let key = &rest[..key_end];
+        rest = rest[key_end..].trim_start();
+
+        let value = if let Some(after_eq) = rest.strip_prefix('=') {
+            let after_eq = after_eq.trim_start();
+            match after_eq.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let body = &after_eq[1..];
+                    let close = body.find(quote)?;
+                    rest = &body[close + 1..];
+                    &body[..close]
+                }
+                _ => {
+                    let value_end = after_eq.find(char::is_whitespace).unwrap_or(after_eq.len());
+                    rest = &after_eq[value_end..];
+                    &after_eq[..value_end]
+                }
+            }
+        } else {
+            ""
+        };
+
+        if key.eq_ignore_ascii_case(name) {
+            return Some(html_escape::decode_html_entities(value).into_owned());
+        }
+    }
+}
+
 struct ParagraphContext {
     paragraph_type: ParagraphType,
     spans: Vec<Span>,
     inline_stack: Vec<Span>,
+    language: Option<String>,
+    expand_emoji_shortcodes: bool,
 }
 
 impl ParagraphContext {
@@ -852,6 +1310,8 @@ impl ParagraphContext {
             paragraph_type,
             spans: Vec::new(),
             inline_stack: Vec::new(),
+            language: None,
+            expand_emoji_shortcodes: false,
         }
     }
 
@@ -921,11 +1381,11 @@ impl ParagraphContext {
 
     fn push_nested_paragraph(&mut self, paragraph: Paragraph) {
         match paragraph {
-            Paragraph::Text { content }
-            | Paragraph::Header1 { content }
-            | Paragraph::Header2 { content }
-            | Paragraph::Header3 { content }
-            | Paragraph::CodeBlock { content } => {
+            Paragraph::Text { content, .. }
+            | Paragraph::Header1 { content, .. }
+            | Paragraph::Header2 { content, .. }
+            | Paragraph::Header3 { content, .. }
+            | Paragraph::CodeBlock { content, .. } => {
                 for span in content {
                     self.push_span(span);
                 }
@@ -957,6 +1417,27 @@ impl ParagraphContext {
         }
     }
 
+    /// Ends a `^superscript^` or `~subscript~` span. As in Pandoc, content
+    /// containing whitespace is no script: it is kept as text between its
+    /// `marker`s.
+    fn end_script(&mut self, style: InlineStyle, marker: &str) {
+        let mut content = String::new();
+        if let Some(span) = self.inline_stack.last() {
+            collect_plain_text(span, &mut content);
+        }
+        if !content.contains(char::is_whitespace) {
+            self.end_inline(style);
+            return;
+        }
+        if let Some(span) = self.inline_stack.pop() {
+            self.push_text(marker);
+            for child in span.children {
+                self.push_span(child);
+            }
+            self.push_text(marker);
+        }
+    }
+
     fn push_span(&mut self, span: Span) {
         if let Some(parent) = self.inline_stack.last_mut() {
             Self::append_span(&mut parent.children, span);
@@ -965,16 +1446,41 @@ impl ParagraphContext {
         }
     }
 
-    fn append_span(target: &mut Vec<Span>, span: Span) {
+    fn append_span(target: &mut Vec<Span>, mut span: Span) {
         if let Some(last) = target.last_mut() {
             if Self::can_merge(last, &span) {
                 last.text.push_str(&span.text);
                 return;
             }
+            if Self::can_merge_styled(last, &span) {
+                // Append the content child by child so the runs meeting at
+                // the seam are merged, too.
+                let mut incoming = Vec::new();
+                if !span.text.is_empty() {
+                    incoming.push(Span::new_text(std::mem::take(&mut span.text)));
+                }
+                incoming.append(&mut span.children);
+                if !last.text.is_empty() {
+                    let text = std::mem::take(&mut last.text);
+                    last.children.insert(0, Span::new_text(text));
+                }
+                for child in incoming {
+                    Self::append_span(&mut last.children, child);
+                }
+                return;
+            }
         }
         target.push(span);
     }
 
+    /// Whether `b` continues the emphasis of `a`, as in `**a****b**`.
+    fn can_merge_styled(a: &Span, b: &Span) -> bool {
+        a.style == b.style
+            && is_mergeable_style(a.style)
+            && a.link_target.is_none()
+            && b.link_target.is_none()
+    }
+
     fn can_merge(a: &Span, b: &Span) -> bool {
         a.style == InlineStyle::None
             && b.style == InlineStyle::None
@@ -1000,12 +1506,201 @@ impl ParagraphContext {
             }
         }
 
-        Paragraph::new(self.paragraph_type).with_content(self.spans)
+        if self.paragraph_type != ParagraphType::CodeBlock {
+            parse_highlights(&mut self.spans);
+            parse_scripts(&mut self.spans);
+            if self.expand_emoji_shortcodes {
+                expand_emoji_in_spans(&mut self.spans);
+            }
+        }
+        restore_escaped_markers(&mut self.spans);
+
+        let paragraph = Paragraph::new(self.paragraph_type).with_content(self.spans);
+        match self.language {
+            Some(language) => paragraph.with_language(language),
+            None => paragraph,
+        }
+    }
+}
+
+/// Turns `==text==` runs in `spans` into [`InlineStyle::Highlight`] spans.
+///
+/// `pulldown-cmark` does not know this syntax, so the markers are looked for
+/// in the text of the finished spans. Like emphasis, an opening `==` must not
+/// be followed by whitespace and a closing one must not be preceded by it;
+/// longer runs such as `===` are no markers. Code and raw spans are skipped,
+/// and so are markers escaped in the source (see [`protect_escaped_marker`]).
+fn parse_highlights(spans: &mut Vec<Span>) {
+    for span in spans.iter_mut() {
+        if !matches!(span.style, InlineStyle::Code | InlineStyle::Raw) {
+            parse_highlights(&mut span.children);
+        }
+    }
+    if !spans
+        .iter()
+        .any(|span| is_plain_text_span(span) && span.text.contains("=="))
+    {
+        return;
+    }
+
+    let count = spans.len();
+    let mut result = Vec::with_capacity(count);
+    // The content after an opening marker that still waits for its closing one.
+    let mut highlighted: Option<Vec<Span>> = None;
+    for (idx, span) in std::mem::take(spans).into_iter().enumerate() {
+        if !is_plain_text_span(&span) {
+            ParagraphContext::append_span(highlighted.as_mut().unwrap_or(&mut result), span);
+            continue;
+        }
+
+        let text = span.text.as_str();
+        let mut start = 0;
+        for (pos, _) in text.match_indices("==") {
+            let before = text[..pos].chars().next_back();
+            let after = text[pos + 2..].chars().next();
+            if before == Some('=') || after == Some('=') {
+                continue;
+            }
+            match highlighted.take() {
+                None => {
+                    let opens = match after {
+                        Some(ch) => !ch.is_whitespace(),
+                        None => idx + 1 < count,
+                    };
+                    if opens {
+                        push_text_span(&mut result, &text[start..pos]);
+                        highlighted = Some(Vec::new());
+                        start = pos + 2;
+                    }
+                }
+                Some(mut content) => {
+                    push_text_span(&mut content, &text[start..pos]);
+                    start = pos;
+                    if content
+                        .last()
+                        .is_some_and(|last| !last.ends_with_whitespace())
+                    {
+                        result
+                            .push(Span::new_styled(InlineStyle::Highlight).with_children(content));
+                        start = pos + 2;
+                    } else {
+                        highlighted = Some(content);
+                    }
+                }
+            }
+        }
+        push_text_span(highlighted.as_mut().unwrap_or(&mut result), &text[start..]);
+    }
+
+    // An unclosed marker is plain text after all.
+    if let Some(content) = highlighted {
+        push_text_span(&mut result, "==");
+        for span in content {
+            ParagraphContext::append_span(&mut result, span);
+        }
+    }
+    *spans = result;
+}
+
+/// Turns `^superscript^` and `~subscript~` runs within words, such as in
+/// `x^2^` or `H~2~O`, into script spans.
+///
+/// `pulldown-cmark` only recognizes these markers at word boundaries, so the
+/// text of the finished spans is searched for the remaining ones. As in
+/// Pandoc, the content must not contain whitespace; `~~` stays strikethrough.
+/// Markers escaped in the source, as in `H\~2\~O`, are no markers.
+fn parse_scripts(spans: &mut Vec<Span>) {
+    for span in spans.iter_mut() {
+        if !matches!(span.style, InlineStyle::Code | InlineStyle::Raw) {
+            parse_scripts(&mut span.children);
+        }
+    }
+    if !spans
+        .iter()
+        .any(|span| is_plain_text_span(span) && span.text.contains(['~', '^']))
+    {
+        return;
+    }
+
+    let mut result = Vec::with_capacity(spans.len());
+    for span in std::mem::take(spans) {
+        if !is_plain_text_span(&span) {
+            result.push(span);
+            continue;
+        }
+
+        let text = span.text.as_str();
+        let mut start = 0;
+        let mut search = 0;
+        while let Some(offset) = text[search..].find(['~', '^']) {
+            let open = search + offset;
+            let marker = text[open..].chars().next().unwrap_or_default();
+            search = open + 1;
+            if text[..open].ends_with(marker) {
+                continue;
+            }
+            let Some(length) = text[open + 1..].find(marker) else {
+                continue;
+            };
+            let close = open + 1 + length;
+            let content = &text[open + 1..close];
+            if content.is_empty()
+                || content.contains(char::is_whitespace)
+                || text[close + 1..].starts_with(marker)
+            {
+                continue;
+            }
+
+            let style = if marker == '^' {
+                InlineStyle::Superscript
+            } else {
+                InlineStyle::Subscript
+            };
+            push_text_span(&mut result, &text[start..open]);
+            result.push(Span::new_styled(style).with_children(vec![Span::new_text(content)]));
+            start = close + 1;
+            search = start;
+        }
+        push_text_span(&mut result, &text[start..]);
+    }
+    *spans = result;
+}
+
+fn is_plain_text_span(span: &Span) -> bool {
+    span.style == InlineStyle::None && span.link_target.is_none() && span.children.is_empty()
+}
+
+fn push_text_span(target: &mut Vec<Span>, text: &str) {
+    if !text.is_empty() {
+        ParagraphContext::append_span(target, Span::new_text(text));
+    }
+}
+
+/// Expands emoji shortcodes in the text of `spans`, skipping code and raw
+/// spans whose text must stay verbatim.
+fn expand_emoji_in_spans(spans: &mut [Span]) {
+    for span in spans {
+        if matches!(span.style, InlineStyle::Code | InlineStyle::Raw) {
+            continue;
+        }
+        if let Cow::Owned(expanded) = emoji::expand_shortcodes(&span.text) {
+            span.text = expanded;
+        }
+        expand_emoji_in_spans(&mut span.children);
     }
 }
 
+/// Extracts the language token from a fenced code block's info string, i.e.
+/// the first whitespace-separated word (`rust` in `` ```rust ignore ``).
+fn info_string_language(info: &str) -> Option<String> {
+    info.split_whitespace().next().map(str::to_string)
+}
+
 const LINE_WIDTH: usize = 80;
 
+/// Starts the paragraph captioning the table that follows it, as in Pandoc.
+const TABLE_CAPTION_PREFIX: &str = "Table:";
+
 /// Serializes a [`Document`] structure back to Markdown, including metadata.
 ///
 /// # Examples
@@ -1044,6 +1739,38 @@ const LINE_WIDTH: usize = 80;
 /// assert!(result.contains("title: Test"));
 /// ```
 pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<()> {
+    write_with_options(writer, document, &WriteOptions::default())
+}
+
+/// Options for [`write_with_options`].
+#[derive(Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Write [`InlineStyle::Highlight`] spans as `==text==`, as understood by
+    /// Obsidian and the parser, instead of `<mark>text</mark>`.
+    pub equals_highlight: bool,
+}
+
+/// Serializes a [`Document`] like [`write`], with the given [`WriteOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::{markdown, Document};
+///
+/// let document = Document::from_markdown("A ==marked== word").unwrap();
+/// let options = markdown::WriteOptions {
+///     equals_highlight: true,
+/// };
+///
+/// let mut output = Vec::new();
+/// markdown::write_with_options(&mut output, &document, &options).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "A ==marked== word\n");
+/// ```
+pub fn write_with_options<W: Write>(
+    writer: &mut W,
+    document: &Document,
+    options: &WriteOptions,
+) -> std::io::Result<()> {
     // Write metadata if present
     if let Some(ref meta) = document.metadata {
         let yaml = metadata::serialize(meta).map_err(std::io::Error::other)?;
@@ -1054,7 +1781,7 @@ pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<(
         }
     }
 
-    write_paragraphs(writer, &document.paragraphs, "", "")
+    write_paragraphs(writer, &document.paragraphs, "", "", options)
 }
 
 fn write_paragraphs<W: Write>(
@@ -1062,8 +1789,10 @@ fn write_paragraphs<W: Write>(
     paragraphs: &[Paragraph],
     prefix: &str,
     continuation_prefix: &str,
+    options: &WriteOptions,
 ) -> std::io::Result<()> {
-    for (i, paragraph) in paragraphs.iter().enumerate() {
+    let visible = paragraphs.iter().filter(|p| p.is_emitted_in("markdown"));
+    for (i, paragraph) in visible.enumerate() {
         if i > 0 {
             if !continuation_prefix.is_empty() {
                 write!(writer, "{}", continuation_prefix)?;
@@ -1081,7 +1810,13 @@ fn write_paragraphs<W: Write>(
             writer.write_all(b"\n")?;
             current_prefix = continuation_prefix;
         }
-        write_paragraph(writer, paragraph, current_prefix, continuation_prefix)?;
+        write_paragraph(
+            writer,
+            paragraph,
+            current_prefix,
+            continuation_prefix,
+            options,
+        )?;
     }
     Ok(())
 }
@@ -1100,27 +1835,34 @@ fn write_paragraph<W: Write>(
     paragraph: &Paragraph,
     prefix: &str,
     continuation_prefix: &str,
+    options: &WriteOptions,
 ) -> std::io::Result<()> {
     match paragraph {
-        Paragraph::Text { content } => {
-            let content = render_spans_to_string(content)?;
+        Paragraph::Text { content, .. } => {
+            let content = render_spans_to_string(content, options)?;
             write_wrapped_lines(writer, prefix, continuation_prefix, &content, true)?;
         }
-        Paragraph::CodeBlock { content } => {
-            write_code_block(writer, prefix, continuation_prefix, content)?;
-        }
-        Paragraph::Header1 { content } => {
-            let content = render_spans_to_string(content)?;
+        Paragraph::CodeBlock { content, language } => {
+            write_code_block(
+                writer,
+                prefix,
+                continuation_prefix,
+                content,
+                language.as_deref(),
+            )?;
+        }
+        Paragraph::Header1 { content, .. } => {
+            let content = render_spans_to_string(content, options)?;
             let first_prefix = format!("{}# ", prefix);
             write_wrapped_lines(writer, &first_prefix, continuation_prefix, &content, false)?;
         }
-        Paragraph::Header2 { content } => {
-            let content = render_spans_to_string(content)?;
+        Paragraph::Header2 { content, .. } => {
+            let content = render_spans_to_string(content, options)?;
             let first_prefix = format!("{}## ", prefix);
             write_wrapped_lines(writer, &first_prefix, continuation_prefix, &content, false)?;
         }
-        Paragraph::Header3 { content } => {
-            let content = render_spans_to_string(content)?;
+        Paragraph::Header3 { content, .. } => {
+            let content = render_spans_to_string(content, options)?;
             let first_prefix = format!("{}### ", prefix);
             write_wrapped_lines(writer, &first_prefix, continuation_prefix, &content, false)?;
         }
@@ -1128,12 +1870,34 @@ fn write_paragraph<W: Write>(
             let quote_prefix = format!("{}> ", prefix);
             let quote_continuation = format!("{}> ", continuation_prefix);
 
-            for (idx, child) in children.iter().enumerate() {
+            let visible = children.iter().filter(|p| p.is_emitted_in("markdown"));
+            for (idx, child) in visible.enumerate() {
+                if idx > 0 {
+                    write!(writer, "{}", quote_continuation)?;
+                    writeln!(writer)?;
+                }
+                write_paragraph(writer, child, &quote_prefix, &quote_continuation, options)?;
+            }
+        }
+        Paragraph::Admonition { kind, children } => {
+            // GitHub-style alert: the marker on the quote's first line, the
+            // body directly below it.
+            writeln!(writer, "{}> [!{}]", prefix, kind.marker())?;
+            let quote_continuation = format!("{}> ", continuation_prefix);
+
+            let visible = children.iter().filter(|p| p.is_emitted_in("markdown"));
+            for (idx, child) in visible.enumerate() {
                 if idx > 0 {
                     write!(writer, "{}", quote_continuation)?;
                     writeln!(writer)?;
                 }
-                write_paragraph(writer, child, &quote_prefix, &quote_continuation)?;
+                write_paragraph(
+                    writer,
+                    child,
+                    &quote_continuation,
+                    &quote_continuation,
+                    options,
+                )?;
             }
         }
         Paragraph::UnorderedList { entries } => {
@@ -1141,24 +1905,71 @@ fn write_paragraph<W: Write>(
                 let bullet_prefix = format!("{}- ", prefix);
                 let bullet_continuation = format!("{}  ", continuation_prefix);
 
-                write_paragraphs(writer, entry, &bullet_prefix, &bullet_continuation)?;
+                write_paragraphs(writer, entry, &bullet_prefix, &bullet_continuation, options)?;
             }
         }
-        Paragraph::OrderedList { entries } => {
+        Paragraph::OrderedList { entries, delimiter } => {
+            let delimiter = match delimiter {
+                ListDelimiter::Period => '.',
+                ListDelimiter::Parenthesis => ')',
+            };
             for (i, entry) in entries.iter().enumerate() {
-                let marker = format!("{}. ", i + 1);
+                let marker = format!("{}{} ", i + 1, delimiter);
                 let bullet_prefix = format!("{}{}", prefix, marker);
                 let bullet_continuation =
                     format!("{}{}", continuation_prefix, " ".repeat(marker.len()));
 
-                write_paragraphs(writer, entry, &bullet_prefix, &bullet_continuation)?;
+                write_paragraphs(writer, entry, &bullet_prefix, &bullet_continuation, options)?;
             }
         }
         Paragraph::Checklist { items } => {
-            write_checklist_items(writer, items, prefix, continuation_prefix)?;
-        }
-        Paragraph::Table { rows } => {
-            write_table(writer, rows, prefix, continuation_prefix)?;
+            write_checklist_items(writer, items, prefix, continuation_prefix, options)?;
+        }
+        Paragraph::Table { rows, .. } => {
+            let mut prefix = prefix;
+            let caption = paragraph.caption();
+            if !caption.is_empty() {
+                let caption = render_spans_to_string(caption, options)?.replace('\n', " ");
+                writeln!(writer, "{}{} {}", prefix, TABLE_CAPTION_PREFIX, caption)?;
+                writeln!(writer, "{}", continuation_prefix)?;
+                prefix = continuation_prefix;
+            }
+            write_table(
+                writer,
+                paragraph,
+                rows,
+                prefix,
+                continuation_prefix,
+                options,
+            )?;
+        }
+        Paragraph::DefinitionList { items } => {
+            write_definition_list(writer, items, prefix, continuation_prefix, options)?;
+        }
+        Paragraph::Details { summary, children } => {
+            // Markdown has no collapsible sections, but renderers such as
+            // GitHub's accept the HTML block around Markdown content.
+            writeln!(writer, "{}<details>", prefix)?;
+            if !summary.is_empty() {
+                let summary = render_spans_to_string(summary, options)?.replace('\n', " ");
+                writeln!(
+                    writer,
+                    "{}<summary>{}</summary>",
+                    continuation_prefix, summary
+                )?;
+            }
+            if children.iter().any(|p| p.is_emitted_in("markdown")) {
+                writeln!(writer, "{}", continuation_prefix)?;
+                write_paragraphs(
+                    writer,
+                    children,
+                    continuation_prefix,
+                    continuation_prefix,
+                    options,
+                )?;
+                writeln!(writer, "{}", continuation_prefix)?;
+            }
+            writeln!(writer, "{}</details>", continuation_prefix)?;
         }
         Paragraph::HorizontalRule => {
             // A thematic break. The caller separates paragraphs with a blank
@@ -1166,15 +1977,61 @@ fn write_paragraph<W: Write>(
             // setext heading underline.
             writeln!(writer, "{}---", prefix)?;
         }
+        Paragraph::Raw { content, .. } => {
+            for (idx, line) in content.lines().enumerate() {
+                let line_prefix = if idx == 0 {
+                    prefix
+                } else {
+                    continuation_prefix
+                };
+                writeln!(writer, "{}{}", line_prefix, line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes the extended `Term` / `:   Definition` syntax understood by Pandoc,
+/// PHP Markdown Extra and pulldown-cmark. Terms must fit on a single line.
+fn write_definition_list<W: Write>(
+    writer: &mut W,
+    items: &[DefinitionItem],
+    prefix: &str,
+    continuation_prefix: &str,
+    options: &WriteOptions,
+) -> std::io::Result<()> {
+    let definition_prefix = format!("{}:   ", continuation_prefix);
+    let definition_continuation = format!("{}    ", continuation_prefix);
+
+    for (idx, item) in items.iter().enumerate() {
+        if idx > 0 {
+            writeln!(writer, "{}", continuation_prefix)?;
+        }
+        let term_prefix = if idx == 0 {
+            prefix
+        } else {
+            continuation_prefix
+        };
+        let term = render_spans_to_string(&item.term, options)?;
+        writeln!(writer, "{}{}", term_prefix, term.replace('\n', " "))?;
+        write_paragraphs(
+            writer,
+            &item.definition,
+            &definition_prefix,
+            &definition_continuation,
+            options,
+        )?;
     }
     Ok(())
 }
 
 fn write_table<W: Write>(
     writer: &mut W,
+    table: &Paragraph,
     rows: &[TableRow],
     prefix: &str,
     continuation_prefix: &str,
+    options: &WriteOptions,
 ) -> std::io::Result<()> {
     if rows.is_empty() {
         return Ok(());
@@ -1190,7 +2047,7 @@ fn write_table<W: Write>(
         let mut texts = Vec::with_capacity(column_count);
         for col in 0..column_count {
             let text = match row.cells.get(col) {
-                Some(cell) => encode_table_cell(&cell.content)?,
+                Some(cell) => encode_table_cell(&cell.content, options)?,
                 None => String::new(),
             };
             texts.push(text);
@@ -1217,7 +2074,18 @@ fn write_table<W: Write>(
         write!(writer, "{}|", prefix)?;
         for (col, text) in row.iter().enumerate() {
             let pad = widths[col].saturating_sub(text.chars().count());
-            write!(writer, " {}{} |", text, " ".repeat(pad))?;
+            let (before, after) = match table.column_alignment(col) {
+                Alignment::Left => (0, pad),
+                Alignment::Center => (pad / 2, pad - pad / 2),
+                Alignment::Right => (pad, 0),
+            };
+            write!(
+                writer,
+                " {}{}{} |",
+                " ".repeat(before),
+                text,
+                " ".repeat(after)
+            )?;
         }
         writeln!(writer)?;
         Ok(())
@@ -1225,8 +2093,13 @@ fn write_table<W: Write>(
 
     let write_separator = |writer: &mut W, prefix: &str| -> std::io::Result<()> {
         write!(writer, "{}|", prefix)?;
-        for &w in &widths {
-            write!(writer, "{}|", "-".repeat(w + 2))?;
+        for (col, &w) in widths.iter().enumerate() {
+            let dashes = match table.column_alignment(col) {
+                Alignment::Left => "-".repeat(w + 2),
+                Alignment::Center => format!(":{}:", "-".repeat(w)),
+                Alignment::Right => format!("{}:", "-".repeat(w + 1)),
+            };
+            write!(writer, "{}|", dashes)?;
         }
         writeln!(writer)?;
         Ok(())
@@ -1251,8 +2124,8 @@ fn write_table<W: Write>(
     Ok(())
 }
 
-fn encode_table_cell(spans: &[Span]) -> std::io::Result<String> {
-    let rendered = render_spans_to_string(spans)?;
+fn encode_table_cell(spans: &[Span], options: &WriteOptions) -> std::io::Result<String> {
+    let rendered = render_spans_to_string(spans, options)?;
     // Flatten newlines — pipe tables are single-line per cell — and escape pipes.
     let flattened = rendered.replace('\n', " ");
     Ok(flattened.replace('|', "\\|"))
@@ -1263,10 +2136,11 @@ fn write_checklist_items<W: Write>(
     items: &[ChecklistItem],
     prefix: &str,
     continuation_prefix: &str,
+    options: &WriteOptions,
 ) -> std::io::Result<()> {
     for item in items {
         let marker = if item.checked { 'x' } else { ' ' };
-        let content = render_spans_to_string(&item.content)?;
+        let content = render_spans_to_string(&item.content, options)?;
         let first_prefix = format!("{}- [{}] ", prefix, marker);
         let continuation = format!("{}{}", continuation_prefix, " ".repeat(6));
         write_wrapped_lines(writer, &first_prefix, &continuation, &content, true)?;
@@ -1274,7 +2148,13 @@ fn write_checklist_items<W: Write>(
         if !item.children.is_empty() {
             let child_prefix = format!("{}  ", prefix);
             let child_continuation = format!("{}  ", continuation_prefix);
-            write_checklist_items(writer, &item.children, &child_prefix, &child_continuation)?;
+            write_checklist_items(
+                writer,
+                &item.children,
+                &child_prefix,
+                &child_continuation,
+                options,
+            )?;
         }
     }
     Ok(())
@@ -1285,8 +2165,9 @@ fn write_code_block<W: Write>(
     prefix: &str,
     continuation_prefix: &str,
     spans: &[Span],
+    language: Option<&str>,
 ) -> std::io::Result<()> {
-    writeln!(writer, "{}```", prefix)?;
+    writeln!(writer, "{}```{}", prefix, language.unwrap_or_default())?;
 
     let mut content = String::new();
     for span in spans {
@@ -1353,11 +2234,44 @@ fn write_span<W: Write>(
             }
         }
         InlineStyle::Code => write_code_span(writer, span, state),
+        InlineStyle::Quotation => {
+            if !span.has_content() {
+                return Ok(());
+            }
+            let mark = if state.quote_depth.is_multiple_of(2) {
+                "\""
+            } else {
+                "'"
+            };
+            state.write_chunk(writer, mark)?;
+            state.quote_depth += 1;
+            let result =
+                write_span_content(writer, span, state, has_more_siblings, mark.chars().next());
+            state.quote_depth -= 1;
+            result?;
+            state.write_chunk(writer, mark)
+        }
+        InlineStyle::Raw => {
+            if let Some(raw) = span.raw_content_for("markdown") {
+                state.write_chunk(writer, raw)?;
+            }
+            Ok(())
+        }
         style => {
             // Emphasis with `_` does not work intraword in CommonMark/GFM, so
             // pick the delimiter based on the surrounding characters.
             let (begin_tag, end_tag) = if style == InlineStyle::Italic {
                 italic_tags(state.last_char, next_char)
+            } else if style == InlineStyle::Highlight && state.options.equals_highlight {
+                ("==", "==")
+            } else if matches!(style, InlineStyle::Superscript | InlineStyle::Subscript)
+                && has_script_content(span)
+            {
+                if style == InlineStyle::Superscript {
+                    ("^", "^")
+                } else {
+                    ("~", "~")
+                }
             } else {
                 inline_tags(style)
             };
@@ -1366,8 +2280,14 @@ fn write_span<W: Write>(
             if !span.has_content() {
                 return Ok(());
             }
+            let begin_tag: Cow<'_, str> = match (style, &span.link_target) {
+                (InlineStyle::Abbreviation, Some(title)) => {
+                    Cow::Owned(format!("<abbr title=\"{}\">", escape_html_attribute(title)))
+                }
+                _ => Cow::Borrowed(begin_tag),
+            };
             if !begin_tag.is_empty() {
-                state.write_chunk(writer, begin_tag)?;
+                state.write_chunk(writer, &begin_tag)?;
             }
             // Inside the span the content is immediately followed by the end tag.
             let content_next = end_tag.chars().next().or(next_char);
@@ -1449,6 +2369,9 @@ fn span_first_char(span: &Span) -> Option<char> {
         }
         // A code span always emits at least its opening backtick.
         InlineStyle::Code => Some('`'),
+        InlineStyle::Raw => span
+            .raw_content_for("markdown")
+            .and_then(|raw| raw.chars().next()),
         InlineStyle::None => content_first_char(span),
         style => {
             if !span.has_content() {
@@ -1466,10 +2389,10 @@ fn content_first_char(span: &Span) -> Option<char> {
         .or_else(|| first_emitted_char(&span.children))
 }
 
-fn render_spans_to_string(spans: &[Span]) -> std::io::Result<String> {
+fn render_spans_to_string(spans: &[Span], options: &WriteOptions) -> std::io::Result<String> {
     let merged = merge_adjacent_spans(spans);
     let mut buffer = Vec::new();
-    let mut state = LineState::new("");
+    let mut state = LineState::new("", options);
     write_spans(&mut buffer, &merged, &mut state, None)?;
     Ok(String::from_utf8(buffer).expect("Rendered markdown should be valid UTF-8"))
 }
@@ -1491,6 +2414,8 @@ fn is_mergeable_style(style: InlineStyle) -> bool {
             | InlineStyle::Strike
             | InlineStyle::Underline
             | InlineStyle::Highlight
+            | InlineStyle::Superscript
+            | InlineStyle::Subscript
     )
 }
 
@@ -1691,6 +2616,10 @@ fn inline_tags(style: InlineStyle) -> (&'static str, &'static str) {
         InlineStyle::Highlight => ("<mark>", "</mark>"),
         InlineStyle::Underline => ("<u>", "</u>"),
         InlineStyle::Strike => ("~~", "~~"),
+        InlineStyle::Superscript => ("<sup>", "</sup>"),
+        InlineStyle::Subscript => ("<sub>", "</sub>"),
+        InlineStyle::Keyboard => ("<kbd>", "</kbd>"),
+        InlineStyle::Abbreviation => ("<abbr>", "</abbr>"),
         _ => ("", ""),
     }
 }
@@ -1731,15 +2660,23 @@ fn escape_markdown_text(text: &str, line_start: bool, is_final_chunk: bool) -> S
 
         match ch {
             // Characters that can start an inline construct anywhere on a line and so
-            // must always be escaped: code spans, emphasis, links, strikethrough, table
-            // cell separators, and the escape character itself. Block-level markers (`#`,
+            // must always be escaped: code spans, emphasis, links, strikethrough,
+            // sub- and superscripts, table cell separators, and the escape character
+            // itself. Block-level markers (`#`,
             // `-`, `+`, `>`, ordered-list numbers) are only meaningful at the start of a
             // line and are handled separately when wrapping, so they are intentionally left
             // untouched here.
-            '\\' | '`' | '*' | '_' | '[' | ']' | '~' | '|' => {
+            '\\' | '`' | '*' | '_' | '[' | ']' | '~' | '^' | '|' => {
                 escaped.push('\\');
                 escaped.push(ch);
             }
+            // A run of exactly two `=` marks a highlight; escaping its first
+            // character is enough. Longer runs are no markers.
+            '=' if (idx == 0 || chars[idx - 1] != '=')
+                && chars[idx..].iter().take_while(|&&c| c == '=').count() == 2 =>
+            {
+                escaped.push_str("\\=");
+            }
             '<' => escaped.push_str("&lt;"),
             '&' => escaped.push_str("&amp;"),
             '\u{2005}' => escaped.push_str("&emsp14;"),
@@ -1807,6 +2744,19 @@ fn prepend_backslash(token: &str) -> Cow<'_, str> {
     Cow::Owned(escaped)
 }
 
+fn escape_html_attribute(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '<' => escaped.push_str("&lt;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
 fn escape_link_destination(dest: &str) -> String {
     const HEX_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
 
@@ -1864,14 +2814,23 @@ fn write_code_span<W: Write>(
         content = content.replace('\n', " ");
     }
 
-    let delimiter_len = longest_backtick_sequence(&content) + 1;
-    let delimiter = "`".repeat(delimiter_len.max(1));
-    // CommonMark strips a single leading/trailing space from a code span, which
-    // lets the content itself begin or end with a backtick without merging into
-    // the delimiter. Pad whenever the content starts or ends with a space (so
-    // that space is preserved) or a backtick (so `` `code` `` is not written as
-    // ```` ```code``` ````, which would re-parse to `code`).
-    let needs_padding = content.starts_with([' ', '`']) || content.ends_with([' ', '`']);
+    if content.is_empty() {
+        // Two lone backticks would be a literal backtick string, not an empty
+        // code span, so there is nothing to write.
+        return Ok(());
+    }
+
+    // A delimiter longer than every backtick run in the content can only be
+    // closed by a run of the same length, so no run inside ends the span.
+    let delimiter = "`".repeat(longest_backtick_sequence(&content) + 1);
+    // CommonMark strips one leading and one trailing space from a code span
+    // unless it consists of spaces only. Padding both sides keeps content
+    // that starts or ends with a space intact, and keeps a backtick at either
+    // edge from merging into the delimiter: `` `code` `` must not be written
+    // as ```` ```code``` ````, which would re-parse to `code`. Space-only
+    // content is never stripped, so it is written as is.
+    let needs_padding = (content.starts_with([' ', '`']) || content.ends_with([' ', '`']))
+        && !content.chars().all(|ch| ch == ' ');
 
     state.ensure_prefix(writer)?;
     writer.write_all(delimiter.as_bytes())?;
@@ -1888,7 +2847,18 @@ fn write_code_span<W: Write>(
     Ok(())
 }
 
+/// Whether `span` can be written as `^superscript^` or `~subscript~`, which
+/// do not allow whitespace in their content.
+fn has_script_content(span: &Span) -> bool {
+    let mut content = String::new();
+    collect_plain_text(span, &mut content);
+    !content.is_empty() && !content.contains(char::is_whitespace)
+}
+
 fn collect_plain_text(span: &Span, buffer: &mut String) {
+    if span.style == InlineStyle::Raw {
+        return;
+    }
     if !span.text.is_empty() {
         buffer.push_str(&span.text);
     }
@@ -1919,10 +2889,13 @@ struct LineState<'a> {
     // Last character emitted to the output, used to choose context-sensitive
     // emphasis delimiters (see `italic_tags`).
     last_char: Option<char>,
+    // Number of enclosing quotations; nested ones alternate `"` and `'`.
+    quote_depth: usize,
+    options: &'a WriteOptions,
 }
 
 impl<'a> LineState<'a> {
-    fn new(continuation_prefix: &'a str) -> Self {
+    fn new(continuation_prefix: &'a str, options: &'a WriteOptions) -> Self {
         Self {
             continuation_prefix,
             // Treat the very first chunk as the start of a line so leading
@@ -1930,6 +2903,8 @@ impl<'a> LineState<'a> {
             // break.
             at_line_start: true,
             last_char: None,
+            quote_depth: 0,
+            options,
         }
     }
 
@@ -2015,6 +2990,328 @@ mod tests {
         assert_eq!(parsed, expected);
     }
 
+    #[test]
+    fn test_parse_equals_highlight() {
+        let input = "A ==highlighted== and ==**bold** one==, but `==code==`, a == b and x==y.";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![p_(vec![
+            span("A "),
+            mark__("highlighted"),
+            span(" and "),
+            Span::new_styled(InlineStyle::Highlight).with_children(vec![b__("bold"), span(" one")]),
+            span(", but "),
+            code__("==code=="),
+            span(", a == b and x==y."),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        let code = parse(Cursor::new(
+            "```
+==kept==
+```",
+        ))
+        .unwrap();
+        assert_eq!(code, doc(vec![code_block__("==kept==")]));
+    }
+
+    #[test]
+    fn test_equals_highlight_round_trip() {
+        let document = doc(vec![p_(vec![span("A "), mark__("marked"), span(" word")])]);
+        let options = WriteOptions {
+            equals_highlight: true,
+        };
+        let mut output = Vec::new();
+        write_with_options(&mut output, &document, &options).unwrap();
+        let written = String::from_utf8(output).unwrap();
+        assert_eq!(written, "A ==marked== word\n");
+        assert_eq!(parse(Cursor::new(written)).unwrap(), document);
+
+        assert_eq!(write_to_string(&document), "A <mark>marked</mark> word\n");
+    }
+
+    #[test]
+    fn test_literal_equals_pairs_round_trip() {
+        for text in ["a==b==c", "==not highlighted==", "x == y == z", "a===b==="] {
+            let document = doc(vec![p__(text)]);
+            let written = write_to_string(&document);
+            assert_eq!(
+                parse(Cursor::new(&written)).unwrap(),
+                document,
+                "{written:?}"
+            );
+        }
+        assert_eq!(write_to_string(&doc(vec![p__("a==b")])), "a\\==b\n");
+
+        let parsed = parse(Cursor::new("a\\=\\=b\\=\\=c &#61;=d&#61;=")).unwrap();
+        assert_eq!(parsed, doc(vec![p__("a==b==c ==d==")]));
+    }
+
+    #[test]
+    fn test_expand_emoji_shortcodes() {
+        let input = "Launch :rocket: now, but keep `:rocket:` as is.\n\n```\n:rocket:\n```";
+        let options = ParseOptions {
+            expand_emoji_shortcodes: true,
+            ..Default::default()
+        };
+        let parsed = parse_with_options(Cursor::new(input), &options).unwrap();
+        let expected = doc(vec![
+            p_(vec![
+                span("Launch 🚀 now, but keep "),
+                code__(":rocket:"),
+                span(" as is."),
+            ]),
+            code_block__(":rocket:"),
+        ]);
+        assert_eq!(parsed, expected);
+
+        let parsed = parse(Cursor::new("Launch :rocket:")).unwrap();
+        assert_eq!(parsed, doc(vec![p__("Launch :rocket:")]));
+    }
+
+    #[test]
+    fn test_deep_nesting_is_clamped() {
+        let input = format!("{} deep\n", ">".repeat(10_000));
+        let document = parse(Cursor::new(input)).unwrap();
+        assert_eq!(document.paragraphs[0].depth(), DEFAULT_MAX_DEPTH + 1);
+
+        let options = ParseOptions {
+            max_depth: 2,
+            ..Default::default()
+        };
+        let input = "- a\n  - b\n    - c\n    - d\n  - e\n";
+        let document = parse_with_options(Cursor::new(input), &options).unwrap();
+        assert_eq!(
+            document,
+            doc(vec![ul_(vec![li_(vec![
+                p__("a"),
+                ul_(vec![
+                    li_(vec![p__("b"), p__("c"), p__("d")]),
+                    li_(vec![p__("e")]),
+                ]),
+            ])])])
+        );
+    }
+
+    #[test]
+    fn test_parenthesis_ordered_list_round_trip() {
+        let input = "1) a\n2) b\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        assert_eq!(
+            parsed,
+            doc(vec![ol_(vec![li_(vec![p__("a")]), li_(vec![p__("b")])])
+                .with_list_delimiter(ListDelimiter::Parenthesis)])
+        );
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+
+        let nested = parse(Cursor::new("> 1. a\n>    1) b\n")).unwrap();
+        let outer = &nested.paragraphs[0].children()[0];
+        assert_eq!(outer.list_delimiter(), Some(ListDelimiter::Period));
+        let inner = &outer.entries()[0][1];
+        assert_eq!(inner.list_delimiter(), Some(ListDelimiter::Parenthesis));
+    }
+
+    #[test]
+    fn test_adjacent_emphasis_runs_merge() {
+        let parsed = parse(Cursor::new("**a**__b__ *c*_d_ [e](u)[f](u)")).unwrap();
+        let expected = doc(vec![p_(vec![
+            b__("ab"),
+            span(" "),
+            i__("cd"),
+            span(" "),
+            link_text__("u", "e"),
+            link_text__("u", "f"),
+        ])]);
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_smart_punctuation() {
+        let input = "\"Wait\" -- it's 1990---2000... `\"a\" -- b...` [x](https://e.test/a--b...)";
+        let options = ParseOptions {
+            smart_punctuation: true,
+            ..Default::default()
+        };
+        let parsed = parse_with_options(Cursor::new(input), &options).unwrap();
+        let expected = doc(vec![p_(vec![
+            span("“Wait” – it’s 1990—2000… "),
+            code__("\"a\" -- b..."),
+            span(" "),
+            link_text__("https://e.test/a--b...", "x"),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        let parsed = parse(Cursor::new("\"Wait\" -- ...")).unwrap();
+        assert_eq!(parsed, doc(vec![p__("\"Wait\" -- ...")]));
+    }
+
+    #[test]
+    fn test_write_code_block_followed_by_paragraph() {
+        let document = doc(vec![code_block__("let x = 1;"), p__("After")]);
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "```\nlet x = 1;\n```\n\nAfter\n"
+        );
+    }
+
+    #[test]
+    fn test_loose_list_item_paragraphs_round_trip() {
+        let input = "- First paragraph.\n\n  Second paragraph.\n- Next item\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![ul_(vec![
+            li_(vec![p__("First paragraph."), p__("Second paragraph.")]),
+            li_(vec![p__("Next item")]),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        let reparsed = parse(Cursor::new(output)).unwrap();
+        assert_eq!(reparsed, expected);
+    }
+
+    #[test]
+    fn test_nested_checklist_round_trip() {
+        let input = "- [ ] Release\n  - [x] Changelog\n    - [x] Draft\n    - [ ] Review\n  - [ ] Tag\n- [x] Announce\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![checklist_(vec![
+            task_(
+                false,
+                "Release",
+                vec![
+                    task_(
+                        true,
+                        "Changelog",
+                        vec![task__(true, "Draft"), task__(false, "Review")],
+                    ),
+                    task__(false, "Tag"),
+                ],
+            ),
+            task__(true, "Announce"),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+    }
+
+    #[test]
+    fn test_superscript_and_subscript_round_trip() {
+        let input = "x<sup>2</sup> and H<sub>2</sub>O\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![p_(vec![
+            span("x"),
+            sup__("2"),
+            span(" and H"),
+            sub__("2"),
+            span("O"),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        assert_eq!(write_to_string(&parsed), "x^2^ and H~2~O\n");
+    }
+
+    #[test]
+    fn test_caret_and_tilde_scripts_round_trip() {
+        let input = "H~2~O and x^2^, but ~~struck~~\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![p_(vec![
+            span("H"),
+            sub__("2"),
+            span("O and x"),
+            sup__("2"),
+            span(", but "),
+            s__("struck"),
+        ])]);
+        assert_eq!(parsed, expected);
+        assert_eq!(write_to_string(&parsed), input);
+    }
+
+    #[test]
+    fn test_literal_script_markers_round_trip() {
+        for text in ["H~2~O", "x^2^", "H~2~O and x^2^ or ~a~ and ^b^"] {
+            let document = doc(vec![p__(text)]);
+            let written = write_to_string(&document);
+            assert_eq!(
+                parse(Cursor::new(&written)).unwrap(),
+                document,
+                "{written:?}"
+            );
+        }
+        assert_eq!(
+            write_to_string(&doc(vec![p__("H~2~O x^2^")])),
+            "H\\~2\\~O x\\^2\\^\n"
+        );
+
+        let parsed = parse(Cursor::new("H\\~2\\~O x\\^2\\^ H&#126;2&#126;O a\\\\~b~")).unwrap();
+        assert_eq!(
+            parsed,
+            doc(vec![p_(vec![span("H~2~O x^2^ H~2~O a\\"), sub__("b")])])
+        );
+    }
+
+    #[test]
+    fn test_scripts_with_spaces_stay_text() {
+        let input = "a ~b c~ and d ^e f^";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        assert_eq!(parsed, doc(vec![p__(input)]));
+
+        let written = write_to_string(&doc(vec![p_(vec![sup__("e f")])]));
+        assert_eq!(written, "<sup>e f</sup>\n");
+    }
+
+    #[test]
+    fn test_keyboard_round_trip() {
+        let input = "Press <kbd>Ctrl</kbd>+<kbd>C</kbd>\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![p_(vec![
+            span("Press "),
+            kbd__("Ctrl"),
+            span("+"),
+            kbd__("C"),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+    }
+
+    #[test]
+    fn test_write_quotation_with_quote_marks() {
+        let document = doc(vec![p_(vec![
+            span("Said "),
+            q_(vec![span("use "), q__("tdoc"), span(" here")]),
+        ])]);
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Said \"use 'tdoc' here\"\n"
+        );
+    }
+
+    #[test]
+    fn test_abbreviation_round_trip() {
+        let input = "Uses <abbr title=\"Cascading &quot;Style&quot; Sheets\">CSS</abbr>.\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        let expected = doc(vec![p_(vec![
+            span("Uses "),
+            abbr__("Cascading \"Style\" Sheets", "CSS"),
+            span("."),
+        ])]);
+        assert_eq!(parsed, expected);
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+    }
+
     #[test]
     fn test_parse_underline() {
         let input = "A <u>styled</u> word";
@@ -2104,6 +3401,33 @@ mod tests {
         assert_eq!(parsed, doc(vec![p__("A"), p__("B")]));
     }
 
+    #[test]
+    fn test_multi_line_html_block() {
+        let html =
+            "<div class=\"note\">\n<p>First <b>bold</b> line</p>\n<p>Tom &amp; Jerry</p>\n</div>";
+        let input = format!("{html}\n\nAfter\n");
+
+        let parsed = parse(Cursor::new(input.as_str())).unwrap();
+        assert_eq!(
+            parsed,
+            doc(vec![
+                p_(vec![span("First "), b__("bold"), span(" line")]),
+                p__("Tom & Jerry"),
+                p__("After"),
+            ])
+        );
+
+        let options = ParseOptions {
+            preserve_raw: true,
+            ..Default::default()
+        };
+        let parsed = parse_with_options(Cursor::new(input.as_str()), &options).unwrap();
+        assert_eq!(
+            parsed,
+            doc(vec![Paragraph::new_raw("html", html), p__("After")])
+        );
+    }
+
     #[test]
     fn test_link_defs_and_comment_between_paragraphs_are_not_empty_paragraphs() {
         // Mirrors a real fixture (progit1-de): two link definitions and an HTML
@@ -2289,6 +3613,32 @@ mod tests {
         assert_eq!(result, "> This is quoted.\n");
     }
 
+    #[test]
+    fn test_parse_admonition() {
+        let parsed = parse(Cursor::new("> [!WARNING]\n> be careful")).unwrap();
+        let expected = doc(vec![Paragraph::new_admonition(AdmonitionKind::Warning)
+            .with_children(vec![p__("be careful")])]);
+        assert_eq!(parsed, expected);
+
+        // Quotes without a marker stay plain quotes.
+        let parsed = parse(Cursor::new("> [!NOPE]\n> be careful")).unwrap();
+        assert_eq!(parsed.paragraphs[0].paragraph_type(), ParagraphType::Quote);
+    }
+
+    #[test]
+    fn test_admonition_round_trip() {
+        let document = doc(vec![Paragraph::new_admonition(AdmonitionKind::Tip)
+            .with_children(vec![p__("First."), p__("Second.")])]);
+
+        let mut output = Vec::new();
+        write(&mut output, &document).unwrap();
+        let rendered = String::from_utf8(output).unwrap();
+        assert_eq!(rendered, "> [!TIP]\n> First.\n> \n> Second.\n");
+
+        let reparsed = parse(Cursor::new(&rendered)).unwrap();
+        assert_eq!(reparsed, document);
+    }
+
     #[test]
     fn test_unordered_list() {
         let mut output = Vec::new();
@@ -2578,12 +3928,31 @@ mod tests {
         assert_eq!(reparsed, parsed);
     }
 
+    #[test]
+    fn test_table_caption_and_alignment_round_trip() {
+        let input = "Table: Prices\n\n\
+                     | Item | Price |\n\
+                     |------|------:|\n\
+                     | Tea  |  3.50 |\n\
+                     | Cake | 12.00 |\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+
+        assert_eq!(parsed.paragraphs.len(), 1);
+        let table = &parsed.paragraphs[0];
+        assert_eq!(table.caption(), &[span("Prices")]);
+        assert_eq!(table.alignments(), &[Alignment::Left, Alignment::Right]);
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), input);
+    }
+
     #[test]
     fn test_parse_code_block_no_trailing_newline() {
         let input = "```\nhello\nworld\n```";
         let parsed = parse(Cursor::new(input)).unwrap();
         assert_eq!(parsed.paragraphs.len(), 1);
-        if let crate::Paragraph::CodeBlock { content } = &parsed.paragraphs[0] {
+        if let crate::Paragraph::CodeBlock { content, .. } = &parsed.paragraphs[0] {
             assert_eq!(content.len(), 1);
             // Trailing newline from pulldown_cmark should be stripped
             assert_eq!(content[0].text, "hello\nworld");
@@ -2601,6 +3970,51 @@ mod tests {
         assert_eq!(String::from_utf8(output).unwrap(), "```\ncode\n```\n");
     }
 
+    #[test]
+    fn test_definition_list_writes_colon_definitions() {
+        let list = Paragraph::new_definition_list().with_definition_items(vec![
+            DefinitionItem::new()
+                .with_term(vec![span("Term")])
+                .with_definition(vec![p_(vec![span("Meaning")])]),
+            DefinitionItem::new()
+                .with_term(vec![span("Other")])
+                .with_definition(vec![p_(vec![span("One")]), p_(vec![span("Two")])]),
+        ]);
+        let mut output = Vec::new();
+        write(&mut output, &doc(vec![list])).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "Term\n:   Meaning\n\nOther\n:   One\n    \n    Two\n"
+        );
+    }
+
+    #[test]
+    fn test_details_writes_html_block() {
+        let details = Paragraph::new_details()
+            .with_summary(vec![span("More")])
+            .with_children(vec![p_(vec![span("Body")])]);
+        let mut output = Vec::new();
+        write(&mut output, &doc(vec![details])).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "<details>\n<summary>More</summary>\n\nBody\n\n</details>\n"
+        );
+    }
+
+    #[test]
+    fn test_fenced_code_block_language_round_trips() {
+        let input = "```rust ignore\nfn main() {}\n```\n";
+        let parsed = parse(Cursor::new(input)).unwrap();
+        assert_eq!(parsed.paragraphs[0].language(), Some("rust"));
+
+        let mut output = Vec::new();
+        write(&mut output, &parsed).unwrap();
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "```rust\nfn main() {}\n```\n"
+        );
+    }
+
     #[test]
     fn test_code_block_between_paragraphs_round_trips() {
         let input = "before\n\n```\ncode\n```\n\nafter\n";
@@ -2622,6 +4036,32 @@ mod tests {
         assert_eq!(String::from_utf8(output).unwrap(), input);
     }
 
+    #[test]
+    fn test_code_span_delimiters_fence_any_content() {
+        let cases = [
+            ("`", "`` ` ``"),
+            ("``", "``` `` ```"),
+            ("``x", "``` ``x ```"),
+            ("x`", "`` x` ``"),
+            ("a ``` b", "````a ``` b````"),
+            ("a`b``c", "```a`b``c```"),
+            (" lead", "`  lead `"),
+            ("trail ", "` trail  `"),
+            (" ", "` `"),
+            ("  ", "`  `"),
+        ];
+        for (content, expected) in cases {
+            let document = doc(vec![p_(vec![span("x "), code__(content), span(" y")])]);
+            let written = write_to_string(&document);
+            assert_eq!(written, format!("x {expected} y\n"), "{content:?}");
+            let reparsed = parse(Cursor::new(written)).unwrap();
+            assert_eq!(reparsed, document, "{content:?}");
+        }
+
+        let empty = doc(vec![p_(vec![span("x"), code__(""), span("y")])]);
+        assert_eq!(write_to_string(&empty), "xy\n");
+    }
+
     #[test]
     fn test_code_span_with_backtick_content_is_preserved() {
         // Guards against silent content corruption: the code-span content must
@@ -0,0 +1,602 @@
+//! Export [`Document`](crate::Document) trees as Emacs Org-mode markup.
+//!
+//! Org has no highlight or abbreviation markup, so those spans are written as
+//! plain text. Headings are only recognized at the start of a line, so headings
+//! nested inside lists or quotes are written as bold text instead.
+
+use crate::{ChecklistItem, Document, InlineStyle, Paragraph, Span, TableRow};
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// Serializes a [`Document`] structure to Org-mode markup.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::{Document, InlineStyle, Paragraph, Span};
+/// use tdoc::org;
+///
+/// let paragraph = Paragraph::new_text().with_content(vec![
+///     Span::new_text("Hello "),
+///     Span::new_styled(InlineStyle::Bold).with_children(vec![Span::new_text("world")]),
+/// ]);
+/// let document = Document::new().with_paragraphs(vec![paragraph]);
+///
+/// let mut output = Vec::new();
+/// org::write(&mut output, &document).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "Hello *world*\n");
+/// ```
+pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<()> {
+    write_paragraphs(writer, &document.paragraphs, "", "")
+}
+
+fn write_paragraphs<W: Write>(
+    writer: &mut W,
+    paragraphs: &[Paragraph],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    let visible = paragraphs.iter().filter(|p| p.is_emitted_in("org"));
+    for (i, paragraph) in visible.enumerate() {
+        let current_prefix = if i == 0 { prefix } else { continuation_prefix };
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        write_paragraph(writer, paragraph, current_prefix, continuation_prefix)?;
+    }
+    Ok(())
+}
+
+fn write_paragraph<W: Write>(
+    writer: &mut W,
+    paragraph: &Paragraph,
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    match paragraph {
+        Paragraph::Text { content, .. } => {
+            write_text(writer, &render_spans(content), prefix, continuation_prefix)?;
+        }
+        Paragraph::Header1 { content, .. } => {
+            write_heading(writer, 1, content, prefix, continuation_prefix)?
+        }
+        Paragraph::Header2 { content, .. } => {
+            write_heading(writer, 2, content, prefix, continuation_prefix)?
+        }
+        Paragraph::Header3 { content, .. } => {
+            write_heading(writer, 3, content, prefix, continuation_prefix)?
+        }
+        Paragraph::CodeBlock { content, language } => {
+            let mut text = String::new();
+            for span in content {
+                collect_plain_text(span, &mut text);
+            }
+            match language {
+                Some(language) => writeln!(writer, "{}#+begin_src {}", prefix, language)?,
+                None => writeln!(writer, "{}#+begin_example", prefix)?,
+            }
+            if !text.is_empty() {
+                for line in text.trim_end_matches('\n').split('\n') {
+                    writeln!(writer, "{}{}", continuation_prefix, escape_block_line(line))?;
+                }
+            }
+            match language {
+                Some(_) => writeln!(writer, "{}#+end_src", continuation_prefix)?,
+                None => writeln!(writer, "{}#+end_example", continuation_prefix)?,
+            }
+        }
+        Paragraph::Quote { children } => {
+            writeln!(writer, "{}#+begin_quote", prefix)?;
+            write_paragraphs(writer, children, continuation_prefix, continuation_prefix)?;
+            writeln!(writer, "{}#+end_quote", continuation_prefix)?;
+        }
+        Paragraph::Admonition { kind, children } => {
+            let quote = Paragraph::admonition_as_quote(*kind, children);
+            write_paragraph(writer, &quote, prefix, continuation_prefix)?;
+        }
+        Paragraph::UnorderedList { entries } => {
+            for (i, entry) in entries.iter().enumerate() {
+                let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+                write_list_entry(writer, entry, "- ", item_prefix, continuation_prefix)?;
+            }
+        }
+        Paragraph::OrderedList { entries, .. } => {
+            for (i, entry) in entries.iter().enumerate() {
+                let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+                let marker = format!("{}. ", i + 1);
+                write_list_entry(writer, entry, &marker, item_prefix, continuation_prefix)?;
+            }
+        }
+        Paragraph::Checklist { items } => {
+            write_checklist_items(writer, items, prefix, continuation_prefix)?;
+        }
+        Paragraph::Table { rows, .. } => {
+            write_table(writer, rows, prefix, continuation_prefix)?;
+        }
+        Paragraph::Details { summary, children } => {
+            // Org has no collapsible blocks; a bold line introduces the body.
+            let mut body_prefix = prefix;
+            if !summary.is_empty() {
+                let title = render_spans(summary).replace('\n', " ");
+                writeln!(writer, "{}*{}*", prefix, title)?;
+                body_prefix = continuation_prefix;
+                if children.iter().any(|p| p.is_emitted_in("org")) {
+                    writeln!(writer)?;
+                }
+            }
+            write_paragraphs(writer, children, body_prefix, continuation_prefix)?;
+        }
+        Paragraph::DefinitionList { items } => {
+            for (i, item) in items.iter().enumerate() {
+                let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+                // Org description items: `- term :: definition`.
+                let term = render_spans(&item.term).replace('\n', " ");
+                let first_prefix = format!("{}- {} :: ", item_prefix, term);
+                let item_continuation = format!("{}  ", continuation_prefix);
+                if item.definition.iter().any(|p| p.is_emitted_in("org")) {
+                    write_paragraphs(writer, &item.definition, &first_prefix, &item_continuation)?;
+                } else {
+                    writeln!(writer, "{}", first_prefix.trim_end())?;
+                }
+            }
+        }
+        Paragraph::HorizontalRule => {
+            writeln!(writer, "{}-----", prefix)?;
+        }
+        Paragraph::Raw { content, .. } => {
+            for (i, line) in content.lines().enumerate() {
+                let line_prefix = if i == 0 { prefix } else { continuation_prefix };
+                writeln!(writer, "{}{}", line_prefix, line)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes inline text, turning hard line breaks into Org's `\\` line ends.
+fn write_text<W: Write>(
+    writer: &mut W,
+    text: &str,
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    let mut lines = text.split('\n').peekable();
+    let mut line_prefix = prefix;
+    while let Some(line) = lines.next() {
+        if lines.peek().is_some() {
+            writeln!(writer, "{}{}\\\\", line_prefix, line)?;
+        } else {
+            writeln!(writer, "{}{}", line_prefix, line)?;
+        }
+        line_prefix = continuation_prefix;
+    }
+    Ok(())
+}
+
+fn write_heading<W: Write>(
+    writer: &mut W,
+    level: usize,
+    content: &[Span],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    // Headlines must fit on a single line.
+    let title = render_spans(content).replace('\n', " ");
+    if prefix.is_empty() && continuation_prefix.is_empty() {
+        writeln!(writer, "{} {}", "*".repeat(level), title)
+    } else {
+        let (leading, inner, trailing) = split_whitespace(&title);
+        if inner.is_empty() {
+            return writeln!(writer, "{}", prefix.trim_end());
+        }
+        writeln!(writer, "{}{}*{}*{}", prefix, leading, inner, trailing)
+    }
+}
+
+fn write_list_entry<W: Write>(
+    writer: &mut W,
+    entry: &[Paragraph],
+    marker: &str,
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    let first_prefix = format!("{}{}", prefix, marker);
+    let item_continuation = format!("{}{}", continuation_prefix, " ".repeat(marker.len()));
+
+    let entry: Vec<&Paragraph> = entry.iter().filter(|p| p.is_emitted_in("org")).collect();
+    if entry.is_empty() {
+        return writeln!(writer, "{}", first_prefix.trim_end());
+    }
+
+    for (i, paragraph) in entry.into_iter().enumerate() {
+        // A nested list cannot start on the marker line of its parent item.
+        if i == 0 && is_list(paragraph) {
+            writeln!(writer, "{}", first_prefix.trim_end())?;
+            write_paragraph(writer, paragraph, &item_continuation, &item_continuation)?;
+            continue;
+        }
+
+        let paragraph_prefix = if i == 0 {
+            first_prefix.as_str()
+        } else {
+            // Lists nest directly below the item text; everything else is a
+            // separate paragraph within the item.
+            if !is_list(paragraph) {
+                writeln!(writer)?;
+            }
+            item_continuation.as_str()
+        };
+        write_paragraph(writer, paragraph, paragraph_prefix, &item_continuation)?;
+    }
+    Ok(())
+}
+
+fn is_list(paragraph: &Paragraph) -> bool {
+    matches!(
+        paragraph,
+        Paragraph::OrderedList { .. }
+            | Paragraph::UnorderedList { .. }
+            | Paragraph::Checklist { .. }
+    )
+}
+
+fn write_checklist_items<W: Write>(
+    writer: &mut W,
+    items: &[ChecklistItem],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    for (i, item) in items.iter().enumerate() {
+        let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+        let marker = if item.checked { "[X]" } else { "[ ]" };
+        writeln!(
+            writer,
+            "{}- {} {}",
+            item_prefix,
+            marker,
+            render_spans(&item.content).replace('\n', " ")
+        )?;
+
+        if !item.children.is_empty() {
+            let child_prefix = format!("{}  ", continuation_prefix);
+            write_checklist_items(writer, &item.children, &child_prefix, &child_prefix)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_table<W: Write>(
+    writer: &mut W,
+    rows: &[TableRow],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    let column_count = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    if column_count == 0 {
+        return Ok(());
+    }
+
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .map(|row| {
+            (0..column_count)
+                .map(|col| {
+                    row.cells
+                        .get(col)
+                        .map(|cell| {
+                            render_spans(&cell.content)
+                                .replace('\n', " ")
+                                .replace('|', "\\vert{}")
+                        })
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..column_count)
+        .map(|col| {
+            cells
+                .iter()
+                .map(|row| UnicodeWidthStr::width(row[col].as_str()))
+                .max()
+                .unwrap_or(0)
+                .max(1)
+        })
+        .collect();
+
+    let header_rows = rows
+        .iter()
+        .take_while(|row| !row.cells.is_empty() && row.cells.iter().all(|cell| cell.is_header))
+        .count();
+
+    for (idx, row) in cells.iter().enumerate() {
+        let row_prefix = if idx == 0 {
+            prefix
+        } else {
+            continuation_prefix
+        };
+        let mut line = String::from("|");
+        for (text, width) in row.iter().zip(&widths) {
+            let padding = width - UnicodeWidthStr::width(text.as_str());
+            line.push_str(&format!(" {}{} |", text, " ".repeat(padding)));
+        }
+        writeln!(writer, "{}{}", row_prefix, line)?;
+
+        if header_rows > 0 && idx + 1 == header_rows && idx + 1 < cells.len() {
+            let separator = widths
+                .iter()
+                .map(|width| "-".repeat(width + 2))
+                .collect::<Vec<_>>()
+                .join("+");
+            writeln!(writer, "{}|{}|", continuation_prefix, separator)?;
+        }
+    }
+    Ok(())
+}
+
+/// Protects code block lines that Org would otherwise read as headlines or
+/// keywords by prefixing them with a comma, as Org itself does.
+fn escape_block_line(line: &str) -> String {
+    let content = line.trim_start_matches([' ', '\t']);
+    let indent = &line[..line.len() - content.len()];
+    let unescaped = content.trim_start_matches(',');
+    if unescaped.starts_with('*') || unescaped.starts_with("#+") {
+        format!("{},{}", indent, content)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Renders inline spans to a single Org string.
+fn render_spans(spans: &[Span]) -> String {
+    let mut output = String::new();
+    for span in spans {
+        render_span(span, &mut output);
+    }
+    output
+}
+
+fn render_span(span: &Span, output: &mut String) {
+    match span.style {
+        InlineStyle::None
+        | InlineStyle::Highlight
+        | InlineStyle::Abbreviation
+        | InlineStyle::Color => {
+            output.push_str(&span.text);
+            for child in &span.children {
+                render_span(child, output);
+            }
+        }
+        InlineStyle::Raw => output.push_str(span.raw_content_for("org").unwrap_or_default()),
+        InlineStyle::Quotation => {
+            output.push('"');
+            output.push_str(&span.text);
+            for child in &span.children {
+                render_span(child, output);
+            }
+            output.push('"');
+        }
+        InlineStyle::Bold => push_markup(span, "*", output),
+        InlineStyle::Italic => push_markup(span, "/", output),
+        InlineStyle::Underline => push_markup(span, "_", output),
+        InlineStyle::Strike => push_markup(span, "+", output),
+        InlineStyle::Superscript => push_script(span, "^", output),
+        InlineStyle::Subscript => push_script(span, "_", output),
+        InlineStyle::Code | InlineStyle::Keyboard => {
+            let mut text = String::new();
+            collect_plain_text(span, &mut text);
+            let text = text.replace('\n', " ");
+            let (leading, inner, trailing) = split_whitespace(&text);
+            if inner.is_empty() {
+                output.push_str(&text);
+                return;
+            }
+            // Verbatim markup cannot contain its own delimiter.
+            let marker = if inner.contains('~') { "=" } else { "~" };
+            output.push_str(&format!(
+                "{}{}{}{}{}",
+                leading, marker, inner, marker, trailing
+            ));
+        }
+        InlineStyle::Link => {
+            let mut text = String::new();
+            for child in &span.children {
+                render_span(child, &mut text);
+            }
+            if !span.text.is_empty() {
+                text.insert_str(0, &span.text);
+            }
+            match &span.link_target {
+                Some(target) if text.trim().is_empty() => {
+                    output.push_str(&format!("[[{}]]", escape_link_target(target)));
+                }
+                Some(target) => {
+                    let text = text.replace('\n', " ");
+                    let (leading, inner, trailing) = split_whitespace(&text);
+                    output.push_str(&format!(
+                        "{}[[{}][{}]]{}",
+                        leading,
+                        escape_link_target(target),
+                        inner,
+                        trailing
+                    ));
+                }
+                None => output.push_str(&text),
+            }
+        }
+    }
+}
+
+/// Wraps the rendered content of `span` in `marker`, moving surrounding
+/// whitespace outside the delimiters (Org ignores emphasis that starts or
+/// ends with whitespace).
+fn push_markup(span: &Span, marker: &str, output: &mut String) {
+    let mut text = span.text.clone();
+    for child in &span.children {
+        render_span(child, &mut text);
+    }
+    let text = text.replace('\n', " ");
+    let (leading, inner, trailing) = split_whitespace(&text);
+    if inner.is_empty() {
+        output.push_str(&text);
+        return;
+    }
+    output.push_str(&format!(
+        "{}{}{}{}{}",
+        leading, marker, inner, marker, trailing
+    ));
+}
+
+/// Writes `^{text}` or `_{text}`, Org's braced super- and subscript syntax.
+fn push_script(span: &Span, marker: &str, output: &mut String) {
+    let mut text = span.text.clone();
+    for child in &span.children {
+        render_span(child, &mut text);
+    }
+    let text = text.replace('\n', " ");
+    let (leading, inner, trailing) = split_whitespace(&text);
+    if inner.is_empty() {
+        output.push_str(&text);
+        return;
+    }
+    output.push_str(&format!("{}{}{{{}}}{}", leading, marker, inner, trailing));
+}
+
+fn split_whitespace(text: &str) -> (&str, &str, &str) {
+    let trimmed_start = text.trim_start();
+    let leading = &text[..text.len() - trimmed_start.len()];
+    let inner = trimmed_start.trim_end();
+    let trailing = &trimmed_start[inner.len()..];
+    (leading, inner, trailing)
+}
+
+/// Escapes square brackets and backslashes so the target cannot end the link early.
+fn escape_link_target(target: &str) -> String {
+    let mut escaped = String::with_capacity(target.len());
+    for ch in target.chars() {
+        if matches!(ch, '[' | ']' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn collect_plain_text(span: &Span, buffer: &mut String) {
+    if span.style == InlineStyle::Raw {
+        return;
+    }
+    if !span.text.is_empty() {
+        buffer.push_str(&span.text);
+    }
+    for child in &span.children {
+        collect_plain_text(child, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::*;
+
+    fn render(document: &Document) -> String {
+        let mut output = Vec::new();
+        write(&mut output, document).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_links() {
+        let doc = doc(vec![p_(vec![
+            span("See "),
+            link_text__("https://example.com", "the site"),
+            span(" or "),
+            link__("https://example.org"),
+        ])]);
+        assert_eq!(
+            render(&doc),
+            "See [[https://example.com][the site]] or [[https://example.org]]\n"
+        );
+    }
+
+    #[test]
+    fn test_write_code_block_with_language() {
+        let doc = doc(vec![
+            code_block__("fn main() {}\n* not a heading").with_language("rust")
+        ]);
+        assert_eq!(
+            render(&doc),
+            "#+begin_src rust\nfn main() {}\n,* not a heading\n#+end_src\n"
+        );
+    }
+
+    #[test]
+    fn test_write_code_block_without_language_is_example() {
+        let doc = doc(vec![code_block__("plain")]);
+        assert_eq!(render(&doc), "#+begin_example\nplain\n#+end_example\n");
+    }
+
+    #[test]
+    fn test_write_headings_and_emphasis() {
+        let doc = doc(vec![
+            h1_("One"),
+            h2_("Two"),
+            h3_("Three"),
+            p_(vec![
+                b__("bold "),
+                i__("italic"),
+                span(" "),
+                code__("x = 1"),
+                span(" "),
+                u__("under"),
+                span(" "),
+                s__("gone"),
+            ]),
+        ]);
+        assert_eq!(
+            render(&doc),
+            "* One\n\n** Two\n\n*** Three\n\n*bold* /italic/ ~x = 1~ _under_ +gone+\n"
+        );
+    }
+
+    #[test]
+    fn test_write_lists_quote_and_checklist() {
+        let doc = doc(vec![
+            ul_(vec![
+                li_(vec![p__("First"), ol_(vec![li_(vec![p__("Nested")])])]),
+                li_(vec![p__("Second")]),
+            ]),
+            quote_(vec![p__("Quoted")]),
+            Paragraph::new_checklist().with_checklist_items(vec![ChecklistItem::new(true)
+                .with_content(vec![span("Done")])
+                .with_children(vec![
+                    ChecklistItem::new(false).with_content(vec![span("Open")])
+                ])]),
+        ]);
+        assert_eq!(
+            render(&doc),
+            "- First\n  1. Nested\n- Second\n\n\
+             #+begin_quote\nQuoted\n#+end_quote\n\n\
+             - [X] Done\n  - [ ] Open\n"
+        );
+    }
+
+    #[test]
+    fn test_write_table_with_header_row() {
+        let doc = doc(vec![Paragraph::new_table().with_rows(vec![
+            TableRow::new().with_cells(vec![
+                crate::TableCell::new_header().with_content(vec![span("Name")]),
+                crate::TableCell::new_header().with_content(vec![span("Qty")]),
+            ]),
+            TableRow::new().with_cells(vec![
+                crate::TableCell::new_data().with_content(vec![span("Apple")]),
+                crate::TableCell::new_data().with_content(vec![span("3")]),
+            ]),
+        ])]);
+        assert_eq!(
+            render(&doc),
+            "| Name  | Qty |\n|-------+-----|\n| Apple | 3   |\n"
+        );
+    }
+}
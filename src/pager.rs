@@ -1,3 +1,4 @@
+use crate::links::{self, LinkBase};
 use crossterm::{
     cursor::{Hide, MoveTo, Show},
     event::{
@@ -16,9 +17,10 @@ use crossterm::{
 use std::collections::HashMap;
 use std::io::{self, Stdout, Write};
 use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
-use unicode_width::UnicodeWidthChar;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use url::Url;
 
 type RegeneratorFn = Box<dyn FnMut(u16, u16) -> Result<String, String>>;
@@ -265,6 +267,39 @@ impl LinkPolicy {
     }
 }
 
+impl LinkPolicy {
+    /// The policy for a document read from the local file `path`: links to
+    /// headings and to existing files next to it can be followed, while
+    /// absolute URLs are left to the terminal as external links.
+    pub fn for_file_origin(path: impl Into<PathBuf>) -> Self {
+        let base = LinkBase::File(path.into());
+        Self::new(
+            true,
+            Arc::new(move |target: &str| resolve_link(Some(&base), target).is_some()),
+        )
+    }
+
+    /// The policy for a document fetched from `base`: links to headings and
+    /// to any `http`, `https` or `gemini` URL, relative ones included, can be
+    /// followed.
+    pub fn for_url_origin(base: Url) -> Self {
+        let base = LinkBase::Url(base);
+        Self::new(
+            false,
+            Arc::new(move |target: &str| resolve_link(Some(&base), target).is_some()),
+        )
+    }
+
+    /// The policy for a document without an origin, such as one read from
+    /// stdin: only links to its own headings can be followed.
+    pub fn for_unknown_origin() -> Self {
+        Self::new(
+            true,
+            Arc::new(|target: &str| resolve_link(None, target).is_some()),
+        )
+    }
+}
+
 impl Default for LinkPolicy {
     fn default() -> Self {
         Self {
@@ -274,6 +309,37 @@ impl Default for LinkPolicy {
     }
 }
 
+/// Where a followed link leads, as determined by [`resolve_link`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LinkDestination {
+    /// A heading of the current document, named by its anchor (without `#`).
+    Fragment(String),
+    /// An existing local file, as canonical path.
+    File(PathBuf),
+    /// A remote document.
+    Url(Url),
+}
+
+/// Resolves the link `target` of a document loaded from `base` (`None` for
+/// documents without an origin). Returns `None` for links the pager cannot
+/// follow: targets using other schemes than `http`, `https` and `gemini`,
+/// missing files, and absolute URLs in local files.
+pub fn resolve_link(base: Option<&LinkBase>, target: &str) -> Option<LinkDestination> {
+    let trimmed = target.trim();
+    if let Some(fragment) = trimmed.strip_prefix('#') {
+        return Some(LinkDestination::Fragment(fragment.to_string()));
+    }
+    match base? {
+        LinkBase::Url(url) => links::resolve_url(url, trimmed).map(LinkDestination::Url),
+        LinkBase::File(path) => {
+            if Url::parse(trimmed).is_ok() {
+                return None;
+            }
+            links::resolve_file(path, trimmed).map(LinkDestination::File)
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 struct LinkSpan {
     line_idx: usize,
@@ -331,12 +397,64 @@ pub trait LinkCallback: Send + Sync {
     fn on_link(&self, target: &str, context: &mut LinkCallbackContext<'_>) -> Result<(), String>;
 }
 
+/// A navigation command that keys can be bound to; see
+/// [`PagerOptions::key_bindings`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PagerAction {
+    Quit,
+    ScrollDown,
+    ScrollUp,
+    PageDown,
+    PageUp,
+    Top,
+    Bottom,
+}
+
+/// The keys bound to each [`PagerAction`] by default: the arrow and paging
+/// keys, plus `less`/vi-like letters.
+pub fn default_key_bindings() -> HashMap<PagerAction, Vec<KeyCode>> {
+    HashMap::from([
+        (PagerAction::Quit, vec![KeyCode::Char('q')]),
+        (
+            PagerAction::ScrollDown,
+            vec![KeyCode::Down, KeyCode::Char('j')],
+        ),
+        (PagerAction::ScrollUp, vec![KeyCode::Up, KeyCode::Char('k')]),
+        (
+            PagerAction::PageDown,
+            vec![KeyCode::PageDown, KeyCode::Char(' '), KeyCode::Char('f')],
+        ),
+        (
+            PagerAction::PageUp,
+            vec![KeyCode::PageUp, KeyCode::Char('b')],
+        ),
+        (PagerAction::Top, vec![KeyCode::Home, KeyCode::Char('g')]),
+        (PagerAction::Bottom, vec![KeyCode::End, KeyCode::Char('G')]),
+    ])
+}
+
+/// Returns the action `code` is bound to in `bindings`, if any.
+fn bound_action(
+    bindings: &HashMap<PagerAction, Vec<KeyCode>>,
+    code: KeyCode,
+) -> Option<PagerAction> {
+    bindings
+        .iter()
+        .find(|(_, codes)| codes.contains(&code))
+        .map(|(action, _)| *action)
+}
+
 #[derive(Clone)]
 pub struct PagerOptions {
     pub enable_mouse_capture: bool,
     pub link_callback: Option<Arc<dyn LinkCallback>>,
     pub link_policy: LinkPolicy,
     pub force_page: bool,
+    /// Keys triggering each navigation action, [`default_key_bindings`] by
+    /// default. Bound keys take precedence over the fixed ones for search
+    /// (`/`, `n`, `N`) and links (Tab, Enter); Esc and the Ctrl shortcuts
+    /// always keep their meaning.
+    pub key_bindings: HashMap<PagerAction, Vec<KeyCode>>,
 }
 
 impl Default for PagerOptions {
@@ -346,6 +464,7 @@ impl Default for PagerOptions {
             link_callback: Some(default_link_callback()),
             link_policy: LinkPolicy::default(),
             force_page: false,
+            key_bindings: default_key_bindings(),
         }
     }
 }
@@ -364,6 +483,8 @@ struct PagerState {
     link_policy: LinkPolicy,
     drag_state: Option<DragState>,
     status_message: Option<String>,
+    /// Digits typed as count for the next command, as in `50%`.
+    count_prefix: Option<usize>,
 }
 
 impl PagerState {
@@ -382,6 +503,7 @@ impl PagerState {
             link_policy,
             drag_state: None,
             status_message: None,
+            count_prefix: None,
         }
     }
 
@@ -588,6 +710,17 @@ impl PagerState {
         self.scroll_offset = self.max_scroll();
     }
 
+    /// The scroll offset `percent` percent into the document, rounded up so
+    /// the position indicator shows the requested percentage. Percentages
+    /// above 100 go to the end.
+    fn percent_offset(&self, percent: usize) -> usize {
+        (self.max_scroll() * percent.min(100)).div_ceil(100)
+    }
+
+    fn jump_to_percent(&mut self, percent: usize) {
+        self.scroll_offset = self.percent_offset(percent);
+    }
+
     fn start_search(&mut self) {
         self.search_mode = SearchMode::EnteringQuery;
         self.search_input.clear();
@@ -989,6 +1122,35 @@ impl<'a> LinkCallbackContext<'a> {
         Ok(())
     }
 
+    /// Scrolls to the `occurrence`-th line (counting from zero) that reads
+    /// `text`, ignoring surrounding whitespace. A line that only holds the
+    /// start of `text` also matches, so wrapped headings can be found.
+    /// Returns `false` if there is no such line.
+    pub fn scroll_to_text(&mut self, text: &str, occurrence: usize) -> bool {
+        let text = text.trim();
+        if text.is_empty() {
+            return false;
+        }
+        let found = self
+            .content
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| {
+                let plain = line.plain.trim();
+                !plain.is_empty() && (plain == text || text.starts_with(plain))
+            })
+            .nth(occurrence);
+        match found {
+            Some((index, _)) => {
+                self.state.scroll_offset = index;
+                self.state.clamp_scroll();
+                *self.needs_redraw = true;
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn set_regenerator(&mut self, regenerator: Option<RegeneratorFn>) {
         *self.regenerator = regenerator;
     }
@@ -1856,21 +2018,36 @@ fn draw_status_line(
     Ok(())
 }
 
+/// Fits `text` into exactly `width` terminal columns for the status line:
+/// line breaks and other control characters become spaces, overlong text is
+/// cut off with `…`, and shorter text is padded with spaces.
 fn truncate_with_padding(text: &str, width: usize) -> String {
     if width == 0 {
         return String::new();
     }
 
+    let text: String = text
+        .chars()
+        .map(|ch| if ch.is_control() { ' ' } else { ch })
+        .collect();
+    let fits = UnicodeWidthStr::width(text.as_str()) <= width;
+    // Leave room for the ellipsis when cutting off.
+    let budget = if fits { width } else { width - 1 };
+
     let mut result = String::new();
     let mut used = 0usize;
     for ch in text.chars() {
         let ch_width = UnicodeWidthChar::width(ch).unwrap_or(0);
-        if used + ch_width > width {
+        if used + ch_width > budget {
             break;
         }
         result.push(ch);
         used += ch_width;
     }
+    if !fits {
+        result.push('…');
+        used += 1;
+    }
 
     if used < width {
         result.push_str(&" ".repeat(width - used));
@@ -1881,6 +2058,7 @@ fn truncate_with_padding(text: &str, width: usize) -> String {
 
 fn handle_key_event(
     key_event: KeyEvent,
+    key_bindings: &HashMap<PagerAction, Vec<KeyCode>>,
     state: &mut PagerState,
     content: &[ParsedLine],
     needs_redraw: &mut bool,
@@ -1930,8 +2108,37 @@ fn handle_key_event(
         return true;
     }
 
+    let action = bound_action(key_bindings, key_event.code);
+    if let (KeyCode::Char(digit), None) = (key_event.code, action) {
+        if let Some(digit) = digit.to_digit(10) {
+            let count = state.count_prefix.unwrap_or(0);
+            state.count_prefix = Some(count.saturating_mul(10).saturating_add(digit as usize));
+            return true;
+        }
+    }
+    if let Some(percent) = state.count_prefix.take() {
+        if key_event.code == KeyCode::Char('%') || action == Some(PagerAction::Bottom) {
+            state.jump_to_percent(percent);
+            *needs_redraw = true;
+            return true;
+        }
+    }
+
+    if let Some(action) = action {
+        match action {
+            PagerAction::Quit => return false,
+            PagerAction::ScrollDown => state.scroll_down(),
+            PagerAction::ScrollUp => state.scroll_up(),
+            PagerAction::PageDown => state.page_down(),
+            PagerAction::PageUp => state.page_up(),
+            PagerAction::Top => state.jump_to_start(),
+            PagerAction::Bottom => state.jump_to_end(),
+        }
+        *needs_redraw = true;
+        return true;
+    }
+
     match key_event.code {
-        KeyCode::Char('q') => return false,
         KeyCode::Esc => {
             if matches!(state.search_mode, SearchMode::Active { .. }) {
                 state.clear_search();
@@ -1970,30 +2177,6 @@ fn handle_key_event(
                 *link_to_open = Some(target.to_string());
             }
         }
-        KeyCode::Down | KeyCode::Char('j') => {
-            state.scroll_down();
-            *needs_redraw = true;
-        }
-        KeyCode::Up | KeyCode::Char('k') => {
-            state.scroll_up();
-            *needs_redraw = true;
-        }
-        KeyCode::PageDown | KeyCode::Char(' ') | KeyCode::Char('f') => {
-            state.page_down();
-            *needs_redraw = true;
-        }
-        KeyCode::PageUp => {
-            state.page_up();
-            *needs_redraw = true;
-        }
-        KeyCode::Home | KeyCode::Char('g') => {
-            state.jump_to_start();
-            *needs_redraw = true;
-        }
-        KeyCode::End | KeyCode::Char('G') => {
-            state.jump_to_end();
-            *needs_redraw = true;
-        }
         _ => {}
     }
 
@@ -2157,6 +2340,26 @@ fn apply_watched_content(content: &mut Vec<ParsedLine>, state: &mut PagerState,
     state.rebuild_links(content);
 }
 
+/// Regenerates the content after a terminal resize. Wrapped lines only depend
+/// on the width, so when just the height changed the current lines are kept
+/// and `None` is returned.
+fn regenerate_for_resize(
+    regenerator: &mut Option<RegeneratorFn>,
+    rendered_width: &mut u16,
+    new_width: u16,
+    new_height: u16,
+) -> io::Result<Option<Vec<ParsedLine>>> {
+    if new_width == *rendered_width {
+        return Ok(None);
+    }
+    let Some(regen) = regenerator.as_mut() else {
+        return Ok(None);
+    };
+    let regenerated = regen(new_width, new_height).map_err(io::Error::other)?;
+    *rendered_width = new_width;
+    Ok(Some(parse_content_to_lines(&regenerated)))
+}
+
 fn run_interactive_pager(
     mut content: Vec<ParsedLine>,
     mut regenerator: Option<RegeneratorFn>,
@@ -2168,6 +2371,7 @@ fn run_interactive_pager(
         link_callback,
         link_policy,
         force_page: _force_page,
+        key_bindings,
     } = options;
 
     enable_raw_mode()?;
@@ -2178,7 +2382,9 @@ fn run_interactive_pager(
     }
     execute!(stdout, Clear(ClearType::All), MoveTo(0, 0))?;
 
-    let (_, current_height) = terminal::size()?;
+    let (current_width, current_height) = terminal::size()?;
+    // Width the current content was rendered for.
+    let mut rendered_width = current_width;
     let viewport_height = current_height.saturating_sub(1) as usize;
     let mut state = PagerState::new(content.len(), viewport_height, link_policy);
     state.rebuild_links(&content);
@@ -2257,6 +2463,7 @@ fn run_interactive_pager(
                 let mut key_redraw = false;
                 if !handle_key_event(
                     key_event,
+                    &key_bindings,
                     &mut state,
                     &content,
                     &mut key_redraw,
@@ -2315,15 +2522,18 @@ fn run_interactive_pager(
                 state.scroll_offset = old_scroll_offset;
 
                 let mut new_total_lines = prev_total_lines;
-                if let Some(regen) = regenerator.as_mut() {
-                    let regenerated = regen(new_width, new_height).map_err(io::Error::other)?;
-                    let regenerated_lines = parse_content_to_lines(&regenerated);
+                if let Some(regenerated_lines) = regenerate_for_resize(
+                    &mut regenerator,
+                    &mut rendered_width,
+                    new_width,
+                    new_height,
+                )? {
                     new_total_lines = regenerated_lines.len();
                     state.rebuild_search_results(&regenerated_lines, active_match_line);
                     content = regenerated_lines;
                     state.rebuild_links(&content);
-                    needs_redraw = true;
                 }
+                needs_redraw = true;
 
                 state.total_lines = new_total_lines;
 
@@ -2446,3 +2656,233 @@ fn page_output_boxed(
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatter::{Formatter, FormattingStyle};
+    use std::cell::Cell;
+    use std::io::Cursor;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_height_only_resize_reuses_rendered_lines() {
+        let input = include_str!("../tests/data/markdown/progit1-de.md");
+        let document = crate::markdown::parse(Cursor::new(input)).unwrap();
+
+        let renders = Rc::new(Cell::new(0));
+        let counter = renders.clone();
+        let mut regenerator: Option<RegeneratorFn> = Some(Box::new(move |width, _height| {
+            counter.set(counter.get() + 1);
+            let mut style = FormattingStyle::ansi();
+            style.wrap_width = width as usize;
+            let mut output = Vec::new();
+            Formatter::new(&mut output, style)
+                .write_document(&document)
+                .map_err(|err| err.to_string())?;
+            String::from_utf8(output).map_err(|err| err.to_string())
+        }));
+
+        let mut rendered_width = 80;
+        let lines = regenerate_for_resize(&mut regenerator, &mut rendered_width, 100, 40).unwrap();
+        assert!(lines.is_some_and(|lines| !lines.is_empty()));
+        assert_eq!(rendered_width, 100);
+
+        for height in 10..60 {
+            let lines =
+                regenerate_for_resize(&mut regenerator, &mut rendered_width, 100, height).unwrap();
+            assert!(lines.is_none());
+        }
+
+        assert_eq!(renders.get(), 1);
+    }
+
+    #[test]
+    fn test_resolve_link_for_file_origin() {
+        let dir = std::env::temp_dir().join(format!("tdoc-pager-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("other.md"), "# Other").unwrap();
+        let index = dir.join("index.md");
+        let base = LinkBase::File(index.clone());
+        let policy = LinkPolicy::for_file_origin(&index);
+
+        let other = std::fs::canonicalize(dir.join("other.md")).unwrap();
+        assert_eq!(
+            resolve_link(Some(&base), " other.md "),
+            Some(LinkDestination::File(other))
+        );
+        assert_eq!(
+            resolve_link(Some(&base), "#intro"),
+            Some(LinkDestination::Fragment("intro".to_string()))
+        );
+        assert_eq!(resolve_link(Some(&base), "missing.md"), None);
+        assert_eq!(resolve_link(Some(&base), "https://example.com/"), None);
+
+        assert!(policy.activates("other.md"));
+        assert!(policy.activates("#intro"));
+        assert!(!policy.activates("https://example.com/"));
+        assert!(policy.keep_external_links());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_link_for_url_origin() {
+        let url = Url::parse("https://example.com/docs/index.html").unwrap();
+        let base = LinkBase::Url(url.clone());
+        let policy = LinkPolicy::for_url_origin(url);
+
+        assert_eq!(
+            resolve_link(Some(&base), "../about.html"),
+            Some(LinkDestination::Url(
+                Url::parse("https://example.com/about.html").unwrap()
+            ))
+        );
+        assert_eq!(
+            resolve_link(Some(&base), "gemini://example.org/"),
+            Some(LinkDestination::Url(
+                Url::parse("gemini://example.org/").unwrap()
+            ))
+        );
+        assert_eq!(
+            resolve_link(Some(&base), "mailto:someone@example.com"),
+            None
+        );
+
+        assert!(policy.activates("../about.html"));
+        assert!(policy.activates("#top"));
+        assert!(!policy.activates("ftp://example.com/file"));
+        assert!(!policy.keep_external_links());
+    }
+
+    #[test]
+    fn test_resolve_link_without_origin() {
+        let policy = LinkPolicy::for_unknown_origin();
+        assert_eq!(
+            resolve_link(None, "#top"),
+            Some(LinkDestination::Fragment("top".to_string()))
+        );
+        assert_eq!(resolve_link(None, "other.md"), None);
+        assert!(policy.activates("#top"));
+        assert!(!policy.activates("https://example.com/"));
+    }
+
+    #[test]
+    fn test_key_bindings_drive_navigation() {
+        let press = |bindings: &HashMap<PagerAction, Vec<KeyCode>>,
+                     state: &mut PagerState,
+                     code: KeyCode| {
+            let mut redraw = false;
+            let mut link = None;
+            let keep_running = handle_key_event(
+                KeyEvent::new(code, KeyModifiers::NONE),
+                bindings,
+                state,
+                &[],
+                &mut redraw,
+                &mut link,
+            );
+            (keep_running, state.scroll_offset)
+        };
+
+        let defaults = default_key_bindings();
+        let mut state = PagerState::new(100, 10, LinkPolicy::default());
+        assert_eq!(press(&defaults, &mut state, KeyCode::Char('j')), (true, 1));
+        assert_eq!(press(&defaults, &mut state, KeyCode::Down), (true, 2));
+        assert_eq!(press(&defaults, &mut state, KeyCode::Char('k')), (true, 1));
+        assert_eq!(press(&defaults, &mut state, KeyCode::Char('G')), (true, 90));
+        assert_eq!(press(&defaults, &mut state, KeyCode::Char('g')), (true, 0));
+        assert!(!press(&defaults, &mut state, KeyCode::Char('q')).0);
+
+        let mut custom = default_key_bindings();
+        custom.insert(PagerAction::ScrollDown, vec![KeyCode::Char('n')]);
+        custom.insert(PagerAction::Quit, vec![KeyCode::Char('x')]);
+        let mut state = PagerState::new(100, 10, LinkPolicy::default());
+        assert_eq!(press(&custom, &mut state, KeyCode::Char('n')), (true, 1));
+        assert_eq!(press(&custom, &mut state, KeyCode::Char('j')), (true, 1));
+        assert_eq!(press(&custom, &mut state, KeyCode::Char('q')), (true, 1));
+        assert!(!press(&custom, &mut state, KeyCode::Char('x')).0);
+    }
+
+    #[test]
+    fn test_percent_offset() {
+        let state = PagerState::new(110, 10, LinkPolicy::default());
+        assert_eq!(state.percent_offset(0), 0);
+        assert_eq!(state.percent_offset(50), 50);
+        assert_eq!(state.percent_offset(100), 100);
+        assert_eq!(state.percent_offset(250), 100);
+
+        // Rounded up, so the indicator shows the requested percentage.
+        let state = PagerState::new(40, 10, LinkPolicy::default());
+        assert_eq!(state.percent_offset(10), 3);
+        assert_eq!(state.percent_offset(10) * 100 / state.max_scroll(), 10);
+
+        let empty = PagerState::new(0, 10, LinkPolicy::default());
+        assert_eq!(empty.percent_offset(75), 0);
+    }
+
+    #[test]
+    fn test_count_prefix_jumps_to_percentage() {
+        let bindings = default_key_bindings();
+        let mut state = PagerState::new(210, 10, LinkPolicy::default());
+        let type_keys = |state: &mut PagerState, keys: &str| {
+            for key in keys.chars() {
+                let mut redraw = false;
+                let mut link = None;
+                handle_key_event(
+                    KeyEvent::new(KeyCode::Char(key), KeyModifiers::NONE),
+                    &bindings,
+                    state,
+                    &[],
+                    &mut redraw,
+                    &mut link,
+                );
+            }
+            state.scroll_offset
+        };
+
+        assert_eq!(type_keys(&mut state, "25%"), 50);
+        assert_eq!(type_keys(&mut state, "75G"), 150);
+        assert_eq!(type_keys(&mut state, "g"), 0);
+        // A count is dropped by any other command.
+        assert_eq!(type_keys(&mut state, "5j%"), 1);
+        assert_eq!(type_keys(&mut state, "G"), 200);
+    }
+
+    #[test]
+    fn test_markdown_images_are_offered_as_links() {
+        // Markdown images are parsed as links to their source, so the pager
+        // offers them like any other link, subject to the link policy.
+        let dir = std::env::temp_dir().join(format!("tdoc-pager-images-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("diagram.png"), b"").unwrap();
+        let policy = LinkPolicy::for_file_origin(dir.join("index.md"));
+
+        let input = "![Diagram](diagram.png) and ![Photo](missing.png)";
+        let document = crate::markdown::parse(Cursor::new(input)).unwrap();
+        let mut output = Vec::new();
+        Formatter::new(&mut output, FormattingStyle::ansi())
+            .write_document(&document)
+            .unwrap();
+        let content = parse_content_to_lines(&String::from_utf8(output).unwrap());
+        let links = collect_links(&content, &policy);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let offered: Vec<(&str, bool)> = links
+            .iter()
+            .map(|link| (link.url.as_str(), link.activates))
+            .collect();
+        assert_eq!(offered, [("diagram.png", true), ("missing.png", false)]);
+    }
+
+    #[test]
+    fn test_status_line_truncation() {
+        let status = "Error: Unable to fetch https://example.com/a/long/path\nretrying";
+        assert_eq!(truncate_with_padding(status, 20), "Error: Unable to fe…");
+        assert_eq!(truncate_with_padding("Loading ...", 14), "Loading ...   ");
+        assert_eq!(truncate_with_padding("two\nlines", 9), "two lines");
+        // Wide characters are cut off by their display width.
+        assert_eq!(truncate_with_padding("日本語のテキスト", 8), "日本語… ");
+        assert_eq!(truncate_with_padding("anything", 1), "…");
+        assert_eq!(truncate_with_padding("anything", 0), "");
+    }
+}
@@ -3,6 +3,11 @@
 use crate::Span;
 use std::fmt;
 
+/// How deeply the parsers nest quotes, lists and other containers by
+/// default. Deeper input is rejected or flattened (see the `max_depth` parse
+/// options) so rendering it cannot exhaust the stack.
+pub const DEFAULT_MAX_DEPTH: usize = 100;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 /// The structural role a [`Paragraph`] plays within a document.
 pub enum ParagraphType {
@@ -26,8 +31,16 @@ pub enum ParagraphType {
     Quote,
     /// A tabular data block (`<table>`).
     Table,
+    /// A definition list (`<dl>`) of terms and their descriptions.
+    DefinitionList,
+    /// A collapsible section (`<details>`) with a summary line.
+    Details,
+    /// A callout such as a note or warning, written as a labeled block quote.
+    Admonition,
     /// A horizontal rule / thematic break (`<hr>`).
     HorizontalRule,
+    /// Verbatim content intended for a single output format.
+    Raw,
 }
 
 impl fmt::Display for ParagraphType {
@@ -43,7 +56,11 @@ impl fmt::Display for ParagraphType {
             ParagraphType::Checklist => "Checklist",
             ParagraphType::Quote => "Quote",
             ParagraphType::Table => "Table",
+            ParagraphType::DefinitionList => "Definition List",
+            ParagraphType::Details => "Details",
+            ParagraphType::Admonition => "Admonition",
             ParagraphType::HorizontalRule => "Horizontal Rule",
+            ParagraphType::Raw => "Raw",
         };
         write!(f, "{}", s)
     }
@@ -60,6 +77,7 @@ impl ParagraphType {
                 | ParagraphType::Header3
                 | ParagraphType::CodeBlock
                 | ParagraphType::HorizontalRule
+                | ParagraphType::Raw
         )
     }
 
@@ -76,7 +94,11 @@ impl ParagraphType {
             ParagraphType::Checklist => "ul",
             ParagraphType::Quote => "blockquote",
             ParagraphType::Table => "table",
+            ParagraphType::DefinitionList => "dl",
+            ParagraphType::Details => "details",
+            ParagraphType::Admonition => "blockquote",
             ParagraphType::HorizontalRule => "hr",
+            ParagraphType::Raw => "div",
         }
     }
 
@@ -92,6 +114,8 @@ impl ParagraphType {
             "ul" => Some(ParagraphType::UnorderedList),
             "blockquote" => Some(ParagraphType::Quote),
             "table" => Some(ParagraphType::Table),
+            "dl" => Some(ParagraphType::DefinitionList),
+            "details" => Some(ParagraphType::Details),
             "hr" => Some(ParagraphType::HorizontalRule),
             _ => None,
         }
@@ -107,6 +131,7 @@ impl ParagraphType {
         matches!(
             (self, closing),
             (ParagraphType::Checklist, ParagraphType::UnorderedList)
+                | (ParagraphType::Admonition, ParagraphType::Quote)
         )
     }
 }
@@ -136,17 +161,44 @@ impl ParagraphType {
 /// ```
 pub enum Paragraph {
     /// A plain text paragraph with inline spans.
-    Text { content: Vec<Span> },
+    ///
+    /// A `lead` paragraph is the emphasized introduction (standfirst) some
+    /// layouts open with, e.g. `<p class="lead">`.
+    Text { content: Vec<Span>, lead: bool },
     /// A level-1 heading paragraph.
-    Header1 { content: Vec<Span> },
+    ///
+    /// Headings may carry an `anchor`, the id that `#anchor` links in the
+    /// same document point to (e.g. from `<h1 id="anchor">`).
+    Header1 {
+        content: Vec<Span>,
+        anchor: Option<String>,
+    },
     /// A level-2 heading paragraph.
-    Header2 { content: Vec<Span> },
+    Header2 {
+        content: Vec<Span>,
+        anchor: Option<String>,
+    },
     /// A level-3 heading paragraph.
-    Header3 { content: Vec<Span> },
+    Header3 {
+        content: Vec<Span>,
+        anchor: Option<String>,
+    },
     /// A preformatted code block paragraph.
-    CodeBlock { content: Vec<Span> },
+    ///
+    /// `language` holds the info string's language token (e.g. `rust` from a
+    /// Markdown fence opened with `` ```rust ``) when the source provided one.
+    CodeBlock {
+        content: Vec<Span>,
+        language: Option<String>,
+    },
     /// An ordered list paragraph that owns list entries.
-    OrderedList { entries: Vec<Vec<Paragraph>> },
+    ///
+    /// `delimiter` records the punctuation after the item numbers, so
+    /// Markdown lists written as `1)` keep that style.
+    OrderedList {
+        entries: Vec<Vec<Paragraph>>,
+        delimiter: ListDelimiter,
+    },
     /// An unordered/bulleted list paragraph.
     UnorderedList { entries: Vec<Vec<Paragraph>> },
     /// A checklist paragraph with checklist items.
@@ -154,9 +206,35 @@ pub enum Paragraph {
     /// A block quote paragraph that contains nested paragraphs.
     Quote { children: Vec<Paragraph> },
     /// A table paragraph composed of rows of cells.
-    Table { rows: Vec<TableRow> },
+    ///
+    /// `caption` is the optional title shown with the table (e.g. from
+    /// `<caption>`), and `alignments` holds the alignment of each column;
+    /// columns without an entry are left-aligned.
+    Table {
+        rows: Vec<TableRow>,
+        caption: Vec<Span>,
+        alignments: Vec<Alignment>,
+    },
+    /// A definition list pairing terms with their definitions.
+    DefinitionList { items: Vec<DefinitionItem> },
+    /// A collapsible section: a `summary` line and the paragraphs it hides.
+    Details {
+        summary: Vec<Span>,
+        children: Vec<Paragraph>,
+    },
+    /// A callout of the given `kind` (e.g. `> [!WARNING]` in Markdown)
+    /// holding the paragraphs of its body.
+    Admonition {
+        kind: AdmonitionKind,
+        children: Vec<Paragraph>,
+    },
     /// A horizontal rule / thematic break. Carries no content.
     HorizontalRule,
+    /// Pass-through content for one output format.
+    ///
+    /// `format` names the target (e.g. `html`); only the writer for that format
+    /// emits `content`, verbatim. Every other writer drops the paragraph.
+    Raw { format: String, content: String },
 }
 
 impl Paragraph {
@@ -173,7 +251,11 @@ impl Paragraph {
             ParagraphType::Checklist => Self::new_checklist(),
             ParagraphType::Quote => Self::new_quote(),
             ParagraphType::Table => Self::new_table(),
+            ParagraphType::DefinitionList => Self::new_definition_list(),
+            ParagraphType::Details => Self::new_details(),
+            ParagraphType::Admonition => Self::new_admonition(AdmonitionKind::default()),
             ParagraphType::HorizontalRule => Self::new_horizontal_rule(),
+            ParagraphType::Raw => Self::new_raw(String::new(), String::new()),
         }
     }
 
@@ -181,6 +263,7 @@ impl Paragraph {
     pub fn new_text() -> Self {
         Self::Text {
             content: Vec::new(),
+            lead: false,
         }
     }
 
@@ -188,6 +271,7 @@ impl Paragraph {
     pub fn new_header1() -> Self {
         Self::Header1 {
             content: Vec::new(),
+            anchor: None,
         }
     }
 
@@ -195,6 +279,7 @@ impl Paragraph {
     pub fn new_header2() -> Self {
         Self::Header2 {
             content: Vec::new(),
+            anchor: None,
         }
     }
 
@@ -202,6 +287,7 @@ impl Paragraph {
     pub fn new_header3() -> Self {
         Self::Header3 {
             content: Vec::new(),
+            anchor: None,
         }
     }
 
@@ -209,6 +295,7 @@ impl Paragraph {
     pub fn new_code_block() -> Self {
         Self::CodeBlock {
             content: Vec::new(),
+            language: None,
         }
     }
 
@@ -216,6 +303,7 @@ impl Paragraph {
     pub fn new_ordered_list() -> Self {
         Self::OrderedList {
             entries: Vec::new(),
+            delimiter: ListDelimiter::default(),
         }
     }
 
@@ -231,6 +319,23 @@ impl Paragraph {
         Self::Checklist { items: Vec::new() }
     }
 
+    /// Creates a checklist holding `items`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{ChecklistItem, Paragraph};
+    ///
+    /// let checklist = Paragraph::checklist(vec![
+    ///     ChecklistItem::new_text("Release")
+    ///         .with_children(vec![ChecklistItem::new_text("Tag").checked(true)]),
+    /// ]);
+    /// assert_eq!(checklist.checklist_items()[0].children.len(), 1);
+    /// ```
+    pub fn checklist(items: Vec<ChecklistItem>) -> Self {
+        Self::Checklist { items }
+    }
+
     /// Convenience constructor for [`ParagraphType::Quote`].
     pub fn new_quote() -> Self {
         Self::Quote {
@@ -240,7 +345,32 @@ impl Paragraph {
 
     /// Convenience constructor for [`ParagraphType::Table`].
     pub fn new_table() -> Self {
-        Self::Table { rows: Vec::new() }
+        Self::Table {
+            rows: Vec::new(),
+            caption: Vec::new(),
+            alignments: Vec::new(),
+        }
+    }
+
+    /// Convenience constructor for [`ParagraphType::DefinitionList`].
+    pub fn new_definition_list() -> Self {
+        Self::DefinitionList { items: Vec::new() }
+    }
+
+    /// Convenience constructor for [`ParagraphType::Details`].
+    pub fn new_details() -> Self {
+        Self::Details {
+            summary: Vec::new(),
+            children: Vec::new(),
+        }
+    }
+
+    /// Creates an empty [`ParagraphType::Admonition`] of the given `kind`.
+    pub fn new_admonition(kind: AdmonitionKind) -> Self {
+        Self::Admonition {
+            kind,
+            children: Vec::new(),
+        }
     }
 
     /// Convenience constructor for [`ParagraphType::HorizontalRule`].
@@ -248,6 +378,15 @@ impl Paragraph {
         Self::HorizontalRule
     }
 
+    /// Creates a [`ParagraphType::Raw`] paragraph holding `content` verbatim
+    /// for the writer of `format`.
+    pub fn new_raw(format: impl Into<String>, content: impl Into<String>) -> Self {
+        Self::Raw {
+            format: format.into(),
+            content: content.into(),
+        }
+    }
+
     /// Returns the [`ParagraphType`] of the current paragraph.
     pub fn paragraph_type(&self) -> ParagraphType {
         match self {
@@ -261,7 +400,21 @@ impl Paragraph {
             Paragraph::Checklist { .. } => ParagraphType::Checklist,
             Paragraph::Quote { .. } => ParagraphType::Quote,
             Paragraph::Table { .. } => ParagraphType::Table,
+            Paragraph::DefinitionList { .. } => ParagraphType::DefinitionList,
+            Paragraph::Details { .. } => ParagraphType::Details,
+            Paragraph::Admonition { .. } => ParagraphType::Admonition,
             Paragraph::HorizontalRule => ParagraphType::HorizontalRule,
+            Paragraph::Raw { .. } => ParagraphType::Raw,
+        }
+    }
+
+    /// Returns `false` for raw paragraphs whose target format is not `format`,
+    /// i.e. paragraphs the writer for `format` must drop. Format names are
+    /// compared case-insensitively.
+    pub fn is_emitted_in(&self, format: &str) -> bool {
+        match self {
+            Paragraph::Raw { format: target, .. } => target.eq_ignore_ascii_case(format),
+            _ => true,
         }
     }
 
@@ -270,14 +423,51 @@ impl Paragraph {
         self.paragraph_type().is_leaf()
     }
 
+    /// Returns how many levels of paragraphs this one spans: `1` for leaves
+    /// and tables, one more than the deepest nested paragraph otherwise.
+    /// Nested checklist items count as a level each.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::Paragraph;
+    ///
+    /// let quote = Paragraph::new_quote()
+    ///     .with_children(vec![Paragraph::new_quote().with_children(vec![Paragraph::new_text()])]);
+    /// assert_eq!(quote.depth(), 3);
+    /// assert_eq!(Paragraph::new_quote().depth(), 1);
+    /// ```
+    pub fn depth(&self) -> usize {
+        let nested = match self {
+            Paragraph::Quote { children }
+            | Paragraph::Details { children, .. }
+            | Paragraph::Admonition { children, .. } => max_depth_of(children),
+            Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
+                entries
+                    .iter()
+                    .map(|entry| max_depth_of(entry))
+                    .max()
+                    .unwrap_or(0)
+            }
+            Paragraph::DefinitionList { items } => items
+                .iter()
+                .map(|item| max_depth_of(&item.definition))
+                .max()
+                .unwrap_or(0),
+            Paragraph::Checklist { items } => checklist_depth(items).saturating_sub(1),
+            _ => 0,
+        };
+        nested + 1
+    }
+
     /// Returns the inline content for leaf paragraphs, or an empty slice otherwise.
     pub fn content(&self) -> &[Span] {
         match self {
-            Paragraph::Text { content }
-            | Paragraph::Header1 { content }
-            | Paragraph::Header2 { content }
-            | Paragraph::Header3 { content }
-            | Paragraph::CodeBlock { content } => content,
+            Paragraph::Text { content, .. }
+            | Paragraph::Header1 { content, .. }
+            | Paragraph::Header2 { content, .. }
+            | Paragraph::Header3 { content, .. }
+            | Paragraph::CodeBlock { content, .. } => content,
             _ => &[],
         }
     }
@@ -285,11 +475,11 @@ impl Paragraph {
     /// Returns mutable inline content for leaf paragraphs.
     pub fn content_mut(&mut self) -> &mut Vec<Span> {
         match self {
-            Paragraph::Text { content }
-            | Paragraph::Header1 { content }
-            | Paragraph::Header2 { content }
-            | Paragraph::Header3 { content }
-            | Paragraph::CodeBlock { content } => content,
+            Paragraph::Text { content, .. }
+            | Paragraph::Header1 { content, .. }
+            | Paragraph::Header2 { content, .. }
+            | Paragraph::Header3 { content, .. }
+            | Paragraph::CodeBlock { content, .. } => content,
             _ => panic!("only leaf paragraphs contain inline content"),
         }
     }
@@ -297,28 +487,88 @@ impl Paragraph {
     /// Replaces the inline content of the paragraph.
     pub fn with_content(self, content: Vec<Span>) -> Self {
         match self {
-            Paragraph::Text { .. } => Paragraph::Text { content },
-            Paragraph::Header1 { .. } => Paragraph::Header1 { content },
-            Paragraph::Header2 { .. } => Paragraph::Header2 { content },
-            Paragraph::Header3 { .. } => Paragraph::Header3 { content },
-            Paragraph::CodeBlock { .. } => Paragraph::CodeBlock { content },
+            Paragraph::Text { lead, .. } => Paragraph::Text { content, lead },
+            Paragraph::Header1 { anchor, .. } => Paragraph::Header1 { content, anchor },
+            Paragraph::Header2 { anchor, .. } => Paragraph::Header2 { content, anchor },
+            Paragraph::Header3 { anchor, .. } => Paragraph::Header3 { content, anchor },
+            Paragraph::CodeBlock { language, .. } => Paragraph::CodeBlock { content, language },
             _ => panic!("only leaf paragraphs can hold inline content"),
         }
     }
 
-    /// Returns the child paragraphs for quote nodes (or an empty slice).
+    /// Returns the anchor of a heading, if one was recorded.
+    pub fn anchor(&self) -> Option<&str> {
+        match self {
+            Paragraph::Header1 { anchor, .. }
+            | Paragraph::Header2 { anchor, .. }
+            | Paragraph::Header3 { anchor, .. } => anchor.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the anchor of a heading. Only valid for heading paragraphs.
+    pub fn with_anchor(self, anchor: impl Into<String>) -> Self {
+        let anchor = Some(anchor.into());
+        match self {
+            Paragraph::Header1 { content, .. } => Paragraph::Header1 { content, anchor },
+            Paragraph::Header2 { content, .. } => Paragraph::Header2 { content, anchor },
+            Paragraph::Header3 { content, .. } => Paragraph::Header3 { content, anchor },
+            _ => panic!("only headings can carry an anchor"),
+        }
+    }
+
+    /// Returns whether this is a lead text paragraph.
+    pub fn is_lead(&self) -> bool {
+        matches!(self, Paragraph::Text { lead: true, .. })
+    }
+
+    /// Marks a text paragraph as lead paragraph. Only valid for text
+    /// paragraphs.
+    pub fn with_lead(self, lead: bool) -> Self {
+        match self {
+            Paragraph::Text { content, .. } => Paragraph::Text { content, lead },
+            _ => panic!("only text paragraphs can be lead paragraphs"),
+        }
+    }
+
+    /// Returns the language of a code block, if one was recorded.
+    pub fn language(&self) -> Option<&str> {
+        match self {
+            Paragraph::CodeBlock { language, .. } => language.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Sets the language of a code block. Only valid for code block paragraphs.
+    pub fn with_language(self, language: impl Into<String>) -> Self {
+        match self {
+            Paragraph::CodeBlock { content, .. } => Paragraph::CodeBlock {
+                content,
+                language: Some(language.into()),
+            },
+            _ => panic!("only code blocks can carry a language"),
+        }
+    }
+
+    /// Returns the child paragraphs for quote, details and admonition nodes
+    /// (or an empty slice).
     pub fn children(&self) -> &[Paragraph] {
         match self {
-            Paragraph::Quote { children } => children,
+            Paragraph::Quote { children }
+            | Paragraph::Details { children, .. }
+            | Paragraph::Admonition { children, .. } => children,
             _ => &[],
         }
     }
 
-    /// Returns mutable child paragraphs for quote nodes.
+    /// Returns mutable child paragraphs for quote, details and admonition
+    /// nodes.
     pub fn children_mut(&mut self) -> &mut Vec<Paragraph> {
         match self {
-            Paragraph::Quote { children } => children,
-            _ => panic!("only block quotes hold child paragraphs"),
+            Paragraph::Quote { children }
+            | Paragraph::Details { children, .. }
+            | Paragraph::Admonition { children, .. } => children,
+            _ => panic!("only block quotes, details and admonitions hold child paragraphs"),
         }
     }
 
@@ -326,7 +576,33 @@ impl Paragraph {
     pub fn with_children(self, children: Vec<Paragraph>) -> Self {
         match self {
             Paragraph::Quote { .. } => Paragraph::Quote { children },
-            _ => panic!("only block quotes can hold child paragraphs"),
+            Paragraph::Details { summary, .. } => Paragraph::Details { summary, children },
+            Paragraph::Admonition { kind, .. } => Paragraph::Admonition { kind, children },
+            _ => panic!("only block quotes, details and admonitions can hold child paragraphs"),
+        }
+    }
+
+    /// Returns the kind of an admonition.
+    pub fn admonition_kind(&self) -> Option<AdmonitionKind> {
+        match self {
+            Paragraph::Admonition { kind, .. } => Some(*kind),
+            _ => None,
+        }
+    }
+
+    /// Returns the summary line of a details section (or an empty slice).
+    pub fn summary(&self) -> &[Span] {
+        match self {
+            Paragraph::Details { summary, .. } => summary,
+            _ => &[],
+        }
+    }
+
+    /// Replaces the summary line of a details section.
+    pub fn with_summary(self, summary: Vec<Span>) -> Self {
+        match self {
+            Paragraph::Details { children, .. } => Paragraph::Details { summary, children },
+            _ => panic!("only details sections carry a summary"),
         }
     }
 
@@ -338,7 +614,9 @@ impl Paragraph {
     /// Returns the list entries for list paragraphs (or an empty slice).
     pub fn entries(&self) -> &[Vec<Paragraph>] {
         match self {
-            Paragraph::OrderedList { entries } | Paragraph::UnorderedList { entries } => entries,
+            Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
+                entries
+            }
             _ => &[],
         }
     }
@@ -346,7 +624,9 @@ impl Paragraph {
     /// Returns mutable access to list entries for list paragraphs.
     pub fn entries_mut(&mut self) -> &mut Vec<Vec<Paragraph>> {
         match self {
-            Paragraph::OrderedList { entries } | Paragraph::UnorderedList { entries } => entries,
+            Paragraph::OrderedList { entries, .. } | Paragraph::UnorderedList { entries } => {
+                entries
+            }
             _ => panic!("only list paragraphs can hold entries"),
         }
     }
@@ -354,12 +634,31 @@ impl Paragraph {
     /// Replaces the paragraph's list entries.
     pub fn with_entries(self, entries: Vec<Vec<Paragraph>>) -> Self {
         match self {
-            Paragraph::OrderedList { .. } => Paragraph::OrderedList { entries },
+            Paragraph::OrderedList { delimiter, .. } => {
+                Paragraph::OrderedList { entries, delimiter }
+            }
             Paragraph::UnorderedList { .. } => Paragraph::UnorderedList { entries },
             _ => panic!("only list paragraphs can hold entries"),
         }
     }
 
+    /// Returns the delimiter of an ordered list's item numbers.
+    pub fn list_delimiter(&self) -> Option<ListDelimiter> {
+        match self {
+            Paragraph::OrderedList { delimiter, .. } => Some(*delimiter),
+            _ => None,
+        }
+    }
+
+    /// Sets the delimiter of an ordered list's item numbers. Only valid for
+    /// ordered list paragraphs.
+    pub fn with_list_delimiter(self, delimiter: ListDelimiter) -> Self {
+        match self {
+            Paragraph::OrderedList { entries, .. } => Paragraph::OrderedList { entries, delimiter },
+            _ => panic!("only ordered lists can carry a delimiter"),
+        }
+    }
+
     /// Appends a single list item built from nested paragraphs.
     pub fn add_list_item(&mut self, item: Vec<Paragraph>) {
         self.entries_mut().push(item);
@@ -397,7 +696,7 @@ impl Paragraph {
     /// Returns the table rows for table paragraphs (or an empty slice).
     pub fn rows(&self) -> &[TableRow] {
         match self {
-            Paragraph::Table { rows } => rows,
+            Paragraph::Table { rows, .. } => rows,
             _ => &[],
         }
     }
@@ -405,7 +704,7 @@ impl Paragraph {
     /// Returns mutable access to table rows for table paragraphs.
     pub fn rows_mut(&mut self) -> &mut Vec<TableRow> {
         match self {
-            Paragraph::Table { rows } => rows,
+            Paragraph::Table { rows, .. } => rows,
             _ => panic!("only table paragraphs can hold rows"),
         }
     }
@@ -413,15 +712,165 @@ impl Paragraph {
     /// Replaces the paragraph's table rows.
     pub fn with_rows(self, rows: Vec<TableRow>) -> Self {
         match self {
-            Paragraph::Table { .. } => Paragraph::Table { rows },
+            Paragraph::Table {
+                caption,
+                alignments,
+                ..
+            } => Paragraph::Table {
+                rows,
+                caption,
+                alignments,
+            },
             _ => panic!("only table paragraphs can hold rows"),
         }
     }
 
+    /// Returns the caption of a table (or an empty slice).
+    pub fn caption(&self) -> &[Span] {
+        match self {
+            Paragraph::Table { caption, .. } => caption,
+            _ => &[],
+        }
+    }
+
+    /// Replaces the caption of a table paragraph.
+    pub fn with_caption(self, caption: Vec<Span>) -> Self {
+        match self {
+            Paragraph::Table {
+                rows, alignments, ..
+            } => Paragraph::Table {
+                rows,
+                caption,
+                alignments,
+            },
+            _ => panic!("only tables carry a caption"),
+        }
+    }
+
+    /// Returns the column alignments of a table (or an empty slice).
+    pub fn alignments(&self) -> &[Alignment] {
+        match self {
+            Paragraph::Table { alignments, .. } => alignments,
+            _ => &[],
+        }
+    }
+
+    /// Returns the alignment of the table column at `column`, which is
+    /// [`Alignment::Left`] unless set otherwise.
+    pub fn column_alignment(&self, column: usize) -> Alignment {
+        self.alignments().get(column).copied().unwrap_or_default()
+    }
+
+    /// Replaces the column alignments of a table paragraph. Trailing
+    /// left-aligned columns are dropped, as they match the default.
+    pub fn with_alignments(self, mut alignments: Vec<Alignment>) -> Self {
+        while alignments.last() == Some(&Alignment::Left) {
+            alignments.pop();
+        }
+        match self {
+            Paragraph::Table { rows, caption, .. } => Paragraph::Table {
+                rows,
+                caption,
+                alignments,
+            },
+            _ => panic!("only tables carry column alignments"),
+        }
+    }
+
     /// Appends a single row to a table paragraph.
     pub fn add_row(&mut self, row: TableRow) {
         self.rows_mut().push(row);
     }
+
+    /// Returns the items of a definition list (or an empty slice).
+    pub fn definition_items(&self) -> &[DefinitionItem] {
+        match self {
+            Paragraph::DefinitionList { items } => items,
+            _ => &[],
+        }
+    }
+
+    /// Returns mutable access to the items of a definition list.
+    pub fn definition_items_mut(&mut self) -> &mut Vec<DefinitionItem> {
+        match self {
+            Paragraph::DefinitionList { items } => items,
+            _ => panic!("only definition lists can hold definition items"),
+        }
+    }
+
+    /// Replaces the items of a definition list.
+    pub fn with_definition_items(self, items: Vec<DefinitionItem>) -> Self {
+        match self {
+            Paragraph::DefinitionList { .. } => Paragraph::DefinitionList { items },
+            _ => panic!("only definition lists can hold definition items"),
+        }
+    }
+
+    /// Appends a single term/definition pair to a definition list.
+    pub fn add_definition_item(&mut self, item: DefinitionItem) {
+        self.definition_items_mut().push(item);
+    }
+
+    /// Converts an admonition into a block quote that opens with its bold
+    /// label, e.g. **Warning**. Writers for formats without admonitions fall
+    /// back to this shape.
+    pub(crate) fn admonition_as_quote(kind: AdmonitionKind, children: &[Paragraph]) -> Paragraph {
+        let label = Span::new_styled(crate::InlineStyle::Bold)
+            .with_children(vec![Span::new_text(kind.label())]);
+        let mut quote = Vec::with_capacity(children.len() + 1);
+        quote.push(Paragraph::new_text().with_content(vec![label]));
+        quote.extend(children.iter().cloned());
+        Paragraph::new_quote().with_children(quote)
+    }
+
+    /// Converts a definition list into an equivalent unordered list (see
+    /// [`DefinitionItem::to_list_entry`]).
+    pub(crate) fn definition_list_as_unordered_list(items: &[DefinitionItem]) -> Paragraph {
+        Paragraph::new_unordered_list()
+            .with_entries(items.iter().map(DefinitionItem::to_list_entry).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Default)]
+/// A term and its definition inside a [`Paragraph::DefinitionList`].
+///
+/// The term (`<dt>`) holds inline [`Span`](crate::Span) content, while the
+/// definition (`<dd>`) may span several paragraphs, like a list entry.
+pub struct DefinitionItem {
+    pub term: Vec<Span>,
+    pub definition: Vec<Paragraph>,
+}
+
+impl DefinitionItem {
+    /// Creates an empty definition item.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the inline content of the term.
+    pub fn with_term(mut self, term: Vec<Span>) -> Self {
+        self.term = term;
+        self
+    }
+
+    /// Replaces the paragraphs of the definition.
+    pub fn with_definition(mut self, definition: Vec<Paragraph>) -> Self {
+        self.definition = definition;
+        self
+    }
+
+    /// Converts the item into an unordered list entry: the term as a bold
+    /// paragraph, followed by the definition. Writers for formats without
+    /// definition lists fall back to this shape.
+    pub(crate) fn to_list_entry(&self) -> Vec<Paragraph> {
+        let mut entry = Vec::with_capacity(self.definition.len() + 1);
+        if !self.term.is_empty() {
+            let term = Span::new_styled(crate::InlineStyle::Bold).with_children(self.term.clone());
+            entry.push(Paragraph::new_text().with_content(vec![term]));
+        }
+        entry.extend(self.definition.iter().cloned());
+        entry
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -455,8 +904,8 @@ impl TableRow {
 /// A single cell inside a [`TableRow`].
 ///
 /// Cells hold inline [`Span`](crate::Span) content and a flag distinguishing
-/// header cells (`<th>`) from data cells (`<td>`). Tables use left-aligned
-/// content by default and do not carry any explicit alignment information.
+/// header cells (`<th>`) from data cells (`<td>`). Alignment is a property of
+/// the whole column; see [`Paragraph::alignments`].
 pub struct TableCell {
     pub is_header: bool,
     pub content: Vec<Span>,
@@ -488,6 +937,100 @@ impl TableCell {
     }
 }
 
+fn max_depth_of(paragraphs: &[Paragraph]) -> usize {
+    paragraphs.iter().map(Paragraph::depth).max().unwrap_or(0)
+}
+
+/// Levels of checklist items in `items`.
+fn checklist_depth(items: &[ChecklistItem]) -> usize {
+    items
+        .iter()
+        .map(|item| checklist_depth(&item.children) + 1)
+        .max()
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The punctuation following the number of an ordered list item.
+pub enum ListDelimiter {
+    /// `1.`, the default.
+    #[default]
+    Period,
+    /// `1)`
+    Parenthesis,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+/// The horizontal alignment of a table column.
+pub enum Alignment {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+/// The kind of a [`Paragraph::Admonition`], following the alerts of
+/// GitHub-flavored Markdown.
+pub enum AdmonitionKind {
+    #[default]
+    Note,
+    Tip,
+    Important,
+    Warning,
+    Caution,
+}
+
+impl AdmonitionKind {
+    /// Every admonition kind, in the order GitHub documents them.
+    pub const ALL: [AdmonitionKind; 5] = [
+        AdmonitionKind::Note,
+        AdmonitionKind::Tip,
+        AdmonitionKind::Important,
+        AdmonitionKind::Warning,
+        AdmonitionKind::Caution,
+    ];
+
+    /// Returns the human-readable label, e.g. `Warning`.
+    pub fn label(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "Note",
+            AdmonitionKind::Tip => "Tip",
+            AdmonitionKind::Important => "Important",
+            AdmonitionKind::Warning => "Warning",
+            AdmonitionKind::Caution => "Caution",
+        }
+    }
+
+    /// Returns the marker name used in Markdown (`[!WARNING]`).
+    pub fn marker(self) -> &'static str {
+        match self {
+            AdmonitionKind::Note => "NOTE",
+            AdmonitionKind::Tip => "TIP",
+            AdmonitionKind::Important => "IMPORTANT",
+            AdmonitionKind::Warning => "WARNING",
+            AdmonitionKind::Caution => "CAUTION",
+        }
+    }
+
+    /// Looks up a kind by its marker name, ignoring case.
+    pub fn from_marker(marker: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.marker().eq_ignore_ascii_case(marker))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// The completion state of a [`ChecklistItem`], taking nested items into
+/// account. See [`ChecklistItem::state`].
+pub enum ChecklistState {
+    Unchecked,
+    Checked,
+    /// Some nested items are done and others are not.
+    Mixed,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// Represents a single item within a checklist.
 ///
@@ -510,6 +1053,17 @@ impl ChecklistItem {
         }
     }
 
+    /// Creates an unchecked item whose content is the plain `text`.
+    pub fn new_text(text: impl Into<String>) -> Self {
+        Self::new(false).with_content(vec![Span::new_text(text)])
+    }
+
+    /// Sets the completion state, returning the updated item.
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
     /// Replaces the inline content of the checklist item.
     pub fn with_content(mut self, content: Vec<Span>) -> Self {
         self.content = content;
@@ -526,6 +1080,35 @@ impl ChecklistItem {
     pub fn add_child(&mut self, child: ChecklistItem) {
         self.children.push(child);
     }
+
+    /// Returns the item's state: [`ChecklistState::Mixed`] when its nested
+    /// items disagree (or any of them is mixed itself), and otherwise the
+    /// item's own `checked` flag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use tdoc::{ChecklistItem, ChecklistState};
+    ///
+    /// let parent = ChecklistItem::new_text("Release").with_children(vec![
+    ///     ChecklistItem::new_text("Notes").checked(true),
+    ///     ChecklistItem::new_text("Tag"),
+    /// ]);
+    /// assert_eq!(parent.state(), ChecklistState::Mixed);
+    /// ```
+    pub fn state(&self) -> ChecklistState {
+        let mut states = self.children.iter().map(ChecklistItem::state);
+        if let Some(first) = states.next() {
+            if first == ChecklistState::Mixed || states.any(|state| state != first) {
+                return ChecklistState::Mixed;
+            }
+        }
+        if self.checked {
+            ChecklistState::Checked
+        } else {
+            ChecklistState::Unchecked
+        }
+    }
 }
 
 #[cfg(test)]
@@ -559,6 +1142,103 @@ mod tests {
         assert!(!ParagraphType::Quote.is_leaf());
     }
 
+    /// Every paragraph type. `type_index` fails to compile when a variant is
+    /// added, so it cannot be left out here.
+    const ALL_TYPES: [ParagraphType; 15] = [
+        ParagraphType::Text,
+        ParagraphType::Header1,
+        ParagraphType::Header2,
+        ParagraphType::Header3,
+        ParagraphType::CodeBlock,
+        ParagraphType::OrderedList,
+        ParagraphType::UnorderedList,
+        ParagraphType::Checklist,
+        ParagraphType::Quote,
+        ParagraphType::Table,
+        ParagraphType::DefinitionList,
+        ParagraphType::Details,
+        ParagraphType::Admonition,
+        ParagraphType::HorizontalRule,
+        ParagraphType::Raw,
+    ];
+
+    fn type_index(paragraph_type: ParagraphType) -> usize {
+        match paragraph_type {
+            ParagraphType::Text => 0,
+            ParagraphType::Header1 => 1,
+            ParagraphType::Header2 => 2,
+            ParagraphType::Header3 => 3,
+            ParagraphType::CodeBlock => 4,
+            ParagraphType::OrderedList => 5,
+            ParagraphType::UnorderedList => 6,
+            ParagraphType::Checklist => 7,
+            ParagraphType::Quote => 8,
+            ParagraphType::Table => 9,
+            ParagraphType::DefinitionList => 10,
+            ParagraphType::Details => 11,
+            ParagraphType::Admonition => 12,
+            ParagraphType::HorizontalRule => 13,
+            ParagraphType::Raw => 14,
+        }
+    }
+
+    #[test]
+    fn test_every_paragraph_type_is_classified() {
+        for (idx, paragraph_type) in ALL_TYPES.into_iter().enumerate() {
+            assert_eq!(type_index(paragraph_type), idx, "{paragraph_type}");
+
+            // Leaves hold inline content (or nothing); everything else nests
+            // paragraphs, list entries, items or rows the HTML parser must
+            // collect before the closing tag.
+            let expected_leaf = match paragraph_type {
+                ParagraphType::Text
+                | ParagraphType::Header1
+                | ParagraphType::Header2
+                | ParagraphType::Header3
+                | ParagraphType::CodeBlock
+                | ParagraphType::HorizontalRule
+                | ParagraphType::Raw => true,
+                ParagraphType::OrderedList
+                | ParagraphType::UnorderedList
+                | ParagraphType::Checklist
+                | ParagraphType::Quote
+                | ParagraphType::Table
+                | ParagraphType::DefinitionList
+                | ParagraphType::Details
+                | ParagraphType::Admonition => false,
+            };
+            assert_eq!(paragraph_type.is_leaf(), expected_leaf, "{paragraph_type}");
+            let paragraph = Paragraph::new(paragraph_type);
+            assert_eq!(paragraph.paragraph_type(), paragraph_type);
+            assert_eq!(paragraph.is_leaf(), expected_leaf, "{paragraph_type}");
+
+            // A paragraph is closed by the end tag it was opened with.
+            match ParagraphType::from_html_tag(paragraph_type.html_tag()) {
+                Some(closing) => {
+                    assert!(
+                        paragraph_type.matches_closing_tag(closing),
+                        "{paragraph_type} is not closed by </{}>",
+                        paragraph_type.html_tag()
+                    );
+                }
+                None => assert_eq!(paragraph_type, ParagraphType::Raw),
+            }
+
+            for closing in ALL_TYPES {
+                let expected = closing == paragraph_type
+                    || (paragraph_type == ParagraphType::Checklist
+                        && closing == ParagraphType::UnorderedList)
+                    || (paragraph_type == ParagraphType::Admonition
+                        && closing == ParagraphType::Quote);
+                assert_eq!(
+                    paragraph_type.matches_closing_tag(closing),
+                    expected,
+                    "{paragraph_type} closed by {closing}"
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_paragraph_creation() {
         let p = Paragraph::new_text().with_content(vec![Span::new_text("Hello")]);
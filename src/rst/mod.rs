@@ -0,0 +1,643 @@
+//! Export [`Document`](crate::Document) trees as reStructuredText.
+//!
+//! reStructuredText cannot nest inline markup, so styled spans inside other
+//! styled spans are flattened to the outermost style. Styles without an RST
+//! equivalent (underline, strikethrough, highlight, abbreviations, colors) are written
+//! as plain text. Raw paragraphs for other formats become `.. raw::`
+//! directives, so docutils can still pass them through to that output.
+
+use crate::{ChecklistItem, Document, InlineStyle, Paragraph, Span, TableRow};
+use std::io::Write;
+use unicode_width::UnicodeWidthStr;
+
+/// Indentation used for literal blocks and block quotes.
+const INDENT: &str = "    ";
+
+/// Serializes a [`Document`] structure to reStructuredText.
+///
+/// # Examples
+///
+/// ```
+/// use tdoc::{Document, Paragraph, Span};
+/// use tdoc::rst;
+///
+/// let heading = Paragraph::new_header2().with_content(vec![Span::new_text("Hello")]);
+/// let document = Document::new().with_paragraphs(vec![heading]);
+///
+/// let mut output = Vec::new();
+/// rst::write(&mut output, &document).unwrap();
+/// assert_eq!(String::from_utf8(output).unwrap(), "Hello\n=====\n");
+/// ```
+pub fn write<W: Write>(writer: &mut W, document: &Document) -> std::io::Result<()> {
+    write_paragraphs(writer, &document.paragraphs, "", "")
+}
+
+fn write_paragraphs<W: Write>(
+    writer: &mut W,
+    paragraphs: &[Paragraph],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    for (i, paragraph) in paragraphs.iter().enumerate() {
+        let mut current_prefix = prefix;
+        if i > 0 {
+            writeln!(writer)?;
+            current_prefix = continuation_prefix;
+
+            // An indented block directly after a list or another indented block
+            // would be read as its continuation; an empty comment ends it.
+            if matches!(paragraph, Paragraph::Quote { .. })
+                && ends_with_indented_block(&paragraphs[i - 1])
+            {
+                writeln!(writer, "{}..", continuation_prefix)?;
+                writeln!(writer)?;
+            }
+        }
+        write_paragraph(writer, paragraph, current_prefix, continuation_prefix)?;
+    }
+    Ok(())
+}
+
+fn ends_with_indented_block(paragraph: &Paragraph) -> bool {
+    matches!(
+        paragraph,
+        Paragraph::OrderedList { .. }
+            | Paragraph::UnorderedList { .. }
+            | Paragraph::Checklist { .. }
+            | Paragraph::Quote { .. }
+            | Paragraph::Admonition { .. }
+            | Paragraph::CodeBlock { .. }
+            | Paragraph::Table { .. }
+            | Paragraph::DefinitionList { .. }
+    ) || !paragraph.is_emitted_in("rst")
+}
+
+fn write_paragraph<W: Write>(
+    writer: &mut W,
+    paragraph: &Paragraph,
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    match paragraph {
+        Paragraph::Text { content, .. } => {
+            let text = render_spans(content);
+            if text.contains('\n') {
+                // Hard line breaks are only preserved inside line blocks.
+                for (idx, line) in text.split('\n').enumerate() {
+                    let line_prefix = if idx == 0 {
+                        prefix
+                    } else {
+                        continuation_prefix
+                    };
+                    writeln!(writer, "{}| {}", line_prefix, line)?;
+                }
+            } else {
+                writeln!(writer, "{}{}", prefix, text)?;
+            }
+        }
+        Paragraph::Header1 { content, .. } => {
+            write_heading(writer, content, '=', true, prefix, continuation_prefix)?
+        }
+        Paragraph::Header2 { content, .. } => {
+            write_heading(writer, content, '=', false, prefix, continuation_prefix)?
+        }
+        Paragraph::Header3 { content, .. } => {
+            write_heading(writer, content, '-', false, prefix, continuation_prefix)?
+        }
+        Paragraph::CodeBlock { content, .. } => {
+            let mut text = String::new();
+            for span in content {
+                collect_plain_text(span, &mut text);
+            }
+            writeln!(writer, "{}::", prefix)?;
+            writeln!(writer)?;
+            for line in text.trim_end_matches('\n').split('\n') {
+                if line.is_empty() {
+                    writeln!(writer)?;
+                } else {
+                    writeln!(writer, "{}{}{}", continuation_prefix, INDENT, line)?;
+                }
+            }
+        }
+        Paragraph::Quote { children } => {
+            let quote_prefix = format!("{}{}", continuation_prefix, INDENT);
+            write_paragraphs(writer, children, &quote_prefix, &quote_prefix)?;
+        }
+        Paragraph::Admonition { kind, children } => {
+            // Every admonition kind has a directive of the same name.
+            writeln!(
+                writer,
+                "{}.. {}::",
+                prefix,
+                kind.marker().to_ascii_lowercase()
+            )?;
+            if children.iter().any(|p| p.is_emitted_in("rst")) {
+                writeln!(writer)?;
+                let body_prefix = format!("{}{}", continuation_prefix, INDENT);
+                write_paragraphs(writer, children, &body_prefix, &body_prefix)?;
+            }
+        }
+        Paragraph::UnorderedList { entries } => {
+            for (i, entry) in entries.iter().enumerate() {
+                let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+                if i > 0 && !is_simple_entry(&entries[i - 1]) {
+                    writeln!(writer)?;
+                }
+                write_list_entry(writer, entry, "- ", item_prefix, continuation_prefix)?;
+            }
+        }
+        Paragraph::OrderedList { entries, .. } => {
+            for (i, entry) in entries.iter().enumerate() {
+                let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+                if i > 0 && !is_simple_entry(&entries[i - 1]) {
+                    writeln!(writer)?;
+                }
+                let marker = format!("{}. ", i + 1);
+                write_list_entry(writer, entry, &marker, item_prefix, continuation_prefix)?;
+            }
+        }
+        Paragraph::Checklist { items } => {
+            write_checklist_items(writer, items, prefix, continuation_prefix)?;
+        }
+        Paragraph::Table { rows, .. } => {
+            write_table(writer, rows, prefix, continuation_prefix)?;
+        }
+        Paragraph::Details { summary, children } => {
+            // reStructuredText has no collapsible sections; a bold line
+            // introduces the body.
+            let mut body_prefix = prefix;
+            if !summary.is_empty() {
+                let title = render_spans(summary).replace('\n', " ");
+                writeln!(writer, "{}**{}**", prefix, title)?;
+                body_prefix = continuation_prefix;
+                if children.iter().any(|p| p.is_emitted_in("rst")) {
+                    writeln!(writer)?;
+                }
+            }
+            write_paragraphs(writer, children, body_prefix, continuation_prefix)?;
+        }
+        Paragraph::DefinitionList { items } => {
+            // Each term on its own line, its definition indented below it.
+            let definition_prefix = format!("{}{}", continuation_prefix, INDENT);
+            for (i, item) in items.iter().enumerate() {
+                let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+                if i > 0 {
+                    writeln!(writer)?;
+                }
+                let term = render_spans(&item.term).replace('\n', " ");
+                writeln!(writer, "{}{}", item_prefix, term)?;
+                write_paragraphs(
+                    writer,
+                    &item.definition,
+                    &definition_prefix,
+                    &definition_prefix,
+                )?;
+            }
+        }
+        Paragraph::HorizontalRule => {
+            writeln!(writer, "{}----", prefix)?;
+        }
+        Paragraph::Raw { format, content } => {
+            if paragraph.is_emitted_in("rst") {
+                for (idx, line) in content.lines().enumerate() {
+                    let line_prefix = if idx == 0 {
+                        prefix
+                    } else {
+                        continuation_prefix
+                    };
+                    writeln!(writer, "{}{}", line_prefix, line)?;
+                }
+            } else {
+                writeln!(writer, "{}.. raw:: {}", prefix, format)?;
+                writeln!(writer)?;
+                for line in content.lines() {
+                    if line.is_empty() {
+                        writeln!(writer)?;
+                    } else {
+                        writeln!(writer, "{}{}{}", continuation_prefix, INDENT, line)?;
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_heading<W: Write>(
+    writer: &mut W,
+    content: &[Span],
+    adornment: char,
+    overline: bool,
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    // Section titles must fit on a single line.
+    let title = render_spans(content).replace('\n', " ");
+    let rule = adornment
+        .to_string()
+        .repeat(UnicodeWidthStr::width(title.as_str()).max(1));
+
+    if overline {
+        writeln!(writer, "{}{}", prefix, rule)?;
+        writeln!(writer, "{}{}", continuation_prefix, title)?;
+    } else {
+        writeln!(writer, "{}{}", prefix, title)?;
+    }
+    writeln!(writer, "{}{}", continuation_prefix, rule)
+}
+
+fn write_list_entry<W: Write>(
+    writer: &mut W,
+    entry: &[Paragraph],
+    marker: &str,
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    let first_prefix = format!("{}{}", prefix, marker);
+    let item_continuation = format!("{}{}", continuation_prefix, " ".repeat(marker.len()));
+
+    if entry.is_empty() {
+        return writeln!(writer, "{}", first_prefix.trim_end());
+    }
+
+    for (i, paragraph) in entry.iter().enumerate() {
+        if i > 0 {
+            writeln!(writer)?;
+        }
+        let paragraph_prefix = if i == 0 {
+            first_prefix.as_str()
+        } else {
+            item_continuation.as_str()
+        };
+        // Nested lists must be separated from the item text by a blank line and
+        // may not start on the marker line.
+        if i == 0 && is_list(paragraph) {
+            writeln!(writer, "{}", first_prefix.trim_end())?;
+            writeln!(writer)?;
+            write_paragraph(writer, paragraph, &item_continuation, &item_continuation)?;
+        } else {
+            write_paragraph(writer, paragraph, paragraph_prefix, &item_continuation)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns `true` for entries consisting of at most one leaf paragraph. Any
+/// other entry ends in an indented block, which must be followed by a blank
+/// line before the next item.
+fn is_simple_entry(entry: &[Paragraph]) -> bool {
+    entry.len() <= 1 && entry.iter().all(|paragraph| paragraph.is_leaf())
+}
+
+fn is_list(paragraph: &Paragraph) -> bool {
+    matches!(
+        paragraph,
+        Paragraph::OrderedList { .. }
+            | Paragraph::UnorderedList { .. }
+            | Paragraph::Checklist { .. }
+    )
+}
+
+fn write_checklist_items<W: Write>(
+    writer: &mut W,
+    items: &[ChecklistItem],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    for (i, item) in items.iter().enumerate() {
+        let item_prefix = if i == 0 { prefix } else { continuation_prefix };
+        let marker = if item.checked { "[x]" } else { "[ ]" };
+        writeln!(
+            writer,
+            "{}- {} {}",
+            item_prefix,
+            marker,
+            render_spans(&item.content).replace('\n', " ")
+        )?;
+
+        if !item.children.is_empty() {
+            let child_prefix = format!("{}  ", continuation_prefix);
+            writeln!(writer)?;
+            write_checklist_items(writer, &item.children, &child_prefix, &child_prefix)?;
+            if i + 1 < items.len() {
+                writeln!(writer)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn write_table<W: Write>(
+    writer: &mut W,
+    rows: &[TableRow],
+    prefix: &str,
+    continuation_prefix: &str,
+) -> std::io::Result<()> {
+    let column_count = rows.iter().map(|row| row.cells.len()).max().unwrap_or(0);
+    if column_count == 0 {
+        return Ok(());
+    }
+
+    // Simple and grid tables cannot hold arbitrary cell content, so use the
+    // `list-table` directive, which only needs a two-level bullet list.
+    let body_prefix = format!("{}   ", continuation_prefix);
+    writeln!(writer, "{}.. list-table::", prefix)?;
+    let header_rows = rows
+        .iter()
+        .take_while(|row| !row.cells.is_empty() && row.cells.iter().all(|cell| cell.is_header))
+        .count();
+    if header_rows > 0 {
+        writeln!(writer, "{}:header-rows: {}", body_prefix, header_rows)?;
+    }
+    writeln!(writer)?;
+
+    for row in rows {
+        for col in 0..column_count {
+            let marker = if col == 0 { "* - " } else { "  - " };
+            let text = row
+                .cells
+                .get(col)
+                .map(|cell| render_spans(&cell.content).replace('\n', " "))
+                .unwrap_or_default();
+            writeln!(
+                writer,
+                "{}{}",
+                body_prefix,
+                format!("{}{}", marker, text).trim_end()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Renders inline spans to a single RST string.
+fn render_spans(spans: &[Span]) -> String {
+    let mut renderer = InlineRenderer::default();
+    for span in spans {
+        renderer.push_span(span);
+    }
+    renderer.output
+}
+
+#[derive(Default)]
+struct InlineRenderer {
+    output: String,
+    // Set after inline markup ends, so that an immediately following word
+    // character gets an escaped space (`\ `) to keep the end-string valid.
+    after_markup: bool,
+}
+
+impl InlineRenderer {
+    fn push_span(&mut self, span: &Span) {
+        match span.style {
+            InlineStyle::Bold => self.push_markup(span, "**", "**"),
+            InlineStyle::Italic => self.push_markup(span, "*", "*"),
+            InlineStyle::Superscript => self.push_markup(span, ":sup:`", "`"),
+            InlineStyle::Subscript => self.push_markup(span, ":sub:`", "`"),
+            InlineStyle::Code | InlineStyle::Keyboard => self.push_code(span),
+            InlineStyle::Link => self.push_link(span),
+            InlineStyle::Quotation => {
+                self.push_text("\"");
+                if !span.text.is_empty() {
+                    self.push_text(&escape_rst(&span.text));
+                }
+                for child in &span.children {
+                    self.push_span(child);
+                }
+                self.push_text("\"");
+            }
+            InlineStyle::Raw => {
+                if let Some(raw) = span.raw_content_for("rst") {
+                    self.output.push_str(raw);
+                    self.after_markup = false;
+                }
+            }
+            InlineStyle::None
+            | InlineStyle::Underline
+            | InlineStyle::Strike
+            | InlineStyle::Highlight
+            | InlineStyle::Abbreviation
+            | InlineStyle::Color => {
+                if !span.text.is_empty() {
+                    self.push_text(&escape_rst(&span.text));
+                }
+                for child in &span.children {
+                    self.push_span(child);
+                }
+            }
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        if text.is_empty() {
+            return;
+        }
+        if self.after_markup && text.starts_with(|ch: char| ch.is_alphanumeric()) {
+            self.output.push_str("\\ ");
+        }
+        self.after_markup = false;
+        self.output.push_str(text);
+    }
+
+    /// Appends inline markup, moving surrounding whitespace outside the
+    /// delimiters (RST forbids whitespace directly inside them).
+    fn push_inline(&mut self, leading: &str, markup: &str, trailing: &str) {
+        self.push_text(leading);
+        if self.output.ends_with(|ch: char| ch.is_alphanumeric()) || self.after_markup {
+            self.output.push_str("\\ ");
+        }
+        self.output.push_str(markup);
+        self.after_markup = true;
+        self.push_text(trailing);
+    }
+
+    fn push_markup(&mut self, span: &Span, start: &str, end: &str) {
+        let mut text = String::new();
+        collect_plain_text(span, &mut text);
+        let text = text.replace('\n', " ");
+        let (leading, inner, trailing) = split_whitespace(&text);
+        if inner.is_empty() {
+            self.push_text(&text);
+            return;
+        }
+        let markup = format!("{}{}{}", start, escape_rst(inner), end);
+        self.push_inline(leading, &markup, trailing);
+    }
+
+    fn push_code(&mut self, span: &Span) {
+        let mut text = String::new();
+        collect_plain_text(span, &mut text);
+        let text = text.replace('\n', " ");
+        let (leading, inner, trailing) = split_whitespace(&text);
+        if inner.is_empty() {
+            self.push_text(&text);
+            return;
+        }
+        // Inline literals are not escaped; their content is taken verbatim.
+        let markup = format!("``{}``", inner);
+        self.push_inline(leading, &markup, trailing);
+    }
+
+    fn push_link(&mut self, span: &Span) {
+        let Some(target) = span.link_target.as_deref() else {
+            let mut text = String::new();
+            collect_plain_text(span, &mut text);
+            self.push_text(&escape_rst(&text));
+            return;
+        };
+
+        let mut text = String::new();
+        collect_plain_text(span, &mut text);
+        let text = text.replace('\n', " ");
+        let (leading, inner, trailing) = split_whitespace(&text);
+        let markup = if inner.is_empty() {
+            format!("`<{}>`_", target)
+        } else {
+            format!("`{} <{}>`_", escape_reference_text(inner), target)
+        };
+        self.push_inline(leading, &markup, trailing);
+    }
+}
+
+fn split_whitespace(text: &str) -> (&str, &str, &str) {
+    let trimmed_start = text.trim_start();
+    let leading = &text[..text.len() - trimmed_start.len()];
+    let inner = trimmed_start.trim_end();
+    let trailing = &trimmed_start[inner.len()..];
+    (leading, inner, trailing)
+}
+
+/// Escapes characters that would otherwise start inline markup.
+fn escape_rst(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '*' | '`' | '_' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Escapes the text part of an embedded-URI reference, where `<` would be
+/// mistaken for the start of the target.
+fn escape_reference_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '`' | '<') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn collect_plain_text(span: &Span, buffer: &mut String) {
+    if span.style == InlineStyle::Raw {
+        return;
+    }
+    if !span.text.is_empty() {
+        buffer.push_str(&span.text);
+    }
+    for child in &span.children {
+        collect_plain_text(child, buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_helpers::*;
+
+    fn render(document: &Document) -> String {
+        let mut output = Vec::new();
+        write(&mut output, document).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_write_headings_sized_to_title() {
+        let doc = doc(vec![h1_("Title"), h2_("Größe"), h3_("日本")]);
+        assert_eq!(
+            render(&doc),
+            "=====\nTitle\n=====\n\nGröße\n=====\n\n日本\n----\n"
+        );
+    }
+
+    #[test]
+    fn test_write_heading_with_markup() {
+        let doc = doc(vec![Paragraph::new_header2().with_content(vec![
+            span("A "),
+            b__("bold"),
+            span(" move"),
+        ])]);
+        assert_eq!(render(&doc), "A **bold** move\n===============\n");
+    }
+
+    #[test]
+    fn test_write_raw_blocks() {
+        let doc = doc(vec![
+            Paragraph::new_raw("html", "<video src=\"clip.mp4\">\n</video>"),
+            Paragraph::new_raw("rst", ".. note:: Kept verbatim."),
+        ]);
+        assert_eq!(
+            render(&doc),
+            ".. raw:: html\n\n    <video src=\"clip.mp4\">\n    </video>\n\n.. note:: Kept verbatim.\n"
+        );
+    }
+
+    #[test]
+    fn test_write_links() {
+        let doc = doc(vec![p_(vec![
+            span("See "),
+            link_text__("https://example.com", "the docs"),
+            span(" or "),
+            link__("https://example.org"),
+            span("."),
+        ])]);
+        assert_eq!(
+            render(&doc),
+            "See `the docs <https://example.com>`_ or `<https://example.org>`_.\n"
+        );
+    }
+
+    #[test]
+    fn test_write_inline_styles() {
+        let doc = doc(vec![p_(vec![
+            b__("bold"),
+            span(" "),
+            i__("italic "),
+            span("and "),
+            code__("x*y"),
+            span(" a*b_"),
+        ])]);
+        assert_eq!(render(&doc), "**bold** *italic* and ``x*y`` a\\*b\\_\n");
+    }
+
+    #[test]
+    fn test_write_intraword_markup_is_separated() {
+        let doc = doc(vec![p_(vec![span("un"), b__("believ"), span("able")])]);
+        assert_eq!(render(&doc), "un\\ **believ**\\ able\n");
+    }
+
+    #[test]
+    fn test_write_literal_block_and_quote() {
+        let doc = doc(vec![
+            code_block__("fn main() {\n\n    run();\n}\n"),
+            quote_(vec![p__("Quoted")]),
+        ]);
+        assert_eq!(
+            render(&doc),
+            "::\n\n    fn main() {\n\n        run();\n    }\n\n..\n\n    Quoted\n"
+        );
+    }
+
+    #[test]
+    fn test_write_nested_lists() {
+        let doc = doc(vec![ol_(vec![
+            li_(vec![p__("First"), ul_(vec![li_(vec![p__("Inner")])])]),
+            li_(vec![p__("Second")]),
+        ])]);
+        assert_eq!(render(&doc), "1. First\n\n   - Inner\n\n2. Second\n");
+    }
+}
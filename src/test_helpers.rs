@@ -1,6 +1,9 @@
 //! Convenience constructors for assembling documents in tests.
 
-use crate::{Document, InlineStyle, Paragraph, Span};
+use crate::{
+    AdmonitionKind, ChecklistItem, Color, DefinitionItem, Document, InlineStyle, Paragraph, Span,
+    TableCell, TableRow,
+};
 
 pub fn p__(s: &str) -> Paragraph {
     Paragraph::new_text().with_content(vec![span(s)])
@@ -22,6 +25,18 @@ pub fn ol_(entries: Vec<Vec<Paragraph>>) -> Paragraph {
     Paragraph::new_ordered_list().with_entries(entries)
 }
 
+pub fn checklist_(items: Vec<ChecklistItem>) -> Paragraph {
+    Paragraph::checklist(items)
+}
+
+pub fn task__(checked: bool, s: &str) -> ChecklistItem {
+    ChecklistItem::new_text(s).checked(checked)
+}
+
+pub fn task_(checked: bool, s: &str, children: Vec<ChecklistItem>) -> ChecklistItem {
+    task__(checked, s).with_children(children)
+}
+
 pub fn h1_(s: &str) -> Paragraph {
     Paragraph::new_header1().with_content(vec![span(s)])
 }
@@ -86,12 +101,42 @@ pub fn s__(txt: &str) -> Span {
     Span::new_styled(InlineStyle::Strike).with_children(spans(txt))
 }
 
+pub fn sup__(txt: &str) -> Span {
+    Span::new_styled(InlineStyle::Superscript).with_children(spans(txt))
+}
+
+pub fn sub__(txt: &str) -> Span {
+    Span::new_styled(InlineStyle::Subscript).with_children(spans(txt))
+}
+
+pub fn kbd__(txt: &str) -> Span {
+    Span::new_styled(InlineStyle::Keyboard).with_children(spans(txt))
+}
+
+pub fn q_(args: Vec<Span>) -> Span {
+    Span::new_styled(InlineStyle::Quotation).with_children(args)
+}
+
+pub fn q__(txt: &str) -> Span {
+    Span::new_styled(InlineStyle::Quotation).with_children(spans(txt))
+}
+
+pub fn abbr__(title: &str, txt: &str) -> Span {
+    Span::new_styled(InlineStyle::Abbreviation)
+        .with_link_target(title)
+        .with_children(spans(txt))
+}
+
+pub fn color__(color: &str, txt: &str) -> Span {
+    Span::new_colored(Color::parse(color).expect("valid color")).with_children(spans(txt))
+}
+
 pub fn code__(txt: &str) -> Span {
     Span::new_styled(InlineStyle::Code).with_children(spans(txt))
 }
 
 pub fn link__(target: &str) -> Span {
-    Span::new_styled(InlineStyle::Link).with_link_target(target)
+    Span::link(target)
 }
 
 pub fn link_text__(target: &str, text: &str) -> Span {
@@ -105,3 +150,47 @@ pub fn link_(target: &str, children: Vec<Span>) -> Span {
         .with_link_target(target)
         .with_children(children)
 }
+
+/// A document using every paragraph type and every inline style.
+pub fn every_type_document() -> Document {
+    doc(vec![
+        h1_("Heading 1"),
+        h2_("Heading 2"),
+        h3_("Heading 3"),
+        p_(vec![
+            span("Plain "),
+            b__("bold"),
+            i__("italic"),
+            mark__("marked"),
+            u__("underlined"),
+            s__("struck"),
+            link_text__("https://example.com", "link"),
+            code__("code"),
+            sup__("2"),
+            sub__("i"),
+            kbd__("Ctrl"),
+            abbr__("HyperText Markup Language", "HTML"),
+            q__("quoted"),
+            color__("red", "red"),
+            Span::new_raw("html", "<wbr>"),
+        ]),
+        code_block__("fn main() {}"),
+        ol_(vec![li_(vec![p__("first")])]),
+        ul_(vec![li_(vec![p__("item")])]),
+        checklist_(vec![task__(true, "done"), task__(false, "open")]),
+        quote_(vec![p__("Quoted")]),
+        Paragraph::new_table().with_rows(vec![
+            TableRow::new().with_cells(vec![TableCell::new_header().with_content(spans("Key"))]),
+            TableRow::new().with_cells(vec![TableCell::new_data().with_content(spans("Value"))]),
+        ]),
+        Paragraph::new_definition_list().with_definition_items(vec![DefinitionItem::new()
+            .with_term(spans("Term"))
+            .with_definition(vec![p__("Meaning")])]),
+        Paragraph::new_details()
+            .with_summary(spans("More"))
+            .with_children(vec![p__("Hidden")]),
+        Paragraph::new_admonition(AdmonitionKind::Warning).with_children(vec![p__("Careful")]),
+        Paragraph::new_horizontal_rule(),
+        Paragraph::new_raw("html", "<video src=\"clip.mp4\"></video>"),
+    ])
+}
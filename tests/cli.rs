@@ -0,0 +1,80 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// Creates an empty scratch directory unique to `name` and this test run.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("tdoc-cli-{name}-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn tdoc(args: &[&std::ffi::OsStr]) {
+    let output = Command::new(env!("CARGO_BIN_EXE_tdoc"))
+        .args(args)
+        .output()
+        .expect("run tdoc");
+    assert!(
+        output.status.success(),
+        "tdoc {args:?} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_markdown_json_markdown_roundtrip() {
+    let dir = scratch_dir("json");
+    let source = dir.join("source.md");
+    let json = dir.join("document.json");
+    let via_json = dir.join("via-json.md");
+    let direct = dir.join("direct.md");
+    std::fs::write(
+        &source,
+        "# Title\n\nSome *styled* `code` and a [link](https://example.com).\n\n\
+         - one\n- two\n\n> quoted\n\n```rust\nfn main() {}\n```\n",
+    )
+    .unwrap();
+
+    tdoc(&[source.as_os_str(), "--output".as_ref(), json.as_os_str()]);
+    tdoc(&[json.as_os_str(), "--output".as_ref(), via_json.as_os_str()]);
+    tdoc(&[source.as_os_str(), "--output".as_ref(), direct.as_os_str()]);
+
+    let json_text = std::fs::read_to_string(&json).unwrap();
+    assert!(json_text.trim_start().starts_with('{'));
+    assert_eq!(
+        std::fs::read_to_string(&via_json).unwrap(),
+        std::fs::read_to_string(&direct).unwrap()
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_stdin_ftml_is_sniffed_as_ftml() {
+    let dir = scratch_dir("stdin-ftml");
+    let source = dir.join("document.ftml");
+    let from_file = dir.join("from-file.json");
+    let from_stdin = dir.join("from-stdin.json");
+    let mut ftml = Vec::new();
+    tdoc::ftml::write(&mut ftml, &tdoc::test_helpers::every_type_document()).unwrap();
+    std::fs::write(&source, &ftml).unwrap();
+
+    tdoc(&[
+        source.as_os_str(),
+        "--output".as_ref(),
+        from_file.as_os_str(),
+    ]);
+    let mut child = Command::new(env!("CARGO_BIN_EXE_tdoc"))
+        .args(["--output".as_ref(), from_stdin.as_os_str()])
+        .stdin(Stdio::piped())
+        .spawn()
+        .expect("run tdoc");
+    child.stdin.take().unwrap().write_all(&ftml).unwrap();
+    assert!(child.wait().unwrap().success());
+
+    assert_eq!(
+        std::fs::read_to_string(&from_stdin).unwrap(),
+        std::fs::read_to_string(&from_file).unwrap()
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -3,6 +3,7 @@ use std::io::Cursor;
 use std::path::{Path, PathBuf};
 
 use tdoc::ftml::parse;
+use tdoc::{ChecklistItem, Document, InlineStyle, Paragraph, Span};
 
 pub fn collect_ftml_fixtures() -> Vec<PathBuf> {
     let mut fixtures = Vec::new();
@@ -85,3 +86,206 @@ fn ftml_roundtrips_ftml_documents() {
         );
     }
 }
+
+/// Small xorshift generator so the random documents are reproducible from
+/// their seed without pulling in a property-testing crate.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, percent: usize) -> bool {
+        self.below(100) < percent
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[self.below(items.len())]
+    }
+}
+
+/// Words include FTML's markup characters and entity lookalikes, which the
+/// writer has to escape.
+const WORDS: &[&str] = &[
+    "alpha",
+    "beta",
+    "Gamma",
+    "a<b",
+    "x>y",
+    "this&that",
+    "&amp;",
+    "&lt;tag&gt;",
+    "<p>",
+    "\"quoted\"",
+    "it's",
+    "Grüße",
+    "naïve",
+    "42",
+    "--",
+    "#hash",
+    "*star*",
+    "_under_",
+    "[x]",
+];
+
+const INLINE_STYLES: &[InlineStyle] = &[
+    InlineStyle::Bold,
+    InlineStyle::Italic,
+    InlineStyle::Underline,
+    InlineStyle::Strike,
+    InlineStyle::Highlight,
+    InlineStyle::Code,
+    InlineStyle::Superscript,
+    InlineStyle::Subscript,
+    InlineStyle::Keyboard,
+    InlineStyle::Abbreviation,
+    InlineStyle::Quotation,
+    InlineStyle::Link,
+];
+
+fn random_words(rng: &mut Rng) -> String {
+    let count = 1 + rng.below(4);
+    (0..count)
+        .map(|_| rng.pick(WORDS))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Builds spans in the parser's canonical shape: plain text never touches
+/// other plain text, styled spans are non-empty, and whitespace only appears
+/// as single spaces between words.
+fn random_spans(rng: &mut Rng, depth: usize) -> Vec<Span> {
+    let count = 1 + rng.below(4);
+    let mut spans: Vec<Span> = Vec::new();
+    for idx in 0..count {
+        let styled = depth < 3 && rng.chance(50);
+        if !styled {
+            let mut text = random_words(rng);
+            if idx + 1 < count {
+                text.push(' ');
+            }
+            match spans.last_mut() {
+                Some(last) if last.style == InlineStyle::None => last.text.push_str(&text),
+                Some(_) => spans.push(Span::new_text(format!(" {text}"))),
+                None => spans.push(Span::new_text(text)),
+            }
+            continue;
+        }
+
+        let style = INLINE_STYLES[rng.below(INLINE_STYLES.len())];
+        let span = match style {
+            InlineStyle::Link => {
+                let target = format!("https://example.com/{}?a=1&b={}", rng.below(100), idx);
+                let link = Span::new_styled(InlineStyle::Link).with_link_target(target);
+                if rng.chance(30) {
+                    link
+                } else {
+                    link.with_children(random_spans(rng, depth + 1))
+                }
+            }
+            InlineStyle::Abbreviation => Span::new_styled(style)
+                .with_link_target(random_words(rng))
+                .with_children(random_spans(rng, depth + 1)),
+            InlineStyle::Code => {
+                Span::new_styled(style).with_children(vec![Span::new_text(random_words(rng))])
+            }
+            _ => Span::new_styled(style).with_children(random_spans(rng, depth + 1)),
+        };
+        if let Some(last) = spans.last_mut() {
+            if last.style == InlineStyle::None && !last.text.ends_with(' ') {
+                last.text.push(' ');
+            }
+        }
+        spans.push(span);
+    }
+    spans
+}
+
+fn random_checklist_items(rng: &mut Rng, depth: usize) -> Vec<ChecklistItem> {
+    let count = 1 + rng.below(3);
+    (0..count)
+        .map(|_| {
+            let item = ChecklistItem::new(rng.chance(50)).with_content(random_spans(rng, 0));
+            if depth < 2 && rng.chance(30) {
+                item.with_children(random_checklist_items(rng, depth + 1))
+            } else {
+                item
+            }
+        })
+        .collect()
+}
+
+fn random_paragraph(rng: &mut Rng, depth: usize) -> Paragraph {
+    let kinds = if depth < 3 { 9 } else { 5 };
+    match rng.below(kinds) {
+        0 | 1 => Paragraph::new_text().with_content(random_spans(rng, 0)),
+        2 => match rng.below(3) {
+            0 => Paragraph::new_header1(),
+            1 => Paragraph::new_header2(),
+            _ => Paragraph::new_header3(),
+        }
+        .with_content(random_spans(rng, 0)),
+        3 => {
+            let lines: Vec<String> = (0..1 + rng.below(3)).map(|_| random_words(rng)).collect();
+            Paragraph::new_code_block().with_content(vec![Span::new_text(lines.join("\n"))])
+        }
+        4 => Paragraph::new_checklist().with_checklist_items(random_checklist_items(rng, depth)),
+        5 | 6 => Paragraph::new_quote().with_children(random_paragraphs(rng, depth + 1)),
+        7 => Paragraph::new_unordered_list().with_entries(random_entries(rng, depth)),
+        _ => Paragraph::new_ordered_list().with_entries(random_entries(rng, depth)),
+    }
+}
+
+fn random_entries(rng: &mut Rng, depth: usize) -> Vec<Vec<Paragraph>> {
+    (0..1 + rng.below(3))
+        .map(|_| random_paragraphs(rng, depth + 1))
+        .collect()
+}
+
+fn random_paragraphs(rng: &mut Rng, depth: usize) -> Vec<Paragraph> {
+    (0..1 + rng.below(3))
+        .map(|_| random_paragraph(rng, depth))
+        .collect()
+}
+
+#[test]
+fn ftml_roundtrips_random_documents() {
+    for seed in 1..=500u64 {
+        let mut rng = Rng(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        let document = Document::new().with_paragraphs(random_paragraphs(&mut rng, 0));
+
+        let rendered = render_ftml(&document);
+        let roundtripped = parse(Cursor::new(rendered.as_bytes())).unwrap_or_else(|err| {
+            panic!("seed {seed}: failed to parse rendered FTML: {err}\n{rendered}")
+        });
+        assert_eq!(
+            roundtripped, document,
+            "seed {seed}: FTML round-trip mismatch for\n{rendered}"
+        );
+    }
+}
+
+#[test]
+fn ftml_roundtrips_code_block_language() {
+    let document = Document::new().with_paragraphs(vec![Paragraph::new_code_block()
+        .with_content(vec![Span::new_text("fn main() {}")])
+        .with_language("rust")]);
+
+    let rendered = render_ftml(&document);
+    assert!(
+        rendered.contains("<pre class=\"language-rust\">"),
+        "{rendered}"
+    );
+
+    let roundtripped = parse(Cursor::new(rendered.as_bytes())).unwrap();
+    assert_eq!(roundtripped.paragraphs[0].language(), Some("rust"));
+    assert_eq!(roundtripped, document);
+}
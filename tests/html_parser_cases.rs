@@ -1,6 +1,7 @@
 use std::fs;
 use std::io::Cursor;
 
+use tdoc::formatter::Formatter;
 use tdoc::ftml;
 use tdoc::ftml::Writer;
 use tdoc::html;
@@ -379,6 +380,28 @@ fn parsing_inline_styles_in_list_items() {
     );
 }
 
+#[test]
+fn inline_quotations_are_parsed_and_quoted() {
+    let document = parse("<p>He said <q>use <q>tdoc</q> here</q>.</p>");
+    assert_eq!(
+        document,
+        doc(vec![p_(vec![
+            span("He said "),
+            q_(vec![span("use "), q__("tdoc"), span(" here")]),
+            span("."),
+        ])])
+    );
+
+    let mut output = Vec::new();
+    Formatter::new_ascii(&mut output)
+        .write_document(&document)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "He said \"use 'tdoc' here\".\n"
+    );
+}
+
 #[test]
 fn unclosed_block_elements() {
     let input = "<p>Hello<h1>World</h1>";
@@ -1,7 +1,8 @@
 use std::io::Cursor;
 use tdoc::ftml::{parse, write};
+use tdoc::test_helpers::{checklist_, doc, task_, task__};
 use tdoc::{
-    formatter::Formatter, ftml, markdown, ChecklistItem, Document, InlineStyle, Paragraph,
+    formatter::Formatter, ftml, html, markdown, ChecklistItem, Document, InlineStyle, Paragraph,
     ParagraphType, Span,
 };
 
@@ -186,6 +187,21 @@ fn test_formatter_code_block_ascii() {
     assert_eq!(rendered, expected);
 }
 
+#[test]
+fn test_formatter_reflows_markdown_soft_breaks() {
+    let input = "A paragraph that the author wrapped\nacross two lines.  \nThen a hard break.\n";
+    let doc = markdown::parse(Cursor::new(input)).unwrap();
+    let mut output = Vec::new();
+    let mut formatter = Formatter::new_ascii(&mut output);
+    formatter.style.wrap_width = 120;
+    formatter.write_document(&doc).unwrap();
+    let rendered = String::from_utf8(output).unwrap();
+    assert_eq!(
+        rendered,
+        "A paragraph that the author wrapped across two lines.\nThen a hard break.\n"
+    );
+}
+
 #[test]
 fn test_formatter_code_block_wrapping() {
     let doc = ftml! { code { "abcdefghijk" } };
@@ -431,6 +447,43 @@ fn test_markdown_nested_checklist_roundtrip() {
     assert_eq!(output, input);
 }
 
+#[test]
+fn test_built_two_level_checklist_roundtrips() {
+    let expected_doc = Document::new().with_paragraphs(vec![Paragraph::checklist(vec![
+        ChecklistItem::new_text("Ship release").with_children(vec![
+            ChecklistItem::new_text("Write notes").checked(true),
+            ChecklistItem::new_text("Tag version"),
+        ]),
+        ChecklistItem::new_text("Announce").checked(true),
+    ])]);
+    assert_eq!(
+        expected_doc,
+        doc(vec![checklist_(vec![
+            task_(
+                false,
+                "Ship release",
+                vec![task__(true, "Write notes"), task__(false, "Tag version")]
+            ),
+            task__(true, "Announce"),
+        ])])
+    );
+
+    let mut buf = Vec::new();
+    markdown::write(&mut buf, &expected_doc).unwrap();
+    let output = String::from_utf8(buf).unwrap();
+    assert_eq!(
+        output,
+        "- [ ] Ship release\n  - [x] Write notes\n  - [ ] Tag version\n- [x] Announce\n"
+    );
+    assert_eq!(markdown::parse(Cursor::new(&output)).unwrap(), expected_doc);
+
+    let html = ftml::Writer::new_html()
+        .write_to_string(&expected_doc)
+        .unwrap();
+    assert!(html.contains("<input type=\"checkbox\" checked />"));
+    assert_eq!(html::parse(Cursor::new(&html)).unwrap(), expected_doc);
+}
+
 #[test]
 fn test_markdown_wikilink_roundtrip() {
     let input = "[[WikiLink]]";
@@ -483,6 +536,26 @@ fn test_markdown_nested_unordered_lists() {
     assert_eq!(parsed, expected);
 }
 
+#[test]
+fn test_markdown_loose_nested_unordered_list_keeps_inner_items() {
+    let input = "- first\n\n  - inner one\n  - inner two\n- second\n";
+    let parsed = markdown::parse(Cursor::new(input)).unwrap();
+    let expected = ftml! {
+        ul {
+            li {
+                p { "first" }
+                ul {
+                    li { p { "inner one" } }
+                    li { p { "inner two" } }
+                }
+            }
+            li { p { "second" } }
+        }
+    };
+
+    assert_eq!(parsed, expected);
+}
+
 #[test]
 fn test_markdown_nested_mixed_lists() {
     let input = "1. ordered item\n   - unordered child\n     - [x] nested task\n";
@@ -835,3 +908,16 @@ fn test_nested_lists_structure() {
     let _parsed_doc = parse(Cursor::new(input));
     // This should fail with "Non-inline token: l" error
 }
+
+#[test]
+fn test_html_to_markdown_roundtrip_is_semantically_equal() {
+    let input = "<h2>Notes </h2>\n<p>Some <b>bold</b> and\n<i>italic</i> text. </p>\n\
+                 <ul><li>first</li><li>second <code>code</code></li></ul>";
+    let from_html = tdoc::html::parse(Cursor::new(input)).unwrap();
+
+    let mut buf = Vec::new();
+    markdown::write(&mut buf, &from_html).unwrap();
+    let from_markdown = markdown::parse(Cursor::new(buf)).unwrap();
+
+    assert_eq!(from_markdown.normalized(), from_html.normalized());
+}
@@ -0,0 +1,77 @@
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use tdoc::{ftml, gemini, html, markdown, Document};
+
+fn collect_fixtures(dir: &str, extension: &str) -> Vec<PathBuf> {
+    let mut fixtures: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("Unable to read {}: {}", dir, err))
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+        })
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "expected fixtures under {}", dir);
+    fixtures
+}
+
+/// Parses every fixture in `dir` once with LF and once with CRLF line
+/// endings and asserts that both produce the same document. Fixtures that do
+/// not parse with LF line endings either are skipped.
+fn assert_crlf_parses_like_lf(
+    dir: &str,
+    extension: &str,
+    parse: fn(&str) -> tdoc::Result<Document>,
+) {
+    for path in collect_fixtures(dir, extension) {
+        let lf = fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("Unable to read {}: {}", path.display(), err))
+            .replace("\r\n", "\n");
+        let crlf = lf.replace('\n', "\r\n");
+
+        let Ok(expected) = parse(&lf) else {
+            continue;
+        };
+        let actual = parse(&crlf)
+            .unwrap_or_else(|err| panic!("Failed to parse CRLF {}: {}", path.display(), err));
+        assert_eq!(
+            actual,
+            expected,
+            "CRLF parse differs for {}",
+            path.display()
+        );
+    }
+}
+
+#[test]
+fn ftml_crlf_parses_like_lf() {
+    assert_crlf_parses_like_lf("tests/data/ftml", "ftml", |input| {
+        Ok(ftml::parse(Cursor::new(input))?)
+    });
+}
+
+#[test]
+fn html_crlf_parses_like_lf() {
+    assert_crlf_parses_like_lf("tests/data/html", "html", |input| {
+        html::parse(Cursor::new(input))
+    });
+}
+
+#[test]
+fn markdown_crlf_parses_like_lf() {
+    assert_crlf_parses_like_lf("tests/data/markdown", "md", |input| {
+        markdown::parse(Cursor::new(input))
+    });
+}
+
+#[test]
+fn gemini_crlf_parses_like_lf() {
+    assert_crlf_parses_like_lf("tests/data/gemini", "gmi", |input| {
+        gemini::parse(Cursor::new(input))
+    });
+}